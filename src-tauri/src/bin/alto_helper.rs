@@ -1,10 +1,14 @@
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use tokio::net::{UnixListener, UnixStream};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use serde::{Deserialize, Serialize};
 use std::fs;
 
 const CHECK_FILE_PATH: &str = "/var/run/com.alto.helper.sock";
+/// Largest request accepted; restore path lists stay far below this.
+const MAX_REQUEST_BYTES: u64 = 1024 * 1024;
+/// SIP-protected and OS-owned locations a snapshot restore never writes to.
+const SYSTEM_ROOTS: [&str; 9] = ["/System", "/bin", "/sbin", "/usr", "/etc", "/private/etc", "/var/db", "/private/var/db", "/Library/Apple"];
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "action", content = "payload")]
@@ -12,6 +16,16 @@ enum Command {
     Ping,
     DeletePath { path: String },
     UninstallApp { bundle_path: String },
+    /// Remove the system and per-user Icon Services caches (root-owned)
+    ResetIconServices,
+    /// Per-user Trash totals for every account under /Users (JSON array in `message`)
+    TrashUsage,
+    /// Empty /Users/<user>/.Trash
+    EmptyUserTrash { user: String },
+    /// `tmutil localsnapshot`; `message` is the snapshot date ("2026-10-16-101500")
+    CreateLocalSnapshot,
+    /// Copy `paths` back from the local snapshot taken at `snapshot` (JSON result in `message`)
+    RestoreFromSnapshot { snapshot: String, paths: Vec<String> },
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,6 +34,20 @@ struct Response {
     message: String,
 }
 
+/// The parts of a cleanup record in the client's ~/.alto/context.json a restore is checked against.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DeletionRecord {
+    paths_deleted: Vec<String>,
+    snapshot: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ClientStore {
+    deletion_history: Vec<DeletionRecord>,
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 1. Cleanup old socket
@@ -52,16 +80,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 async fn handle_connection(mut stream: UnixStream) -> Result<(), Box<dyn std::error::Error>> {
-    let mut buf = vec![0; 1024];
-    let n = stream.read(&mut buf).await?;
-
-    if n == 0 { return Ok(()); }
+    // The client shuts down its side after writing, so read to EOF once (capped) and parse once
+    let mut buf = Vec::new();
+    (&mut stream).take(MAX_REQUEST_BYTES + 1).read_to_end(&mut buf).await?;
+    if buf.is_empty() { return Ok(()); }
+    if buf.len() as u64 > MAX_REQUEST_BYTES {
+        let response = Response { success: false, message: "Request too large".into() };
+        stream.write_all(&serde_json::to_vec(&response)?).await?;
+        return Ok(());
+    }
+    let peer_uid = stream.peer_cred()?.uid();
 
-    let request: Command = serde_json::from_slice(&buf[0..n])?;
+    let request: Command = serde_json::from_slice(&buf)?;
     println!("Received command: {:?}", request);
 
     let response = match request {
         Command::Ping => Response { success: true, message: "Pong".into() },
+        Command::DeletePath { path } if is_alto_path(&path) => refuse(&path),
+        Command::UninstallApp { bundle_path } if is_alto_path(&bundle_path) => refuse(&bundle_path),
         Command::DeletePath { path } => {
             // DANGEROUS: For prototype we allow deleting anything
             // In prod: Validate path is safe (not /, not /System)
@@ -76,6 +112,17 @@ async fn handle_connection(mut stream: UnixStream) -> Result<(), Box<dyn std::er
                 Err(e) => Response { success: false, message: e.to_string() },
             }
         }
+        Command::ResetIconServices => reset_icon_services(),
+        Command::TrashUsage | Command::EmptyUserTrash { .. } if !is_admin(peer_uid) => {
+            Response { success: false, message: "Only an administrator can read or empty other accounts' Trash".into() }
+        }
+        Command::TrashUsage => trash_usage(),
+        Command::EmptyUserTrash { user } => empty_user_trash(&user),
+        Command::CreateLocalSnapshot => create_local_snapshot(),
+        Command::RestoreFromSnapshot { snapshot, paths } => match home_of(peer_uid) {
+            Some(home) => restore_from_snapshot(&snapshot, &paths, &recorded_paths(&home, &snapshot), &home),
+            None => Response { success: false, message: "No home folder for this account".into() },
+        },
     };
 
     let response_data = serde_json::to_vec(&response)?;
@@ -83,3 +130,340 @@ async fn handle_connection(mut stream: UnixStream) -> Result<(), Box<dyn std::er
 
     Ok(())
 }
+
+/// The helper's own files, Alto's app bundle and every account's ~/.alto data (the helper runs
+/// as root, so home directories are matched by pattern). Only the self-uninstall flow removes these.
+fn is_alto_path(path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    let inside = |root: &str| path == root || path.starts_with(&format!("{}/", root)) || root.starts_with(&format!("{}/", path));
+    if [CHECK_FILE_PATH, "/usr/local/bin/alto_helper", "/Library/LaunchDaemons/com.alto.helper.plist", "/Applications/Alto.app"]
+        .iter()
+        .any(|root| inside(root))
+    {
+        return true;
+    }
+    // /Users/<name>/.alto and /Users/<name>/Library/Application Support/com.maccleaner.app
+    let parts: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    match parts.as_slice() {
+        ["Users"] | ["Users", _] => true,
+        ["Users", _, ".alto", ..] => true,
+        ["Users", _, "Library"] | ["Users", _, "Library", "Application Support"] => true,
+        ["Users", _, "Library", "Application Support", "com.maccleaner.app", ..] => true,
+        _ => false,
+    }
+}
+
+fn refuse(path: &str) -> Response {
+    Response { success: false, message: format!("Refusing to delete {}: it belongs to Alto", path) }
+}
+
+/// Icon Services keeps a system store plus per-user caches under /private/var/folders.
+/// They are rebuilt on demand once Dock and Finder restart.
+fn reset_icon_services() -> Response {
+    let failed = |message: String| Response { success: false, message };
+    match fs::remove_dir_all("/Library/Caches/com.apple.iconservices.store") {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return failed(format!("Could not remove the Icon Services store: {}", e)),
+    }
+    // find exits non-zero when it or one of the rm runs failed
+    let status = std::process::Command::new("find")
+        .args([
+            "/private/var/folders",
+            "(", "-name", "com.apple.dock.iconcache", "-o", "-name", "com.apple.iconservices", ")",
+            "-prune", "-exec", "rm", "-rf", "{}", "+",
+        ])
+        .status();
+    match status {
+        Ok(s) if s.success() => Response { success: true, message: "Icon Services caches removed".into() },
+        Ok(s) => failed(format!("Removing per-user icon caches failed ({})", s)),
+        Err(e) => failed(e.to_string()),
+    }
+}
+
+fn allocated_size(path: &Path) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.blocks() * 512)
+        .sum()
+}
+
+/// Whether the connecting account is root or in the admin group. The socket is open to every
+/// account, so commands reaching into other accounts check this here, not only in the app.
+fn is_admin(uid: u32) -> bool {
+    uid == 0
+        || std::process::Command::new("id")
+            .args(["-Gn", &uid.to_string()])
+            .output()
+            .map(|o| o.status.success() && String::from_utf8_lossy(&o.stdout).split_whitespace().any(|g| g == "admin"))
+            .unwrap_or(false)
+}
+
+/// /Users/<user>/.Trash when it is a real folder (not a symlink) owned by the same account as the
+/// home folder, so root never follows a Trash pointed somewhere else.
+fn user_trash(user: &str) -> Option<PathBuf> {
+    use std::os::unix::fs::MetadataExt;
+    if user.is_empty() || user.contains('/') || user.starts_with('.') {
+        return None;
+    }
+    let home = Path::new("/Users").join(user);
+    let trash = home.join(".Trash");
+    let home_meta = fs::symlink_metadata(&home).ok()?;
+    let trash_meta = fs::symlink_metadata(&trash).ok()?;
+    (home_meta.is_dir() && trash_meta.is_dir() && trash_meta.uid() == home_meta.uid()).then_some(trash)
+}
+
+/// Trash entries of one account, without Finder's .DS_Store and friends.
+fn trash_entries(user: &str) -> Vec<std::path::PathBuf> {
+    let Some(trash) = user_trash(user) else { return vec![] };
+    fs::read_dir(trash)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn trash_usage() -> Response {
+    let Ok(users) = fs::read_dir("/Users") else {
+        return Response { success: false, message: "Cannot read /Users".into() };
+    };
+    let usage: Vec<serde_json::Value> = users
+        .flatten()
+        .filter(|u| user_trash(&u.file_name().to_string_lossy()).is_some())
+        .map(|u| {
+            let user = u.file_name().to_string_lossy().to_string();
+            let entries = trash_entries(&user);
+            serde_json::json!({
+                "user": user,
+                "item_count": entries.len(),
+                "size_bytes": entries.iter().map(|p| allocated_size(p)).sum::<u64>(),
+            })
+        })
+        .collect();
+    Response { success: true, message: serde_json::Value::Array(usage).to_string() }
+}
+
+fn empty_user_trash(user: &str) -> Response {
+    if user_trash(user).is_none() {
+        return Response { success: false, message: format!("No Trash for user {}", user) };
+    }
+    let mut removed = 0usize;
+    let mut bytes_freed = 0u64;
+    let mut errors = Vec::new();
+    for path in trash_entries(user) {
+        let size = allocated_size(&path);
+        let result = if path.is_dir() && !path.is_symlink() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+        match result {
+            Ok(_) => {
+                removed += 1;
+                bytes_freed += size;
+            }
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    Response {
+        success: errors.is_empty(),
+        message: serde_json::json!({ "removed": removed, "bytes_freed": bytes_freed, "errors": errors }).to_string(),
+    }
+}
+
+/// Snapshot dates as printed by tmutil, e.g. "2026-10-16-101500".
+fn valid_snapshot_date(date: &str) -> bool {
+    !date.is_empty() && date.chars().all(|c| c.is_ascii_digit() || c == '-')
+}
+
+fn create_local_snapshot() -> Response {
+    let out = match std::process::Command::new("tmutil").arg("localsnapshot").output() {
+        Ok(o) => o,
+        Err(e) => return Response { success: false, message: e.to_string() },
+    };
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    // "Created local snapshot with date: 2026-10-16-101500"
+    match stdout.rsplit_once("date:").map(|(_, d)| d.trim().to_string()) {
+        Some(date) if out.status.success() && valid_snapshot_date(&date) => Response { success: true, message: date },
+        _ => Response { success: false, message: String::from_utf8_lossy(&out.stderr).trim().to_string() },
+    }
+}
+
+/// The home folder under /Users owned by `uid`.
+fn home_of(uid: u32) -> Option<PathBuf> {
+    use std::os::unix::fs::MetadataExt;
+    fs::read_dir("/Users")
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| !p.is_symlink() && p.metadata().map(|m| m.is_dir() && m.uid() == uid).unwrap_or(false))
+}
+
+/// Paths the connecting user's Alto recorded deleting in the cleanup that took `snapshot`.
+fn recorded_paths(home: &Path, snapshot: &str) -> Vec<String> {
+    let store: ClientStore = fs::read_to_string(home.join(".alto/context.json"))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default();
+    store
+        .deletion_history
+        .into_iter()
+        .filter(|r| r.snapshot.as_deref() == Some(snapshot))
+        .flat_map(|r| r.paths_deleted)
+        .collect()
+}
+
+fn is_system_path(path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    !path.starts_with("/usr/local/")
+        && SYSTEM_ROOTS.iter().any(|root| path == *root || path.starts_with(&format!("{}/", root)))
+}
+
+/// Why `path` can't be restored, if it can't: it must be an absolute path without `..` that the
+/// cleanup recorded, and never a system location or one of Alto's own files.
+fn restore_refusal(path: &str, recorded: &[String]) -> Option<&'static str> {
+    let p = Path::new(path);
+    if !p.is_absolute() || p.components().any(|c| c == Component::ParentDir) {
+        Some("not an absolute path")
+    } else if !recorded.iter().any(|r| r == path) {
+        Some("not part of this cleanup")
+    } else if is_system_path(path) {
+        Some("system location")
+    } else if is_alto_path(path) {
+        Some("belongs to Alto")
+    } else {
+        None
+    }
+}
+
+/// Whether ditto, running as root, may write `path`: nothing (not even a dangling symlink) is
+/// there now, and its parent folder resolves, symlinks followed, to somewhere inside `home`.
+fn restorable_into(path: &Path, home: &Path) -> Result<(), &'static str> {
+    if fs::symlink_metadata(path).is_ok() {
+        return Err("exists again");
+    }
+    let parent = path.parent().and_then(|p| fs::canonicalize(p).ok()).ok_or("parent folder is missing")?;
+    let home = fs::canonicalize(home).map_err(|_| "home folder is missing")?;
+    if parent.starts_with(&home) { Ok(()) } else { Err("outside your home folder") }
+}
+
+/// Mount the Data volume's snapshot read-only and copy each deleted path back (ownership and
+/// metadata preserved by ditto). Only paths `recorded` for that cleanup are restored, only into
+/// the requesting account's `home`; paths that exist again are left alone.
+fn restore_from_snapshot(snapshot: &str, paths: &[String], recorded: &[String], home: &Path) -> Response {
+    if !valid_snapshot_date(snapshot) {
+        return Response { success: false, message: format!("Invalid snapshot: {}", snapshot) };
+    }
+    let mut errors = Vec::new();
+    let paths: Vec<&String> = paths
+        .iter()
+        .filter(|path| match restore_refusal(path, recorded) {
+            Some(reason) => {
+                errors.push(format!("{}: {}", path, reason));
+                false
+            }
+            None => true,
+        })
+        .collect();
+    if paths.is_empty() {
+        return Response { success: false, message: serde_json::json!({ "restored": 0, "errors": errors }).to_string() };
+    }
+    let mount_point = format!("/private/tmp/alto-restore-{}", snapshot);
+    if let Err(e) = fs::create_dir_all(&mount_point) {
+        return Response { success: false, message: e.to_string() };
+    }
+    let mounted = std::process::Command::new("mount_apfs")
+        .args(["-o", "rdonly", "-s", &format!("com.apple.TimeMachine.{}.local", snapshot), "/System/Volumes/Data", &mount_point])
+        .output();
+    match mounted {
+        Ok(o) if o.status.success() => {}
+        Ok(o) => return Response { success: false, message: String::from_utf8_lossy(&o.stderr).trim().to_string() },
+        Err(e) => return Response { success: false, message: e.to_string() },
+    }
+
+    let mut restored = 0usize;
+    for path in paths {
+        match restorable_into(Path::new(path), home) {
+            Ok(()) => {}
+            Err("exists again") => continue,
+            Err(reason) => {
+                errors.push(format!("{}: {}", path, reason));
+                continue;
+            }
+        }
+        // A symlink inside the snapshot could otherwise point the copy at the live system
+        let source = format!("{}{}", mount_point, path);
+        match fs::canonicalize(&source) {
+            Ok(resolved) if resolved.starts_with(&mount_point) => {}
+            Ok(_) => {
+                errors.push(format!("{}: resolves outside the snapshot", path));
+                continue;
+            }
+            Err(_) => {
+                errors.push(format!("{}: not in snapshot", path));
+                continue;
+            }
+        }
+        match std::process::Command::new("ditto").args([source.as_str(), path]).status() {
+            Ok(s) if s.success() => restored += 1,
+            _ => errors.push(format!("{}: copy failed", path)),
+        }
+    }
+    let _ = std::process::Command::new("umount").arg(&mount_point).status();
+    let _ = fs::remove_dir(&mount_point);
+    Response {
+        success: errors.is_empty(),
+        message: serde_json::json!({ "restored": restored, "errors": errors }).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_alto_paths() {
+        assert!(is_alto_path("/Users/jane/.alto/context.json"));
+        assert!(is_alto_path("/Users/jane/.alto"));
+        assert!(is_alto_path("/usr/local/bin/alto_helper"));
+        assert!(is_alto_path("/Applications/Alto.app/Contents"));
+        assert!(is_alto_path("/Users/jane"));
+        assert!(is_alto_path("/Users/jane/Library/Application Support/com.maccleaner.app/x"));
+        assert!(!is_alto_path("/Users/jane/Library/Application Support/com.example.app"));
+        assert!(!is_alto_path("/Library/Caches/com.apple.iconservices.store"));
+    }
+
+    #[test]
+    fn restores_only_recorded_paths() {
+        let recorded: Vec<String> = ["/Users/jane/Library/Caches/com.example", "/System/Library/Kernels", "/Users/jane/.alto/context.json", "/usr/local/lib/libfoo.dylib"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(restore_refusal("/Users/jane/Library/Caches/com.example", &recorded), None);
+        assert_eq!(restore_refusal("/usr/local/lib/libfoo.dylib", &recorded), None);
+        assert_eq!(restore_refusal("/Users/jane/Documents/x", &recorded), Some("not part of this cleanup"));
+        assert_eq!(restore_refusal("/System/Library/Kernels", &recorded), Some("system location"));
+        assert_eq!(restore_refusal("/Users/jane/.alto/context.json", &recorded), Some("belongs to Alto"));
+        assert_eq!(restore_refusal("/Users/jane/Library/Caches/../../.ssh", &recorded), Some("not an absolute path"));
+    }
+
+    #[test]
+    fn restores_never_write_through_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("jane");
+        let elsewhere = dir.path().join("elsewhere");
+        fs::create_dir_all(home.join("Library/Caches")).unwrap();
+        fs::create_dir_all(&elsewhere).unwrap();
+        std::os::unix::fs::symlink(&elsewhere, home.join("Library/Linked")).unwrap();
+        std::os::unix::fs::symlink(&elsewhere, home.join("Library/Caches/gone")).unwrap();
+
+        assert_eq!(restorable_into(&home.join("Library/Caches/com.example"), &home), Ok(()));
+        assert_eq!(restorable_into(&home.join("Library/Linked/com.example"), &home), Err("outside your home folder"));
+        assert_eq!(restorable_into(&home.join("Library/Caches/gone"), &home), Err("exists again"));
+        assert_eq!(restorable_into(&home.join("Library/Missing/com.example"), &home), Err("parent folder is missing"));
+    }
+}
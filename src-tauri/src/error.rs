@@ -0,0 +1,146 @@
+use serde::Serialize;
+
+/// Error returned by Tauri commands. Serialized as `{ "code": "not_found", "message": "...", ...context }`
+/// so the frontend can react to the kind of failure (offer Full Disk Access, ask to quit an app)
+/// instead of matching on text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum AltoError {
+    /// macOS refused access; usually fixed by granting Full Disk Access
+    PermissionDenied { message: String, path: Option<String> },
+    /// The file or folder is gone, e.g. removed since the scan that listed it
+    NotFound { message: String, path: Option<String> },
+    /// A running app holds what the command needs; quitting it and retrying helps
+    AppRunning { message: String, app: String },
+    /// Refused by a safety rule: administrator policy, protected or excluded paths, Alto's own data
+    Blocked { message: String, path: Option<String> },
+    /// The request itself doesn't make sense (empty path, unknown id, value out of range)
+    InvalidInput { message: String },
+    /// macOS refused to let Alto script another app; `settings_url` opens the Automation pane
+    AutomationPermission { message: String, settings_url: String },
+    Failed { message: String },
+}
+
+impl AltoError {
+    pub fn not_found(path: impl Into<String>) -> Self {
+        let path = path.into();
+        Self::NotFound { message: format!("{} no longer exists", path), path: Some(path) }
+    }
+
+    pub fn app_running(app: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::AppRunning { message: message.into(), app: app.into() }
+    }
+
+    pub fn blocked(message: impl Into<String>) -> Self {
+        Self::Blocked { message: message.into(), path: None }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::InvalidInput { message: message.into() }
+    }
+
+    /// An I/O failure on `path`, classified by its kind.
+    pub fn io(path: &str, e: &std::io::Error) -> Self {
+        Self::from_io_kind(e.kind(), format!("{}: {}", path, e), Some(path.to_string()))
+    }
+
+    fn from_io_kind(kind: std::io::ErrorKind, message: String, path: Option<String>) -> Self {
+        match kind {
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied { message, path },
+            std::io::ErrorKind::NotFound => Self::NotFound { message, path },
+            _ => Self::Failed { message },
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::PermissionDenied { message, .. }
+            | Self::NotFound { message, .. }
+            | Self::AppRunning { message, .. }
+            | Self::Blocked { message, .. }
+            | Self::InvalidInput { message }
+            | Self::AutomationPermission { message, .. }
+            | Self::Failed { message } => message,
+        }
+    }
+}
+
+impl std::fmt::Display for AltoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AltoError {}
+
+impl From<crate::scanners::automation::AutomationPermissionError> for AltoError {
+    fn from(e: crate::scanners::automation::AutomationPermissionError) -> Self {
+        Self::AutomationPermission { message: e.message, settings_url: e.settings_url.to_string() }
+    }
+}
+
+/// Scanners and helpers still report plain strings, usually with the `std::io::Error` text
+/// inside; its "(os error N)" suffix tells permission problems and vanished files apart.
+impl From<String> for AltoError {
+    fn from(message: String) -> Self {
+        if message.contains("(os error 1)") || message.contains("(os error 13)") {
+            Self::PermissionDenied { message, path: None }
+        } else if message.contains("(os error 2)") {
+            Self::NotFound { message, path: None }
+        } else {
+            Self::Failed { message }
+        }
+    }
+}
+
+impl From<&str> for AltoError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<std::io::Error> for AltoError {
+    fn from(e: std::io::Error) -> Self {
+        Self::from_io_kind(e.kind(), e.to_string(), None)
+    }
+}
+
+impl From<serde_json::Error> for AltoError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Failed { message: e.to_string() }
+    }
+}
+
+impl From<tauri::Error> for AltoError {
+    fn from(e: tauri::Error) -> Self {
+        Self::Failed { message: e.to_string() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_with_a_code_and_classifies_io_text() {
+        let json = serde_json::to_value(AltoError::app_running("Safari", "Quit Safari first")).unwrap();
+        assert_eq!(json, serde_json::json!({ "code": "app_running", "message": "Quit Safari first", "app": "Safari" }));
+
+        let denied: AltoError = "/Users/jane/Library/Mail: Operation not permitted (os error 1)".into();
+        assert!(matches!(denied, AltoError::PermissionDenied { .. }));
+        let gone: AltoError = "/tmp/x: No such file or directory (os error 2)".into();
+        assert!(matches!(gone, AltoError::NotFound { .. }));
+        assert!(matches!(AltoError::from("Something else"), AltoError::Failed { .. }));
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "not allowed");
+        assert!(matches!(AltoError::from(io), AltoError::PermissionDenied { .. }));
+    }
+
+    #[test]
+    fn automation_permission_errors_keep_their_settings_link() {
+        let error = crate::scanners::automation::AutomationPermissionError::new("Reading font activation");
+        let json = serde_json::to_value(AltoError::from(error)).unwrap();
+        assert_eq!(json["code"], "automation_permission");
+        assert_eq!(json["settings_url"], crate::scanners::automation::AUTOMATION_SETTINGS_URL);
+        assert!(json["message"].as_str().unwrap().starts_with("Reading font activation needs Automation permission"));
+    }
+}
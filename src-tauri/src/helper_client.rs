@@ -12,6 +12,16 @@ pub enum Command {
     Ping,
     DeletePath { path: String },
     UninstallApp { bundle_path: String },
+    /// Remove the system and per-user Icon Services caches (root-owned)
+    ResetIconServices,
+    /// Per-user Trash totals for every account under /Users (JSON array in `message`)
+    TrashUsage,
+    /// Empty /Users/<user>/.Trash
+    EmptyUserTrash { user: String },
+    /// `tmutil localsnapshot`; `message` is the snapshot date ("2026-10-16-101500")
+    CreateLocalSnapshot,
+    /// Copy `paths` back from the local snapshot taken at `snapshot` (JSON result in `message`)
+    RestoreFromSnapshot { snapshot: String, paths: Vec<String> },
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -20,8 +30,18 @@ pub struct Response {
     pub message: String,
 }
 
+/// Refuse helper deletions of Alto's own files before they reach the root helper.
+fn guard(cmd: &Command) -> Result<(), String> {
+    match cmd {
+        Command::DeletePath { path } => crate::mcp::self_protect::check("Delete", path),
+        Command::UninstallApp { bundle_path } => crate::mcp::self_protect::check("Uninstall", bundle_path),
+        _ => Ok(()),
+    }
+}
+
 #[cfg(unix)]
 pub async fn send_command(cmd: Command) -> Result<Response, String> {
+    guard(&cmd)?;
     // 1. Connect to socket
     let mut stream = UnixStream::connect(CHECK_FILE_PATH).await
         .map_err(|e| format!("Failed to connect to helper: {}", e))?;
@@ -32,24 +52,28 @@ pub async fn send_command(cmd: Command) -> Result<Response, String> {
     
     stream.write_all(&req_data).await
         .map_err(|e| e.to_string())?;
+    // The helper reads the request until EOF
+    stream.shutdown().await
+        .map_err(|e| e.to_string())?;
 
-    // 3. Read Response
-    let mut buf = vec![0; 1024];
-    let n = stream.read(&mut buf).await
+    // 3. Read Response (the helper closes the connection after writing it)
+    let mut buf = Vec::new();
+    stream.read_to_end(&mut buf).await
         .map_err(|e| e.to_string())?;
 
-    if n == 0 {
+    if buf.is_empty() {
         return Err("Empty response from helper".to_string());
     }
 
-    let response: Response = serde_json::from_slice(&buf[0..n])
+    let response: Response = serde_json::from_slice(&buf)
         .map_err(|e| e.to_string())?;
 
     Ok(response)
 }
 
 #[cfg(not(unix))]
-pub async fn send_command(_cmd: Command) -> Result<Response, String> {
+pub async fn send_command(cmd: Command) -> Result<Response, String> {
+    guard(&cmd)?;
     Err("Helper client is not supported on this platform".to_string())
 }
 
@@ -64,6 +88,11 @@ pub async fn ensure_helper_installed() -> bool {
         }
     }
 
+    if crate::mcp::managed::ManagedPolicy::load().disable_helper_install {
+        println!("Helper install is disabled by managed policy.");
+        return false;
+    }
+
     println!("Helper not running. Attempting installation...");
 
     // 2. Locate current executable to find the bundled helper or script
@@ -118,3 +147,16 @@ pub async fn ensure_helper_installed() -> bool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn helper_deletions_of_alto_data_are_refused() {
+        let store = dirs::home_dir().unwrap().join(".alto/context.json").to_string_lossy().to_string();
+        assert!(guard(&Command::DeletePath { path: store }).is_err());
+        assert!(guard(&Command::DeletePath { path: "/Library/Caches/com.example.app".to_string() }).is_ok());
+        assert!(guard(&Command::Ping).is_ok());
+    }
+}
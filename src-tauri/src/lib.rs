@@ -1,34 +1,93 @@
 mod scanners;
 pub mod helper_client;
 mod mcp;
+mod error;
 
-use scanners::{junk::scan_junk, large_files::scan_large_files, scheduler::Scheduler, system_stats::get_stats, watcher::start_watcher, ScanResult};
+use scanners::automation::ScriptOutcome;
+use scanners::{junk::{scan_junk, scan_junk_with_options, JunkScanOptions}, large_files::scan_large_files, scheduler::Scheduler, system_stats::get_stats, watcher::start_watcher, ScanResult};
 use tauri::{State, Manager, AppHandle, Emitter};
-use mcp::file_index::{index_file, index_files, IndexedFile, FileCategory};
-use mcp::context_store::ContextStore;
+use mcp::file_index::{index_file, index_files_measured, IndexedFile, FileCategory};
+use mcp::context_store::{path_is_excluded, ContextStore};
+use mcp::managed::ManagedPolicy;
 use tauri_plugin_positioner::{WindowExt, Position};
 use std::path::{Path, PathBuf};
+use error::AltoError;
 
 /// Canonicalize path and ensure it is under one of the allowed roots (e.g. home). Rejects path traversal.
-fn canonicalize_and_validate_path(path_str: &str, allowed_roots: &[PathBuf]) -> Result<PathBuf, String> {
+fn canonicalize_and_validate_path(path_str: &str, allowed_roots: &[PathBuf]) -> Result<PathBuf, AltoError> {
     let path = Path::new(path_str);
     if !path.exists() {
-        return Err("Path does not exist".to_string());
+        return Err(AltoError::not_found(path_str));
     }
-    let canonical = path.canonicalize().map_err(|e| e.to_string())?;
+    let canonical = path.canonicalize().map_err(|e| AltoError::io(path_str, &e))?;
     let allowed = allowed_roots.iter().any(|root| canonical.starts_with(root));
     if !allowed {
-        return Err("Path is outside allowed directories (e.g. home).".to_string());
+        return Err(AltoError::blocked("Path is outside allowed directories (e.g. home)."));
     }
     Ok(canonical)
 }
 
+/// `AppRunning` while the app bundle at `path` is open, so the UI can offer to quit it.
+fn ensure_app_quit(path: &str, action: &str) -> Result<(), AltoError> {
+    if scanners::process::is_bundle_running(path) {
+        let name = Path::new(path).file_stem().unwrap_or_default().to_string_lossy().to_string();
+        return Err(AltoError::app_running(name.clone(), format!("Please quit {} before {} it.", name, action)));
+    }
+    Ok(())
+}
+
+/// Where confirm_delete may remove things: home and, on macOS, /Applications, /Library, the temp
+/// folders and mounted external drives. The index rules still decide what's safe inside them.
+fn delete_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = dirs::home_dir().into_iter().map(|h| h.canonicalize().unwrap_or(h)).collect();
+    #[cfg(target_os = "macos")]
+    {
+        roots.extend(["/Applications", "/Library", "/private/tmp", "/private/var/folders"].map(PathBuf::from));
+        roots.extend(scanners::system_stats::external_volumes(false).into_iter().map(|vol| PathBuf::from(vol.mount_point)));
+    }
+    roots
+}
+
+/// Resolve a path to delete against `roots`. Its folder is canonicalized, so relative parts, `..` and
+/// symlinked folders can't reach outside them; the last component is kept as is, so a symlink is
+/// deleted itself rather than what it points to.
+fn resolve_delete_path(path: &str, roots: &[PathBuf]) -> Result<String, String> {
+    let path = Path::new(path.trim());
+    if !path.is_absolute() {
+        return Err("Path must be absolute".to_string());
+    }
+    let Some(std::path::Component::Normal(name)) = path.components().next_back() else {
+        return Err("Path does not name a file or folder".to_string());
+    };
+    std::fs::symlink_metadata(path).map_err(|_| "Path does not exist".to_string())?;
+    let parent = path.parent().ok_or("Path does not name a file or folder")?;
+    let parent = canonicalize_and_validate_path(&parent.to_string_lossy(), roots).map_err(|e| e.to_string())?;
+    Ok(parent.join(name).to_string_lossy().to_string())
+}
+
+/// Resolved paths (duplicates dropped) and the (path, reason) pairs that were rejected.
+fn resolve_delete_paths(paths: &[String]) -> (Vec<String>, Vec<(String, String)>) {
+    let roots = delete_roots();
+    let mut resolved: Vec<String> = Vec::new();
+    let mut rejected = Vec::new();
+    for path in paths {
+        match resolve_delete_path(path, &roots) {
+            Ok(p) if !resolved.contains(&p) => resolved.push(p),
+            Ok(_) => {}
+            Err(e) => rejected.push((path.clone(), e)),
+        }
+    }
+    (resolved, rejected)
+}
+
 #[derive(Clone, serde::Serialize)]
 struct DeepScanProgress {
     directory: String,
     files_found: usize,
     size_bytes: u64,
     percent: u8,
+    /// Estimated seconds left, from throughput of previous runs (None on a first run)
+    eta_secs: Option<u64>,
 }
 
 #[derive(Clone, serde::Serialize)]
@@ -39,106 +98,211 @@ struct DeepScanComplete {
     duration_secs: f64,
 }
 
-#[tauri::command]
-async fn start_deep_scan_command(app: AppHandle) -> Result<(), String> {
-    // Fire-and-forget: spawn background task and return immediately
-    tokio::spawn(async move {
-        let home = match dirs::home_dir() {
-            Some(h) => h,
-            None => return,
-        };
-        let start = std::time::Instant::now();
-
-        // Deep scan templates — no caps, much more comprehensive than quick scan
-        let deep_templates: &[(&str, &str)] = &[
-            ("Library/Caches", "System Caches"),
-            ("Library/Logs", "System Logs"),
-            ("Library/Application Support/Google/Chrome/Default/Cache", "Chrome Cache"),
-            ("Library/Application Support/BraveSoftware/Brave-Browser/Default/Cache", "Brave Cache"),
-            ("Library/Application Support/Firefox/Profiles", "Firefox Cache"),
-            ("Library/Application Support/Slack/Cache", "Slack Cache"),
-            ("Library/Application Support/Discord/Cache", "Discord Cache"),
-            ("Library/Application Support/Code/Cache", "VS Code Cache"),
-            ("Library/Application Support/Code/CachedData", "VS Code Cache"),
-            ("Library/Application Support/Spotify/PersistentCache", "Spotify Cache"),
-            ("Library/Developer/Xcode/DerivedData", "Xcode DerivedData"),
-            ("Library/Developer/Xcode/Archives", "Xcode Archives"),
-            ("Library/Developer/Xcode/iOS DeviceSupport", "Xcode Device Support"),
-            ("Library/Developer/CoreSimulator/Caches", "Simulator Caches"),
-            ("Library/Developer/CoreSimulator/Devices", "Simulator Devices"),
-            (".npm/_cacache", "NPM Cache"),
-            (".yarn/cache", "Yarn Cache"),
-            (".gradle/caches", "Gradle Cache"),
-            (".m2/repository", "Maven Cache"),
-            ("Library/Application Support/CrashReporter", "Crash Reports"),
-            ("Library/Saved Application State", "App Saved State"),
-            ("Downloads", "Downloads"),
-            (".Trash", "Trash"),
-        ];
-
-        let total = deep_templates.len();
-        let mut grand_total_files = 0usize;
-        let mut grand_total_bytes = 0u64;
-        let mut category_map: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
-
-        for (idx, (tpl, label)) in deep_templates.iter().enumerate() {
-            let path = home.join(tpl);
-            if !path.exists() {
-                continue;
-            }
+/// Deep scan progress persisted after each template so an interrupted scan can resume.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct DeepScanCheckpoint {
+    completed_templates: Vec<String>,
+    total_files: usize,
+    total_bytes: u64,
+    category_map: std::collections::HashMap<String, u64>,
+    elapsed_secs: f64,
+}
 
-            let percent = ((idx as f64 / total as f64) * 100.0) as u8;
-            let mut dir_files = 0usize;
-            let mut dir_bytes = 0u64;
-
-            // Walk with generous limits — this IS the deep scan
-            let walker = walkdir::WalkDir::new(&path)
-                .max_depth(20)
-                .into_iter();
-
-            for entry in walker.flatten() {
-                if entry.path().is_file() {
-                    if let Ok(meta) = entry.metadata() {
-                        let size = meta.len();
-                        dir_files += 1;
-                        dir_bytes += size;
-                    }
+const DEEP_SCAN_CHECKPOINT: &str = "deep_scan";
+
+/// Walk the deep scan templates, emitting progress events. Templates already listed in `resume` are skipped.
+fn run_deep_scan(app: &AppHandle, resume: Option<DeepScanCheckpoint>) {
+    let _priority = scanners::priority::lower_scan_priority();
+    let home = match dirs::home_dir() {
+        Some(h) => h,
+        None => return,
+    };
+    let start = std::time::Instant::now();
+
+    // Deep scan templates — no caps, much more comprehensive than quick scan
+    let deep_templates: &[(&str, &str)] = &[
+        ("Library/Caches", "System Caches"),
+        ("Library/Logs", "System Logs"),
+        ("Library/Application Support/Google/Chrome/Default/Cache", "Chrome Cache"),
+        ("Library/Application Support/BraveSoftware/Brave-Browser/Default/Cache", "Brave Cache"),
+        ("Library/Application Support/Firefox/Profiles", "Firefox Cache"),
+        ("Library/Application Support/Slack/Cache", "Slack Cache"),
+        ("Library/Application Support/Discord/Cache", "Discord Cache"),
+        ("Library/Application Support/Code/Cache", "VS Code Cache"),
+        ("Library/Application Support/Code/CachedData", "VS Code Cache"),
+        ("Library/Application Support/Spotify/PersistentCache", "Spotify Cache"),
+        ("Library/Developer/Xcode/DerivedData", "Xcode DerivedData"),
+        ("Library/Developer/Xcode/Archives", "Xcode Archives"),
+        ("Library/Developer/Xcode/iOS DeviceSupport", "Xcode Device Support"),
+        ("Library/Developer/CoreSimulator/Caches", "Simulator Caches"),
+        ("Library/Developer/CoreSimulator/Devices", "Simulator Devices"),
+        (".npm/_cacache", "NPM Cache"),
+        (".yarn/cache", "Yarn Cache"),
+        (".gradle/caches", "Gradle Cache"),
+        (".m2/repository", "Maven Cache"),
+        ("Library/Application Support/CrashReporter", "Crash Reports"),
+        ("Library/Saved Application State", "App Saved State"),
+        ("Downloads", "Downloads"),
+        (".Trash", "Trash"),
+    ];
+
+    let total = deep_templates.len();
+    let ctx = ContextStore::load();
+    let excluded = ctx.user_preferences.skipped_paths();
+    let expected: Vec<Option<f64>> = deep_templates.iter()
+        .map(|(tpl, _)| ctx.scan_throughput.get(&format!("{}/{}", DEEP_SCAN_CHECKPOINT, tpl)).and_then(|t| t.expected_secs()))
+        .collect();
+    let mut samples = Vec::new();
+    // Actual vs expected time for templates done so far; scales the remaining estimate to this machine's current speed
+    let (mut actual_so_far, mut expected_so_far) = (0.0f64, 0.0f64);
+    let mut state = resume.unwrap_or_default();
+    let previous_elapsed = state.elapsed_secs;
+
+    for (idx, (tpl, label)) in deep_templates.iter().enumerate() {
+        if state.completed_templates.iter().any(|t| t == tpl) {
+            continue;
+        }
+        let path = home.join(tpl);
+        if !path.exists() {
+            continue;
+        }
+
+        let percent = ((idx as f64 / total as f64) * 100.0) as u8;
+        let mut dir_files = 0usize;
+        let mut dir_bytes = 0u64;
+        let template_start = std::time::Instant::now();
+
+        // Walk with generous limits — this IS the deep scan
+        let walker = walkdir::WalkDir::new(&path)
+            .max_depth(20)
+            .into_iter()
+            .filter_entry(|e| !path_is_excluded(&e.path().to_string_lossy(), &excluded));
+
+        for entry in walker.flatten() {
+            if entry.path().is_file() {
+                if let Ok(meta) = entry.metadata() {
+                    let size = meta.len();
+                    dir_files += 1;
+                    dir_bytes += size;
                 }
             }
+        }
 
-            grand_total_files += dir_files;
-            grand_total_bytes += dir_bytes;
-            *category_map.entry(label.to_string()).or_insert(0) += dir_bytes;
-
-            // Emit progress event to frontend
-            let _ = app.emit("deep-scan-progress", DeepScanProgress {
-                directory: label.to_string(),
-                files_found: dir_files,
-                size_bytes: dir_bytes,
-                percent,
-            });
+        state.total_files += dir_files;
+        state.total_bytes += dir_bytes;
+        *state.category_map.entry(label.to_string()).or_insert(0) += dir_bytes;
+        state.completed_templates.push(tpl.to_string());
+        state.elapsed_secs = previous_elapsed + start.elapsed().as_secs_f64();
+        scanners::checkpoint::save(DEEP_SCAN_CHECKPOINT, &state);
+
+        let template_secs = template_start.elapsed().as_secs_f64();
+        samples.push((format!("{}/{}", DEEP_SCAN_CHECKPOINT, tpl), dir_files as u64, dir_bytes, template_secs));
+        if let Some(exp) = expected[idx] {
+            actual_so_far += template_secs;
+            expected_so_far += exp;
         }
+        let remaining: Vec<f64> = expected[idx + 1..].iter().flatten().copied().collect();
+        let eta_secs = if remaining.is_empty() && idx + 1 < total {
+            None
+        } else {
+            let speed = if expected_so_far > 0.0 { actual_so_far / expected_so_far } else { 1.0 };
+            Some((remaining.iter().sum::<f64>() * speed).round() as u64)
+        };
 
-        // Sort categories by size for the summary
-        let mut top_categories: Vec<(String, u64)> = category_map.into_iter().collect();
-        top_categories.sort_by(|a, b| b.1.cmp(&a.1));
-        top_categories.truncate(8);
+        // Emit progress event to frontend
+        let _ = app.emit("deep-scan-progress", DeepScanProgress {
+            directory: label.to_string(),
+            files_found: dir_files,
+            size_bytes: dir_bytes,
+            percent,
+            eta_secs,
+        });
+    }
 
-        let duration = start.elapsed().as_secs_f64();
+    // Cache developer/trash totals for the storage map
+    let mut developer_bytes = 0u64;
+    let mut trash_bytes = 0u64;
+    for (label, bytes) in &state.category_map {
+        match scanners::storage::segment_for_deep_scan_label(label) {
+            Some("Developer") => developer_bytes += bytes,
+            Some("Trash") => trash_bytes += bytes,
+            _ => {}
+        }
+    }
+    let mut ctx = ContextStore::load();
+    ctx.record_storage_segments(&[("Developer", developer_bytes), ("Trash", trash_bytes)]);
+    ctx.record_throughput(&samples);
+
+    // Sort categories by size for the summary
+    let mut top_categories: Vec<(String, u64)> = state.category_map.into_iter().collect();
+    top_categories.sort_by_key(|c| std::cmp::Reverse(c.1));
+    top_categories.truncate(8);
+
+    let duration = previous_elapsed + start.elapsed().as_secs_f64();
+    scanners::checkpoint::clear(DEEP_SCAN_CHECKPOINT);
+
+    let _ = app.emit("deep-scan-complete", DeepScanComplete {
+        total_files: state.total_files,
+        total_size_bytes: state.total_bytes,
+        top_categories,
+        duration_secs: duration,
+    });
+}
 
-        let _ = app.emit("deep-scan-complete", DeepScanComplete {
-            total_files: grand_total_files,
-            total_size_bytes: grand_total_bytes,
-            top_categories,
-            duration_secs: duration,
-        });
+#[tauri::command]
+async fn start_deep_scan_command(app: AppHandle) -> Result<(), AltoError> {
+    // Fire-and-forget: spawn background task and return immediately
+    tokio::spawn(async move {
+        run_deep_scan(&app, None);
     });
 
     Ok(())
 }
 
+/// Scans that were checkpointed but never finished (app crashed or was quit mid-scan).
+#[tauri::command]
+async fn get_interrupted_scans_command() -> Vec<scanners::checkpoint::InterruptedScan> {
+    scanners::checkpoint::list_interrupted()
+}
+
+/// Continue an interrupted scan from its checkpoint. Deep scans resume in the background
+/// (same events as start_deep_scan_command); large-file scans return their ScanResult.
+#[tauri::command]
+async fn resume_interrupted_scan_command(app: AppHandle, scan_type: String) -> Result<serde_json::Value, AltoError> {
+    match scan_type.as_str() {
+        DEEP_SCAN_CHECKPOINT => {
+            let resume = scanners::checkpoint::load::<DeepScanCheckpoint>(DEEP_SCAN_CHECKPOINT)
+                .ok_or("No interrupted deep scan to resume")?;
+            tokio::spawn(async move {
+                run_deep_scan(&app, Some(resume));
+            });
+            Ok(serde_json::json!({ "resumed": scan_type }))
+        }
+        scanners::large_files::CHECKPOINT_NAME => {
+            let resume = scanners::checkpoint::load::<scanners::large_files::LargeFilesCheckpoint>(&scan_type)
+                .ok_or("No interrupted large file scan to resume")?;
+            let home = dirs::home_dir().ok_or("No home directory")?;
+            let home_str = home.to_string_lossy().to_string();
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                scanners::large_files::scan_large_files_resumable(&home_str, Some(resume))
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+            serde_json::to_value(&result).map_err(AltoError::from)
+        }
+        _ => Err(AltoError::invalid(format!("Unknown scan type: {}", scan_type))),
+    }
+}
+
+/// Drop a checkpoint the user chose not to resume.
+#[tauri::command]
+async fn discard_interrupted_scan_command(scan_type: String) -> Result<(), AltoError> {
+    scanners::checkpoint::clear(&scan_type);
+    Ok(())
+}
+
 #[tauri::command]
-async fn cancel_deep_scan_command() -> Result<(), String> {
+async fn cancel_deep_scan_command() -> Result<(), AltoError> {
     // For now, the background task will finish naturally.
     // A real cancel would use a shared AtomicBool / channel.
     Ok(())
@@ -147,28 +311,32 @@ async fn cancel_deep_scan_command() -> Result<(), String> {
 
 /// MCP: Return the full context store so the frontend/AI can use it
 #[tauri::command]
-async fn get_mcp_context() -> Result<serde_json::Value, String> {
+async fn get_mcp_context() -> Result<serde_json::Value, AltoError> {
     println!("[Backend] get_mcp_context called");
     let ctx = ContextStore::load();
-    serde_json::to_value(&ctx).map_err(|e| e.to_string())
+    serde_json::to_value(&ctx).map_err(AltoError::from)
 }
 
 #[tauri::command]
-async fn reset_mcp_context_command() -> Result<serde_json::Value, String> {
+async fn reset_mcp_context_command() -> Result<serde_json::Value, AltoError> {
     let mut ctx = ContextStore::load();
     ctx.clear();
-    serde_json::to_value(&ctx).map_err(|e| e.to_string())
+    serde_json::to_value(&ctx).map_err(AltoError::from)
 }
 
 #[tauri::command]
-async fn update_user_preferences_command(prefs: mcp::context_store::UserPrefs) -> Result<(), String> {
+async fn update_user_preferences_command(prefs: mcp::context_store::UserPrefs) -> Result<(), AltoError> {
+    let invalid = mcp::whitelist::invalid_patterns(&prefs.always_skip_patterns);
+    if !invalid.is_empty() {
+        return Err(AltoError::invalid(format!("Invalid skip pattern: {}", invalid.join(", "))));
+    }
     let mut ctx = ContextStore::load();
     ctx.update_user_preferences(prefs);
     Ok(())
 }
 
 #[tauri::command]
-async fn get_mcp_status() -> Result<serde_json::Value, String> {
+async fn get_mcp_status() -> Result<serde_json::Value, AltoError> {
     // In a real app, we might check if the watcher thread is alive
     // For now, we'll return based on whether the store can be loaded
     let store_exists = ContextStore::store_path().exists();
@@ -181,32 +349,155 @@ async fn get_mcp_status() -> Result<serde_json::Value, String> {
 
 struct AppState {
     scheduler: Scheduler,
+    smart_scan_cache: std::sync::Mutex<Option<CachedSmartScan>>,
 }
 
-#[derive(serde::Serialize)]
+/// Last smart scan result, reused while younger than the configured TTL.
+struct CachedSmartScan {
+    at: std::time::Instant,
+    modules: Vec<String>,
+    result: SmartScanResult,
+}
+
+/// Sub-scanners that make up a smart scan, in the order they are reported.
+const SMART_SCAN_MODULES: &[&str] = &["junk", "large_files", "malware"];
+
+/// Modules that were not selected are `None`.
+#[derive(Clone, serde::Serialize)]
 struct SmartScanResult {
-    junk: ScanResult,
-    large_files: ScanResult,
-    malware: scanners::malware::MalwareResult,
+    junk: Option<ScanResult>,
+    large_files: Option<ScanResult>,
+    malware: Option<scanners::malware::MalwareResult>,
+    scanned_at: String,
+    from_cache: bool,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct SmartScanStage {
+    stage: String,   // "junk" | "large_files" | "malware"
+    status: String,  // "started" | "finished"
+    percent: u8,     // overall progress: share of selected sub-scanners finished
+    items_found: usize,
+    size_bytes: u64,
+}
+
+/// Emit a `smart-scan-stage` event. `percent` reflects how many sub-scanners have finished.
+fn emit_smart_scan_stage(app: &AppHandle, stage: &str, status: &str, percent: u8, items_found: usize, size_bytes: u64) {
+    let _ = app.emit("smart-scan-stage", SmartScanStage {
+        stage: stage.to_string(),
+        status: status.to_string(),
+        percent,
+        items_found,
+        size_bytes,
+    });
+}
+
+/// Await an optional blocking task; `None` means the module was not selected.
+async fn join_module<T>(task: Option<tokio::task::JoinHandle<T>>) -> Result<Option<T>, String> {
+    match task {
+        Some(handle) => handle.await.map(Some).map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
 }
 
+/// Junk scan options for a named scan profile (defaults when no profile is given).
+fn junk_options_for_profile(profile: Option<&str>) -> Result<JunkScanOptions, String> {
+    match profile {
+        Some(name) => {
+            let ctx = ContextStore::load();
+            let p = ctx.scan_profile(name).ok_or_else(|| format!("Unknown scan profile: {}", name))?;
+            Ok(JunkScanOptions::from_profile(p))
+        }
+        None => Ok(JunkScanOptions::default()),
+    }
+}
+
+/// Run a smart scan. `modules` picks sub-scanners ("junk", "large_files", "malware"); all run when omitted.
+/// A cached result for the same modules is returned if younger than the TTL in UserPrefs, unless `force` is set.
+/// `profile` names a scan profile whose scopes/caps apply to the junk scan.
 #[tauri::command]
-async fn smart_scan_command() -> Result<SmartScanResult, String> {
+async fn smart_scan_command(app: AppHandle, state: State<'_, AppState>, modules: Option<Vec<String>>, force: Option<bool>, profile: Option<String>) -> Result<SmartScanResult, AltoError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
     let home = dirs::home_dir().ok_or("No home directory")?;
     let home_str = home.to_string_lossy().to_string();
-    let (junk, large_files, malware) = tokio::task::spawn_blocking(move || {
-        let junk = scan_junk(&home_str);
-        let large = scan_large_files(&home_str);
-        let malware = scanners::malware::scan_malware();
-        (junk, large, malware)
-    })
-    .await
-    .map_err(|e| e.to_string())?;
-    Ok(SmartScanResult {
-        junk,
-        large_files,
-        malware,
-    })
+
+    let selected: Vec<String> = match modules {
+        Some(m) if !m.is_empty() => m,
+        _ => SMART_SCAN_MODULES.iter().map(|m| m.to_string()).collect(),
+    };
+    if let Some(unknown) = selected.iter().find(|m| !SMART_SCAN_MODULES.contains(&m.as_str())) {
+        return Err(AltoError::invalid(format!("Unknown smart scan module: {}", unknown)));
+    }
+    let wants = |module: &str| selected.iter().any(|m| m == module);
+    let junk_options = junk_options_for_profile(profile.as_deref())?;
+    let mut cache_key: Vec<String> = selected.clone();
+    cache_key.sort();
+    cache_key.dedup();
+    if let Some(p) = &profile {
+        cache_key.push(format!("profile:{}", p));
+    }
+
+    let ttl = std::time::Duration::from_secs(ContextStore::load().user_preferences.smart_scan_cache_ttl_secs);
+    if !force.unwrap_or(false) && !ttl.is_zero() {
+        if let Some(cached) = state.smart_scan_cache.lock().unwrap().as_ref() {
+            if cached.modules == cache_key && cached.at.elapsed() < ttl {
+                return Ok(SmartScanResult { from_cache: true, ..cached.result.clone() });
+            }
+        }
+    }
+
+    // Sub-scanners are independent, so each gets its own blocking thread and they run concurrently.
+    let stages = SMART_SCAN_MODULES.iter().filter(|m| wants(m)).count();
+    let finished = Arc::new(AtomicUsize::new(0));
+    let finish_percent = move |finished: &AtomicUsize| ((finished.fetch_add(1, Ordering::SeqCst) + 1) * 100 / stages) as u8;
+
+    let junk_task = wants("junk").then(|| {
+        let (app, home_str, finished) = (app.clone(), home_str.clone(), finished.clone());
+        tokio::task::spawn_blocking(move || {
+            emit_smart_scan_stage(&app, "junk", "started", 0, 0, 0);
+            let junk = scan_junk_with_options(&home_str, &junk_options);
+            emit_smart_scan_stage(&app, "junk", "finished", finish_percent(&finished), junk.items.len(), junk.total_size_bytes);
+            junk
+        })
+    });
+    let large_task = wants("large_files").then(|| {
+        let (app, home_str, finished) = (app.clone(), home_str.clone(), finished.clone());
+        tokio::task::spawn_blocking(move || {
+            emit_smart_scan_stage(&app, "large_files", "started", 0, 0, 0);
+            let large = scan_large_files(&home_str);
+            emit_smart_scan_stage(&app, "large_files", "finished", finish_percent(&finished), large.items.len(), large.total_size_bytes);
+            large
+        })
+    });
+    let malware_task = wants("malware").then(|| {
+        let (app, finished) = (app.clone(), finished.clone());
+        tokio::task::spawn_blocking(move || {
+            emit_smart_scan_stage(&app, "malware", "started", 0, 0, 0);
+            let malware = scanners::malware::scan_malware();
+            emit_smart_scan_stage(&app, "malware", "finished", finish_percent(&finished), malware.threats_found.len(), 0);
+            malware
+        })
+    });
+
+    let (junk, large_files, malware) = tokio::join!(join_module(junk_task), join_module(large_task), join_module(malware_task));
+    let result = SmartScanResult {
+        junk: junk?,
+        large_files: large_files?,
+        malware: malware?,
+        scanned_at: chrono::Local::now().to_rfc3339(),
+        from_cache: false,
+    };
+    if let Some(junk) = &result.junk {
+        record_junk_trend(junk);
+    }
+    *state.smart_scan_cache.lock().unwrap() = Some(CachedSmartScan {
+        at: std::time::Instant::now(),
+        modules: cache_key,
+        result: result.clone(),
+    });
+    Ok(result)
 }
 
 #[tauri::command]
@@ -215,15 +506,15 @@ async fn get_system_stats_command() -> scanners::system_stats::SystemStats {
 }
 
 #[tauri::command]
-async fn get_home_dir_command() -> Result<String, String> {
+async fn get_home_dir_command() -> Result<String, AltoError> {
     dirs::home_dir()
         .map(|p| p.to_string_lossy().to_string())
-        .ok_or_else(|| "No home directory".to_string())
+        .ok_or_else(|| AltoError::from("No home directory"))
 }
 
 #[cfg(target_os = "macos")]
 #[tauri::command]
-async fn open_full_disk_access_settings_command() -> Result<(), String> {
+async fn open_full_disk_access_settings_command() -> Result<(), AltoError> {
     std::process::Command::new("open")
         .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_AllFiles")
         .spawn()
@@ -233,245 +524,1483 @@ async fn open_full_disk_access_settings_command() -> Result<(), String> {
 
 #[cfg(not(target_os = "macos"))]
 #[tauri::command]
-async fn open_full_disk_access_settings_command() -> Result<(), String> {
+async fn open_full_disk_access_settings_command() -> Result<(), AltoError> {
     Ok(())
 }
 
+/// `group_by_directory` reports per-app cache folders as single items so they can be trashed in one go.
 #[tauri::command]
-async fn scan_junk_command() -> Result<ScanResult, String> {
+async fn scan_junk_command(profile: Option<String>, group_by_directory: Option<bool>) -> Result<ScanResult, AltoError> {
     let home = dirs::home_dir().ok_or("No home directory")?;
     let home_str = home.to_string_lossy();
+    let group_by_directory = group_by_directory.unwrap_or(false);
+    if profile.is_none() && !group_by_directory {
+        let result = scan_junk(&home_str);
+        record_junk_trend(&result);
+        return Ok(result);
+    }
+    let mut options = junk_options_for_profile(profile.as_deref())?;
+    options.group_by_directory = group_by_directory;
     // Perform scan in a blocking task to ensure it doesn't block the async runtime if it were to stay on the same thread (though tauri handles async commands on separate threads, explicit spawn_blocking is safer for heavy IO)
     // Actually, simple async fn in tauri is enough to unblock the main thread.
-    Ok(scan_junk(&home_str))
+    let result = scan_junk_with_options(&home_str, &options);
+    // Profile scans only cover part of the disk and would make the trend line jump
+    if profile.is_none() {
+        record_junk_trend(&result);
+    }
+    Ok(result)
 }
 
+/// Re-scan one junk category (e.g. "Xcode Data") and clean everything in it through confirm_delete,
+/// so the frontend confirms once instead of sending back every path. `dry_run` only reports.
 #[tauri::command]
-async fn scan_large_files_command() -> Result<ScanResult, String> {
+async fn clean_category_command(app: AppHandle, category_name: String, dry_run: Option<bool>, state: State<'_, AppState>) -> Result<serde_json::Value, AltoError> {
     let home = dirs::home_dir().ok_or("No home directory")?;
-    let home_str = home.to_string_lossy().to_string();
-    let result = tauri::async_runtime::spawn_blocking(move || scan_large_files(&home_str))
+    let options = JunkScanOptions {
+        only_categories: vec![category_name.clone()],
+        // Per-app cache folders go as one item each rather than thousands of files
+        group_by_directory: true,
+        ..Default::default()
+    };
+    let result = tauri::async_runtime::spawn_blocking(move || scan_junk_with_options(&home.to_string_lossy(), &options))
         .await
         .map_err(|e| e.to_string())?;
-    Ok(result)
+    let paths: Vec<String> = result.items.into_iter().map(|i| i.path).collect();
+    if paths.is_empty() {
+        return Err(format!("Nothing to clean in {}", category_name).into());
+    }
+    confirm_delete(app, paths, dry_run, state).await
 }
 
-#[tauri::command]
-async fn scan_space_lens_command(path: Option<String>, depth: Option<u32>) -> Result<scanners::space_lens::FileNode, String> {
-    let home = dirs::home_dir().ok_or("No home directory")?;
-    // Build in block so Windows build does not need mut on outer binding (macOS pushes extra roots).
-    let allowed_roots: Vec<PathBuf> = {
-        let mut v = vec![home.clone()];
-        #[cfg(target_os = "macos")]
-        {
-            v.push(PathBuf::from("/Applications"));
-            v.push(PathBuf::from("/Library"));
-        }
-        v
-    };
-    let target_path = if let Some(p) = path {
-        let p = p.trim();
-        if p.is_empty() {
-            home.to_string_lossy().to_string()
-        } else {
-            let canonical = canonicalize_and_validate_path(p, &allowed_roots)?;
-            canonical.to_string_lossy().to_string()
-        }
-    } else {
-        home.to_string_lossy().to_string()
-    };
-    let depth_limit = depth.unwrap_or(4).min(8);
-
-    Ok(scanners::space_lens::scan_space_lens(&target_path, depth_limit))
+/// Add the scan's per-category junk totals and current free space to the trend history.
+fn record_junk_trend(junk: &ScanResult) {
+    let mut by_category: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
+    for item in &junk.items {
+        *by_category.entry(item.category_name.clone()).or_insert(0) += item.size_bytes;
+    }
+    let (total, used) = scanners::system_stats::root_disk_usage();
+    ContextStore::load().record_scan_snapshot(by_category, total.saturating_sub(used));
 }
 
+/// Trend series (junk per category, free space, installed apps) for "week", "month", "quarter", "year" or "all".
 #[tauri::command]
-async fn scan_malware_command() -> Result<scanners::malware::MalwareResult, String> {
-    Ok(scanners::malware::scan_malware())
+async fn get_trends_command(range: Option<String>) -> Result<mcp::trends::Trends, AltoError> {
+    mcp::trends::trends(range.as_deref().unwrap_or("month")).map_err(AltoError::from)
 }
 
 #[tauri::command]
-async fn run_speed_task_command(task_id: String) -> Result<scanners::speed::SpeedTaskResult, String> {
-    Ok(scanners::speed::run_optimization_task(&task_id))
+async fn list_scan_profiles_command() -> Vec<mcp::context_store::ScanProfile> {
+    ContextStore::load().user_preferences.scan_profiles
 }
 
-/// MCP Phase 1: Preview what would be deleted — NEVER deletes anything.
-/// Returns an indexed list of files with safety flags.
+/// Create or replace a scan profile (matched by name).
 #[tauri::command]
-async fn preview_delete(paths: Vec<String>) -> Result<Vec<IndexedFile>, String> {
-    Ok(index_files(&paths))
+async fn save_scan_profile_command(profile: mcp::context_store::ScanProfile) -> Result<(), AltoError> {
+    if profile.name.trim().is_empty() {
+        return Err(AltoError::invalid("Profile name cannot be empty"));
+    }
+    ContextStore::load().save_scan_profile(profile);
+    Ok(())
 }
 
-/// MCP Phase 2: Confirm and execute deletion — only called after user approves.
-/// Logs the deletion to the context store for history.
+/// Sort ~/Downloads with the user's rules and list stale downloads; `dry_run` only reports the plan.
 #[tauri::command]
-async fn confirm_delete(paths: Vec<String>) -> Result<serde_json::Value, String> {
-    // Only delete files that are safe according to the indexer
-    let indexed = index_files(&paths);
-    let safe_paths: Vec<String> = indexed.iter()
-        .filter(|f| f.is_safe_to_delete)
-        .map(|f| f.path.clone())
-        .collect();
-    let blocked: Vec<String> = indexed.iter()
-        .filter(|f| !f.is_safe_to_delete)
-        .map(|f| f.path.clone())
-        .collect();
-
-    if safe_paths.is_empty() {
-        return Ok(serde_json::json!({
-            "removed": 0,
-            "blocked": blocked,
-            "errors": ["No safe files to delete after safety check."]
-        }));
-    }
-
-    let path_refs: Vec<&str> = safe_paths.iter().map(|s| s.as_str()).collect();
-    let total_bytes: u64 = indexed.iter().filter(|f| f.is_safe_to_delete).map(|f| f.size_bytes).sum();
-
-    match trash::delete_all(&path_refs) {
-        Ok(_) => {
-            let mut ctx = ContextStore::load();
-            ctx.record_deletion(safe_paths.clone(), total_bytes);
-            Ok(serde_json::json!({
-                "removed": safe_paths.len(),
-                "bytes_freed": total_bytes,
-                "blocked": blocked,
-                "errors": []
-            }))
-        },
-        Err(e) => Err(format!("Delete failed: {}", e)),
-    }
+async fn organize_downloads_command(dry_run: Option<bool>) -> Result<scanners::downloads::OrganizeResult, AltoError> {
+    // Planned moves are checked against the administrator's exclusions one by one
+    let dry_run = mcp::dry_run::enabled(dry_run);
+    tauri::async_runtime::spawn_blocking(move || scanners::downloads::organize_downloads(dry_run))
+        .await
+        .map_err(AltoError::from)
 }
 
-/// Legacy command — kept for compatibility but now routes through safety layer.
 #[tauri::command]
-async fn clean_items(paths: Vec<String>) -> Result<serde_json::Value, String> {
-    // Route through the safe confirm_delete
-    confirm_delete(paths).await
+async fn get_download_rules_command() -> mcp::context_store::DownloadsPolicy {
+    ContextStore::load().user_preferences.downloads
 }
 
+/// Replace the Downloads organizer rules (and the auto-organize switch).
 #[tauri::command]
-async fn schedule_task(cron: String, task_type: String, state: State<'_, AppState>) -> Result<String, String> {
-    Ok(state.scheduler.add_job(cron, task_type))
+async fn save_download_rules_command(policy: mcp::context_store::DownloadsPolicy) -> Result<(), AltoError> {
+    if policy.rules.iter().any(|r| r.name.trim().is_empty() || r.extensions.is_empty()) {
+        return Err(AltoError::invalid("Each rule needs a name and at least one extension"));
+    }
+    ContextStore::load().set_download_rules(policy);
+    Ok(())
 }
 
 #[tauri::command]
-async fn scan_apps_command() -> Vec<scanners::uninstaller::AppInfo> {
-    scanners::uninstaller::scan_apps()
+async fn delete_scan_profile_command(name: String) -> Result<(), AltoError> {
+    ContextStore::load().delete_scan_profile(&name);
+    Ok(())
 }
 
+/// Runs the large file scan, emitting "large-files-progress" events with the largest files so far
+/// (and an ETA once a previous run is known).
+/// `query` narrows it by minimum size, folders, file types and last-opened age.
 #[tauri::command]
-async fn uninstall_app_command(path: String) -> Result<(), String> {
-    scanners::uninstaller::uninstall_app(&path).await
+async fn scan_large_files_command(app: AppHandle, query: Option<scanners::large_files::LargeFilesQuery>) -> Result<ScanResult, AltoError> {
+    let home = dirs::home_dir().ok_or("No home directory")?;
+    let home_str = home.to_string_lossy().to_string();
+    let query = query.unwrap_or_default();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        scanners::large_files::scan_large_files_with_progress(&home_str, &query, None, &|progress| {
+            let _ = app.emit("large-files-progress", progress);
+        })
+    })
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result)
 }
 
+/// Big files not opened for 6/12/24 months, grouped by folder with move/compress/trash suggestions.
 #[tauri::command]
-async fn scan_leftovers_command(_id: String) -> scanners::uninstaller::LeftoverGroups {
-    #[cfg(target_os = "macos")]
-    return scanners::uninstaller::scan_leftovers(&_id);
-    #[cfg(not(target_os = "macos"))]
-    return scanners::uninstaller::LeftoverGroups::default();
+async fn scan_old_files_command(query: Option<scanners::old_files::OldFilesQuery>) -> Result<scanners::old_files::OldFilesReport, AltoError> {
+    let query = query.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || scanners::old_files::scan_old_files(&query))
+        .await
+        .map_err(AltoError::from)
 }
 
+/// Folders over 500 MB untouched for a year, with the space compressing each would save.
 #[tauri::command]
-async fn scan_outdated_apps_command() -> Vec<scanners::updater::OutdatedApp> {
-    scanners::updater::scan_outdated_apps()
+async fn find_compressible_folders_command() -> Result<scanners::compression::CompressionReport, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::compression::find_compressible_folders)
+        .await
+        .map_err(AltoError::from)
 }
 
+/// Archive a folder in place (zip or tar.zst), verify it and move the original to the Trash,
+/// emitting "compress-progress" events.
 #[tauri::command]
-async fn move_paths_command(paths: Vec<String>, destination: String) -> Result<serde_json::Value, String> {
-    let dest = PathBuf::from(&destination);
-    if !dest.is_dir() {
-        return Err("Destination is not a directory".to_string());
-    }
-    let mut moved = 0usize;
-    let mut errors = Vec::<String>::new();
-    for path_str in &paths {
-        let src = Path::new(path_str);
-        if !src.exists() {
-            errors.push(format!("Not found: {}", path_str));
-            continue;
-        }
-        let name = src.file_name().and_then(|n| n.to_str()).unwrap_or("file");
-        let dest_path = dest.join(name);
-        if std::fs::rename(src, &dest_path).is_ok() {
-            moved += 1;
-        } else if std::fs::copy(src, &dest_path).is_ok() {
-            if trash::delete(path_str).is_ok() {
-                moved += 1;
-            } else {
-                errors.push(format!("Moved copy but could not remove original: {}", path_str));
-            }
-        } else {
-            errors.push(format!("Failed to move: {}", path_str));
-        }
+async fn compress_folder_command(
+    app: AppHandle,
+    path: String,
+    format: Option<scanners::compression::ArchiveFormat>,
+) -> Result<scanners::compression::CompressResult, AltoError> {
+    let home = dirs::home_dir().ok_or_else(|| AltoError::from("Could not find home directory"))?;
+    let canonical = canonicalize_and_validate_path(path.trim(), std::slice::from_ref(&home))?;
+    if canonical == home {
+        return Err(AltoError::invalid("Alto won't archive your whole home folder"));
+    }
+    let path_str = canonical.to_string_lossy().to_string();
+    ManagedPolicy::load().check_destructive("Compress", std::slice::from_ref(&path_str)).map_err(AltoError::blocked)?;
+    mcp::self_protect::check("Compress", &path_str).map_err(AltoError::blocked)?;
+    ContextStore::load().check_protected("Compress", std::slice::from_ref(&path_str)).map_err(AltoError::blocked)?;
+    if ContextStore::load().is_excluded(&path_str) {
+        return Err(AltoError::blocked(format!("{} is in your excluded paths", path_str)));
     }
-    Ok(serde_json::json!({ "moved": moved, "errors": errors }))
+    tauri::async_runtime::spawn_blocking(move || {
+        scanners::compression::compress_folder(&canonical, format.unwrap_or_default(), &|progress| {
+            let _ = app.emit("compress-progress", progress);
+        })
+    })
+    .await?
+    .map_err(AltoError::from)
 }
 
+/// Stop a running large file scan; it returns its partial result as truncated.
 #[tauri::command]
-async fn shred_path_command(path: String) -> Result<(), String> {
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
-    let allowed_roots = vec![home.clone()];
-    let canonical = canonicalize_and_validate_path(path.trim(), &allowed_roots)?;
-    let path_str = canonical.to_string_lossy().to_string();
+async fn cancel_large_files_scan_command() -> Result<(), AltoError> {
+    scanners::large_files::cancel_scan();
+    Ok(())
+}
 
-    let indexed = index_file(&path_str);
-    if !indexed.is_safe_to_delete {
-        return Err(format!(
-            "Shredder blocked: {}. Alto will not shred system or user data.",
-            indexed.reason
-        ));
-    }
-    if matches!(indexed.category, FileCategory::SystemCritical | FileCategory::UserData) {
-        return Err(format!(
-            "Shredder blocked: {} (category: {:?})",
-            indexed.reason, indexed.category
-        ));
-    }
+/// Space Lens on an external disk is allowed for these mounts; network shares only with `include_network`.
+#[tauri::command]
+async fn list_external_volumes_command(include_network: Option<bool>) -> Vec<scanners::system_stats::ExternalVolume> {
+    scanners::system_stats::external_volumes(include_network.unwrap_or(false))
+}
 
-    scanners::shredder::shred_path(&path_str)
+#[tauri::command]
+async fn scan_space_lens_command(path: Option<String>, depth: Option<u32>, include_network: Option<bool>) -> Result<scanners::space_lens::FileNode, AltoError> {
+    build_space_lens_tree(path, depth, include_network.unwrap_or(false))
 }
 
+/// Same scan as `scan_space_lens_command`, returned as a gzip-compressed `CompactTree` JSON body
+/// (parent-id scheme, no repeated paths) to keep big trees small on the IPC bridge.
 #[tauri::command]
-async fn scan_mail_command() -> Vec<scanners::mail::MailAttachment> {
-    scanners::mail::scan_mail_attachments()
+async fn scan_space_lens_compact_command(path: Option<String>, depth: Option<u32>, include_network: Option<bool>) -> Result<tauri::ipc::Response, AltoError> {
+    use std::io::Write;
+    let tree = build_space_lens_tree(path, depth, include_network.unwrap_or(false))?;
+    let json = serde_json::to_vec(&scanners::space_lens::compact_tree(&tree)).map_err(|e| e.to_string())?;
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder.write_all(&json).map_err(|e| e.to_string())?;
+    Ok(tauri::ipc::Response::new(encoder.finish().map_err(|e| e.to_string())?))
 }
 
+/// Children of one Space Lens folder, reusing sizes from earlier scans, so drilling in doesn't re-walk the disk.
 #[tauri::command]
-async fn clean_mail_command(paths: Vec<String>) -> Result<(), String> {
-    scanners::mail::clean_mail_attachments(paths)
+async fn space_lens_expand_node(path: String, include_network: Option<bool>) -> Result<scanners::space_lens::FileNode, AltoError> {
+    let target = space_lens_target(Some(path), include_network.unwrap_or(false))?;
+    tauri::async_runtime::spawn_blocking(move || scanners::space_lens::expand_node(&target))
+        .await
+        .map_err(AltoError::from)
 }
 
+/// Daily snapshots of home folder sizes, oldest first.
 #[tauri::command]
-async fn scan_extensions_command() -> Vec<scanners::extensions::ExtensionItem> {
-    scanners::extensions::scan_extensions()
+async fn list_space_lens_snapshots_command() -> Vec<scanners::space_lens_history::SnapshotInfo> {
+    scanners::space_lens_history::list_snapshots()
 }
 
+/// Folders that grew or shrank between two snapshots ("What ate 40 GB since last week?").
+/// Without `to`, compares against the current sizes.
 #[tauri::command]
-async fn remove_extension_command(path: String) -> Result<(), String> {
-    scanners::extensions::remove_extension(path).await
+async fn space_lens_diff_command(from: String, to: Option<String>) -> Result<scanners::space_lens_history::SpaceLensDiff, AltoError> {
+    tauri::async_runtime::spawn_blocking(move || scanners::space_lens_history::diff(&from, to.as_deref()))
+        .await?
+        .map_err(AltoError::from)
+}
+
+/// Folder a Space Lens request may look at: the given path inside an allowed root (home, system
+/// folders, external volumes), or home.
+fn space_lens_target(path: Option<String>, include_network: bool) -> Result<String, AltoError> {
+    let home = dirs::home_dir().ok_or_else(|| AltoError::from("No home directory"))?;
+    // Build in block so Windows build does not need mut on outer binding (macOS pushes extra roots).
+    let allowed_roots: Vec<PathBuf> = {
+        let mut v = vec![home.clone()];
+        #[cfg(target_os = "macos")]
+        {
+            v.push(PathBuf::from("/Applications"));
+            v.push(PathBuf::from("/Library"));
+        }
+        v.extend(scanners::system_stats::external_volumes(include_network).into_iter().map(|vol| PathBuf::from(vol.mount_point)));
+        v
+    };
+    let target_path = if let Some(p) = path {
+        let p = p.trim();
+        if p.is_empty() {
+            home.to_string_lossy().to_string()
+        } else {
+            let canonical = canonicalize_and_validate_path(p, &allowed_roots)?;
+            canonical.to_string_lossy().to_string()
+        }
+    } else {
+        home.to_string_lossy().to_string()
+    };
+    Ok(target_path)
+}
+
+fn build_space_lens_tree(path: Option<String>, depth: Option<u32>, include_network: bool) -> Result<scanners::space_lens::FileNode, AltoError> {
+    let home = dirs::home_dir().ok_or_else(|| AltoError::from("No home directory"))?;
+    let target_path = space_lens_target(path, include_network)?;
+    let depth_limit = depth.unwrap_or(4).min(8);
+
+    let tree = scanners::space_lens::scan_space_lens(&target_path, depth_limit);
+    scanners::size_index::save();
+    if Path::new(&target_path) == home {
+        if let Some(documents) = scanners::storage::documents_size_from_home_tree(&tree) {
+            ContextStore::load().record_storage_segments(&[("Documents", documents)]);
+        }
+    }
+    Ok(tree)
 }
 
+/// Admin-enforced constraints so the UI can hide features the backend will refuse anyway.
 #[tauri::command]
-async fn get_maintenance_tasks_command() -> Vec<scanners::maintenance::MaintenanceTask> {
-    scanners::maintenance::get_tasks()
+async fn get_managed_policy_command() -> ManagedPolicy {
+    ManagedPolicy::load()
 }
 
+/// Finder-style storage breakdown from cached scan results — no disk walk.
 #[tauri::command]
-async fn run_maintenance_task_command(id: String) -> Result<String, String> {
-    // In a real production app, this should run in a separate thread/task if long-running
-    scanners::maintenance::run_task(&id)
+async fn get_storage_summary_command() -> scanners::storage::StorageSummary {
+    scanners::storage::storage_summary()
 }
 
+/// What macOS's "System Data" is made of: caches, logs, snapshots, app data, swap and system volumes.
 #[tauri::command]
-async fn scan_privacy_command() -> Vec<scanners::privacy::PrivacyItem> {
-    scanners::privacy::scan_privacy()
+async fn analyze_system_data_command() -> Result<scanners::system_data::SystemDataReport, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::system_data::analyze_system_data)
+        .await
+        .map_err(AltoError::from)
 }
 
 #[tauri::command]
-async fn clean_privacy_item_command(path: String) -> Result<(), String> {
-    scanners::privacy::clean_privacy_item(&path)
+async fn scan_malware_command() -> Result<scanners::malware::MalwareResult, AltoError> {
+    let result = tauri::async_runtime::spawn_blocking(scanners::malware::scan_malware).await?;
+    scanners::malware::record(&result, false);
+    Ok(result)
+}
+
+#[tauri::command]
+async fn get_virustotal_policy_command() -> Result<mcp::context_store::VirusTotalPolicy, AltoError> {
+    let has_api_key = tauri::async_runtime::spawn_blocking(scanners::virustotal::stored_api_key).await?.is_some();
+    Ok(mcp::context_store::VirusTotalPolicy { has_api_key, ..ContextStore::load().user_preferences.virustotal })
+}
+
+/// Turn VirusTotal lookups on or off and, when `api_key` is set, save it in the Keychain.
+#[tauri::command]
+async fn save_virustotal_policy_command(policy: mcp::context_store::VirusTotalPolicy) -> Result<(), AltoError> {
+    let api_key = policy.api_key.trim().to_string();
+    let has_api_key = tauri::async_runtime::spawn_blocking(move || {
+        if api_key.is_empty() {
+            Ok(scanners::virustotal::stored_api_key().is_some())
+        } else {
+            scanners::virustotal::store_api_key(&api_key).map(|_| true)
+        }
+    })
+    .await?
+    .map_err(AltoError::invalid)?;
+    if policy.enabled && !has_api_key {
+        return Err(AltoError::invalid("Enter a VirusTotal API key to turn lookups on"));
+    }
+    ContextStore::load().set_virustotal_policy(mcp::context_store::VirusTotalPolicy { enabled: policy.enabled, ..Default::default() });
+    Ok(())
+}
+
+/// Look up suspicious files found by the malware scan or the watcher on VirusTotal by SHA-256
+/// (files are never uploaded). Verdicts are cached, and the public API's rate limit is respected.
+#[tauri::command]
+async fn virustotal_lookup_command(paths: Vec<String>) -> Result<Vec<scanners::virustotal::VirusTotalLookup>, AltoError> {
+    let enabled = ContextStore::load().user_preferences.virustotal.enabled;
+    let api_key = tauri::async_runtime::spawn_blocking(scanners::virustotal::stored_api_key).await?;
+    let Some(api_key) = api_key.filter(|_| enabled) else {
+        return Err(AltoError::invalid("VirusTotal lookups are off. Turn them on and add an API key in Settings."));
+    };
+    tauri::async_runtime::spawn_blocking(move || scanners::virustotal::lookup(&paths, &api_key))
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Past malware scans, newest last; scheduled ones are marked.
+#[tauri::command]
+async fn get_malware_scan_history_command() -> Vec<mcp::context_store::MalwareScanRecord> {
+    ContextStore::load().malware_scans
+}
+
+/// Move suspected malware into the quarantine vault instead of deleting it: it's locked so it can't
+/// run, and stays until restored or shredded. `threat` is what the scan reported it as.
+#[tauri::command]
+async fn quarantine_items_command(paths: Vec<String>, threat: Option<String>) -> Result<serde_json::Value, AltoError> {
+    let (paths, rejected) = resolve_delete_paths(&paths);
+    let mut errors: Vec<String> = rejected.into_iter().map(|(path, reason)| format!("{}: {}", path, reason)).collect();
+    let ctx = ContextStore::load();
+    mcp::safety_gate::check_paths("Quarantine", &paths, &ManagedPolicy::load(), &ctx).map_err(AltoError::blocked)?;
+    let whitelist = mcp::whitelist::Whitelist::for_store(&ctx);
+    for path in &paths {
+        if mcp::file_index::index_file_with(path, &whitelist).category == FileCategory::SystemCritical {
+            return Err(AltoError::blocked(format!("{} is a system file and can't be quarantined", path)));
+        }
+        if path.ends_with(".app") {
+            ensure_app_quit(path, "quarantining")?;
+        }
+    }
+    let threat = threat.unwrap_or_else(|| "Suspected malware".to_string());
+    let (items, move_errors) = tauri::async_runtime::spawn_blocking(move || {
+        let vault = scanners::quarantine::vault_root();
+        let mut items = Vec::new();
+        let mut errors = Vec::new();
+        for path in &paths {
+            match scanners::quarantine::quarantine(&vault, Path::new(path), &threat) {
+                Ok(item) => items.push(item),
+                Err(e) => errors.push(e),
+            }
+        }
+        (items, errors)
+    })
+    .await?;
+    errors.extend(move_errors);
+    ContextStore::load().record_quarantined(items.clone());
+    Ok(serde_json::json!({ "quarantined": items, "errors": errors }))
+}
+
+#[tauri::command]
+async fn list_quarantine_command() -> Vec<mcp::context_store::QuarantinedItem> {
+    ContextStore::load().quarantine
+}
+
+/// Unlock quarantined items and move them back where they were found. Items stay in the store
+/// until they are actually back, so a failed restore never loses track of the vault.
+#[tauri::command]
+async fn restore_quarantined_command(ids: Vec<String>) -> Result<serde_json::Value, AltoError> {
+    let items = ContextStore::load().quarantined(&ids);
+    let (restored, errors) = tauri::async_runtime::spawn_blocking(move || {
+        let mut restored = Vec::new();
+        let mut errors = Vec::new();
+        for item in items {
+            match scanners::quarantine::restore(&item) {
+                Ok(_) => restored.push(item.id),
+                Err(e) => errors.push(e),
+            }
+        }
+        (restored, errors)
+    })
+    .await?;
+    ContextStore::load().take_quarantined(&restored);
+    Ok(serde_json::json!({ "restored": restored.len(), "errors": errors }))
+}
+
+/// Permanently shred quarantined items.
+#[tauri::command]
+async fn shred_quarantined_command(ids: Vec<String>) -> Result<serde_json::Value, AltoError> {
+    let policy = ManagedPolicy::load();
+    if policy.disable_shredder {
+        return Err(AltoError::blocked("Shredder is disabled by your administrator."));
+    }
+    let items = ContextStore::load().quarantined(&ids);
+    let originals: Vec<String> = items.iter().map(|i| i.original_path.clone()).collect();
+    policy.check_destructive("Shredder", &originals).map_err(AltoError::blocked)?;
+    let (shredded, errors) = tauri::async_runtime::spawn_blocking(move || {
+        let mut shredded = Vec::new();
+        let mut errors = Vec::new();
+        for item in items {
+            match scanners::quarantine::shred(&item) {
+                Ok(_) => shredded.push(item.id),
+                Err(e) => errors.push(e),
+            }
+        }
+        (shredded, errors)
+    })
+    .await?;
+    ContextStore::load().take_quarantined(&shredded);
+    Ok(serde_json::json!({ "shredded": shredded.len(), "errors": errors }))
+}
+
+/// Apple's built-in protection: XProtect and MRT versions, background scans, automatic updates.
+#[tauri::command]
+async fn get_xprotect_status_command() -> Result<scanners::xprotect::XProtectStatus, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::xprotect::status)
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Version and size of the malware definitions in use.
+#[tauri::command]
+async fn get_malware_definitions_command() -> scanners::malware_signatures::DefinitionsStatus {
+    scanners::malware_signatures::status()
+}
+
+/// Download newer signed malware definitions; they are used from the next scan on.
+#[tauri::command]
+async fn update_malware_definitions_command() -> Result<scanners::malware_signatures::DefinitionsStatus, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::malware_signatures::update)
+        .await?
+        .map_err(AltoError::from)
+}
+
+/// Cloud sync clients with per-folder local sizes and "make online-only" suggestions.
+#[tauri::command]
+async fn scan_cloud_sync_command() -> Result<Vec<scanners::cloud_sync::SyncClient>, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::cloud_sync::scan_cloud_sync)
+        .await
+        .map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn get_speed_recommendations_command() -> Result<Vec<scanners::speed::SpeedRecommendation>, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::speed::recommendations)
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Installed fonts per scope; `include_activation` asks Font Book which families are disabled.
+#[tauri::command]
+async fn scan_fonts_command(include_activation: Option<bool>) -> Result<scanners::fonts::FontReport, AltoError> {
+    let include_activation = include_activation.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || scanners::fonts::scan_fonts(include_activation)).await?
+}
+
+/// Deactivate font families in Font Book (files are kept).
+#[tauri::command]
+async fn deactivate_fonts_command(families: Vec<String>) -> Result<serde_json::Value, AltoError> {
+    let (deactivated, errors) = tauri::async_runtime::spawn_blocking(move || scanners::fonts::deactivate_families(&families))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(serde_json::json!({ "deactivated": deactivated, "errors": errors }))
+}
+
+#[tauri::command]
+async fn run_speed_task_command(task_id: String) -> Result<scanners::speed::SpeedTaskResult, AltoError> {
+    Ok(scanners::speed::run_optimization_task(&task_id))
+}
+
+/// MCP Phase 1: Preview what would be deleted — NEVER deletes anything.
+/// Returns an indexed list of files with safety flags. Paths confirm_delete would reject, and files
+/// a running app has open, come back unsafe with the reason.
+#[tauri::command]
+async fn preview_delete(paths: Vec<String>) -> Result<Vec<IndexedFile>, AltoError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (resolved, rejected) = resolve_delete_paths(&paths);
+        let mut indexed = index_files_measured(&resolved, &mcp::whitelist::Whitelist::load());
+        let safe: Vec<String> = indexed.iter().filter(|f| f.is_safe_to_delete).map(|f| f.path.clone()).collect();
+        for open in scanners::process::open_files(&safe) {
+            if let Some(f) = indexed.iter_mut().find(|f| f.path == open.path && f.is_safe_to_delete) {
+                f.is_safe_to_delete = false;
+                f.reason = format!("Open in {}; quit it first", open.app_name.as_deref().unwrap_or(&open.process));
+            }
+        }
+        indexed.extend(rejected.into_iter().map(|(path, reason)| IndexedFile {
+            path,
+            size_bytes: 0,
+            category: FileCategory::Unknown,
+            app_owner: None,
+            is_safe_to_delete: false,
+            reason,
+        }));
+        indexed
+    })
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Quick metadata (kind, dates, dimensions, duration, text excerpt, owner app) for the review list.
+#[tauri::command]
+async fn get_file_preview_command(path: String) -> Result<mcp::file_preview::FilePreview, AltoError> {
+    tauri::async_runtime::spawn_blocking(move || mcp::file_preview::preview_file(&path))
+        .await?
+        .map_err(AltoError::from)
+}
+
+/// MCP Phase 2: Confirm and execute deletion — only called after user approves.
+/// Logs the deletion to the context store for history. Trashes in batches, emitting "delete-progress"
+/// events; files that fail (e.g. locked) are reported in `errors` without stopping the rest.
+/// In dry-run mode (`dry_run`, the setting, or enforced by an administrator) it only reports
+/// what it would remove and how.
+#[tauri::command]
+async fn confirm_delete(app: AppHandle, paths: Vec<String>, dry_run: Option<bool>, state: State<'_, AppState>) -> Result<serde_json::Value, AltoError> {
+    // Only delete files that are safe according to the indexer and not excluded by the user or an administrator
+    let policy = ManagedPolicy::load();
+    let ctx = ContextStore::load();
+    let dry_run = mcp::dry_run::enabled(dry_run);
+    let (paths, rejected) = resolve_delete_paths(&paths);
+    // Rejected paths are reported, never queued for review: there's nothing valid to override
+    let with_rejected = |errors: Vec<String>| -> Vec<String> {
+        rejected.iter().map(|(path, reason)| format!("{}: {}", path, reason)).chain(errors).collect()
+    };
+    // The indexer already refuses whitelisted paths; checked here too so the review queue can say why
+    let whitelist = mcp::whitelist::Whitelist::for_store(&ctx);
+    let index_whitelist = whitelist.clone();
+    let indexed = tauri::async_runtime::spawn_blocking(move || index_files_measured(&paths, &index_whitelist))
+        .await
+        .map_err(|e| e.to_string())?;
+    let passes_gate = |f: &IndexedFile| mcp::safety_gate::passes(f, &whitelist, &policy, &ctx);
+    // Files a running app has open (a live SQLite WAL, say) are skipped, not deleted under it;
+    // `in_use` names the app so the user can quit it and retry
+    let gated: Vec<String> = indexed.iter().filter(|f| passes_gate(f)).map(|f| f.path.clone()).collect();
+    let in_use = tauri::async_runtime::spawn_blocking(move || scanners::process::open_files(&gated))
+        .await
+        .map_err(|e| e.to_string())?;
+    let busy: std::collections::HashSet<String> = in_use.iter().map(|f| f.path.clone()).collect();
+    let is_allowed = |f: &IndexedFile| passes_gate(f) && !busy.contains(&f.path);
+    let safe_paths: Vec<String> = indexed.iter()
+        .filter(|f| is_allowed(f))
+        .map(|f| f.path.clone())
+        .collect();
+    let blocked: Vec<String> = indexed.iter()
+        .filter(|f| !passes_gate(f))
+        .map(|f| f.path.clone())
+        .collect();
+
+    // Keep blocked paths in the review queue instead of dropping them
+    let queued_at = chrono::Local::now().to_rfc3339();
+    let review: Vec<mcp::context_store::ReviewItem> = indexed.iter()
+        .filter(|f| !passes_gate(f))
+        .map(|f| {
+            let (blocked_by, reason) = if policy.is_excluded(&f.path) {
+                ("managed", "Excluded by your administrator".to_string())
+            } else if ctx.is_protected(&f.path) {
+                ("protected", "Protected in your settings".to_string())
+            } else if ctx.is_excluded(&f.path) {
+                ("excluded", "In your excluded paths".to_string())
+            } else if let Some(pattern) = whitelist.matching(&f.path) {
+                ("whitelist", format!("Matches the skip pattern \"{}\"", pattern))
+            } else {
+                ("safety", f.reason.clone())
+            };
+            mcp::context_store::ReviewItem {
+                id: uuid::Uuid::new_v4().to_string(),
+                path: f.path.clone(),
+                size_bytes: f.size_bytes,
+                blocked_by: blocked_by.to_string(),
+                reason,
+                queued_at: queued_at.clone(),
+            }
+        })
+        .collect();
+    if !dry_run {
+        ContextStore::load().enqueue_review(review);
+    }
+
+    if safe_paths.is_empty() {
+        return Ok(serde_json::json!({
+            "removed": 0,
+            "blocked": blocked,
+            "in_use": in_use,
+            "errors": with_rejected(vec!["No safe files to delete after safety check.".to_string()])
+        }));
+    }
+
+    let total_bytes: u64 = indexed.iter().filter(|f| is_allowed(f)).map(|f| f.size_bytes).sum();
+
+    if dry_run {
+        let method = if ctx.user_preferences.staging.enabled { "stage" } else { "trash" };
+        let items: Vec<mcp::dry_run::PlannedRemoval> = indexed.iter()
+            .filter(|f| is_allowed(f))
+            .map(|f| mcp::dry_run::PlannedRemoval { path: f.path.clone(), size_bytes: f.size_bytes, method: method.to_string() })
+            .collect();
+        let note = if policy.force_dry_run { "Dry-run enforced by your administrator; nothing was deleted." } else { "Dry run; nothing was deleted." };
+        return Ok(serde_json::json!({
+            "removed": 0,
+            "would_remove": safe_paths,
+            "items": items,
+            "method": method,
+            "bytes_freed": 0,
+            "would_free": total_bytes,
+            "blocked": blocked,
+            "in_use": in_use,
+            "dry_run": true,
+            "errors": with_rejected(vec![note.to_string()])
+        }));
+    }
+
+    // Archive first when backups are on; nothing is removed if that fails
+    let backup = if ctx.user_preferences.backup.enabled {
+        let items: Vec<(String, u64)> = indexed.iter().filter(|f| is_allowed(f)).map(|f| (f.path.clone(), f.size_bytes)).collect();
+        let backup = tauri::async_runtime::spawn_blocking(move || mcp::backup::back_up(&items))
+            .await
+            .map_err(|e| e.to_string())??;
+        Some(backup.id)
+    } else {
+        None
+    };
+
+    if ctx.user_preferences.staging.enabled {
+        let sizes: Vec<u64> = indexed.iter().filter(|f| is_allowed(f)).map(|f| f.size_bytes).collect();
+        let (staged, errors) = mcp::staging::stage_paths(&safe_paths, &sizes);
+        *state.smart_scan_cache.lock().unwrap() = None;
+        let staged_paths: Vec<String> = staged.iter().map(|i| i.original_path.clone()).collect();
+        let staged_bytes: u64 = staged.iter().map(|i| i.size_bytes).sum();
+        if !staged_paths.is_empty() {
+            let locations = staged.iter().map(|i| (i.original_path.clone(), i.staged_path.clone())).collect();
+            ContextStore::load().record_deletion_with_locations(staged_paths, staged_bytes, None, locations);
+        }
+        return Ok(serde_json::json!({
+            "removed": staged.len(),
+            "bytes_freed": staged_bytes,
+            "blocked": blocked,
+            "in_use": in_use,
+            "method": "staged",
+            "backup": backup,
+            "purge_after_days": ctx.user_preferences.staging.retention_days,
+            "errors": with_rejected(errors)
+        }));
+    }
+
+    let snapshot = mcp::restore_point::before_cleanup(total_bytes).await;
+    let items: Vec<(String, u64)> = indexed.iter().filter(|f| is_allowed(f)).map(|f| (f.path.clone(), f.size_bytes)).collect();
+    // Emits "delete-progress" after each batch; cancel_delete_command stops before the next one
+    let scanners::trash::BatchDeleteResult { removed, bytes_freed, errors, cancelled, remaining } = tauri::async_runtime::spawn_blocking(move || {
+        scanners::trash::trash_in_batches(&items, |progress| {
+            let _ = app.emit("delete-progress", progress);
+        })
+    })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !removed.is_empty() {
+        // Cached smart scan results and Space Lens sizes would still count the removed files
+        *state.smart_scan_cache.lock().unwrap() = None;
+        for path in &removed {
+            scanners::space_lens::invalidate_cached_sizes(path);
+        }
+        // Looked up now, while the Trash still matches, so undo_last_deletion_command can put them back
+        let locations = scanners::trash::locate_trashed_all(&removed);
+        let mut ctx = ContextStore::load();
+        ctx.record_deletion_with_locations(removed.clone(), bytes_freed, snapshot.clone(), locations);
+    }
+    Ok(serde_json::json!({
+        "removed": removed.len(),
+        "bytes_freed": bytes_freed,
+        "blocked": blocked,
+        "in_use": in_use,
+        "snapshot": snapshot,
+        "backup": backup,
+        "cancelled": cancelled,
+        "remaining": remaining,
+        "errors": with_rejected(errors)
+    }))
+}
+
+/// Stop a running confirm_delete after its current batch.
+#[tauri::command]
+async fn cancel_delete_command() -> Result<(), AltoError> {
+    scanners::trash::cancel_delete();
+    Ok(())
+}
+
+/// Put the most recent cleanup back where it came from, from the Trash or the staging area.
+#[tauri::command]
+async fn undo_last_deletion_command(state: State<'_, AppState>) -> Result<mcp::undo::UndoResult, AltoError> {
+    let result = tauri::async_runtime::spawn_blocking(mcp::undo::undo_last_deletion)
+        .await
+        .map_err(|e| e.to_string())??;
+    if !result.restored.is_empty() {
+        *state.smart_scan_cache.lock().unwrap() = None;
+        for path in &result.restored {
+            scanners::space_lens::invalidate_cached_sizes(path);
+        }
+    }
+    Ok(result)
+}
+
+/// Paths `confirm_delete` blocked, newest last.
+#[tauri::command]
+async fn list_review_queue_command() -> Vec<mcp::context_store::ReviewItem> {
+    ContextStore::load().review_queue
+}
+
+/// Override the safety gate for queued items and move them to the Trash. `confirm_paths` must
+/// repeat every path being approved, and an administrator must authenticate. Items excluded by an
+/// administrator, system files and Alto's own data can't be overridden.
+#[tauri::command]
+async fn approve_review_items_command(ids: Vec<String>, confirm_paths: Vec<String>, state: State<'_, AppState>) -> Result<serde_json::Value, AltoError> {
+    let ctx = ContextStore::load();
+    let pending: Vec<&mcp::context_store::ReviewItem> = ctx.review_queue.iter().filter(|q| ids.contains(&q.id)).collect();
+    if pending.is_empty() {
+        return Err(AltoError::invalid("No matching items in the review queue"));
+    }
+    if pending.iter().any(|q| !confirm_paths.iter().any(|c| mcp::path_norm::same_path(c, &q.path))) {
+        return Err(AltoError::invalid("Confirm each path to delete it despite the safety check"));
+    }
+    let policy = ManagedPolicy::load();
+    if let Some(q) = pending.iter().find(|q| q.blocked_by == "managed" || policy.is_excluded(&q.path)) {
+        return Err(AltoError::blocked(format!("{} is excluded by your administrator and can't be overridden", q.path)));
+    }
+    let paths: Vec<String> = pending.iter().map(|q| q.path.clone()).collect();
+    policy.check_destructive("Delete", &paths).map_err(AltoError::blocked)?;
+    ctx.check_protected("Delete", &paths).map_err(AltoError::blocked)?;
+    let whitelist = mcp::whitelist::Whitelist::for_store(&ctx);
+    for path in &paths {
+        mcp::self_protect::check("Delete", path).map_err(AltoError::blocked)?;
+        if mcp::file_index::index_file_with(path, &whitelist).category == FileCategory::SystemCritical {
+            return Err(AltoError::blocked(format!("{} is a system file and can't be overridden", path)));
+        }
+    }
+    let reason = format!("Alto wants to move {} item(s) the safety check blocked to the Trash.", paths.len());
+    tauri::async_runtime::spawn_blocking(move || scanners::automation::authorize_admin(&reason))
+        .await?
+        .map_err(AltoError::blocked)?;
+
+    // Reloaded: the store may have changed while the password dialog was open
+    let mut ctx = ContextStore::load();
+    let items = ctx.take_review_items(&ids);
+    let mut removed = Vec::new();
+    let mut bytes_freed = 0u64;
+    let mut errors = Vec::new();
+    for item in items {
+        if !std::path::Path::new(&item.path).exists() {
+            continue; // Gone already; nothing to review
+        }
+        match trash::delete(&item.path) {
+            Ok(_) => {
+                bytes_freed += item.size_bytes;
+                removed.push(item.path);
+            }
+            Err(e) => {
+                errors.push(format!("{}: {}", item.path, e));
+                ctx.review_queue.push(item);
+            }
+        }
+    }
+    if !removed.is_empty() {
+        *state.smart_scan_cache.lock().unwrap() = None;
+        ctx.record_deletion(removed.clone(), bytes_freed);
+    } else {
+        ctx.save();
+    }
+    Ok(serde_json::json!({
+        "removed": removed.len(),
+        "bytes_freed": bytes_freed,
+        "errors": errors
+    }))
+}
+
+/// Drop items from the review queue without deleting them.
+#[tauri::command]
+async fn dismiss_review_items_command(ids: Vec<String>) -> usize {
+    ContextStore::load().take_review_items(&ids).len()
+}
+
+/// Copy the files of a cleanup back from its pre-clean APFS snapshot (`timestamp` of the deletion record).
+#[tauri::command]
+async fn revert_pre_clean_snapshot_command(timestamp: String) -> Result<mcp::restore_point::RevertResult, AltoError> {
+    mcp::restore_point::revert(&timestamp).await.map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn list_staged_items_command() -> Vec<mcp::staging::StagedItem> {
+    mcp::staging::list_staged()
+}
+
+#[tauri::command]
+async fn get_staging_policy_command() -> mcp::context_store::StagingPolicy {
+    ContextStore::load().user_preferences.staging
+}
+
+/// Turn staging on or off and set how long staged items are kept before the janitor purges them.
+#[tauri::command]
+async fn save_staging_policy_command(policy: mcp::context_store::StagingPolicy) -> Result<(), AltoError> {
+    if policy.retention_days == 0 {
+        return Err(AltoError::invalid("Keep staged items for at least one day"));
+    }
+    ContextStore::load().set_staging_policy(policy);
+    Ok(())
+}
+
+/// Move staged items back to their original locations. `batch` restores everything from one cleanup.
+#[tauri::command]
+async fn restore_staged_items_command(ids: Vec<String>, batch: Option<String>) -> Result<serde_json::Value, AltoError> {
+    let ids = match batch {
+        Some(batch) => mcp::staging::batch_ids(&batch),
+        None => ids,
+    };
+    let (restored, errors) = mcp::staging::restore(&ids);
+    Ok(serde_json::json!({ "restored": restored, "errors": errors }))
+}
+
+/// Permanently delete staged items before their grace period ends.
+#[tauri::command]
+async fn purge_staged_items_command(ids: Vec<String>) -> Result<serde_json::Value, AltoError> {
+    let staged_paths: Vec<String> = mcp::staging::list_staged()
+        .into_iter()
+        .filter(|item| ids.contains(&item.id))
+        .map(|item| item.original_path)
+        .collect();
+    ManagedPolicy::load().check_destructive("Purge", &staged_paths).map_err(AltoError::blocked)?;
+    let (purged, errors) = mcp::staging::purge(&ids);
+    Ok(serde_json::json!({ "purged": purged, "errors": errors }))
+}
+
+#[tauri::command]
+async fn get_backup_policy_command() -> mcp::context_store::BackupPolicy {
+    ContextStore::load().user_preferences.backup
+}
+
+/// Turn pre-clean backups on or off and set how long they're kept and the largest item archived.
+#[tauri::command]
+async fn save_backup_policy_command(policy: mcp::context_store::BackupPolicy) -> Result<(), AltoError> {
+    if policy.retention_days == 0 {
+        return Err(AltoError::invalid("Keep backups for at least one day"));
+    }
+    ContextStore::load().set_backup_policy(policy);
+    Ok(())
+}
+
+/// Pre-clean backups in ~/.alto/backups, newest first.
+#[tauri::command]
+async fn list_backups_command() -> Vec<mcp::backup::Backup> {
+    mcp::backup::list_backups()
+}
+
+/// Put a backup's items (or only `paths`) back where they were. Never overwrites existing files.
+#[tauri::command]
+async fn restore_backup_command(id: String, paths: Option<Vec<String>>, state: State<'_, AppState>) -> Result<serde_json::Value, AltoError> {
+    let (restored, errors) = tauri::async_runtime::spawn_blocking(move || mcp::backup::restore(&id, paths.as_deref()))
+        .await
+        .map_err(|e| e.to_string())??;
+    if restored > 0 {
+        *state.smart_scan_cache.lock().unwrap() = None;
+    }
+    Ok(serde_json::json!({ "restored": restored, "errors": errors }))
+}
+
+#[tauri::command]
+async fn delete_backup_command(id: String) -> Result<(), AltoError> {
+    let archives: Vec<String> = mcp::backup::list_backups().into_iter().filter(|b| b.id == id).map(|b| b.archive).collect();
+    ManagedPolicy::load().check_destructive("Delete backup", &archives).map_err(AltoError::blocked)?;
+    mcp::backup::delete_backup(&id).map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn get_auto_clean_policy_command() -> mcp::context_store::AutoCleanPolicy {
+    ContextStore::load().user_preferences.auto_clean
+}
+
+/// Turn background auto-clean on or off, and set its free-space threshold and approved categories.
+#[tauri::command]
+async fn save_auto_clean_policy_command(policy: mcp::context_store::AutoCleanPolicy) -> Result<(), AltoError> {
+    if policy.enabled && policy.min_free_bytes == 0 {
+        return Err(AltoError::invalid("Set a free-space threshold for auto-clean"));
+    }
+    let disallowed = scanners::auto_clean::disallowed_categories(&policy.categories);
+    if !disallowed.is_empty() {
+        return Err(AltoError::invalid(format!(
+            "Auto-clean only handles {}; not {}",
+            scanners::auto_clean::ALLOWED_CATEGORIES.join(", "),
+            disallowed.join(", ")
+        )));
+    }
+    ContextStore::load().set_auto_clean_policy(policy);
+    Ok(())
+}
+
+/// Run auto-clean now, whatever the free space, with the saved categories. `dry_run` behaves as in confirm_delete.
+#[tauri::command]
+async fn run_auto_clean_command(dry_run: Option<bool>, state: State<'_, AppState>) -> Result<scanners::auto_clean::AutoCleanReport, AltoError> {
+    let policy = ContextStore::load().user_preferences.auto_clean;
+    if policy.categories.is_empty() {
+        return Err(AltoError::invalid("No categories are approved for auto-clean"));
+    }
+    let dry_run = mcp::dry_run::enabled(dry_run);
+    let report = tauri::async_runtime::spawn_blocking(move || scanners::auto_clean::run(&policy, dry_run))
+        .await
+        .map_err(|e| e.to_string())?;
+    if !report.dry_run && !report.paths.is_empty() {
+        *state.smart_scan_cache.lock().unwrap() = None;
+    }
+    Ok(report)
+}
+
+/// Legacy command — kept for compatibility but now routes through safety layer.
+#[tauri::command]
+async fn clean_items(app: AppHandle, paths: Vec<String>, state: State<'_, AppState>) -> Result<serde_json::Value, AltoError> {
+    // Route through the safe confirm_delete
+    confirm_delete(app, paths, None, state).await
+}
+
+#[tauri::command]
+async fn list_excluded_paths() -> Vec<String> {
+    ContextStore::load().user_preferences.excluded_paths
+}
+
+/// Tell Alto to never scan or delete `path` (a file or folder, `~` allowed).
+#[tauri::command]
+async fn add_excluded_path(path: String) -> Result<Vec<String>, AltoError> {
+    if path.trim().is_empty() {
+        return Err(AltoError::invalid("Path cannot be empty"));
+    }
+    let mut ctx = ContextStore::load();
+    ctx.add_excluded_path(&path);
+    Ok(ctx.user_preferences.excluded_paths)
+}
+
+#[tauri::command]
+async fn remove_excluded_path(path: String) -> Result<Vec<String>, AltoError> {
+    let mut ctx = ContextStore::load();
+    ctx.remove_excluded_path(&path);
+    Ok(ctx.user_preferences.excluded_paths)
+}
+
+#[tauri::command]
+async fn list_protected_paths_command() -> Vec<String> {
+    ContextStore::load().user_preferences.protected_paths
+}
+
+/// Protect `path` (a file or folder, `~` allowed): scans never list it, and no delete, shred,
+/// uninstall or move may remove it or a folder containing it.
+#[tauri::command]
+async fn add_protected_path_command(path: String) -> Result<Vec<String>, AltoError> {
+    if path.trim().is_empty() {
+        return Err(AltoError::invalid("Path cannot be empty"));
+    }
+    let mut ctx = ContextStore::load();
+    ctx.add_protected_path(&path);
+    Ok(ctx.user_preferences.protected_paths)
+}
+
+#[tauri::command]
+async fn remove_protected_path_command(path: String) -> Result<Vec<String>, AltoError> {
+    let mut ctx = ContextStore::load();
+    ctx.remove_protected_path(&path);
+    Ok(ctx.user_preferences.protected_paths)
+}
+
+#[tauri::command]
+async fn schedule_task(cron: String, task_type: String, heavy: Option<bool>, profile: Option<String>, state: State<'_, AppState>) -> Result<String, AltoError> {
+    if let Some(name) = &profile {
+        if ContextStore::load().scan_profile(name).is_none() {
+            return Err(AltoError::invalid(format!("Unknown scan profile: {}", name)));
+        }
+    }
+    // A malware scan walks Downloads and runs codesign/spctl on many files, so it waits out quiet mode by default
+    let heavy = heavy.unwrap_or(task_type == scanners::scheduler::MALWARE_SCAN_TASK);
+    Ok(state.scheduler.add_job(cron, task_type, heavy, profile))
+}
+
+/// Current Low Power Mode / Focus state, so the UI can explain deferred jobs and quiet alerts.
+#[tauri::command]
+async fn get_power_state_command() -> scanners::power::PowerState {
+    scanners::power::current_state()
+}
+
+#[tauri::command]
+async fn scan_apps_command() -> Vec<scanners::uninstaller::AppInfo> {
+    let apps = scanners::uninstaller::scan_apps();
+    let apps_bytes: u64 = apps.iter().map(|a| a.size_bytes).sum();
+    if apps_bytes > 0 {
+        let mut ctx = ContextStore::load();
+        ctx.apps_installed = Some(apps.len());
+        ctx.record_storage_segments(&[("Apps", apps_bytes)]);
+    }
+    apps
+}
+
+/// Uninstall an app and its leftovers; in dry-run mode returns what would be removed instead.
+#[tauri::command]
+async fn uninstall_app_command(path: String, dry_run: Option<bool>) -> Result<Option<mcp::dry_run::DryRunReport>, AltoError> {
+    mcp::self_protect::check("Uninstall", &path).map_err(AltoError::blocked)?;
+    ContextStore::load().check_protected("Uninstall", std::slice::from_ref(&path)).map_err(AltoError::blocked)?;
+    if mcp::dry_run::enabled(dry_run) {
+        ManagedPolicy::load().check_excluded("Uninstall", std::slice::from_ref(&path)).map_err(AltoError::blocked)?;
+        return tauri::async_runtime::spawn_blocking(move || Some(scanners::uninstaller::plan_uninstall(&path)))
+            .await
+            .map_err(AltoError::from);
+    }
+    mcp::safety_gate::check_paths("Uninstall", std::slice::from_ref(&path), &ManagedPolicy::load(), &ContextStore::load())
+        .map_err(AltoError::blocked)?;
+    ensure_app_quit(&path, "uninstalling")?;
+    scanners::uninstaller::uninstall_app(&path).await.map(|_| None).map_err(AltoError::from)
+}
+
+/// Disk images and packages in Downloads, tagged when the app they contain is already installed.
+#[tauri::command]
+async fn scan_installers_command() -> Result<Vec<scanners::installers::InstallerInfo>, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::installers::scan_installers)
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Apps installed more than once (same bundle id), with the copy to keep and the ones to remove.
+#[tauri::command]
+async fn scan_duplicate_apps_command() -> Result<Vec<scanners::app_versions::DuplicateApp>, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::app_versions::scan_duplicate_apps)
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Trash one copy of a duplicated app. Refused unless another copy with the same bundle id stays.
+#[tauri::command]
+async fn remove_app_copy_command(path: String) -> Result<(), AltoError> {
+    mcp::safety_gate::check_paths("Uninstall", std::slice::from_ref(&path), &ManagedPolicy::load(), &ContextStore::load())
+        .map_err(AltoError::blocked)?;
+    let duplicates = tauri::async_runtime::spawn_blocking(scanners::app_versions::scan_duplicate_apps)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !duplicates.iter().any(|d| d.others.iter().any(|c| c.path == path)) {
+        return Err(AltoError::blocked("Not a removable copy: no other copy of this app would remain"));
+    }
+    ensure_app_quit(&path, "removing")?;
+    scanners::uninstaller::remove_app_copy(&path).await.map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn scan_leftovers_command(_id: String) -> scanners::uninstaller::LeftoverGroups {
+    #[cfg(target_os = "macos")]
+    return scanners::uninstaller::scan_leftovers(&_id);
+    #[cfg(not(target_os = "macos"))]
+    return scanners::uninstaller::LeftoverGroups::default();
+}
+
+#[tauri::command]
+async fn scan_outdated_apps_command() -> Vec<scanners::updater::OutdatedApp> {
+    scanners::updater::scan_outdated_apps()
+}
+
+/// Move files or folders into `destination` (home or an external drive). Within a volume it's a
+/// rename; across volumes each item is copied, verified, and only then is the original trashed.
+/// Emits "move-progress" events while copying.
+#[tauri::command]
+async fn move_paths_command(app: AppHandle, paths: Vec<String>, destination: String) -> Result<serde_json::Value, AltoError> {
+    let (paths, rejected) = resolve_delete_paths(&paths);
+    let mut errors: Vec<String> = rejected.into_iter().map(|(path, reason)| format!("{}: {}", path, reason)).collect();
+    mcp::safety_gate::check_paths("Move", &paths, &ManagedPolicy::load(), &ContextStore::load()).map_err(AltoError::blocked)?;
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    let mut dest_roots = vec![home.canonicalize().unwrap_or(home)];
+    dest_roots.extend(scanners::system_stats::external_volumes(false).into_iter().map(|vol| PathBuf::from(vol.mount_point)));
+    let dest = canonicalize_and_validate_path(destination.trim(), &dest_roots)?;
+    if !dest.is_dir() {
+        return Err(AltoError::invalid("Destination is not a directory"));
+    }
+    mcp::self_protect::check("Move", &dest.to_string_lossy()).map_err(AltoError::blocked)?;
+
+    let (moved, bytes_moved, move_errors) = tauri::async_runtime::spawn_blocking(move || {
+        let items_total = paths.len();
+        let mut moved = 0usize;
+        let mut bytes_moved = 0u64;
+        let mut errors = Vec::new();
+        for (i, path_str) in paths.iter().enumerate() {
+            let src = Path::new(path_str);
+            if dest.starts_with(src) {
+                errors.push(format!("Can't move {} into itself", path_str));
+                continue;
+            }
+            let name = src.file_name().unwrap_or_default();
+            let dest_path = dest.join(name);
+            if std::fs::symlink_metadata(&dest_path).is_ok() {
+                errors.push(format!("{} already exists in the destination", name.to_string_lossy()));
+                continue;
+            }
+            let bytes_total = scanners::mover::total_len(src);
+            if std::fs::rename(src, &dest_path).is_ok() {
+                moved += 1;
+                bytes_moved += bytes_total;
+                continue;
+            }
+            let mut on_bytes = |bytes_done| {
+                let _ = app.emit("move-progress", scanners::mover::MoveProgress {
+                    path: path_str.clone(),
+                    items_done: i,
+                    items_total,
+                    bytes_done,
+                    bytes_total,
+                });
+            };
+            let mut progress = scanners::mover::Progress::new(&mut on_bytes);
+            match scanners::mover::copy_verified(src, &dest_path, &mut progress) {
+                Ok(()) => match trash::delete(path_str) {
+                    Ok(_) => {
+                        moved += 1;
+                        bytes_moved += bytes_total;
+                    }
+                    Err(e) => errors.push(format!("Copied {} but could not remove the original: {}", path_str, e)),
+                },
+                Err(e) => errors.push(format!("Failed to move {}: {}", path_str, e)),
+            }
+        }
+        (moved, bytes_moved, errors)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    errors.extend(move_errors);
+    Ok(serde_json::json!({ "moved": moved, "bytes_moved": bytes_moved, "errors": errors }))
+}
+
+#[tauri::command]
+async fn shred_path_command(path: String) -> Result<(), AltoError> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    let allowed_roots = vec![home.clone()];
+    let canonical = canonicalize_and_validate_path(path.trim(), &allowed_roots)?;
+    let path_str = canonical.to_string_lossy().to_string();
+
+    let policy = ManagedPolicy::load();
+    if policy.disable_shredder {
+        return Err(AltoError::blocked("Shredder is disabled by your administrator."));
+    }
+    policy.check_destructive("Shredder", std::slice::from_ref(&path_str)).map_err(AltoError::blocked)?;
+    ContextStore::load().check_protected("Shredder", std::slice::from_ref(&path_str)).map_err(AltoError::blocked)?;
+
+    let indexed = index_file(&path_str);
+    if !indexed.is_safe_to_delete {
+        return Err(AltoError::blocked(format!(
+            "Shredder blocked: {}. Alto will not shred system or user data.",
+            indexed.reason
+        )));
+    }
+    if matches!(indexed.category, FileCategory::SystemCritical | FileCategory::UserData) {
+        return Err(AltoError::blocked(format!(
+            "Shredder blocked: {} (category: {:?})",
+            indexed.reason, indexed.category
+        )));
+    }
+
+    scanners::shredder::shred_path(&path_str).map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn scan_mail_command() -> Vec<scanners::mail::MailAttachment> {
+    scanners::mail::scan_mail_attachments()
+}
+
+/// Envelope Index and per-account mailbox sizes (read-only).
+#[tauri::command]
+async fn analyze_mail_store_command() -> Result<scanners::mail::MailStoreReport, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::mail::analyze_mail_store)
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Move Mail attachment copies to the Trash through confirm_delete (safety index, exclusions,
+/// history, dry run). Only paths the Mail scan reports are accepted.
+#[tauri::command]
+async fn clean_mail_command(app: AppHandle, paths: Vec<String>, dry_run: Option<bool>, state: State<'_, AppState>) -> Result<serde_json::Value, AltoError> {
+    if let Some(p) = paths.iter().find(|p| !scanners::mail::is_attachment_path(p)) {
+        return Err(AltoError::blocked(format!("Not a Mail attachment: {}", p)));
+    }
+    confirm_delete(app, paths, dry_run, state).await
+}
+
+/// Xcode DerivedData, unavailable simulators, old device support, archives and module caches, grouped.
+#[tauri::command]
+async fn scan_xcode_command(archive_stale_days: Option<u32>) -> Result<Vec<scanners::xcode::XcodeGroup>, AltoError> {
+    let days = archive_stale_days.unwrap_or(scanners::xcode::ARCHIVE_STALE_DAYS);
+    tauri::async_runtime::spawn_blocking(move || scanners::xcode::scan_xcode(days))
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Preview a single Xcode group (fresh scan).
+#[tauri::command]
+async fn preview_xcode_group_command(group: scanners::xcode::XcodeGroupKind, archive_stale_days: Option<u32>) -> Result<scanners::xcode::XcodeGroup, AltoError> {
+    let days = archive_stale_days.unwrap_or(scanners::xcode::ARCHIVE_STALE_DAYS);
+    tauri::async_runtime::spawn_blocking(move || scanners::xcode::scan_group(group, days))
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Clean an Xcode group: the given item ids, or the recommended items when `ids` is omitted.
+#[tauri::command]
+async fn clean_xcode_group_command(group: scanners::xcode::XcodeGroupKind, ids: Option<Vec<String>>, archive_stale_days: Option<u32>) -> Result<scanners::xcode::XcodeCleanResult, AltoError> {
+    // Item paths are checked against the administrator's exclusions once resolved in clean_group
+    ManagedPolicy::load().check_destructive("Xcode cleanup", &[]).map_err(AltoError::blocked)?;
+    let days = archive_stale_days.unwrap_or(scanners::xcode::ARCHIVE_STALE_DAYS);
+    tauri::async_runtime::spawn_blocking(move || scanners::xcode::clean_group(group, ids.as_deref(), days))
+        .await
+        .map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn scan_extensions_command() -> Vec<scanners::extensions::ExtensionItem> {
+    scanners::extensions::scan_extensions()
+}
+
+/// Remove an extension (root helper if needed); in dry-run mode returns what would be removed instead.
+#[tauri::command]
+async fn remove_extension_command(path: String, dry_run: Option<bool>) -> Result<Option<mcp::dry_run::DryRunReport>, AltoError> {
+    ContextStore::load().check_protected("Extension removal", std::slice::from_ref(&path)).map_err(AltoError::blocked)?;
+    if mcp::dry_run::enabled(dry_run) {
+        ManagedPolicy::load().check_excluded("Extension removal", std::slice::from_ref(&path)).map_err(AltoError::blocked)?;
+        return Ok(Some(mcp::dry_run::plan("Extension removal", std::slice::from_ref(&path), "delete")));
+    }
+    ManagedPolicy::load().check_destructive("Extension removal", std::slice::from_ref(&path)).map_err(AltoError::blocked)?;
+    scanners::extensions::remove_extension(path).await.map(|_| None).map_err(AltoError::from)
+}
+
+/// Parallels / UTM / VirtualBox / VMware Fusion / Docker VM disks with on-disk vs logical size.
+#[tauri::command]
+async fn scan_vm_images_command() -> Result<Vec<scanners::vm_images::VmImage>, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::vm_images::scan_vm_images)
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Near-duplicate photos and burst shots, emitting "similar-photos-progress" events while hashing.
+#[tauri::command]
+async fn scan_similar_photos_command(app: AppHandle) -> Result<scanners::similar_photos::SimilarPhotosReport, AltoError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        scanners::similar_photos::scan_similar_photos(&|progress| {
+            let _ = app.emit("similar-photos-progress", progress);
+        })
+    })
+    .await
+    .map_err(AltoError::from)
+}
+
+/// Screenshots and screen recordings across the home folder, grouped by age and screen.
+#[tauri::command]
+async fn scan_screenshots_command() -> Result<scanners::screenshots::ScreenshotsReport, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::screenshots::scan_screenshots)
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Trash every screenshot at least `older_than_days` old, through the same safety checks as confirm_delete.
+#[tauri::command]
+async fn trash_old_screenshots_command(app: AppHandle, older_than_days: u64, state: State<'_, AppState>) -> Result<serde_json::Value, AltoError> {
+    let report = tauri::async_runtime::spawn_blocking(scanners::screenshots::scan_screenshots)
+        .await
+        .map_err(|e| e.to_string())?;
+    let paths = scanners::screenshots::older_than(&report, older_than_days);
+    ManagedPolicy::load().check_destructive("Delete", &paths).map_err(AltoError::blocked)?;
+    confirm_delete(app, paths, None, state).await
+}
+
+/// Move screenshots into `destination`, or ~/Pictures/Screenshots when none is given.
+#[tauri::command]
+async fn move_screenshots_command(app: AppHandle, paths: Vec<String>, destination: Option<String>) -> Result<serde_json::Value, AltoError> {
+    let destination = match destination {
+        Some(d) => d,
+        None => {
+            let folder = scanners::screenshots::default_folder();
+            std::fs::create_dir_all(&folder).map_err(|e| AltoError::io(&folder.to_string_lossy(), &e))?;
+            folder.to_string_lossy().to_string()
+        }
+    };
+    move_paths_command(app, paths, destination).await
+}
+
+/// Safety, size breakdown, duplicates and suggested actions for files/folders dropped onto the window or tray icon.
+#[tauri::command]
+async fn analyze_dropped_paths_command(paths: Vec<String>) -> Result<mcp::drop_analysis::DropAnalysis, AltoError> {
+    tauri::async_runtime::spawn_blocking(move || mcp::drop_analysis::analyze_dropped_paths(&paths))
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Docker Desktop / OrbStack / Colima disk usage.
+#[tauri::command]
+async fn scan_docker_command() -> Result<scanners::docker::DockerUsage, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::docker::scan_docker)
+        .await
+        .map_err(AltoError::from)
+}
+
+/// `docker system prune`. The UI must ask first; `confirm` has to be true for anything to run.
+#[tauri::command]
+async fn prune_docker_command(confirm: bool, all_images: Option<bool>, volumes: Option<bool>) -> Result<scanners::docker::DockerPruneResult, AltoError> {
+    if !confirm {
+        return Err(AltoError::invalid("Docker prune needs confirmation"));
+    }
+    ManagedPolicy::load().check_destructive("Docker prune", &[]).map_err(AltoError::blocked)?;
+    let (all_images, volumes) = (all_images.unwrap_or(false), volumes.unwrap_or(false));
+    tauri::async_runtime::spawn_blocking(move || scanners::docker::prune_docker(all_images, volumes))
+        .await?
+        .map_err(AltoError::from)
+}
+
+/// node_modules of projects not touched in `stale_months` months (default 3), largest first.
+#[tauri::command]
+async fn scan_stale_node_modules_command(stale_months: Option<u32>) -> Result<Vec<scanners::node_modules::StaleNodeModules>, AltoError> {
+    let months = stale_months.unwrap_or(scanners::node_modules::DEFAULT_STALE_MONTHS);
+    tauri::async_runtime::spawn_blocking(move || scanners::node_modules::find_stale_node_modules(months))
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Developer caches per toolchain (cargo, pip, Go, Gradle, Maven, CocoaPods, pnpm, Rust target dirs).
+#[tauri::command]
+async fn scan_dev_caches_command() -> Result<Vec<scanners::dev_caches::DevCache>, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::dev_caches::scan_dev_caches)
+        .await
+        .map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn clean_dev_cache_command(id: String) -> Result<scanners::dev_caches::DevCacheCleanResult, AltoError> {
+    ManagedPolicy::load().check_destructive("Developer cache cleanup", &[]).map_err(AltoError::blocked)?;
+    tauri::async_runtime::spawn_blocking(move || scanners::dev_caches::clean_dev_cache(&id))
+        .await?
+        .map_err(AltoError::from)
+}
+
+/// JetBrains IDE caches/settings/logs grouped by product and version.
+#[tauri::command]
+async fn scan_jetbrains_command() -> Result<Vec<scanners::jetbrains::JetBrainsProduct>, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::jetbrains::scan_jetbrains)
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Clean one JetBrains IDE version; `settings` lists the settings folders the user picked to remove too.
+#[tauri::command]
+async fn clean_jetbrains_version_command(data_dir_name: String, settings: Option<Vec<String>>) -> Result<scanners::jetbrains::JetBrainsCleanResult, AltoError> {
+    ManagedPolicy::load().check_destructive("JetBrains cleanup", &[]).map_err(AltoError::blocked)?;
+    let settings = settings.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || scanners::jetbrains::clean_jetbrains_version(&data_dir_name, &settings))
+        .await?
+        .map_err(AltoError::from)
+}
+
+/// Android SDK system images, build-tools and emulator disks.
+#[tauri::command]
+async fn scan_android_sdk_command() -> Result<scanners::android::AndroidSdkReport, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::android::scan_android_sdk)
+        .await
+        .map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn remove_android_items_command(ids: Vec<String>) -> Result<scanners::android::AndroidCleanResult, AltoError> {
+    ManagedPolicy::load().check_destructive("Android SDK cleanup", &[]).map_err(AltoError::blocked)?;
+    tauri::async_runtime::spawn_blocking(move || scanners::android::remove_android_items(&ids))
+        .await
+        .map_err(AltoError::from)
+}
+
+/// Third-party preference panes, flagged when their app is gone or they no longer load.
+#[tauri::command]
+async fn scan_pref_panes_command() -> Result<Vec<scanners::pref_panes::PrefPaneItem>, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::pref_panes::scan_pref_panes)
+        .await
+        .map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn remove_pref_pane_command(path: String) -> Result<(), AltoError> {
+    mcp::safety_gate::check_paths("Preference pane removal", std::slice::from_ref(&path), &ManagedPolicy::load(), &ContextStore::load())
+        .map_err(AltoError::blocked)?;
+    scanners::pref_panes::remove_pref_pane(path).await.map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn get_maintenance_tasks_command() -> Vec<scanners::maintenance::MaintenanceTask> {
+    scanners::maintenance::get_tasks()
+}
+
+#[tauri::command]
+async fn run_maintenance_task_command(id: String) -> Result<String, AltoError> {
+    scanners::maintenance::run_task(&id).await.map_err(AltoError::from)
+}
+
+#[tauri::command]
+async fn scan_privacy_command() -> Vec<scanners::privacy::PrivacyItem> {
+    scanners::privacy::scan_privacy()
+}
+
+/// Browsers that must be quit before their privacy data can be cleaned.
+#[tauri::command]
+async fn get_running_browsers_command() -> Vec<scanners::privacy::RunningBrowser> {
+    scanners::privacy::running_browsers()
+}
+
+const QUIT_APP_TIMEOUT_SECS: u64 = 10;
+
+/// Gracefully quit an app by bundle id; with `force`, kill it if it hasn't quit after the timeout.
+#[tauri::command]
+async fn quit_app_command(bundle_id: String, force: Option<bool>) -> Result<scanners::process::QuitResult, AltoError> {
+    let force = force.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || {
+        scanners::process::quit_app(&bundle_id, force, std::time::Duration::from_secs(QUIT_APP_TIMEOUT_SECS))
+    })
+    .await?
+    .map_err(AltoError::from)
+}
+
+/// Clean a browser data file. History and cookie databases only lose the rows `options` picks (by
+/// default all history, and every cookie except those of sites with a saved password); other files
+/// are trashed. With `quit_browser` the owning browser is quit first ("Quit Chrome and clean"), and
+/// with `relaunch` it is started again afterwards. In dry-run mode nothing is quit or removed and
+/// the plan is returned.
+#[tauri::command]
+async fn clean_privacy_item_command(
+    path: String,
+    options: Option<scanners::browser_db::PrivacyCleanOptions>,
+    quit_browser: Option<bool>,
+    relaunch: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<Option<mcp::dry_run::DryRunReport>, AltoError> {
+    ContextStore::load().check_protected("Privacy cleanup", std::slice::from_ref(&path)).map_err(AltoError::blocked)?;
+    mcp::self_protect::check("Privacy cleanup", &path).map_err(AltoError::blocked)?;
+    if mcp::dry_run::enabled(dry_run) {
+        ManagedPolicy::load().check_excluded("Privacy cleanup", std::slice::from_ref(&path)).map_err(AltoError::blocked)?;
+        let method = if scanners::browser_db::supports(Path::new(&path)) { "delete_rows" } else { "trash" };
+        return Ok(Some(mcp::dry_run::plan("Privacy cleanup", std::slice::from_ref(&path), method)));
+    }
+    ManagedPolicy::load().check_destructive("Privacy cleanup", std::slice::from_ref(&path)).map_err(AltoError::blocked)?;
+    let browser = scanners::privacy::browser_for_path(&path);
+    let mut relaunch_id = None;
+    if let (true, Some((name, bundle_id))) = (quit_browser.unwrap_or(false), browser) {
+        let quit = tauri::async_runtime::spawn_blocking(move || {
+            scanners::process::quit_app(bundle_id, false, std::time::Duration::from_secs(QUIT_APP_TIMEOUT_SECS))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        if !quit.quit {
+            return Err(AltoError::app_running(name, format!("{} did not quit. Close it manually and try again.", name)));
+        }
+        if quit.was_running && relaunch.unwrap_or(false) {
+            relaunch_id = Some(bundle_id);
+        }
+    } else if let Some((name, _)) = browser.filter(|(_, id)| scanners::process::is_bundle_id_running(id)) {
+        return Err(AltoError::app_running(name, format!("Please close {} to clean this item.", name)));
+    }
+    let options = options.unwrap_or_default();
+    let result = tauri::async_runtime::spawn_blocking(move || scanners::privacy::clean_privacy_item(&path, &options)).await?;
+    if let Some(bundle_id) = relaunch_id {
+        // Relaunch even if the clean failed, so the user gets their browser back
+        let _ = scanners::process::relaunch_app(bundle_id);
+    }
+    result.map(|_| None).map_err(AltoError::from)
 }
 
 #[derive(serde::Serialize)]
@@ -479,96 +2008,249 @@ struct TrashScanResult {
     item_count: usize,
     total_size_bytes: u64,
     items: Vec<String>,
+    /// Size, trash date and original location of each item in `items`
+    entries: Vec<scanners::trash::TrashItem>,
+    /// This account's Trash on each mounted external drive, not counted in the totals above
+    volumes: Vec<scanners::trash::VolumeTrash>,
 }
 
 #[tauri::command]
-async fn scan_trash_command() -> Result<TrashScanResult, String> {
+async fn scan_trash_command() -> Result<TrashScanResult, AltoError> {
     let trash_dir = dirs::home_dir()
         .ok_or("Could not find home directory")?
         .join(".Trash");
 
     if !trash_dir.exists() {
-        return Ok(TrashScanResult { item_count: 0, total_size_bytes: 0, items: vec![] });
+        return Ok(TrashScanResult { item_count: 0, total_size_bytes: 0, items: vec![], entries: vec![], volumes: vec![] });
     }
 
-    let mut total_size: u64 = 0;
-    let mut items: Vec<String> = Vec::new();
-
-    fn dir_size(path: &std::path::Path) -> u64 {
-        let mut size = 0u64;
-        if let Ok(entries) = std::fs::read_dir(path) {
-            for entry in entries.flatten() {
-                let p = entry.path();
-                if p.is_file() {
-                    size += entry.metadata().map(|m| m.len()).unwrap_or(0);
-                } else if p.is_dir() {
-                    size += dir_size(&p);
-                }
-            }
-        }
-        size
-    }
+    let (entries, volumes) = tauri::async_runtime::spawn_blocking(move || {
+        (scanners::trash::list_trash(&trash_dir, Path::new("/")), scanners::trash::volume_trashes())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let total_size: u64 = entries.iter().map(|e| e.size_bytes).sum();
+    let items: Vec<String> = entries.iter().map(|e| e.name.clone()).collect();
 
-    if let Ok(entries) = std::fs::read_dir(&trash_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-            if name.starts_with('.') { continue; }
-            let size = if path.is_dir() { dir_size(&path) } else { entry.metadata().map(|m| m.len()).unwrap_or(0) };
-            total_size += size;
-            items.push(name);
-        }
-    }
+    ContextStore::load().record_storage_segments(&[("Trash", total_size)]);
 
     Ok(TrashScanResult {
         item_count: items.len(),
         total_size_bytes: total_size,
         items,
+        entries,
+        volumes,
+    })
+}
+
+/// Permanently delete only the chosen items from ~/.Trash or an external drive's Trash.
+#[tauri::command]
+async fn delete_trash_items_command(paths: Vec<String>) -> Result<serde_json::Value, AltoError> {
+    ManagedPolicy::load().check_destructive("Empty Trash", &paths).map_err(AltoError::blocked)?;
+    ContextStore::load().check_protected("Empty Trash", &paths).map_err(AltoError::blocked)?;
+    let trash_dir = dirs::home_dir().ok_or("Could not find home directory")?.join(".Trash");
+    let (removed, bytes_freed, errors) = tauri::async_runtime::spawn_blocking(move || {
+        let mut trash_dirs = vec![trash_dir];
+        trash_dirs.extend(scanners::trash::volume_trash_dirs().into_iter().map(|(_, _, trash)| trash));
+        scanners::trash::delete_trash_items(&trash_dirs, &paths)
     })
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(serde_json::json!({
+        "removed": removed,
+        "bytes_freed": bytes_freed,
+        "errors": errors
+    }))
+}
+
+/// Put an item back from ~/.Trash to where it was trashed from, or into `destination`.
+#[tauri::command]
+async fn restore_trash_item_command(name: String, destination: Option<String>) -> Result<String, AltoError> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    let destination = match destination {
+        Some(d) => Some(canonicalize_and_validate_path(d.trim(), std::slice::from_ref(&home))?),
+        None => None,
+    };
+    let trash_dir = home.join(".Trash");
+    let restored = scanners::trash::restore_trash_item(&trash_dir, &name, destination.as_deref())?;
+    scanners::space_lens::invalidate_cached_sizes(&restored);
+    Ok(restored)
 }
 
+/// Empty the Trash through Finder (covers iCloud items and every drive's Trash). With
+/// `include_external_volumes` false, trashed items on external drives are kept: when any exist,
+/// ~/.Trash is emptied directly instead, since Finder can't leave them out. Without Automation
+/// access to Finder this fails with `automation_permission` unless `empty_without_finder` is set,
+/// in which case the local and drive Trashes are emptied directly (iCloud items stay).
 #[tauri::command]
-async fn empty_trash_command() -> Result<serde_json::Value, String> {
-    // Count items in ~/.Trash first for reporting
+async fn empty_trash_command(
+    include_external_volumes: Option<bool>,
+    empty_without_finder: Option<bool>,
+) -> Result<serde_json::Value, AltoError> {
+    ManagedPolicy::load().check_destructive("Empty Trash", &[]).map_err(AltoError::blocked)?;
+    let include_volumes = include_external_volumes.unwrap_or(true);
+    // Measure every item first so what Finder removed can be reported exactly
     let trash_dir = dirs::home_dir()
         .ok_or("Could not find home directory")?
         .join(".Trash");
+    let listed_dir = trash_dir.clone();
+    let (before, volumes) = tauri::async_runtime::spawn_blocking(move || {
+        (scanners::trash::list_trash(&listed_dir, Path::new("/")), scanners::trash::volume_trashes())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
 
-    let mut pre_count = 0usize;
-
-    if trash_dir.exists() {
-        if let Ok(entries) = std::fs::read_dir(&trash_dir) {
-            for entry in entries.flatten() {
-                let name = entry.path().file_name().unwrap_or_default().to_string_lossy().to_string();
-                if !name.starts_with('.') { pre_count += 1; }
-            }
-        }
+    if !include_volumes && !volumes.is_empty() {
+        let (removed, bytes_freed, errors) = scanners::trash::empty_trash_directly(&trash_dir, None);
+        return Ok(serde_json::json!({
+            "removed": removed,
+            "bytes_freed": bytes_freed,
+            "method": "direct",
+            "volumes_kept": volumes.iter().map(|v| v.volume.clone()).collect::<Vec<_>>(),
+            "errors": errors
+        }));
     }
 
     // Use AppleScript to empty ALL Finder Trash (including iCloud-backed items)
     // This is the same as clicking "Empty Trash" in Finder
-    let output = std::process::Command::new("osascript")
-        .arg("-e")
-        .arg("tell application \"Finder\" to empty trash")
-        .output()
-        .map_err(|e| format!("Failed to run osascript: {}", e))?;
-
-    if !output.status.success() {
-        let err = String::from_utf8_lossy(&output.stderr);
+    match scanners::automation::run_osascript("tell application \"Finder\" to empty trash")? {
+        ScriptOutcome::Ok(_) => {}
         // If Finder reports "already empty", that's fine
-        if !err.contains("empty") {
-            return Err(format!("AppleScript error: {}", err));
+        ScriptOutcome::Failed(err) if err.contains("empty") => {}
+        ScriptOutcome::Failed(err) => return Err(format!("AppleScript error: {}", err).into()),
+        ScriptOutcome::PermissionDenied if !empty_without_finder.unwrap_or(false) => {
+            return Err(scanners::automation::AutomationPermissionError::new("Emptying the Trash").into());
+        }
+        ScriptOutcome::PermissionDenied => {
+            // The caller agreed to skip Finder; empty the local Trash ourselves
+            let (mut removed, mut bytes_freed, mut errors) = scanners::trash::empty_trash_directly(&trash_dir, None);
+            for volume in volumes.iter().filter(|_| include_volumes) {
+                let (r, b, e) = scanners::trash::empty_trash_directly(Path::new(&volume.path), None);
+                removed += r;
+                bytes_freed += b;
+                errors.extend(e);
+            }
+            let permission = scanners::automation::AutomationPermissionError::new("Emptying iCloud Trash");
+            return Ok(serde_json::json!({
+                "removed": removed,
+                "bytes_freed": bytes_freed,
+                "method": "direct",
+                "needs_automation_permission": true,
+                "settings_url": permission.settings_url,
+                "message": permission.message,
+                "errors": errors
+            }));
         }
     }
 
-    // Report back — since iCloud items may have been included, report what we know
+    // Whatever is no longer on disk was emptied; iCloud-only items have no local size to report
+    let measured: Vec<scanners::trash::TrashItem> = before
+        .into_iter()
+        .chain(volumes.into_iter().filter(|_| include_volumes).flat_map(|v| v.items))
+        .collect();
+    let (gone, left): (Vec<_>, Vec<_>) = measured
+        .into_iter()
+        .partition(|item| std::fs::symlink_metadata(&item.path).is_err());
+    let home_trash_left: u64 = left.iter().filter(|i| Path::new(&i.path).starts_with(&trash_dir)).map(|i| i.size_bytes).sum();
+    ContextStore::load().record_storage_segments(&[("Trash", home_trash_left)]);
     Ok(serde_json::json!({
-        "removed": pre_count,
-        "bytes_freed": 0, // Can't easily measure iCloud items pre-deletion
+        "removed": gone.len(),
+        "bytes_freed": gone.iter().map(|i| i.size_bytes).sum::<u64>(),
+        "not_removed": left.iter().map(|i| i.path.clone()).collect::<Vec<_>>(),
         "method": "finder_applescript"
     }))
 }
 
+/// Permanently remove only ~/.Trash items trashed more than `days` days ago (defaults to the UserPrefs threshold),
+/// plus the same on external drives with `include_external_volumes`.
+#[tauri::command]
+async fn empty_trash_older_than_command(days: Option<u32>, include_external_volumes: Option<bool>) -> Result<serde_json::Value, AltoError> {
+    ManagedPolicy::load().check_destructive("Empty Trash", &[]).map_err(AltoError::blocked)?;
+    let days = days.unwrap_or(ContextStore::load().user_preferences.trash_min_age_days);
+    let cutoff = scanners::trash::age_cutoff(days).ok_or("Choose an age of at least one day")?;
+    let trash_dir = dirs::home_dir().ok_or("Could not find home directory")?.join(".Trash");
+    let (removed, bytes_freed, errors) = tauri::async_runtime::spawn_blocking(move || {
+        let mut result = scanners::trash::empty_trash_directly(&trash_dir, Some(cutoff));
+        if include_external_volumes.unwrap_or(false) {
+            for (_, _, volume_trash) in scanners::trash::volume_trash_dirs() {
+                let (removed, bytes_freed, errors) = scanners::trash::empty_trash_directly(&volume_trash, Some(cutoff));
+                result.0 += removed;
+                result.1 += bytes_freed;
+                result.2.extend(errors);
+            }
+        }
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(serde_json::json!({
+        "removed": removed,
+        "bytes_freed": bytes_freed,
+        "older_than_days": days,
+        "errors": errors
+    }))
+}
+
+/// Other accounts' Trash usage (admin opt-in, via the privileged helper).
+#[tauri::command]
+async fn scan_other_users_trash_command() -> Result<Vec<scanners::trash::OtherUserTrash>, AltoError> {
+    scanners::trash::other_users_trash().await.map_err(AltoError::from)
+}
+
+/// Permanently empty another account's Trash. `confirm_user` must repeat the account name,
+/// so each account is confirmed separately.
+#[tauri::command]
+async fn empty_user_trash_command(user: String, confirm_user: String) -> Result<scanners::trash::OtherUserTrashCleanResult, AltoError> {
+    if user.is_empty() || confirm_user != user {
+        return Err(AltoError::invalid(format!("Confirm emptying the Trash of {} by entering the account name", user)));
+    }
+    let trash = vec![format!("/Users/{}/.Trash", user)];
+    ManagedPolicy::load().check_destructive("Empty Trash", &trash).map_err(AltoError::blocked)?;
+    ContextStore::load().check_protected("Empty Trash", &trash).map_err(AltoError::blocked)?;
+    scanners::trash::empty_other_user_trash(&user).await.map_err(AltoError::from)
+}
+
+/// Clutter in /Users/Shared (admin opt-in).
+#[tauri::command]
+async fn scan_shared_folder_command() -> Result<Vec<scanners::trash::SharedFolderItem>, AltoError> {
+    tauri::async_runtime::spawn_blocking(scanners::trash::shared_folder_items)
+        .await?
+        .map_err(AltoError::from)
+}
+
+/// System Settings > Privacy & Security > Profiles, where suspicious configuration profiles are removed.
+#[cfg(target_os = "macos")]
+#[tauri::command]
+async fn open_profiles_settings_command() -> Result<(), AltoError> {
+    std::process::Command::new("open")
+        .arg(scanners::config_profiles::PROFILES_SETTINGS_URL)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+async fn open_profiles_settings_command() -> Result<(), AltoError> {
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+#[tauri::command]
+async fn open_automation_settings_command() -> Result<(), AltoError> {
+    std::process::Command::new("open")
+        .arg(scanners::automation::AUTOMATION_SETTINGS_URL)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+async fn open_automation_settings_command() -> Result<(), AltoError> {
+    Ok(())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -579,8 +2261,11 @@ pub fn run() {
         .plugin(tauri_plugin_positioner::init())
         .setup(|app| {
             app.manage(AppState {
-                scheduler: Scheduler::new(),
+                scheduler: Scheduler::new(app.handle().clone()),
+                smart_scan_cache: std::sync::Mutex::new(None),
             });
+            // Before anything saves the store, which would drop a key kept there by earlier versions
+            scanners::virustotal::migrate_api_key();
 
             // System Tray Setup
             use tauri::menu::{Menu, MenuItem};
@@ -627,6 +2312,10 @@ pub fn run() {
 
             scanners::monitor::start_monitor_thread(app.handle().clone());
             start_watcher(app.handle().clone());
+            scanners::size_index::start_background_indexing();
+            mcp::staging::start_janitor_thread();
+            mcp::backup::start_janitor_thread();
+            scanners::auto_clean::start_auto_clean_thread(app.handle().clone());
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -637,25 +2326,80 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             smart_scan_command,
-            scan_junk_command, 
+            scan_junk_command,
+            clean_category_command, 
             scan_large_files_command, 
+            cancel_large_files_scan_command,
+            scan_old_files_command,
+            find_compressible_folders_command,
+            compress_folder_command,
+            list_external_volumes_command,
             scan_space_lens_command,
+            scan_space_lens_compact_command,
+            space_lens_expand_node,
+            list_space_lens_snapshots_command,
+            space_lens_diff_command,
             scan_malware_command,
+            get_malware_definitions_command,
+            get_malware_scan_history_command,
+            get_xprotect_status_command,
+            get_virustotal_policy_command,
+            save_virustotal_policy_command,
+            virustotal_lookup_command,
+            quarantine_items_command,
+            list_quarantine_command,
+            restore_quarantined_command,
+            shred_quarantined_command,
+            update_malware_definitions_command,
+            open_profiles_settings_command,
             run_speed_task_command,
+            get_speed_recommendations_command,
+            scan_fonts_command,
+            deactivate_fonts_command,
             clean_items,
             schedule_task,
             get_system_stats_command,
             get_home_dir_command,
             scan_apps_command,
             uninstall_app_command,
+            scan_duplicate_apps_command,
+            scan_installers_command,
+            remove_app_copy_command,
             scan_outdated_apps_command,
             shred_path_command,
             scan_mail_command,
             clean_mail_command,
+            analyze_mail_store_command,
+            scan_xcode_command,
+            preview_xcode_group_command,
+            clean_xcode_group_command,
             scan_extensions_command,
             remove_extension_command,
+            scan_pref_panes_command,
+            scan_stale_node_modules_command,
+            scan_dev_caches_command,
+            clean_dev_cache_command,
+            scan_jetbrains_command,
+            clean_jetbrains_version_command,
+            scan_android_sdk_command,
+            remove_android_items_command,
+            scan_docker_command,
+            scan_vm_images_command,
+            analyze_dropped_paths_command,
+            scan_similar_photos_command,
+            scan_screenshots_command,
+            trash_old_screenshots_command,
+            move_screenshots_command,
+            prune_docker_command,
+            remove_pref_pane_command,
             preview_delete,
             confirm_delete,
+            cancel_delete_command,
+            undo_last_deletion_command,
+            list_review_queue_command,
+            revert_pre_clean_snapshot_command,
+            approve_review_items_command,
+            dismiss_review_items_command,
             get_mcp_context,
             reset_mcp_context_command,
             update_user_preferences_command,
@@ -664,13 +2408,57 @@ pub fn run() {
             run_maintenance_task_command,
             scan_privacy_command,
             clean_privacy_item_command,
+            get_running_browsers_command,
+            quit_app_command,
             scan_trash_command,
+            restore_trash_item_command,
+            delete_trash_items_command,
             empty_trash_command,
             start_deep_scan_command,
             cancel_deep_scan_command,
             scan_leftovers_command,
             move_paths_command,
-            open_full_disk_access_settings_command
+            open_full_disk_access_settings_command,
+            get_power_state_command,
+            get_storage_summary_command,
+            analyze_system_data_command,
+            scan_cloud_sync_command,
+            get_managed_policy_command,
+            get_interrupted_scans_command,
+            resume_interrupted_scan_command,
+            discard_interrupted_scan_command,
+            get_trends_command,
+            list_scan_profiles_command,
+            save_scan_profile_command,
+            delete_scan_profile_command,
+            organize_downloads_command,
+            get_download_rules_command,
+            save_download_rules_command,
+            list_excluded_paths,
+            list_protected_paths_command,
+            add_protected_path_command,
+            remove_protected_path_command,
+            add_excluded_path,
+            remove_excluded_path,
+            open_automation_settings_command,
+            empty_trash_older_than_command,
+            scan_other_users_trash_command,
+            empty_user_trash_command,
+            scan_shared_folder_command,
+            get_file_preview_command,
+            list_staged_items_command,
+            get_staging_policy_command,
+            save_staging_policy_command,
+            get_backup_policy_command,
+            save_backup_policy_command,
+            list_backups_command,
+            restore_backup_command,
+            delete_backup_command,
+            get_auto_clean_policy_command,
+            save_auto_clean_policy_command,
+            run_auto_clean_command,
+            restore_staged_items_command,
+            purge_staged_items_command
         ])
         .run(tauri::generate_context!())
         .expect("error while running Alto");
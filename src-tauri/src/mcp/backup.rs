@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::context_store::ContextStore;
+
+const JANITOR_INTERVAL_SECS: u64 = 6 * 3600;
+
+/// A compressed copy of what one cleanup removed: ~/.alto/backups/<timestamp>.tar.gz plus a
+/// `<timestamp>.json` manifest describing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backup {
+    /// The timestamp both files are named after
+    pub id: String,
+    pub archive: String,
+    pub created_at: String,
+    pub items: Vec<BackedUpItem>,
+    /// Size of the archive on disk
+    pub archive_bytes: u64,
+    /// Items left out for being larger than the policy's `max_item_bytes`
+    #[serde(default)]
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackedUpItem {
+    pub original_path: String,
+    pub size_bytes: u64,
+}
+
+pub fn backups_root() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("backups")
+}
+
+fn read_manifest(path: &Path) -> Option<Backup> {
+    std::fs::read_to_string(path).ok().and_then(|d| serde_json::from_str(&d).ok())
+}
+
+fn list_in(dir: &Path) -> Vec<Backup> {
+    let mut backups: Vec<Backup> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .filter_map(|p| read_manifest(&p))
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    backups
+}
+
+/// Backups, newest first.
+pub fn list_backups() -> Vec<Backup> {
+    list_in(&backups_root())
+}
+
+/// Archive member name for an absolute path: archives are rooted at `/` so they extract in place.
+fn member(path: &str) -> String {
+    path.trim_start_matches('/').to_string()
+}
+
+fn run_tar(args: &mut Command) -> Result<(), String> {
+    let out = args.output().map_err(|e| format!("Could not run tar: {}", e))?;
+    if out.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&out.stderr).trim().to_string())
+    }
+}
+
+fn create_in(dir: &Path, items: &[(String, u64)], max_item_bytes: u64) -> Result<Backup, String> {
+    let now = chrono::Local::now();
+    std::fs::create_dir_all(dir).map_err(|e| format!("Cannot create backup folder: {}", e))?;
+    let mut id = now.format("%Y-%m-%dT%H-%M-%S").to_string();
+    let mut n = 2;
+    while dir.join(format!("{}.json", id)).exists() {
+        id = format!("{}-{}", now.format("%Y-%m-%dT%H-%M-%S"), n);
+        n += 1;
+    }
+    let (kept, skipped): (Vec<_>, Vec<_>) =
+        items.iter().partition(|(_, size)| max_item_bytes == 0 || *size <= max_item_bytes);
+    let archive = dir.join(format!("{}.tar.gz", id));
+    if !kept.is_empty() {
+        // NUL-separated member list, so any file name (and any number of them) is safe
+        let list = dir.join(format!("{}.list", id));
+        let names: Vec<u8> = kept.iter().flat_map(|(p, _)| member(p).into_bytes().into_iter().chain([0])).collect();
+        std::fs::write(&list, names).map_err(|e| e.to_string())?;
+        let result = run_tar(Command::new("tar").arg("-czf").arg(&archive).arg("-C").arg("/").arg("--null").arg("-T").arg(&list));
+        let _ = std::fs::remove_file(&list);
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&archive);
+            return Err(format!("Backup failed: {}", e));
+        }
+    }
+    let backup = Backup {
+        id: id.clone(),
+        archive: archive.to_string_lossy().to_string(),
+        created_at: now.to_rfc3339(),
+        items: kept.iter().map(|(p, size)| BackedUpItem { original_path: p.clone(), size_bytes: *size }).collect(),
+        archive_bytes: std::fs::metadata(&archive).map(|m| m.len()).unwrap_or(0),
+        skipped: skipped.iter().map(|(p, _)| p.clone()).collect(),
+    };
+    let json = serde_json::to_string_pretty(&backup).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(format!("{}.json", id)), json).map_err(|e| e.to_string())?;
+    Ok(backup)
+}
+
+/// Archive `items` (path, size) before a cleanup removes them.
+pub fn back_up(items: &[(String, u64)]) -> Result<Backup, String> {
+    let max_item_bytes = ContextStore::load().user_preferences.backup.max_item_bytes;
+    create_in(&backups_root(), items, max_item_bytes)
+}
+
+fn restore_from(backup: &Backup, paths: Option<&[String]>) -> (usize, Vec<String>) {
+    let mut errors = Vec::new();
+    let wanted: Vec<&BackedUpItem> = backup
+        .items
+        .iter()
+        .filter(|i| paths.map(|p| p.contains(&i.original_path)).unwrap_or(true))
+        .filter(|i| {
+            // Never overwrite something that reappeared at the original location
+            let exists = std::fs::symlink_metadata(&i.original_path).is_ok();
+            if exists {
+                errors.push(format!("{} already exists; not restoring over it", i.original_path));
+            }
+            !exists
+        })
+        .collect();
+    if wanted.is_empty() {
+        return (0, errors);
+    }
+    let mut cmd = Command::new("tar");
+    cmd.arg("-xzkf").arg(&backup.archive).arg("-C").arg("/");
+    cmd.args(wanted.iter().map(|i| member(&i.original_path)));
+    match run_tar(&mut cmd) {
+        Ok(()) => (wanted.len(), errors),
+        Err(e) => {
+            errors.push(format!("Restore failed: {}", e));
+            (wanted.iter().filter(|i| std::fs::symlink_metadata(&i.original_path).is_ok()).count(), errors)
+        }
+    }
+}
+
+/// Extract a backup's items (or only `paths`) back to where they were.
+pub fn restore(id: &str, paths: Option<&[String]>) -> Result<(usize, Vec<String>), String> {
+    let backup = read_manifest(&backups_root().join(format!("{}.json", id))).ok_or("No such backup")?;
+    Ok(restore_from(&backup, paths))
+}
+
+fn remove_in(dir: &Path, id: &str) -> Result<(), String> {
+    let manifest = dir.join(format!("{}.json", id));
+    if !manifest.exists() {
+        return Err("No such backup".to_string());
+    }
+    match std::fs::remove_file(dir.join(format!("{}.tar.gz", id))) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.to_string()),
+        _ => {}
+    }
+    std::fs::remove_file(manifest).map_err(|e| e.to_string())
+}
+
+pub fn delete_backup(id: &str) -> Result<(), String> {
+    remove_in(&backups_root(), id)
+}
+
+/// Delete backups older than `retention_days`.
+pub fn purge_expired(retention_days: u32) -> (usize, Vec<String>) {
+    let cutoff = chrono::Local::now() - chrono::Duration::days(retention_days as i64);
+    let mut purged = 0;
+    let mut errors = Vec::new();
+    for backup in list_backups() {
+        let expired = chrono::DateTime::parse_from_rfc3339(&backup.created_at).map(|t| t < cutoff).unwrap_or(false);
+        if expired {
+            match delete_backup(&backup.id) {
+                Ok(()) => purged += 1,
+                Err(e) => errors.push(format!("{}: {}", backup.id, e)),
+            }
+        }
+    }
+    (purged, errors)
+}
+
+/// Background janitor: deletes expired backups at launch and every few hours.
+pub fn start_janitor_thread() {
+    std::thread::spawn(|| loop {
+        let retention_days = ContextStore::load().user_preferences.backup.retention_days;
+        let (purged, errors) = purge_expired(retention_days);
+        if purged > 0 || !errors.is_empty() {
+            println!("🧹 Backup janitor deleted {} backup(s), {} error(s)", purged, errors.len());
+        }
+        std::thread::sleep(std::time::Duration::from_secs(JANITOR_INTERVAL_SECS));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_up_and_restores_in_place() {
+        let work = tempfile::tempdir().unwrap();
+        let backups = tempfile::tempdir().unwrap();
+        let prefs = work.path().join("com.example.app.plist");
+        let big = work.path().join("big.bin");
+        std::fs::write(&prefs, b"<plist/>").unwrap();
+        std::fs::write(&big, vec![0u8; 100]).unwrap();
+        let items = vec![(prefs.to_string_lossy().to_string(), 8), (big.to_string_lossy().to_string(), 100)];
+
+        let backup = create_in(backups.path(), &items, 50).unwrap();
+        assert_eq!(backup.items.len(), 1);
+        assert_eq!(backup.skipped, vec![big.to_string_lossy().to_string()]);
+        assert_eq!(list_in(backups.path()).len(), 1);
+
+        std::fs::remove_file(&prefs).unwrap();
+        assert_eq!(restore_from(&backup, None), (1, vec![]));
+        assert_eq!(std::fs::read(&prefs).unwrap(), b"<plist/>");
+
+        // Already back: reported, not overwritten
+        let (restored, errors) = restore_from(&backup, None);
+        assert_eq!((restored, errors.len()), (0, 1));
+
+        remove_in(backups.path(), &backup.id).unwrap();
+        assert!(list_in(backups.path()).is_empty());
+    }
+}
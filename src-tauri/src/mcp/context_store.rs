@@ -1,11 +1,96 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use serde::{Deserialize, Serialize};
 
+use super::path_norm::{path_within, same_path};
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DeletionRecord {
     pub timestamp: String,
     pub paths_deleted: Vec<String>,
     pub total_bytes_freed: u64,
+    /// Local APFS snapshot taken right before this cleanup ("pre-clean restore point")
+    #[serde(default)]
+    pub snapshot: Option<String>,
+    /// Where each deleted path went (its Trash item or staged copy), so the cleanup can be undone
+    #[serde(default)]
+    pub locations: HashMap<String, String>,
+    /// Set once the cleanup was undone; `paths_deleted` then lists only what couldn't be put back
+    #[serde(default)]
+    pub undone: bool,
+}
+
+/// A path `confirm_delete` refused, kept until the user approves an override or dismisses it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewItem {
+    pub id: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// "safety" (file index verdict) | "excluded" (user exclusion) | "whitelist" (skip pattern) | "protected" (user protection) | "managed" (administrator policy)
+    pub blocked_by: String,
+    pub reason: String,
+    pub queued_at: String,
+}
+
+/// Junk per category and disk state captured at the end of each junk scan, for trend charts.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ScanSnapshot {
+    pub timestamp: String,
+    pub junk_by_category: HashMap<String, u64>,
+    pub junk_total_bytes: u64,
+    pub disk_free_bytes: u64,
+    /// Last known app count (from the most recent app scan)
+    pub apps_installed: Option<usize>,
+}
+
+/// Outcome of one malware scan, kept so scheduled scans only alert about what's new.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct MalwareScanRecord {
+    pub timestamp: String,
+    pub threats: Vec<String>,
+    pub definitions_version: u64,
+    /// Run by the scheduler rather than the user
+    pub scheduled: bool,
+}
+
+/// A suspected threat moved into the quarantine vault, kept until the user restores or shreds it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct QuarantinedItem {
+    pub id: String,
+    pub original_path: String,
+    /// ~/.alto/quarantine/<id>/<name>
+    pub vault_path: String,
+    /// What the malware scan called it ("Known Adware/PUP found: Genieo ...")
+    pub threat: String,
+    /// Unix permissions it had before being locked, put back on restore
+    pub original_mode: u32,
+    pub size_bytes: u64,
+    pub is_directory: bool,
+    pub quarantined_at: String,
+}
+
+/// Threats in `threats` the previous scan didn't report.
+pub fn new_threats(previous: Option<&MalwareScanRecord>, threats: &[String]) -> Vec<String> {
+    threats.iter().filter(|t| !previous.is_some_and(|p| p.threats.contains(t))).cloned().collect()
+}
+
+/// Alerts still owed after a scan: earlier pending ones whose threat is still there, plus what a
+/// scheduled scan newly found. A scan the user ran showed them everything, so nothing is owed.
+pub fn pending_alerts(pending: &[String], new: &[String], threats: &[String], scheduled: bool) -> Vec<String> {
+    if !scheduled {
+        return vec![];
+    }
+    let mut owed: Vec<String> = pending.iter().filter(|t| threats.contains(t)).cloned().collect();
+    for threat in new {
+        if !owed.contains(threat) {
+            owed.push(threat.clone());
+        }
+    }
+    owed
 }
 
 /// Live system event recorded by the watcher (app installs, downloads, etc.)
@@ -17,18 +102,316 @@ pub struct SystemEvent {
     pub path: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UserPrefs {
     pub always_skip_patterns: Vec<String>,
     pub auto_confirm_caches: bool,
+    pub quiet_mode: QuietModePolicy,
+    /// Smart scan results younger than this are returned from cache (0 disables caching)
+    pub smart_scan_cache_ttl_secs: u64,
+    /// Named scan profiles, selectable when starting scans and referenced by scheduled jobs
+    pub scan_profiles: Vec<ScanProfile>,
+    /// Folders/files (`~` allowed) that scans skip and deletions never touch
+    pub excluded_paths: Vec<String>,
+    /// Folders/files (`~` allowed) nothing may remove: no scan lists them and every delete, shred,
+    /// uninstall or move refuses them, and anything containing them
+    pub protected_paths: Vec<String>,
+    pub staging: StagingPolicy,
+    pub backup: BackupPolicy,
+    pub scan_mode: ScanMode,
+    /// Only Trash items trashed more than this many days ago count as junk (0 = whole Trash)
+    pub trash_min_age_days: u32,
+    /// Folders searched for stale node_modules (`~` allowed); empty means ~/Projects, ~/dev and friends
+    pub project_roots: Vec<String>,
+    /// Take a local APFS snapshot before cleanups over 5 GB so they can be reverted (needs the helper)
+    pub pre_clean_snapshot: bool,
+    /// Admin opt-in: report other accounts' Trash and /Users/Shared (needs the privileged helper)
+    pub include_other_users: bool,
+    pub downloads: DownloadsPolicy,
+    /// Destructive commands only report what they would remove unless a call asks otherwise
+    pub dry_run: bool,
+    pub auto_clean: AutoCleanPolicy,
+    pub virustotal: VirusTotalPolicy,
 }
 
+/// "background" throttles scan threads (low QoS + throttled disk IO) so the Mac stays responsive;
+/// "performance" lets scans run at normal priority.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanMode {
+    Performance,
+    #[default]
+    Background,
+}
+
+/// Optional grace period: cleaned files go to ~/.alto/staged instead of the Trash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StagingPolicy {
+    pub enabled: bool,
+    /// Staged items are permanently deleted after this many days
+    pub retention_days: u32,
+}
+
+impl Default for StagingPolicy {
+    fn default() -> Self {
+        Self { enabled: false, retention_days: 7 }
+    }
+}
+
+/// Optional belt-and-braces copy: cleaned items are archived in ~/.alto/backups before removal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupPolicy {
+    pub enabled: bool,
+    /// Backups are deleted after this many days
+    pub retention_days: u32,
+    /// Items larger than this aren't archived (0 = no limit)
+    pub max_item_bytes: u64,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        Self { enabled: false, retention_days: 30, max_item_bytes: 1024 * 1024 * 1024 }
+    }
+}
+
+/// Opt-in background cleanup that kicks in when the startup disk runs low on free space.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoCleanPolicy {
+    pub enabled: bool,
+    /// Auto-clean runs once free space drops below this
+    pub min_free_bytes: u64,
+    /// Junk categories cleaned without asking (e.g. "User Caches", "User Logs", "Trash Bin")
+    pub categories: Vec<String>,
+    /// "Trash Bin" only empties items trashed more than this many days ago
+    pub trash_min_age_days: u32,
+}
+
+impl Default for AutoCleanPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_free_bytes: 10 * 1024 * 1024 * 1024,
+            categories: vec!["User Caches".to_string(), "User Logs".to_string(), "Trash Bin".to_string()],
+            trash_min_age_days: 30,
+        }
+    }
+}
+
+/// Opt-in VirusTotal lookups of suspicious files by SHA-256. Files are never uploaded.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct VirusTotalPolicy {
+    pub enabled: bool,
+    /// The user's own VirusTotal API key as entered in Settings. It's kept in the Keychain and never
+    /// written to the store or sent back to the UI.
+    #[serde(skip_serializing)]
+    pub api_key: String,
+    /// Whether a key is saved in the Keychain; filled in for the UI
+    #[serde(skip_deserializing)]
+    pub has_api_key: bool,
+}
+
+/// Rules for sorting ~/Downloads; the first rule matching a file's extension wins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadsPolicy {
+    /// Apply move rules to new downloads as the watcher sees them
+    pub auto_organize: bool,
+    pub rules: Vec<DownloadRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DownloadRule {
+    pub name: String,
+    /// Lowercase, without the dot
+    pub extensions: Vec<String>,
+    /// Subfolder of Downloads, or an absolute / `~` path; unused by "flag_stale" rules
+    pub destination: String,
+    /// Only files at least this many days old match
+    pub min_age_days: u32,
+    pub action: DownloadRuleAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadRuleAction {
+    #[default]
+    Move,
+    /// Report as a deletion candidate (e.g. installers already used)
+    FlagStale,
+}
+
+impl Default for DownloadsPolicy {
+    fn default() -> Self {
+        let rule = |name: &str, extensions: &[&str], destination: &str, min_age_days: u32, action: DownloadRuleAction| DownloadRule {
+            name: name.to_string(),
+            extensions: extensions.iter().map(|e| e.to_string()).collect(),
+            destination: destination.to_string(),
+            min_age_days,
+            action,
+        };
+        Self {
+            auto_organize: false,
+            rules: vec![
+                rule("Stale installers", &["dmg", "pkg", "mpkg"], "", 14, DownloadRuleAction::FlagStale),
+                rule("Installers", &["dmg", "pkg", "mpkg"], "Installers", 0, DownloadRuleAction::Move),
+                rule("Images", &["png", "jpg", "jpeg", "heic", "gif", "webp", "svg"], "Images", 0, DownloadRuleAction::Move),
+                rule("Documents", &["pdf", "doc", "docx", "pages", "txt", "rtf", "key", "ppt", "pptx"], "Documents", 0, DownloadRuleAction::Move),
+                rule("Spreadsheets", &["xls", "xlsx", "csv", "numbers"], "Spreadsheets", 0, DownloadRuleAction::Move),
+                rule("Archives", &["zip", "rar", "7z", "tar", "gz", "tgz", "bz2", "xz"], "Archives", 0, DownloadRuleAction::Move),
+                rule("Audio & Video", &["mp3", "m4a", "wav", "flac", "mp4", "mov", "mkv", "avi"], "Media", 0, DownloadRuleAction::Move),
+            ],
+        }
+    }
+}
+
+/// A named scan scope, e.g. a nightly "dev caches only" and a weekly "full home" profile.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ScanProfile {
+    pub name: String,
+    /// Folders (relative to home) the scan is limited to; empty means everything
+    pub scopes: Vec<String>,
+    /// Junk categories to leave out (e.g. "Trash Bin", "Old Installers")
+    pub excluded_categories: Vec<String>,
+    pub max_files: Option<usize>,
+    pub timeout_secs: Option<u64>,
+}
+
+impl Default for UserPrefs {
+    fn default() -> Self {
+        Self {
+            always_skip_patterns: Vec::new(),
+            auto_confirm_caches: false,
+            quiet_mode: QuietModePolicy::default(),
+            smart_scan_cache_ttl_secs: 300,
+            scan_profiles: Vec::new(),
+            excluded_paths: Vec::new(),
+            protected_paths: Vec::new(),
+            staging: StagingPolicy::default(),
+            backup: BackupPolicy::default(),
+            scan_mode: ScanMode::default(),
+            trash_min_age_days: 0,
+            project_roots: Vec::new(),
+            pre_clean_snapshot: false,
+            include_other_users: false,
+            downloads: DownloadsPolicy::default(),
+            dry_run: false,
+            auto_clean: AutoCleanPolicy::default(),
+            virustotal: VirusTotalPolicy::default(),
+        }
+    }
+}
+
+/// How Alto behaves while Low Power Mode or a Focus (Do Not Disturb) is active.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietModePolicy {
+    pub respect_low_power: bool,
+    pub respect_focus: bool,
+    /// Alert types held back while quiet: "high_cpu" | "memory_full" | "app_installed" | "file_downloaded" | "auto_clean" | "malware"
+    pub suppressed_alerts: Vec<String>,
+}
+
+impl Default for QuietModePolicy {
+    fn default() -> Self {
+        Self {
+            respect_low_power: true,
+            respect_focus: true,
+            // Suspicious downloads are critical and are never suppressed by default
+            suppressed_alerts: vec![
+                "high_cpu".to_string(),
+                "memory_full".to_string(),
+                "app_installed".to_string(),
+                "file_downloaded".to_string(),
+            ],
+        }
+    }
+}
+
+/// True if `path` is one of `excluded` or lives inside one. Entries may start with `~`.
+/// Compared in NFC and, on case-insensitive filesystems, ignoring case.
+pub fn path_is_excluded(path: &str, excluded: &[String]) -> bool {
+    let home = dirs::home_dir().map(|h| h.to_string_lossy().to_string()).unwrap_or_default();
+    excluded.iter().any(|ex| {
+        let ex = if let Some(rest) = ex.strip_prefix('~') { format!("{}{}", home, rest) } else { ex.clone() };
+        path_within(path, &ex)
+    })
+}
+
+/// True if `path` is one of `protected`, lives inside one, or contains one (removing a folder
+/// would take what's protected inside with it).
+pub fn path_is_protected(path: &str, protected: &[String]) -> bool {
+    let home = dirs::home_dir().map(|h| h.to_string_lossy().to_string()).unwrap_or_default();
+    protected.iter().any(|pr| {
+        let pr = if let Some(rest) = pr.strip_prefix('~') { format!("{}{}", home, rest) } else { pr.clone() };
+        path_within(path, &pr) || path_within(&pr, path)
+    })
+}
+
+impl UserPrefs {
+    /// Everything scans leave out: excluded and protected paths.
+    pub fn skipped_paths(&self) -> Vec<String> {
+        self.excluded_paths.iter().chain(&self.protected_paths).cloned().collect()
+    }
+}
+
+/// Last measured size of a storage map segment ("Apps", "Documents", "Developer", "Trash").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedSize {
+    pub size_bytes: u64,
+    pub measured_at: String,
+}
+
+/// Historical speed of one unit of scan work (a deep scan template, a large-files mount point), used for ETAs.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ScanThroughput {
+    /// Files/bytes seen on the last run
+    pub files: u64,
+    pub bytes: u64,
+    /// Smoothed rates across runs
+    pub files_per_sec: f64,
+    pub bytes_per_sec: f64,
+}
+
+impl ScanThroughput {
+    /// Seconds the unit took last time at its smoothed rate.
+    pub fn expected_secs(&self) -> Option<f64> {
+        if self.files_per_sec > 0.0 { Some(self.files as f64 / self.files_per_sec) } else { None }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct ContextStore {
     pub last_scan_timestamp: Option<String>,
     pub deletion_history: Vec<DeletionRecord>,
     pub system_events: Vec<SystemEvent>,   // NEW: live events from watcher
     pub user_preferences: UserPrefs,
+    /// Segment sizes captured by regular scans, used for the storage bar without a fresh deep scan
+    pub storage_cache: HashMap<String, CachedSize>,
+    /// Keyed "<scanner>/<unit>", e.g. "deep_scan/Library/Caches" or "large_files//Volumes/Data"
+    pub scan_throughput: HashMap<String, ScanThroughput>,
+    /// Deletions blocked by the safety gate, awaiting review
+    pub review_queue: Vec<ReviewItem>,
+    pub scan_history: Vec<ScanSnapshot>,
+    /// Installed app count from the last app scan
+    pub apps_installed: Option<usize>,
+    pub malware_scans: Vec<MalwareScanRecord>,
+    /// New threats from scheduled scans that no notification has shown yet (quiet mode, focus)
+    pub pending_malware_alerts: Vec<String>,
+    /// Items in the quarantine vault, oldest first
+    pub quarantine: Vec<QuarantinedItem>,
+    /// context.json exists but didn't parse. Such a store holds none of the user's protections, so
+    /// it refuses destructive checks and is never saved over the file.
+    #[serde(skip)]
+    pub unreadable: bool,
 }
 
 impl ContextStore {
@@ -40,28 +423,58 @@ impl ContextStore {
     pub fn load() -> Self {
         let path = Self::store_path();
         if let Ok(data) = std::fs::read_to_string(&path) {
-            serde_json::from_str(&data).unwrap_or_default()
+            serde_json::from_str(&data).unwrap_or_else(|e| {
+                eprintln!("⚠️ {} could not be read: {}", path.display(), e);
+                Self { unreadable: true, ..Self::default() }
+            })
         } else {
             Self::default()
         }
     }
 
+    /// Several threads load, change and save the store, so write a temp file and rename it over
+    /// context.json: a concurrent `load` sees the old or the new file, never a partial one.
     pub fn save(&self) {
+        static SAVES: AtomicU64 = AtomicU64::new(0);
+        if self.unreadable {
+            return;
+        }
         let path = Self::store_path();
         if let Some(parent) = path.parent() {
             let _ = std::fs::create_dir_all(parent);
         }
         if let Ok(json) = serde_json::to_string_pretty(self) {
-            let _ = std::fs::write(path, json);
+            let n = SAVES.fetch_add(1, Ordering::Relaxed);
+            let tmp = path.with_extension(format!("json.{}.{}.tmp", std::process::id(), n));
+            if std::fs::write(&tmp, json).is_ok() && std::fs::rename(&tmp, &path).is_err() {
+                let _ = std::fs::remove_file(&tmp);
+            }
         }
     }
 
     pub fn record_deletion(&mut self, paths: Vec<String>, bytes_freed: u64) {
+        self.record_deletion_with_snapshot(paths, bytes_freed, None);
+    }
+
+    pub fn record_deletion_with_snapshot(&mut self, paths: Vec<String>, bytes_freed: u64, snapshot: Option<String>) {
+        self.record_deletion_with_locations(paths, bytes_freed, snapshot, HashMap::new());
+    }
+
+    pub fn record_deletion_with_locations(
+        &mut self,
+        paths: Vec<String>,
+        bytes_freed: u64,
+        snapshot: Option<String>,
+        locations: HashMap<String, String>,
+    ) {
         let now = chrono::Local::now().to_rfc3339();
         self.deletion_history.push(DeletionRecord {
             timestamp: now,
             paths_deleted: paths,
             total_bytes_freed: bytes_freed,
+            snapshot,
+            locations,
+            undone: false,
         });
         if self.deletion_history.len() > 100 {
             self.deletion_history.drain(0..self.deletion_history.len() - 100);
@@ -69,6 +482,94 @@ impl ContextStore {
         self.save();
     }
 
+    /// Append a trend snapshot. Several scans in quick succession collapse into one point per hour.
+    pub fn record_scan_snapshot(&mut self, junk_by_category: HashMap<String, u64>, disk_free_bytes: u64) {
+        let now = chrono::Local::now();
+        let hour = now.format("%Y-%m-%dT%H").to_string();
+        if self.scan_history.last().map(|s| s.timestamp.starts_with(&hour)).unwrap_or(false) {
+            self.scan_history.pop();
+        }
+        self.scan_history.push(ScanSnapshot {
+            timestamp: now.to_rfc3339(),
+            junk_total_bytes: junk_by_category.values().sum(),
+            junk_by_category,
+            disk_free_bytes,
+            apps_installed: self.apps_installed,
+        });
+        // About three years of daily scans
+        if self.scan_history.len() > 1000 {
+            self.scan_history.drain(0..self.scan_history.len() - 1000);
+        }
+        self.save();
+    }
+
+    /// Store a malware scan result and return the threats that are new since the previous scan.
+    pub fn record_malware_scan(&mut self, threats: Vec<String>, definitions_version: u64, scheduled: bool) -> Vec<String> {
+        let new = new_threats(self.malware_scans.last(), &threats);
+        self.pending_malware_alerts = pending_alerts(&self.pending_malware_alerts, &new, &threats, scheduled);
+        self.malware_scans.push(MalwareScanRecord {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            threats,
+            definitions_version,
+            scheduled,
+        });
+        if self.malware_scans.len() > 50 {
+            self.malware_scans.drain(0..self.malware_scans.len() - 50);
+        }
+        self.save();
+        new
+    }
+
+    /// Forget pending malware alerts once a notification has shown them.
+    pub fn clear_malware_alerts(&mut self) {
+        self.pending_malware_alerts.clear();
+        self.save();
+    }
+
+    pub fn record_quarantined(&mut self, items: Vec<QuarantinedItem>) {
+        if items.is_empty() {
+            return;
+        }
+        self.quarantine.extend(items);
+        self.save();
+    }
+
+    /// Quarantined items with the given ids, left in the store.
+    pub fn quarantined(&self, ids: &[String]) -> Vec<QuarantinedItem> {
+        self.quarantine.iter().filter(|q| ids.contains(&q.id)).cloned().collect()
+    }
+
+    /// Remove quarantined items by id, returning them.
+    pub fn take_quarantined(&mut self, ids: &[String]) -> Vec<QuarantinedItem> {
+        let (taken, kept) = std::mem::take(&mut self.quarantine).into_iter().partition(|q| ids.contains(&q.id));
+        self.quarantine = kept;
+        self.save();
+        taken
+    }
+
+    /// Queue blocked paths for review; a path already queued gets its entry refreshed.
+    pub fn enqueue_review(&mut self, items: Vec<ReviewItem>) {
+        if items.is_empty() {
+            return;
+        }
+        for item in items {
+            self.review_queue.retain(|q| !same_path(&q.path, &item.path));
+            self.review_queue.push(item);
+        }
+        if self.review_queue.len() > 500 {
+            self.review_queue.drain(0..self.review_queue.len() - 500);
+        }
+        self.save();
+    }
+
+    /// Remove queued items by id, returning them.
+    pub fn take_review_items(&mut self, ids: &[String]) -> Vec<ReviewItem> {
+        let (taken, kept) = std::mem::take(&mut self.review_queue).into_iter().partition(|q| ids.contains(&q.id));
+        self.review_queue = kept;
+        self.save();
+        taken
+    }
+
     /// Record a live system event from the watcher
     pub fn record_system_event(&mut self, event: SystemEvent) {
         self.system_events.push(event);
@@ -79,6 +580,40 @@ impl ContextStore {
         self.save();
     }
 
+    /// Remember segment sizes measured by a scan (e.g. ("Apps", 12 GB) from the uninstaller scan)
+    pub fn record_storage_segments(&mut self, segments: &[(&str, u64)]) {
+        let now = chrono::Local::now().to_rfc3339();
+        for (name, size_bytes) in segments {
+            self.storage_cache.insert(name.to_string(), CachedSize {
+                size_bytes: *size_bytes,
+                measured_at: now.clone(),
+            });
+        }
+        self.save();
+    }
+
+    /// Fold (key, files, bytes, seconds) measurements into the stored rates (exponential moving average).
+    pub fn record_throughput(&mut self, samples: &[(String, u64, u64, f64)]) {
+        const WEIGHT: f64 = 0.5;
+        for (key, files, bytes, secs) in samples {
+            if *secs <= 0.0 {
+                continue;
+            }
+            let (fps, bps) = (*files as f64 / secs, *bytes as f64 / secs);
+            let entry = self.scan_throughput.entry(key.clone()).or_default();
+            if entry.files_per_sec > 0.0 {
+                entry.files_per_sec = WEIGHT * fps + (1.0 - WEIGHT) * entry.files_per_sec;
+                entry.bytes_per_sec = WEIGHT * bps + (1.0 - WEIGHT) * entry.bytes_per_sec;
+            } else {
+                entry.files_per_sec = fps;
+                entry.bytes_per_sec = bps;
+            }
+            entry.files = *files;
+            entry.bytes = *bytes;
+        }
+        self.save();
+    }
+
     pub fn clear(&mut self) {
         self.last_scan_timestamp = None;
         self.deletion_history.clear();
@@ -86,8 +621,146 @@ impl ContextStore {
         self.save();
     }
 
+    pub fn scan_profile(&self, name: &str) -> Option<&ScanProfile> {
+        self.user_preferences.scan_profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Insert or replace a scan profile by name
+    pub fn save_scan_profile(&mut self, profile: ScanProfile) {
+        let profiles = &mut self.user_preferences.scan_profiles;
+        match profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => profiles.push(profile),
+        }
+        self.save();
+    }
+
+    pub fn set_staging_policy(&mut self, policy: StagingPolicy) {
+        self.user_preferences.staging = policy;
+        self.save();
+    }
+
+    pub fn set_backup_policy(&mut self, policy: BackupPolicy) {
+        self.user_preferences.backup = policy;
+        self.save();
+    }
+
+    pub fn set_auto_clean_policy(&mut self, policy: AutoCleanPolicy) {
+        self.user_preferences.auto_clean = policy;
+        self.save();
+    }
+
+    pub fn set_virustotal_policy(&mut self, policy: VirusTotalPolicy) {
+        self.user_preferences.virustotal = policy;
+        self.save();
+    }
+
+    pub fn set_download_rules(&mut self, policy: DownloadsPolicy) {
+        self.user_preferences.downloads = policy;
+        self.save();
+    }
+
+    pub fn delete_scan_profile(&mut self, name: &str) {
+        self.user_preferences.scan_profiles.retain(|p| p.name != name);
+        self.save();
+    }
+
+    pub fn is_excluded(&self, path: &str) -> bool {
+        self.unreadable
+            || path_is_excluded(path, &self.user_preferences.excluded_paths) || self.is_protected(path)
+    }
+
+    pub fn is_protected(&self, path: &str) -> bool {
+        path_is_protected(path, &self.user_preferences.protected_paths)
+    }
+
+    /// Refuse `action` when any of `paths` is protected, contains a protected path or lives in one.
+    pub fn check_protected(&self, action: &str, paths: &[String]) -> Result<(), String> {
+        if self.unreadable {
+            return Err(format!(
+                "{} blocked: your protected paths couldn't be read from {}.",
+                action,
+                Self::store_path().display()
+            ));
+        }
+        if let Some(p) = paths.iter().find(|p| self.is_protected(p)) {
+            return Err(format!("{} blocked: {} is protected in your settings.", action, p));
+        }
+        Ok(())
+    }
+
+    pub fn add_excluded_path(&mut self, path: &str) {
+        let path = path.trim();
+        let path = if path.len() > 1 { path.trim_end_matches('/') } else { path };
+        if !self.user_preferences.excluded_paths.iter().any(|p| same_path(p, path)) {
+            self.user_preferences.excluded_paths.push(path.to_string());
+        }
+        self.save();
+    }
+
+    pub fn remove_excluded_path(&mut self, path: &str) {
+        let path = path.trim();
+        let path = if path.len() > 1 { path.trim_end_matches('/') } else { path };
+        self.user_preferences.excluded_paths.retain(|p| !same_path(p, path));
+        self.save();
+    }
+
+    pub fn add_protected_path(&mut self, path: &str) {
+        let path = path.trim();
+        let path = if path.len() > 1 { path.trim_end_matches('/') } else { path };
+        if !self.user_preferences.protected_paths.iter().any(|p| same_path(p, path)) {
+            self.user_preferences.protected_paths.push(path.to_string());
+        }
+        self.save();
+    }
+
+    pub fn remove_protected_path(&mut self, path: &str) {
+        let path = path.trim();
+        let path = if path.len() > 1 { path.trim_end_matches('/') } else { path };
+        self.user_preferences.protected_paths.retain(|p| !same_path(p, path));
+        self.save();
+    }
+
     pub fn update_user_preferences(&mut self, prefs: UserPrefs) {
         self.user_preferences = prefs;
         self.save();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protection_covers_children_and_parents() {
+        let protected = vec!["/Users/jane/Documents/Taxes".to_string()];
+        assert!(path_is_protected("/Users/jane/Documents/Taxes/2024.pdf", &protected));
+        assert!(path_is_protected("/Users/jane/Documents", &protected));
+        assert!(!path_is_protected("/Users/jane/Documents/Receipts", &protected));
+    }
+
+    #[test]
+    fn unreadable_store_refuses_destructive_checks() {
+        let ctx = ContextStore { unreadable: true, ..Default::default() };
+        assert!(ctx.check_protected("Auto-clean", &["/Users/jane/Library/Caches/x".to_string()]).is_err());
+        assert!(ctx.is_excluded("/Users/jane/Library/Caches/x"));
+        assert!(ContextStore::default().check_protected("Auto-clean", &["/Users/jane/Library/Caches/x".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn scheduled_threats_stay_pending_until_alerted() {
+        let pending = vec!["Genieo".to_string(), "Removed".to_string()];
+        let threats = vec!["Genieo".to_string(), "Search Baron".to_string()];
+        let new = vec!["Search Baron".to_string()];
+        assert_eq!(pending_alerts(&pending, &new, &threats, true), threats);
+        assert!(pending_alerts(&pending, &new, &threats, false).is_empty());
+    }
+
+    #[test]
+    fn only_threats_missing_from_the_previous_scan_are_new() {
+        let previous = MalwareScanRecord { threats: vec!["Genieo".to_string()], ..Default::default() };
+        let threats = vec!["Genieo".to_string(), "Search Baron".to_string()];
+        assert_eq!(new_threats(Some(&previous), &threats), vec!["Search Baron".to_string()]);
+        assert_eq!(new_threats(None, &threats), threats);
+    }
+}
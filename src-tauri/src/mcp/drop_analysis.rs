@@ -0,0 +1,246 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::hash::Hasher;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::context_store::ContextStore;
+use super::file_index::{index_file_with, FileCategory};
+use super::managed::ManagedPolicy;
+use super::path_norm::path_key;
+use super::whitelist::Whitelist;
+use crate::scanners::{dir_byte_size, ByteSize};
+
+/// Files looked at for duplicates across one drop; a dropped home folder shouldn't hang the window.
+const MAX_FILES_FOR_DUPLICATES: usize = 20_000;
+/// Children listed in a folder's size breakdown.
+const BREAKDOWN_LIMIT: usize = 10;
+/// Files this large that aren't already compressed are worth zipping.
+const COMPRESS_MIN_BYTES: u64 = 100 * 1024 * 1024;
+/// User data this large is suggested for moving to an external disk.
+const MOVE_MIN_BYTES: u64 = 1024 * 1024 * 1024;
+const COMPRESSED_EXTENSIONS: &[&str] = &[
+    "zip", "gz", "tgz", "bz2", "xz", "7z", "rar", "dmg", "pkg", "mp4", "mov", "m4v", "mkv", "mp3", "m4a", "aac",
+    "jpg", "jpeg", "heic", "png", "webp", "pdf",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SizeEntry {
+    pub name: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DroppedItem {
+    pub path: String,
+    pub exists: bool,
+    pub is_directory: bool,
+    pub category: FileCategory,
+    pub app_owner: Option<String>,
+    pub is_safe_to_delete: bool,
+    /// Excluded by the user or an administrator
+    pub excluded: bool,
+    pub reason: String,
+    pub logical_bytes: u64,
+    pub allocated_bytes: u64,
+    pub file_count: usize,
+    /// Largest children of a folder
+    pub breakdown: Vec<SizeEntry>,
+    /// "trash" | "shred" | "compress" | "move"
+    pub suggested_actions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub size_bytes: u64,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DropAnalysis {
+    pub items: Vec<DroppedItem>,
+    pub duplicates: Vec<DuplicateGroup>,
+    pub total_bytes: u64,
+    /// Bytes the extra copies in `duplicates` take
+    pub duplicate_bytes: u64,
+}
+
+fn breakdown(dir: &Path) -> Vec<SizeEntry> {
+    let mut entries: Vec<SizeEntry> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| {
+                    let path = e.path();
+                    let size_bytes = match std::fs::symlink_metadata(&path) {
+                        Ok(m) if m.is_dir() => dir_byte_size(&path).allocated,
+                        Ok(m) => crate::scanners::allocated_bytes(&m),
+                        Err(_) => 0,
+                    };
+                    SizeEntry { name: e.file_name().to_string_lossy().to_string(), size_bytes }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+    entries.truncate(BREAKDOWN_LIMIT);
+    entries
+}
+
+pub(crate) fn is_compressed(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .map(|x| COMPRESSED_EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn suggested_actions(item: &DroppedItem, path: &Path) -> Vec<String> {
+    let mut actions = Vec::new();
+    if !item.exists || item.excluded || item.category == FileCategory::SystemCritical {
+        return actions;
+    }
+    if item.is_safe_to_delete {
+        actions.push("trash".to_string());
+        // Same gate as shred_path_command
+        if item.category != FileCategory::UserData {
+            actions.push("shred".to_string());
+        }
+    }
+    if item.allocated_bytes >= COMPRESS_MIN_BYTES && (item.is_directory || !is_compressed(path)) {
+        actions.push("compress".to_string());
+    }
+    if item.allocated_bytes >= MOVE_MIN_BYTES && matches!(item.category, FileCategory::UserData | FileCategory::Unknown) {
+        actions.push("move".to_string());
+    }
+    actions
+}
+
+fn analyze_one(path_str: &str, policy: &ManagedPolicy, ctx: &ContextStore, whitelist: &Whitelist) -> DroppedItem {
+    let path = Path::new(path_str);
+    let indexed = index_file_with(path_str, whitelist);
+    let meta = std::fs::symlink_metadata(path).ok();
+    let is_directory = meta.as_ref().map(|m| m.is_dir()).unwrap_or(false);
+    let (size, file_count) = match &meta {
+        Some(_) if is_directory => (
+            dir_byte_size(path),
+            WalkDir::new(path).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()).count(),
+        ),
+        Some(m) => (ByteSize::of(m), 1),
+        None => (ByteSize::default(), 0),
+    };
+    let mut item = DroppedItem {
+        path: path_str.to_string(),
+        exists: meta.is_some(),
+        is_directory,
+        category: indexed.category,
+        app_owner: indexed.app_owner,
+        is_safe_to_delete: indexed.is_safe_to_delete,
+        excluded: policy.is_excluded(path_str) || ctx.is_excluded(path_str),
+        reason: indexed.reason,
+        logical_bytes: size.logical,
+        allocated_bytes: size.allocated,
+        file_count,
+        breakdown: if is_directory { breakdown(path) } else { vec![] },
+        suggested_actions: vec![],
+    };
+    item.suggested_actions = suggested_actions(&item, path);
+    item
+}
+
+fn content_hash(path: &Path) -> Option<u64> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).ok()?;
+        if n == 0 {
+            break;
+        }
+        hasher.write(&buf[..n]);
+    }
+    Some(hasher.finish())
+}
+
+/// Identical files among everything dropped: same size first, then same content hash.
+fn find_duplicates(roots: &[String]) -> Vec<DuplicateGroup> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let mut seen = std::collections::HashSet::new();
+    let files = roots
+        .iter()
+        .flat_map(|r| WalkDir::new(r).into_iter().filter_map(|e| e.ok()))
+        .filter(|e| e.file_type().is_file())
+        .take(MAX_FILES_FOR_DUPLICATES);
+    for entry in files {
+        // A folder and a file inside it can both be dropped
+        if !seen.insert(path_key(&entry.path().to_string_lossy())) {
+            continue;
+        }
+        let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if len > 0 {
+            by_size.entry(len).or_default().push(entry.into_path());
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (size, paths) in by_size.into_iter().filter(|(_, p)| p.len() > 1) {
+        let mut by_hash: HashMap<u64, Vec<String>> = HashMap::new();
+        for p in paths {
+            if let Some(h) = content_hash(&p) {
+                by_hash.entry(h).or_default().push(p.to_string_lossy().to_string());
+            }
+        }
+        groups.extend(
+            by_hash
+                .into_values()
+                .filter(|p| p.len() > 1)
+                .map(|mut paths| {
+                    paths.sort();
+                    DuplicateGroup { size_bytes: size, paths }
+                }),
+        );
+    }
+    groups.sort_by_key(|g| std::cmp::Reverse(g.size_bytes * (g.paths.len() as u64 - 1)));
+    groups
+}
+
+/// "What is this and can I delete it?" for files and folders dropped onto the window or tray icon.
+pub fn analyze_dropped_paths(paths: &[String]) -> DropAnalysis {
+    let policy = ManagedPolicy::load();
+    let ctx = ContextStore::load();
+    let whitelist = Whitelist::for_store(&ctx);
+    let items: Vec<DroppedItem> = paths.iter().map(|p| analyze_one(p, &policy, &ctx, &whitelist)).collect();
+    let duplicates = find_duplicates(paths);
+    DropAnalysis {
+        total_bytes: items.iter().map(|i| i.allocated_bytes).sum(),
+        duplicate_bytes: duplicates.iter().map(|g| g.size_bytes * (g.paths.len() as u64 - 1)).sum(),
+        items,
+        duplicates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_duplicates_within_the_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "same content").unwrap();
+        std::fs::write(dir.path().join("sub/b.txt"), "same content").unwrap();
+        std::fs::write(dir.path().join("c.txt"), "diff content").unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        // The folder and one of its files dropped together must not pair a file with itself
+        let groups = find_duplicates(&[root.clone(), format!("{}/a.txt", root)]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].paths.len(), 2);
+    }
+
+    #[test]
+    fn missing_paths_get_no_actions() {
+        let item = analyze_one("/nonexistent/alto-drop-test", &ManagedPolicy::default(), &ContextStore::default(), &Whitelist::default());
+        assert!(!item.exists);
+        assert!(item.suggested_actions.is_empty());
+    }
+}
@@ -0,0 +1,51 @@
+use serde::Serialize;
+use std::path::Path;
+
+use super::context_store::ContextStore;
+use super::managed::ManagedPolicy;
+
+/// One thing a destructive command would remove.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedRemoval {
+    pub path: String,
+    pub size_bytes: u64,
+    /// "trash" | "stage" | "delete" (permanent) | "helper" (root helper, permanent) | "run_uninstaller"
+    pub method: String,
+}
+
+/// What a destructive command would have done, returned instead of doing it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunReport {
+    pub action: String,
+    pub items: Vec<PlannedRemoval>,
+    pub total_bytes: u64,
+}
+
+/// True when a command should only report: asked for per call (`requested`), or, when the call
+/// doesn't say, the dry-run setting. An administrator's forced dry-run always wins.
+pub fn enabled(requested: Option<bool>) -> bool {
+    ManagedPolicy::load().force_dry_run || requested.unwrap_or_else(|| ContextStore::load().user_preferences.dry_run)
+}
+
+/// Allocated size of a file, or of everything in a folder.
+pub fn size_of(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => crate::scanners::dir_byte_size(path).allocated,
+        Ok(meta) => crate::scanners::allocated_bytes(&meta),
+        Err(_) => 0,
+    }
+}
+
+/// Plan `method` for each path that exists; missing paths would be skipped, so they're left out.
+pub fn plan(action: &str, paths: &[String], method: &str) -> DryRunReport {
+    let items: Vec<PlannedRemoval> = paths
+        .iter()
+        .filter(|p| std::fs::symlink_metadata(p).is_ok())
+        .map(|p| PlannedRemoval { path: p.clone(), size_bytes: size_of(Path::new(p)), method: method.to_string() })
+        .collect();
+    report(action, items)
+}
+
+pub fn report(action: &str, items: Vec<PlannedRemoval>) -> DryRunReport {
+    DryRunReport { action: action.to_string(), total_bytes: items.iter().map(|i| i.size_bytes).sum(), items }
+}
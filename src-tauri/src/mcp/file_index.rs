@@ -1,6 +1,12 @@
 use std::path::Path;
+use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 
+use super::whitelist::Whitelist;
+
+/// Time one preview or delete may spend measuring folders; folders past it keep a partial total.
+const MEASURE_BUDGET: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum FileCategory {
     Cache,
@@ -9,6 +15,7 @@ pub enum FileCategory {
     UserData,
     SystemCritical,
     AppSupport,
+    Installer,
     Unknown,
 }
 
@@ -22,10 +29,29 @@ pub struct IndexedFile {
     pub reason: String,
 }
 
-/// Categorizes a file path and determines if it is safe to delete.
+/// Categorizes a file path and determines if it is safe to delete. Loads the whitelist from disk,
+/// so scans and batches use `index_file_with` instead.
 pub fn index_file(path: &str) -> IndexedFile {
+    index_file_with(path, &Whitelist::load())
+}
+
+/// `index_file` with an already loaded whitelist, for callers indexing many paths.
+pub fn index_file_with(path: &str, whitelist: &Whitelist) -> IndexedFile {
     let p = Path::new(path);
-    let path_lower = path.to_lowercase();
+    // Rules below are written in lowercase NFC
+    let path_lower = super::path_norm::nfc(path).to_lowercase();
+
+    // --- BLOCKED: Alto's own data (context store, staging, helper, app bundle) ---
+    if super::self_protect::is_self_protected(path) {
+        return IndexedFile {
+            path: path.to_string(),
+            size_bytes: get_size(p),
+            category: FileCategory::AppSupport,
+            app_owner: Some("Alto".to_string()),
+            is_safe_to_delete: false,
+            reason: "Alto's own data: never cleaned.".to_string(),
+        };
+    }
 
     // --- BLOCKED: System Critical ---
     #[cfg(target_os = "macos")]
@@ -52,6 +78,31 @@ pub fn index_file(path: &str) -> IndexedFile {
         }
     }
 
+    // --- BLOCKED: Whitelist (built-in names and the user's always-skip patterns) ---
+    if let Some(pattern) = whitelist.matching(path) {
+        return IndexedFile {
+            path: path.to_string(),
+            size_bytes: get_size(p),
+            category: FileCategory::UserData,
+            app_owner: None,
+            is_safe_to_delete: false,
+            reason: format!("Matches the skip pattern \"{}\": never cleaned.", pattern),
+        };
+    }
+
+    // --- SAFE: Known junk locations (checked before user data: ~/.Trash/Documents-old.pdf is still trash) ---
+    #[cfg(target_os = "macos")]
+    if let Some((category, reason)) = known_junk_location(p, &path_lower) {
+        return IndexedFile {
+            path: path.to_string(),
+            size_bytes: get_size(p),
+            category,
+            app_owner: None,
+            is_safe_to_delete: true,
+            reason: reason.to_string(),
+        };
+    }
+
     // --- BLOCKED: User Data ---
     let user_data_patterns = [
         "documents", "desktop", "downloads", "pictures",
@@ -101,7 +152,7 @@ pub fn index_file(path: &str) -> IndexedFile {
 
     // --- SAFE: Temp ---
     #[cfg(target_os = "macos")]
-    let is_temp = path_lower.starts_with("/tmp/") || path_lower.contains("/var/folders/");
+    let is_temp = path_lower.starts_with("/tmp/") || path_lower.starts_with("/private/tmp/") || path_lower.contains("/var/folders/");
     #[cfg(target_os = "windows")]
     let is_temp = path_lower.contains("\\temp\\") || path_lower.contains("\\tmp\\") || path_lower.contains("\\appdata\\local\\temp");
 
@@ -116,6 +167,24 @@ pub fn index_file(path: &str) -> IndexedFile {
         };
     }
 
+    // --- SAFE: macOS installers & iOS restore images (re-downloadable) ---
+    #[cfg(target_os = "macos")]
+    {
+        let is_installer = (path_lower.starts_with("/applications/install macos") && path_lower.contains(".app"))
+            || path_lower.contains("/library/itunes/iphone software updates/")
+            || path_lower.contains("/library/itunes/ipad software updates/");
+        if is_installer {
+            return IndexedFile {
+                path: path.to_string(),
+                size_bytes: get_size(p),
+                category: FileCategory::Installer,
+                app_owner: None,
+                is_safe_to_delete: true,
+                reason: "macOS installer or iOS software update. Can be downloaded again from Apple.".to_string(),
+            };
+        }
+    }
+
     // --- CAUTION: App Support ---
     let app_support_pattern = if cfg!(target_os = "macos") { "application support" } else { "appdata" };
     if path_lower.contains(app_support_pattern) {
@@ -141,13 +210,77 @@ pub fn index_file(path: &str) -> IndexedFile {
     }
 }
 
+/// Locations the junk scanner targets that the generic cache/log/temp rules don't recognize.
+#[cfg(target_os = "macos")]
+fn known_junk_location(p: &Path, path_lower: &str) -> Option<(FileCategory, &'static str)> {
+    let rules: &[(&str, FileCategory, &str)] = &[
+        ("/.trash/", FileCategory::Temp, "Already in the Trash."),
+        ("/library/saved application state/", FileCategory::Cache, "Saved window state. Apps recreate it on launch."),
+        ("/library/developer/xcode/deriveddata/", FileCategory::Cache, "Xcode build products. Rebuilt automatically."),
+        ("/library/developer/xcode/ios devicesupport/", FileCategory::Cache, "Device debug symbols. Re-created when the device is connected."),
+        ("/library/application support/crashreporter/", FileCategory::Log, "Crash report. Safe to delete."),
+        ("/.pnpm-store/", FileCategory::Cache, "Package manager store. Re-downloaded on demand."),
+    ];
+    if let Some((_, category, reason)) = rules.iter().find(|(pattern, _, _)| path_lower.contains(pattern)) {
+        return Some((category.clone(), reason));
+    }
+    if path_lower.contains("/containers/com.apple.mail/data/library/mail downloads/") {
+        return Some((FileCategory::Cache, "Copy Mail saved when an attachment was opened. The message keeps the original."));
+    }
+    // Only files whose content shows they are what the scanner offers them as
+    if crate::scanners::screenshots::is_verified_screenshot(p) {
+        return Some((FileCategory::Temp, "Screenshot macOS saved to the Desktop or the screenshot folder."));
+    }
+    if path_lower.contains("/downloads/") && crate::scanners::installers::is_installer_image(p) {
+        return Some((FileCategory::Installer, "Downloaded disk image or installer package. Can be downloaded again."));
+    }
+    if path_lower.ends_with("/node_modules") && p.parent().map(|d| d.join("package.json").is_file()).unwrap_or(false) {
+        return Some((FileCategory::Cache, "Project dependencies. `npm install` restores them."));
+    }
+    // Preference files that no longer parse are ignored by their apps anyway
+    if path_lower.contains("/library/preferences/") && path_lower.ends_with(".plist") && p.is_file()
+        && crate::scanners::junk::is_broken_plist(p)
+    {
+        return Some((FileCategory::AppSupport, "Corrupt preference file. The app ignores it and writes a new one."));
+    }
+    None
+}
+
 /// Index a list of file paths.
-pub fn index_files(paths: &[String]) -> Vec<IndexedFile> {
-    paths.iter().map(|p| index_file(p)).collect()
+pub fn index_files(paths: &[String], whitelist: &Whitelist) -> Vec<IndexedFile> {
+    paths.iter().map(|p| index_file_with(p, whitelist)).collect()
+}
+
+/// `index_files` with folders measured recursively, so bytes freed count their contents rather
+/// than the folder entry itself. All folders share `MEASURE_BUDGET`.
+pub fn index_files_measured(paths: &[String], whitelist: &Whitelist) -> Vec<IndexedFile> {
+    let deadline = Instant::now() + MEASURE_BUDGET;
+    let mut files = index_files(paths, whitelist);
+    for file in &mut files {
+        let p = Path::new(&file.path);
+        if std::fs::symlink_metadata(p).map(|m| m.is_dir()).unwrap_or(false) {
+            file.size_bytes = dir_size_until(p, deadline);
+        }
+    }
+    files
+}
+
+/// Allocated size of everything under `dir`, or what was counted when `deadline` passed.
+fn dir_size_until(dir: &Path, deadline: Instant) -> u64 {
+    let mut total = 0;
+    for entry in walkdir::WalkDir::new(dir).follow_links(false).into_iter().filter_map(|e| e.ok()) {
+        if Instant::now() >= deadline {
+            break;
+        }
+        if let Ok(meta) = entry.metadata() {
+            total += crate::scanners::allocated_bytes(&meta);
+        }
+    }
+    total
 }
 
 fn get_size(p: &Path) -> u64 {
-    std::fs::metadata(p).map(|m| m.len()).unwrap_or(0)
+    std::fs::metadata(p).map(|m| crate::scanners::allocated_bytes(&m)).unwrap_or(0)
 }
 
 fn extract_app_owner(path: &str) -> Option<String> {
@@ -172,7 +305,12 @@ fn extract_app_owner(path: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{index_file, FileCategory};
+    use super::{dir_size_until, index_file_with, FileCategory, IndexedFile, Whitelist};
+
+    /// Built-in patterns only, so results don't depend on the user's own ContextStore
+    fn index_file(path: &str) -> IndexedFile {
+        index_file_with(path, &Whitelist::new(&[]))
+    }
 
     #[cfg(target_os = "macos")]
     #[test]
@@ -213,6 +351,26 @@ mod tests {
         assert_eq!(r.category, FileCategory::Log);
     }
 
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn trash_and_xcode_junk_safe() {
+        let r = index_file("/Users/jane/.Trash/Documents-old.pdf");
+        assert!(r.is_safe_to_delete);
+        assert!(index_file("/Users/jane/Library/Developer/Xcode/DerivedData/App-abc/Build").is_safe_to_delete);
+        // Archives may be a build that just shipped; Xcode cleanup judges them by age instead
+        assert!(!index_file("/Users/jane/Library/Developer/Xcode/Archives/2026-10-15/App.xcarchive").is_safe_to_delete);
+        assert!(!index_file("/Users/jane/Library/Developer/Xcode/UserData/KeyBindings").is_safe_to_delete);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn installers_safe() {
+        let r = index_file("/Applications/Install macOS Sonoma.app");
+        assert!(r.is_safe_to_delete);
+        assert_eq!(r.category, FileCategory::Installer);
+        assert!(!index_file("/Applications/Installer Helper.app").is_safe_to_delete);
+    }
+
     #[cfg(target_os = "macos")]
     #[test]
     fn tmp_safe() {
@@ -220,4 +378,59 @@ mod tests {
         assert!(r.is_safe_to_delete);
         assert_eq!(r.category, FileCategory::Temp);
     }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn mail_attachment_copies_safe() {
+        let r = index_file("/Users/jane/Library/Containers/com.apple.mail/Data/Library/Mail Downloads/4F2A/report.pdf");
+        assert!(r.is_safe_to_delete);
+        // POP and On My Mac mailboxes keep their only copy under Attachments
+        assert!(!index_file("/Users/jane/Library/Mail/V10/ABCD/INBOX.mbox/Attachments/12/1.2/photo.jpg").is_safe_to_delete);
+        assert!(!index_file("/Users/jane/Library/Mail/V10/ABCD/INBOX.mbox/Info.plist").is_safe_to_delete);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn verified_screenshots_and_installers_safe() {
+        let home = tempfile::tempdir().unwrap();
+        let (desktop, downloads) = (home.path().join("Desktop"), home.path().join("Downloads"));
+        std::fs::create_dir_all(&desktop).unwrap();
+        std::fs::create_dir_all(&downloads).unwrap();
+        let shot = desktop.join("Screenshot 2024-05-01 at 10.00.00.png");
+        let photo = desktop.join("Holiday.png");
+        std::fs::write(&shot, b"png").unwrap();
+        std::fs::write(&photo, b"png").unwrap();
+        std::process::Command::new("xattr").args(["-w", "com.apple.metadata:kMDItemIsScreenCapture", "1"]).arg(&shot).status().unwrap();
+        let mut udif = vec![0u8; 4096];
+        udif[4096 - 512..4096 - 508].copy_from_slice(b"koly");
+        std::fs::write(downloads.join("App.dmg"), &udif).unwrap();
+        std::fs::write(downloads.join("Notes.dmg"), b"not an image").unwrap();
+
+        assert!(index_file(&shot.to_string_lossy()).is_safe_to_delete);
+        let dmg = index_file(&downloads.join("App.dmg").to_string_lossy());
+        assert!(dmg.is_safe_to_delete);
+        assert_eq!(dmg.category, FileCategory::Installer);
+        assert!(!index_file(&photo.to_string_lossy()).is_safe_to_delete);
+        assert!(!index_file(&downloads.join("Notes.dmg").to_string_lossy()).is_safe_to_delete);
+    }
+
+    #[test]
+    fn alto_data_never_safe() {
+        let home = dirs::home_dir().unwrap();
+        // Cache-looking names inside ~/.alto must not fall through to the cache rule
+        let r = index_file(&home.join(".alto/staging/cache.db").to_string_lossy());
+        assert!(!r.is_safe_to_delete);
+        assert_eq!(r.app_owner.as_deref(), Some("Alto"));
+    }
+
+    #[test]
+    fn folders_are_measured_recursively() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        std::fs::write(dir.path().join("a.bin"), vec![1u8; 20_000]).unwrap();
+        std::fs::write(dir.path().join("nested/b.bin"), vec![1u8; 20_000]).unwrap();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        assert!(dir_size_until(dir.path(), deadline) >= 40_000);
+        assert_eq!(dir_size_until(dir.path(), std::time::Instant::now()), 0);
+    }
 }
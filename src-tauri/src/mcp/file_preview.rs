@@ -0,0 +1,122 @@
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use super::file_index::index_file;
+
+const PREVIEW_MAX_LINES: usize = 12;
+const PREVIEW_MAX_BYTES: u64 = 8 * 1024;
+
+/// Quick metadata shown next to an item in the deletion review list.
+#[derive(Debug, Serialize, Default)]
+pub struct FilePreview {
+    pub path: String,
+    pub is_directory: bool,
+    pub size_bytes: u64,
+    /// Human readable kind from Spotlight (e.g. "PNG image")
+    pub kind: Option<String>,
+    /// Uniform Type Identifier (e.g. "public.png")
+    pub uti: Option<String>,
+    pub created: Option<i64>,
+    pub modified: Option<i64>,
+    pub pixel_width: Option<u64>,
+    pub pixel_height: Option<u64>,
+    pub duration_secs: Option<f64>,
+    /// First lines of plain-text files
+    pub text_excerpt: Option<String>,
+    pub app_owner: Option<String>,
+}
+
+fn unix_secs(t: std::io::Result<std::time::SystemTime>) -> Option<i64> {
+    t.ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
+
+/// Spotlight attributes via `mdls`. Values are "(null)" when Spotlight doesn't know the attribute.
+#[cfg(target_os = "macos")]
+fn fill_spotlight_attributes(path: &Path, preview: &mut FilePreview) {
+    let output = match std::process::Command::new("mdls")
+        .args([
+            "-name", "kMDItemKind",
+            "-name", "kMDItemContentType",
+            "-name", "kMDItemPixelWidth",
+            "-name", "kMDItemPixelHeight",
+            "-name", "kMDItemDurationSeconds",
+        ])
+        .arg(path)
+        .output()
+    {
+        Ok(o) if o.status.success() => o,
+        _ => return,
+    };
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let (key, value) = match line.split_once('=') {
+            Some((k, v)) => (k.trim(), v.trim().trim_matches('"')),
+            None => continue,
+        };
+        if value == "(null)" {
+            continue;
+        }
+        match key {
+            "kMDItemKind" => preview.kind = Some(value.to_string()),
+            "kMDItemContentType" => preview.uti = Some(value.to_string()),
+            "kMDItemPixelWidth" => preview.pixel_width = value.parse().ok(),
+            "kMDItemPixelHeight" => preview.pixel_height = value.parse().ok(),
+            "kMDItemDurationSeconds" => preview.duration_secs = value.parse().ok(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn fill_spotlight_attributes(_path: &Path, _preview: &mut FilePreview) {}
+
+/// First few lines of a file, or None if it doesn't look like UTF-8 text.
+fn text_excerpt(path: &Path) -> Option<String> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut buf = Vec::new();
+    file.take(PREVIEW_MAX_BYTES).read_to_end(&mut buf).ok()?;
+    if buf.contains(&0) {
+        return None; // binary
+    }
+    let lines: Vec<String> = BufReader::new(buf.as_slice())
+        .lines()
+        .take(PREVIEW_MAX_LINES)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+fn looks_like_text(preview: &FilePreview, path: &Path) -> bool {
+    if let Some(uti) = &preview.uti {
+        return uti.starts_with("public.") && (uti.contains("text") || uti.contains("source") || uti.contains("json") || uti.contains("log"));
+    }
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    ["txt", "log", "md", "json", "csv", "xml", "yml", "yaml", "plist", "sh", "conf", "ini"].contains(&ext.as_str())
+}
+
+pub fn preview_file(path: &str) -> Result<FilePreview, String> {
+    let p = Path::new(path);
+    let meta = std::fs::symlink_metadata(p).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+
+    let mut preview = FilePreview {
+        path: path.to_string(),
+        is_directory: meta.is_dir(),
+        size_bytes: meta.len(),
+        created: unix_secs(meta.created()),
+        modified: unix_secs(meta.modified()),
+        app_owner: index_file(path).app_owner,
+        ..Default::default()
+    };
+    fill_spotlight_attributes(p, &mut preview);
+    if meta.is_file() && looks_like_text(&preview, p) {
+        preview.text_excerpt = text_excerpt(p);
+    }
+    Ok(preview)
+}
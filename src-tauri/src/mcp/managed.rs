@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use super::context_store::path_is_excluded;
+
+/// Configuration profile payload deployed by IT (MDM) for com.alto.
+#[cfg(target_os = "macos")]
+pub const MANAGED_PLIST_PATH: &str = "/Library/Managed Preferences/com.alto.plist";
+
+/// Admin-enforced constraints. Keys in the plist are PascalCase (`DisableShredder`, `ForceDryRun`, ...).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all(deserialize = "PascalCase"))]
+pub struct ManagedPolicy {
+    /// True when a managed plist was found; all other fields are defaults otherwise
+    #[serde(skip_deserializing)]
+    pub is_managed: bool,
+    pub disable_shredder: bool,
+    pub disable_helper_install: bool,
+    /// Paths (or folders, `~` allowed) Alto must never remove
+    pub excluded_paths: Vec<String>,
+    /// Destructive commands only report what they would do
+    pub force_dry_run: bool,
+}
+
+impl ManagedPolicy {
+    pub fn load() -> Self {
+        #[cfg(target_os = "macos")]
+        {
+            if let Ok(mut policy) = plist::from_file::<_, ManagedPolicy>(MANAGED_PLIST_PATH) {
+                policy.is_managed = true;
+                return policy;
+            }
+        }
+        Self::default()
+    }
+
+    /// True if `path` is one of the admin-pinned exclusions or lives inside one.
+    pub fn is_excluded(&self, path: &str) -> bool {
+        path_is_excluded(path, &self.excluded_paths)
+    }
+
+    /// Gate for any command that removes or moves user files. Returns a user-facing error when blocked.
+    pub fn check_destructive(&self, action: &str, paths: &[String]) -> Result<(), String> {
+        if self.force_dry_run {
+            return Err(format!("{} is disabled: your administrator enforces dry-run mode.", action));
+        }
+        self.check_excluded(action, paths)
+    }
+
+    /// The exclusion half of `check_destructive`, for dry runs that report instead of acting.
+    pub fn check_excluded(&self, action: &str, paths: &[String]) -> Result<(), String> {
+        if let Some(p) = paths.iter().find(|p| self.is_excluded(p)) {
+            return Err(format!("{} blocked: {} is excluded by your administrator.", action, p));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ManagedPolicy;
+
+    #[test]
+    fn excluded_paths_cover_children_only() {
+        let policy = ManagedPolicy {
+            excluded_paths: vec!["/Users/jane/Work/".to_string()],
+            ..Default::default()
+        };
+        assert!(policy.is_excluded("/Users/jane/Work"));
+        assert!(policy.is_excluded("/Users/jane/Work/report.key"));
+        assert!(!policy.is_excluded("/Users/jane/Workshop/notes.txt"));
+        assert!(policy.check_destructive("Shredder", &["/Users/jane/Work/a".to_string()]).is_err());
+    }
+
+    #[test]
+    fn forced_dry_run_blocks_destructive_actions() {
+        let policy = ManagedPolicy { force_dry_run: true, ..Default::default() };
+        assert!(policy.check_destructive("Empty Trash", &[]).is_err());
+        assert!(ManagedPolicy::default().check_destructive("Empty Trash", &[]).is_ok());
+        // Dry runs still report, but never for excluded paths
+        assert!(policy.check_excluded("Empty Trash", &[]).is_ok());
+    }
+}
@@ -1,2 +1,15 @@
 pub mod file_index;
 pub mod context_store;
+pub mod managed;
+pub mod file_preview;
+pub mod staging;
+pub mod trends;
+pub mod path_norm;
+pub mod self_protect;
+pub mod restore_point;
+pub mod drop_analysis;
+pub mod undo;
+pub mod dry_run;
+pub mod backup;
+pub mod whitelist;
+pub mod safety_gate;
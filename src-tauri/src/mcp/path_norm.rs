@@ -0,0 +1,57 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// APFS and HFS+ ignore case by default, and so does NTFS; most Linux filesystems don't.
+const CASE_INSENSITIVE_FS: bool = cfg!(any(target_os = "macos", target_os = "windows"));
+
+/// NFC form of `s`. HFS+ hands out decomposed (NFD) names and APFS keeps whatever form a file
+/// was created with, so "Café" can reach us either way.
+pub fn nfc(s: &str) -> String {
+    s.nfc().collect()
+}
+
+/// Key for comparing paths the way the filesystem does: NFC, case-folded where case is ignored.
+pub fn path_key(path: &str) -> String {
+    let normalized = nfc(path);
+    if CASE_INSENSITIVE_FS { normalized.to_lowercase() } else { normalized }
+}
+
+pub fn same_path(a: &str, b: &str) -> bool {
+    path_key(a) == path_key(b)
+}
+
+/// True if `path` is `root` or lives inside it. A trailing slash on `root` is ignored.
+pub fn path_within(path: &str, root: &str) -> bool {
+    let path = path_key(path);
+    let root = path_key(root);
+    let root = root.trim_end_matches(['/', '\\']);
+    !root.is_empty()
+        && (path == root
+            || path.strip_prefix(root).map(|rest| rest.starts_with('/') || rest.starts_with('\\')).unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decomposed_and_composed_names_match() {
+        let nfd = "/Users/jane/Documents/Cafe\u{301}";
+        let nfc_form = "/Users/jane/Documents/Caf\u{e9}";
+        assert!(same_path(nfd, nfc_form));
+        assert!(path_within(&format!("{}/menu.pdf", nfd), nfc_form));
+    }
+
+    #[test]
+    fn within_requires_a_component_boundary() {
+        assert!(path_within("/Users/jane/Work/a.txt", "/Users/jane/Work/"));
+        assert!(path_within("/Users/jane/Work", "/Users/jane/Work"));
+        assert!(!path_within("/Users/jane/Workshop", "/Users/jane/Work"));
+        assert!(!path_within("/Users/jane", ""));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn case_is_ignored_on_macos() {
+        assert!(same_path("/Users/Jane/Downloads", "/users/jane/downloads"));
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use super::context_store::ContextStore;
+use crate::helper_client::{self, Command};
+
+/// Cleanups at least this large get a restore point when the user opted in.
+pub const MIN_CLEANUP_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+/// macOS thins local snapshots after about a day, so reverting is only offered this long.
+pub const REVERT_WINDOW_HOURS: i64 = 24;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevertResult {
+    pub restored: usize,
+    pub errors: Vec<String>,
+}
+
+/// Snapshot before a cleanup of `bytes`, if the user opted in. Failing to snapshot doesn't block
+/// the cleanup; it's logged and the record just has no restore point.
+pub async fn before_cleanup(bytes: u64) -> Option<String> {
+    if bytes < MIN_CLEANUP_BYTES || !ContextStore::load().user_preferences.pre_clean_snapshot {
+        return None;
+    }
+    if !helper_client::ensure_helper_installed().await {
+        eprintln!("Pre-clean snapshot skipped: helper not available");
+        return None;
+    }
+    match helper_client::send_command(Command::CreateLocalSnapshot).await {
+        Ok(res) if res.success => Some(res.message),
+        Ok(res) => {
+            eprintln!("Pre-clean snapshot failed: {}", res.message);
+            None
+        }
+        Err(e) => {
+            eprintln!("Pre-clean snapshot failed: {}", e);
+            None
+        }
+    }
+}
+
+fn within_window(timestamp: &str) -> bool {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| chrono::Local::now().signed_duration_since(t) < chrono::Duration::hours(REVERT_WINDOW_HOURS))
+        .unwrap_or(false)
+}
+
+/// Copy the files of the cleanup recorded at `timestamp` back from its pre-clean snapshot.
+pub async fn revert(timestamp: &str) -> Result<RevertResult, String> {
+    let record = ContextStore::load()
+        .deletion_history
+        .into_iter()
+        .find(|r| r.timestamp == timestamp)
+        .ok_or("No cleanup recorded at that time")?;
+    let snapshot = record.snapshot.ok_or("This cleanup has no pre-clean snapshot")?;
+    if !within_window(&record.timestamp) {
+        return Err(format!("Pre-clean snapshots can only be reverted within {} hours", REVERT_WINDOW_HOURS));
+    }
+    if !helper_client::ensure_helper_installed().await {
+        return Err("Failed to install execution helper".to_string());
+    }
+    let res = helper_client::send_command(Command::RestoreFromSnapshot { snapshot, paths: record.paths_deleted })
+        .await
+        .map_err(|e| format!("Helper communication failed: {}", e))?;
+    // Partial failures still carry the JSON result
+    serde_json::from_str(&res.message).map_err(|_| res.message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revert_window() {
+        assert!(within_window(&chrono::Local::now().to_rfc3339()));
+        let old = chrono::Local::now() - chrono::Duration::hours(REVERT_WINDOW_HOURS + 1);
+        assert!(!within_window(&old.to_rfc3339()));
+    }
+}
@@ -0,0 +1,54 @@
+use super::context_store::ContextStore;
+use super::file_index::IndexedFile;
+use super::managed::ManagedPolicy;
+use super::whitelist::Whitelist;
+
+/// Whether confirm_delete may remove `file` without review: the indexer calls it safe, and no skip
+/// pattern, administrator exclusion or user exclusion/protection covers it.
+pub fn passes(file: &IndexedFile, whitelist: &Whitelist, policy: &ManagedPolicy, ctx: &ContextStore) -> bool {
+    file.is_safe_to_delete && !whitelist.matches(&file.path) && !policy.is_excluded(&file.path) && !ctx.is_excluded(&file.path)
+}
+
+/// Checks for commands that move or remove the paths they're given outright (move, uninstall,
+/// quarantine): administrator policy, the user's protected paths, then Alto's own data.
+pub fn check_paths(action: &str, paths: &[String], policy: &ManagedPolicy, ctx: &ContextStore) -> Result<(), String> {
+    policy.check_destructive(action, paths)?;
+    ctx.check_protected(action, paths)?;
+    super::self_protect::check_all(action, paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::file_index::index_file_with;
+
+    fn gate(path: &str, ctx: &ContextStore) -> bool {
+        passes(&index_file_with(path, &Whitelist::default()), &Whitelist::default(), &ManagedPolicy::default(), ctx)
+    }
+
+    #[test]
+    fn user_data_and_alto_data_are_held_for_review() {
+        let ctx = ContextStore::default();
+        let home = dirs::home_dir().unwrap();
+        assert!(!gate("/Users/jane/Documents/Taxes/2024.pdf", &ctx));
+        assert!(!gate(&home.join(".alto/context.json").to_string_lossy(), &ctx));
+        assert!(gate("/Users/jane/Library/Caches/com.example.app/blob.bin", &ctx));
+
+        let mut ctx = ContextStore::default();
+        ctx.user_preferences.protected_paths = vec!["/Users/jane/Library/Caches/com.example.app".to_string()];
+        assert!(!gate("/Users/jane/Library/Caches/com.example.app/blob.bin", &ctx));
+    }
+
+    #[test]
+    fn moves_and_uninstalls_refuse_protected_and_alto_paths() {
+        let policy = ManagedPolicy::default();
+        let home = dirs::home_dir().unwrap();
+        let alto = vec![home.join(".alto/staging").to_string_lossy().to_string()];
+        assert!(check_paths("Move", &alto, &policy, &ContextStore::default()).is_err());
+
+        let mut ctx = ContextStore::default();
+        ctx.user_preferences.protected_paths = vec!["/Users/jane/Projects".to_string()];
+        assert!(check_paths("Move", &["/Users/jane".to_string()], &policy, &ctx).is_err());
+        assert!(check_paths("Move", &["/Users/jane/Movies/clip.mov".to_string()], &policy, &ctx).is_ok());
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use super::path_norm::path_within;
+
+/// Where install_helper.sh puts the privileged helper and its launchd job.
+pub const HELPER_BINARY: &str = "/usr/local/bin/alto_helper";
+pub const HELPER_PLIST: &str = "/Library/LaunchDaemons/com.alto.helper.plist";
+pub const HELPER_SOCKET: &str = "/var/run/com.alto.helper.sock";
+/// Bundle identifier from tauri.conf.json
+const APP_IDENTIFIER: &str = "com.maccleaner.app";
+
+/// Alto's own data and binaries: ~/.alto (context store, staging, checkpoints, quarantine),
+/// the app's support folder, the helper and the running app bundle.
+pub fn protected_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(HELPER_BINARY), PathBuf::from(HELPER_PLIST), PathBuf::from(HELPER_SOCKET)];
+    if let Some(home) = dirs::home_dir() {
+        paths.push(home.join(".alto"));
+        paths.push(home.join("Library/Application Support").join(APP_IDENTIFIER));
+    }
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(bundle) = exe.ancestors().find(|p| p.extension().map(|x| x == "app").unwrap_or(false)) {
+            paths.push(bundle.to_path_buf());
+        }
+    }
+    paths
+}
+
+/// True if removing `path` would remove Alto's own data: it is inside a protected path or contains one.
+pub fn is_self_protected(path: &str) -> bool {
+    protected_paths().iter().any(|p| {
+        let p = p.to_string_lossy();
+        path_within(path, &p) || path_within(&p, path)
+    })
+}
+
+/// Guard for destructive entry points. Alto's data is only removed by the self-uninstall flow.
+pub fn check(action: &str, path: &str) -> Result<(), String> {
+    if is_self_protected(path) {
+        return Err(format!("{} blocked: {} belongs to Alto itself.", action, path));
+    }
+    Ok(())
+}
+
+/// `check` for every path.
+pub fn check_all(action: &str, paths: &[String]) -> Result<(), String> {
+    paths.iter().try_for_each(|p| check(action, p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn home_path(rel: &str) -> String {
+        dirs::home_dir().unwrap().join(rel).to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn alto_data_and_helper_are_protected() {
+        assert!(is_self_protected(&home_path(".alto/context.json")));
+        assert!(is_self_protected(&home_path(".alto/staging/2026-01-01/report.pdf")));
+        assert!(is_self_protected(HELPER_BINARY));
+        assert!(is_self_protected(HELPER_PLIST));
+        assert!(check("Delete", &home_path(".alto/checkpoints")).is_err());
+    }
+
+    #[test]
+    fn parents_of_alto_data_are_protected() {
+        assert!(is_self_protected(&home_path("")));
+        assert!(is_self_protected("/usr/local/bin"));
+    }
+
+    #[test]
+    fn ordinary_paths_are_not_protected() {
+        assert!(!is_self_protected(&home_path("Library/Caches/com.example.app")));
+        assert!(!is_self_protected(&home_path(".altogether")));
+        assert!(check("Delete", &home_path("Downloads/setup.dmg")).is_ok());
+    }
+}
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::context_store::ContextStore;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const JANITOR_INTERVAL_SECS: u64 = 6 * 3600;
+
+/// A cleaned file parked in ~/.alto/staged/<timestamp> until its grace period ends.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StagedItem {
+    pub id: String,
+    pub original_path: String,
+    pub staged_path: String,
+    pub size_bytes: u64,
+    pub staged_at: String,
+    /// Folder of the cleanup this item was staged with; every item of one cleanup shares it
+    #[serde(default)]
+    pub batch: String,
+    #[serde(default)]
+    pub is_directory: bool,
+}
+
+/// One folder per cleanup: ~/.alto/staged/<timestamp>/<id>/<name>, plus its manifest.
+pub fn staging_root() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("staged")
+}
+
+/// Where earlier versions staged, one folder per day. Still read so those items restore and expire.
+fn legacy_staging_root() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("staging")
+}
+
+fn read_manifest(day_dir: &Path) -> Vec<StagedItem> {
+    std::fs::read_to_string(day_dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|d| serde_json::from_str(&d).ok())
+        .unwrap_or_default()
+}
+
+fn write_manifest(day_dir: &Path, items: &[StagedItem]) {
+    if items.is_empty() {
+        let _ = std::fs::remove_dir_all(day_dir);
+        return;
+    }
+    if let Ok(json) = serde_json::to_string_pretty(items) {
+        let _ = std::fs::write(day_dir.join(MANIFEST_FILE), json);
+    }
+}
+
+fn batch_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = [legacy_staging_root(), staging_root()]
+        .iter()
+        .flat_map(|root| std::fs::read_dir(root).into_iter().flatten().flatten())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// A new cleanup folder named after the current time, suffixed if two cleanups land in the same second.
+fn new_batch_dir(now: &chrono::DateTime<chrono::Local>) -> std::io::Result<PathBuf> {
+    let root = staging_root();
+    std::fs::create_dir_all(&root)?;
+    let stamp = now.format("%Y-%m-%dT%H-%M-%S").to_string();
+    let mut n = 1;
+    loop {
+        let dir = if n == 1 { root.join(&stamp) } else { root.join(format!("{}-{}", stamp, n)) };
+        match std::fs::create_dir(&dir) {
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => n += 1,
+            result => return result.map(|_| dir),
+        }
+    }
+}
+
+/// Move `paths` into a new staging folder for this cleanup. Returns the staged items and per-path errors.
+pub fn stage_paths(paths: &[String], sizes: &[u64]) -> (Vec<StagedItem>, Vec<String>) {
+    let now = chrono::Local::now();
+    let mut staged = Vec::new();
+    let mut errors = Vec::new();
+    let batch_dir = match new_batch_dir(&now) {
+        Ok(dir) => dir,
+        Err(e) => return (staged, vec![format!("Cannot create staging folder: {}", e)]),
+    };
+    let batch = batch_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    for (i, path) in paths.iter().enumerate() {
+        let id = uuid::Uuid::new_v4().to_string();
+        let name = Path::new(path).file_name().unwrap_or_default();
+        // One folder per item so identical file names never collide
+        let item_dir = batch_dir.join(&id);
+        let dest = item_dir.join(name);
+        let is_directory = std::fs::symlink_metadata(path).map(|m| m.is_dir()).unwrap_or(false);
+        let moved = std::fs::create_dir(&item_dir).and_then(|_| std::fs::rename(path, &dest));
+        match moved {
+            Ok(_) => {
+                let item = StagedItem {
+                    id,
+                    original_path: path.clone(),
+                    staged_path: dest.to_string_lossy().to_string(),
+                    size_bytes: sizes.get(i).copied().unwrap_or(0),
+                    staged_at: now.to_rfc3339(),
+                    batch: batch.clone(),
+                    is_directory,
+                };
+                staged.push(item);
+            }
+            Err(e) => {
+                let _ = std::fs::remove_dir(&item_dir);
+                errors.push(format!("{}: {}", path, e));
+            }
+        }
+    }
+    write_manifest(&batch_dir, &staged);
+    (staged, errors)
+}
+
+pub fn list_staged() -> Vec<StagedItem> {
+    batch_dirs().iter().flat_map(|d| read_manifest(d)).collect()
+}
+
+/// Ids of every item staged by one cleanup, for restoring or purging it as a whole.
+pub fn batch_ids(batch: &str) -> Vec<String> {
+    list_staged().into_iter().filter(|i| i.batch == batch).map(|i| i.id).collect()
+}
+
+/// Remove items matching `pick` from every manifest, applying `action` to each. Items whose action fails stay staged.
+fn take_items<F>(pick: impl Fn(&StagedItem) -> bool, mut action: F) -> (usize, Vec<String>)
+where
+    F: FnMut(&StagedItem) -> Result<(), String>,
+{
+    let mut done = 0usize;
+    let mut errors = Vec::new();
+    for batch_dir in batch_dirs() {
+        let manifest = read_manifest(&batch_dir);
+        let mut keep = Vec::new();
+        for item in manifest {
+            if !pick(&item) {
+                keep.push(item);
+                continue;
+            }
+            match action(&item) {
+                Ok(_) => {
+                    done += 1;
+                    if let Some(item_dir) = Path::new(&item.staged_path).parent() {
+                        let _ = std::fs::remove_dir_all(item_dir);
+                    }
+                }
+                Err(e) => {
+                    errors.push(e);
+                    keep.push(item);
+                }
+            }
+        }
+        write_manifest(&batch_dir, &keep);
+    }
+    (done, errors)
+}
+
+/// Move staged items back to where they came from. Never overwrites a file that reappeared there.
+pub fn restore(ids: &[String]) -> (usize, Vec<String>) {
+    take_items(|item| ids.contains(&item.id), |item| {
+        let original = Path::new(&item.original_path);
+        if original.exists() {
+            return Err(format!("{} already exists; not restoring over it", item.original_path));
+        }
+        if let Some(parent) = original.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", item.original_path, e))?;
+        }
+        std::fs::rename(&item.staged_path, original).map_err(|e| format!("{}: {}", item.original_path, e))
+    })
+}
+
+fn remove_staged(item: &StagedItem) -> Result<(), String> {
+    let path = Path::new(&item.staged_path);
+    let result = if path.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) };
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("{}: {}", item.staged_path, e)),
+    }
+}
+
+/// Permanently delete the given staged items now.
+pub fn purge(ids: &[String]) -> (usize, Vec<String>) {
+    take_items(|item| ids.contains(&item.id), remove_staged)
+}
+
+/// Permanently delete items staged longer than `retention_days` ago.
+pub fn purge_expired(retention_days: u32) -> (usize, Vec<String>) {
+    let cutoff = chrono::Local::now() - chrono::Duration::days(retention_days as i64);
+    take_items(
+        |item| {
+            chrono::DateTime::parse_from_rfc3339(&item.staged_at)
+                .map(|t| t < cutoff)
+                .unwrap_or(false)
+        },
+        remove_staged,
+    )
+}
+
+/// Background janitor: purges expired staged items at launch and every few hours.
+pub fn start_janitor_thread() {
+    std::thread::spawn(|| loop {
+        let retention_days = ContextStore::load().user_preferences.staging.retention_days;
+        let (purged, errors) = purge_expired(retention_days);
+        if purged > 0 || !errors.is_empty() {
+            println!("🧹 Staging janitor purged {} item(s), {} error(s)", purged, errors.len());
+        }
+        std::thread::sleep(std::time::Duration::from_secs(JANITOR_INTERVAL_SECS));
+    });
+}
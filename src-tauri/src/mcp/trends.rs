@@ -0,0 +1,111 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use super::context_store::{ContextStore, ScanSnapshot};
+
+/// One calendar month of snapshots, reduced to its last scan (the state the month ended in).
+#[derive(Debug, Clone, Serialize)]
+pub struct MonthlyTrend {
+    /// "2026-09"
+    pub month: String,
+    pub scans: usize,
+    pub junk_total_bytes: u64,
+    pub junk_by_category: HashMap<String, u64>,
+    pub disk_free_bytes: u64,
+    pub apps_installed: Option<usize>,
+    /// Change from the previous month's end state; None for the first month
+    pub junk_change_bytes: Option<i64>,
+    pub disk_free_change_bytes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Trends {
+    pub range: String,
+    pub points: Vec<ScanSnapshot>,
+    pub monthly: Vec<MonthlyTrend>,
+}
+
+/// Days covered by a range name; None means all history.
+fn range_days(range: &str) -> Result<Option<i64>, String> {
+    match range {
+        "week" => Ok(Some(7)),
+        "month" => Ok(Some(31)),
+        "quarter" => Ok(Some(92)),
+        "year" => Ok(Some(366)),
+        "all" => Ok(None),
+        other => Err(format!("Unknown trend range: {} (use week, month, quarter, year or all)", other)),
+    }
+}
+
+fn monthly(points: &[ScanSnapshot]) -> Vec<MonthlyTrend> {
+    let mut by_month: BTreeMap<String, (usize, &ScanSnapshot)> = BTreeMap::new();
+    for p in points {
+        let month = p.timestamp.get(..7).unwrap_or_default().to_string();
+        let entry = by_month.entry(month).or_insert((0, p));
+        entry.0 += 1;
+        entry.1 = p;
+    }
+    let mut out: Vec<MonthlyTrend> = Vec::new();
+    for (month, (scans, last)) in by_month {
+        let prev = out.last();
+        out.push(MonthlyTrend {
+            junk_change_bytes: prev.map(|p| last.junk_total_bytes as i64 - p.junk_total_bytes as i64),
+            disk_free_change_bytes: prev.map(|p| last.disk_free_bytes as i64 - p.disk_free_bytes as i64),
+            month,
+            scans,
+            junk_total_bytes: last.junk_total_bytes,
+            junk_by_category: last.junk_by_category.clone(),
+            disk_free_bytes: last.disk_free_bytes,
+            apps_installed: last.apps_installed,
+        });
+    }
+    out
+}
+
+/// Snapshot series and month-over-month rollup for `range` ("week", "month", "quarter", "year", "all").
+pub fn trends(range: &str) -> Result<Trends, String> {
+    let days = range_days(range)?;
+    let history = ContextStore::load().scan_history;
+    let points: Vec<ScanSnapshot> = match days {
+        Some(days) => {
+            let cutoff = chrono::Local::now() - chrono::Duration::days(days);
+            history
+                .into_iter()
+                .filter(|s| chrono::DateTime::parse_from_rfc3339(&s.timestamp).map(|t| t >= cutoff).unwrap_or(false))
+                .collect()
+        }
+        None => history,
+    };
+    Ok(Trends { range: range.to_string(), monthly: monthly(&points), points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snap(timestamp: &str, junk: u64, free: u64) -> ScanSnapshot {
+        ScanSnapshot { timestamp: timestamp.to_string(), junk_total_bytes: junk, disk_free_bytes: free, ..Default::default() }
+    }
+
+    #[test]
+    fn monthly_rollup_uses_last_scan_and_diffs_months() {
+        let points = vec![
+            snap("2026-08-03T10:00:00+02:00", 900, 100),
+            snap("2026-08-28T10:00:00+02:00", 800, 120),
+            snap("2026-09-15T10:00:00+02:00", 500, 200),
+        ];
+        let months = monthly(&points);
+        assert_eq!(months.len(), 2);
+        assert_eq!(months[0].scans, 2);
+        assert_eq!(months[0].junk_total_bytes, 800);
+        assert_eq!(months[0].junk_change_bytes, None);
+        assert_eq!(months[1].junk_change_bytes, Some(-300));
+        assert_eq!(months[1].disk_free_change_bytes, Some(80));
+    }
+
+    #[test]
+    fn rejects_unknown_ranges() {
+        assert!(range_days("decade").is_err());
+        assert_eq!(range_days("all").unwrap(), None);
+    }
+}
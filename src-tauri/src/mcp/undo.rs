@@ -0,0 +1,69 @@
+use serde::Serialize;
+use std::path::Path;
+
+use super::context_store::ContextStore;
+use super::staging;
+use crate::scanners::trash;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UndoResult {
+    /// When the undone cleanup ran
+    pub timestamp: String,
+    pub restored: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Put back everything from the most recent cleanup that hasn't been undone yet, from the Trash or
+/// the staging area. Items already purged or emptied from the Trash are reported as errors. The
+/// cleanup stays undoable while anything it removed could still be put back (say, once its
+/// original location is free again).
+pub fn undo_last_deletion() -> Result<UndoResult, String> {
+    let mut ctx = ContextStore::load();
+    let index = ctx
+        .deletion_history
+        .iter()
+        .rposition(|r| !r.undone)
+        .ok_or("There is no cleanup to undo")?;
+    let record = ctx.deletion_history[index].clone();
+    let staged = staging::list_staged();
+
+    let mut restored = Vec::new();
+    let mut errors = Vec::new();
+    // Paths that failed with nothing left to restore from
+    let mut gone = Vec::new();
+    for original in &record.paths_deleted {
+        let location = record
+            .locations
+            .get(original)
+            .cloned()
+            .or_else(|| trash::locate_trashed(Path::new(original)).map(|p| p.to_string_lossy().to_string()));
+        let result = match location {
+            None => {
+                gone.push(original.clone());
+                Err(format!("{} is no longer in the Trash", original))
+            }
+            Some(location) => match staged.iter().find(|s| s.staged_path == location) {
+                Some(item) => match staging::restore(std::slice::from_ref(&item.id)) {
+                    (1, _) => Ok(()),
+                    (_, e) => Err(e.join("; ")),
+                },
+                None if std::fs::symlink_metadata(&location).is_err() => {
+                    gone.push(original.clone());
+                    Err(format!("{} is no longer in the Trash", original))
+                }
+                None => trash::put_back(Path::new(&location), Path::new(original)),
+            },
+        };
+        match result {
+            Ok(()) => restored.push(original.clone()),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let record = &mut ctx.deletion_history[index];
+    record.paths_deleted.retain(|p| !restored.contains(p));
+    record.undone = record.paths_deleted.iter().all(|p| gone.contains(p));
+    let timestamp = record.timestamp.clone();
+    ctx.save();
+    Ok(UndoResult { timestamp, restored, errors })
+}
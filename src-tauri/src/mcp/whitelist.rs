@@ -0,0 +1,125 @@
+use glob::{MatchOptions, Pattern};
+
+use super::context_store::ContextStore;
+use super::path_norm::path_key;
+
+/// Never deleted automatically, even inside cache folders.
+const BUILT_IN: &[&str] = &[
+    ".DS_Store",
+    "localized",
+    "Icon\r",
+    ".lock",
+    "settings.json",
+    "config.json",
+    "User Data",   // Protect Chrome User Data root if scanned
+    "bookmarks",   // Protect bookmarks
+    "Login Data*", // Saved passwords and their journal
+    "desktop.ini", // Windows system file
+    "ntuser.dat",  // Windows registry
+];
+
+// Patterns and paths both go through `path_key`, so case follows the filesystem
+const OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Glob patterns for files that scans skip and deletions refuse: the built-in list plus
+/// UserPrefs.always_skip_patterns. A pattern without `/` matches the file name (`*.sqlite-wal`);
+/// any other is matched against the whole path (`**/Login Data*`, `~/Library/Application Support/MyApp/**`).
+#[derive(Debug, Clone, Default)]
+pub struct Whitelist {
+    names: Vec<(String, Pattern)>,
+    paths: Vec<(String, Pattern)>,
+}
+
+fn key(path: &str) -> String {
+    let key = path_key(path);
+    if cfg!(target_os = "windows") { key.replace('\\', "/") } else { key }
+}
+
+impl Whitelist {
+    pub fn new(user_patterns: &[String]) -> Self {
+        let home = dirs::home_dir().map(|h| h.to_string_lossy().to_string()).unwrap_or_default();
+        let mut list = Self::default();
+        for raw in BUILT_IN.iter().copied().chain(user_patterns.iter().map(|p| p.trim())) {
+            if raw.is_empty() {
+                continue;
+            }
+            let expanded = key(&match raw.strip_prefix('~') {
+                Some(rest) => format!("{}{}", home, rest),
+                None => raw.to_string(),
+            });
+            let target = if expanded.contains('/') { &mut list.paths } else { &mut list.names };
+            // `Folder/**` also protects the folder itself
+            let variants = std::iter::once(expanded.as_str()).chain(expanded.strip_suffix("/**"));
+            for variant in variants {
+                match Pattern::new(variant) {
+                    Ok(pattern) => target.push((raw.to_string(), pattern)),
+                    Err(e) => eprintln!("Ignoring whitelist pattern {:?}: {}", raw, e),
+                }
+            }
+        }
+        list
+    }
+
+    /// Built-in patterns plus the user's.
+    pub fn load() -> Self {
+        Self::for_store(&ContextStore::load())
+    }
+
+    /// Built-in patterns plus the user's, from a context store the caller already loaded.
+    pub fn for_store(store: &ContextStore) -> Self {
+        Self::new(&store.user_preferences.always_skip_patterns)
+    }
+
+    /// The pattern (as written) that protects `path`, if any.
+    pub fn matching(&self, path: &str) -> Option<&str> {
+        let path = key(path);
+        let name = path.rsplit('/').next().unwrap_or(&path);
+        self.names
+            .iter()
+            .find(|(_, p)| p.matches_with(name, OPTIONS))
+            .or_else(|| self.paths.iter().find(|(_, p)| p.matches_with(&path, OPTIONS)))
+            .map(|(raw, _)| raw.as_str())
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.matching(path).is_some()
+    }
+}
+
+/// Patterns that don't parse; the UI can flag these before saving.
+pub fn invalid_patterns(patterns: &[String]) -> Vec<String> {
+    patterns.iter().filter(|p| Pattern::new(p.trim()).is_err()).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_and_paths_match_globs() {
+        let list = Whitelist::new(&[
+            "*.sqlite-wal".to_string(),
+            "**/Login Data*".to_string(),
+            "~/Library/Application Support/MyApp/**".to_string(),
+        ]);
+        assert!(list.matches("/Users/jane/Library/Caches/app/db.sqlite-wal"));
+        assert!(list.matches("/Users/jane/Library/Caches/app/.DS_Store"));
+        assert!(list.matches("/Users/jane/Library/Google/Chrome/Default/Login Data-journal"));
+        assert!(!list.matches("/Users/jane/Library/Caches/app/db.sqlite"));
+
+        let app = dirs::home_dir().unwrap().join("Library/Application Support/MyApp");
+        assert!(list.matches(&app.to_string_lossy()));
+        assert!(list.matches(&app.join("state/data.bin").to_string_lossy()));
+        assert!(!list.matches(&app.with_file_name("MyAppHelper").to_string_lossy()));
+        assert_eq!(list.matching(&app.join("x").to_string_lossy()), Some("~/Library/Application Support/MyApp/**"));
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported() {
+        assert_eq!(invalid_patterns(&["*.log".to_string(), "[abc".to_string()]), vec!["[abc".to_string()]);
+    }
+}
@@ -0,0 +1,282 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::dir_byte_size;
+use crate::mcp::context_store::ContextStore;
+use crate::mcp::managed::ManagedPolicy;
+use crate::mcp::safety_gate;
+
+/// Newest build-tools versions kept; older ones are recommended for removal.
+const BUILD_TOOLS_KEEP: usize = 2;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AndroidItem {
+    /// sdkmanager package id ("system-images;android-34;google_apis;arm64-v8a", "build-tools;30.0.3")
+    /// or "avd:<name>" for emulators
+    pub id: String,
+    /// "system_image" | "build_tools" | "avd"
+    pub kind: String,
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub recommended: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AndroidSdkReport {
+    pub sdk_path: Option<String>,
+    pub items: Vec<AndroidItem>,
+    pub total_bytes: u64,
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AndroidCleanResult {
+    pub removed: usize,
+    pub bytes_freed: u64,
+    pub errors: Vec<String>,
+}
+
+fn sdk_root() -> Option<PathBuf> {
+    let env = ["ANDROID_HOME", "ANDROID_SDK_ROOT"].iter().filter_map(std::env::var_os).map(PathBuf::from);
+    let default = dirs::home_dir().map(|h| h.join("Library/Android/sdk"));
+    env.chain(default).find(|p| p.is_dir())
+}
+
+fn avd_root() -> Option<PathBuf> {
+    std::env::var_os("ANDROID_AVD_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".android/avd")))
+        .filter(|p| p.is_dir())
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect())
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+fn file_name(p: &Path) -> String {
+    p.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// "34.0.0" > "33.0.2" > "30.0.3"; pre-release suffixes ("35.0.0-rc1") are ignored.
+fn version_key(v: &str) -> Vec<u32> {
+    v.split(['.', '-']).map_while(|part| part.parse().ok()).collect()
+}
+
+/// Key/value pairs of an AVD config.ini / <name>.ini.
+fn read_ini(path: &Path) -> Vec<(String, String)> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+struct Avd {
+    name: String,
+    dir: PathBuf,
+    /// e.g. "system-images/android-34/google_apis/arm64-v8a"
+    sysdir: Option<String>,
+}
+
+fn avds(root: &Path) -> Vec<Avd> {
+    subdirs(root)
+        .into_iter()
+        .filter(|d| d.extension().map(|x| x == "avd").unwrap_or(false))
+        .map(|dir| {
+            let config = read_ini(&dir.join("config.ini"));
+            let get = |key: &str| config.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+            Avd {
+                name: get("AvdId").unwrap_or_else(|| dir.file_stem().unwrap_or_default().to_string_lossy().to_string()),
+                sysdir: get("image.sysdir.1").map(|s| s.trim_end_matches('/').to_string()),
+                dir,
+            }
+        })
+        .collect()
+}
+
+fn system_images(sdk: &Path, used: &HashSet<String>) -> Vec<AndroidItem> {
+    let mut items = Vec::new();
+    for api in subdirs(&sdk.join("system-images")) {
+        for tag in subdirs(&api) {
+            for abi in subdirs(&tag) {
+                let rel = format!("system-images/{}/{}/{}", file_name(&api), file_name(&tag), file_name(&abi));
+                let in_use = used.contains(&rel);
+                items.push(AndroidItem {
+                    id: rel.replace('/', ";"),
+                    kind: "system_image".to_string(),
+                    name: format!("{} {} ({})", file_name(&api), file_name(&tag), file_name(&abi)),
+                    size_bytes: dir_byte_size(&abi).allocated,
+                    path: abi.to_string_lossy().to_string(),
+                    recommended: !in_use,
+                    detail: if in_use { "Used by an emulator".to_string() } else { "No emulator uses this image".to_string() },
+                });
+            }
+        }
+    }
+    items
+}
+
+fn build_tools(sdk: &Path) -> Vec<AndroidItem> {
+    let mut versions = subdirs(&sdk.join("build-tools"));
+    versions.sort_by_key(|p| std::cmp::Reverse(version_key(&file_name(p))));
+    versions
+        .into_iter()
+        .enumerate()
+        .map(|(i, dir)| {
+            let version = file_name(&dir);
+            let obsolete = i >= BUILD_TOOLS_KEEP;
+            AndroidItem {
+                id: format!("build-tools;{}", version),
+                kind: "build_tools".to_string(),
+                name: format!("Build-tools {}", version),
+                size_bytes: dir_byte_size(&dir).allocated,
+                path: dir.to_string_lossy().to_string(),
+                recommended: obsolete,
+                detail: if obsolete {
+                    "Older than the newest versions; Gradle downloads it again if a project still pins it".to_string()
+                } else {
+                    "Recent version".to_string()
+                },
+            }
+        })
+        .collect()
+}
+
+fn avd_items(avds: &[Avd]) -> Vec<AndroidItem> {
+    avds.iter()
+        .map(|avd| AndroidItem {
+            id: format!("avd:{}", avd.name),
+            kind: "avd".to_string(),
+            name: avd.name.clone(),
+            path: avd.dir.to_string_lossy().to_string(),
+            size_bytes: dir_byte_size(&avd.dir).allocated,
+            recommended: false,
+            detail: avd.sysdir.clone().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Android SDK system images, build-tools and emulator (AVD) disks with sizes, largest first.
+pub fn scan_android_sdk() -> AndroidSdkReport {
+    let ctx = ContextStore::load();
+    let avds = avd_root().map(|r| avds(&r)).unwrap_or_default();
+    let used: HashSet<String> = avds.iter().filter_map(|a| a.sysdir.clone()).collect();
+
+    let sdk = sdk_root();
+    let mut items = avd_items(&avds);
+    if let Some(sdk) = &sdk {
+        items.extend(system_images(sdk, &used));
+        items.extend(build_tools(sdk));
+    }
+    items.retain(|i| !ctx.is_excluded(&i.path));
+    items.sort_by_key(|i| std::cmp::Reverse(i.size_bytes));
+
+    AndroidSdkReport {
+        sdk_path: sdk.map(|p| p.to_string_lossy().to_string()),
+        total_bytes: items.iter().map(|i| i.size_bytes).sum(),
+        reclaimable_bytes: items.iter().filter(|i| i.recommended).map(|i| i.size_bytes).sum(),
+        items,
+    }
+}
+
+/// sdkmanager / avdmanager from the command-line tools, newest first.
+fn sdk_tool(sdk: Option<&Path>, name: &str) -> Option<PathBuf> {
+    let sdk = sdk?;
+    let mut candidates = vec![sdk.join("cmdline-tools/latest/bin").join(name)];
+    let mut versions = subdirs(&sdk.join("cmdline-tools"));
+    versions.sort_by_key(|p| std::cmp::Reverse(version_key(&file_name(p))));
+    candidates.extend(versions.into_iter().map(|v| v.join("bin").join(name)));
+    candidates.push(sdk.join("tools/bin").join(name));
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+fn run_tool(tool: &Path, args: &[&str]) -> Result<(), String> {
+    let out = Command::new(tool).args(args).output().map_err(|e| e.to_string())?;
+    if out.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&out.stderr).trim().to_string())
+    }
+}
+
+fn remove_item(item: &AndroidItem, sdk: Option<&Path>) -> Result<(), String> {
+    let path = Path::new(&item.path);
+    let tool_result = if item.kind == "avd" {
+        sdk_tool(sdk, "avdmanager").map(|t| run_tool(&t, &["delete", "avd", "-n", &item.name]))
+    } else {
+        sdk_tool(sdk, "sdkmanager").map(|t| run_tool(&t, &["--uninstall", &item.id]))
+    };
+    if matches!(tool_result, Some(Ok(()))) && !path.exists() {
+        return Ok(());
+    }
+    // No command-line tools (or no Java for them): remove the files directly
+    std::fs::remove_dir_all(path).map_err(|e| format!("{}: {}", item.path, e))?;
+    if item.kind == "avd" {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::remove_file(parent.join(format!("{}.ini", item.name)));
+        }
+    }
+    Ok(())
+}
+
+/// Remove the given items (ids from `scan_android_sdk`), preferring sdkmanager/avdmanager.
+pub fn remove_android_items(ids: &[String]) -> AndroidCleanResult {
+    let report = scan_android_sdk();
+    let sdk = report.sdk_path.as_deref().map(Path::new);
+    let ctx = ContextStore::load();
+    let policy = ManagedPolicy::load();
+    let mut result = AndroidCleanResult { removed: 0, bytes_freed: 0, errors: vec![] };
+    for id in ids {
+        let Some(item) = report.items.iter().find(|i| &i.id == id) else {
+            result.errors.push(format!("{}: not found", id));
+            continue;
+        };
+        if let Err(e) = safety_gate::check_paths("Android SDK cleanup", std::slice::from_ref(&item.path), &policy, &ctx) {
+            result.errors.push(e);
+            continue;
+        }
+        match remove_item(item, sdk) {
+            Ok(_) => {
+                result.removed += 1;
+                result.bytes_freed += item.size_bytes;
+            }
+            Err(e) => result.errors.push(e),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_tools_versions_sort_numerically() {
+        let mut v = vec!["9.0.0", "30.0.3", "34.0.0", "35.0.0-rc1"];
+        v.sort_by_key(|s| std::cmp::Reverse(version_key(s)));
+        assert_eq!(v, vec!["35.0.0-rc1", "34.0.0", "30.0.3", "9.0.0"]);
+    }
+
+    #[test]
+    fn unused_system_images_are_recommended() {
+        let sdk = tempfile::tempdir().unwrap();
+        for abi in ["android-33/google_apis/arm64-v8a", "android-34/google_apis/arm64-v8a"] {
+            std::fs::create_dir_all(sdk.path().join("system-images").join(abi)).unwrap();
+        }
+        let used: HashSet<String> = ["system-images/android-34/google_apis/arm64-v8a".to_string()].into_iter().collect();
+        let items = system_images(sdk.path(), &used);
+        assert_eq!(items.len(), 2);
+        let old = items.iter().find(|i| i.id == "system-images;android-33;google_apis;arm64-v8a").unwrap();
+        assert!(old.recommended);
+        assert!(items.iter().any(|i| i.id.contains("android-34") && !i.recommended));
+    }
+}
@@ -0,0 +1,238 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// `diskutil apfs list` takes ~100 ms; the dashboard polls stats every few seconds.
+const CACHE_TTL: Duration = Duration::from_secs(30);
+
+lazy_static::lazy_static! {
+    static ref CACHE: Mutex<Option<(Instant, Vec<ApfsContainer>)>> = Mutex::new(None);
+    static ref PURGEABLE_CACHE: Mutex<Option<(Instant, Purgeable)>> = Mutex::new(None);
+}
+
+/// Space macOS can free on demand: purgeable files (iCloud-evictable, caches macOS manages) and
+/// Time Machine local snapshots. Finder counts it as available; `statfs` doesn't.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Purgeable {
+    /// "Available for important usage" minus the container's free space; None when unknown
+    pub purgeable_bytes: Option<u64>,
+    /// Dates of Time Machine local snapshots on the boot volume, oldest first
+    pub local_snapshots: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApfsVolume {
+    /// e.g. "disk3s5"
+    pub device: String,
+    pub name: String,
+    /// "System", "Data", "VM", "Preboot", "Recovery", ... (empty for plain user volumes)
+    pub roles: Vec<String>,
+    pub used_bytes: u64,
+    pub mount_point: Option<String>,
+}
+
+/// Volumes in one container share its free space, so per-volume "available" figures all
+/// report the same pool and must not be added up.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApfsContainer {
+    /// e.g. "disk3"
+    pub container_id: String,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub volumes: Vec<ApfsVolume>,
+}
+
+impl ApfsContainer {
+    pub fn used_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.free_bytes)
+    }
+}
+
+/// "/dev/disk3s1s1" (the sealed system snapshot) belongs to volume "disk3s1"; "disk3s10" does not.
+fn device_belongs_to_volume(device: &str, volume: &str) -> bool {
+    let device = device.trim_start_matches("/dev/");
+    device == volume
+        || device
+            .strip_prefix(volume)
+            .and_then(|rest| rest.strip_prefix('s'))
+            .map(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+            .unwrap_or(false)
+}
+
+/// (device, mount point) pairs of mounted APFS volumes, from `mount` output lines like
+/// "/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)".
+fn parse_mounts(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter(|l| l.contains("(apfs"))
+        .filter_map(|l| {
+            let (device, rest) = l.split_once(" on ")?;
+            let (mount, _) = rest.rsplit_once(" (")?;
+            Some((device.trim_start_matches("/dev/").to_string(), mount.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn load_containers() -> Vec<ApfsContainer> {
+    use std::process::Command;
+
+    let Ok(out) = Command::new("diskutil").args(["apfs", "list", "-plist"]).output() else { return vec![] };
+    let Ok(value) = plist::from_bytes::<serde_json::Value>(&out.stdout) else { return vec![] };
+    let mounts = Command::new("mount")
+        .output()
+        .map(|o| parse_mounts(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default();
+
+    let str_of = |v: &serde_json::Value, key: &str| v.get(key).and_then(|x| x.as_str()).unwrap_or("").to_string();
+    let u64_of = |v: &serde_json::Value, key: &str| v.get(key).and_then(|x| x.as_u64()).unwrap_or(0);
+
+    value
+        .get("Containers")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .map(|c| ApfsContainer {
+            container_id: str_of(c, "ContainerReference"),
+            total_bytes: u64_of(c, "CapacityCeiling"),
+            free_bytes: u64_of(c, "CapacityFree"),
+            volumes: c
+                .get("Volumes")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .map(|v| {
+                    let device = str_of(v, "DeviceIdentifier");
+                    ApfsVolume {
+                        mount_point: mounts
+                            .iter()
+                            .find(|(dev, _)| device_belongs_to_volume(dev, &device))
+                            .map(|(_, m)| m.clone()),
+                        name: str_of(v, "Name"),
+                        roles: v
+                            .get("Roles")
+                            .and_then(|r| r.as_array())
+                            .map(|r| r.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect())
+                            .unwrap_or_default(),
+                        used_bytes: u64_of(v, "CapacityInUse"),
+                        device,
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn load_containers() -> Vec<ApfsContainer> {
+    vec![]
+}
+
+/// APFS containers with their volumes (cached for a few seconds).
+pub fn containers() -> Vec<ApfsContainer> {
+    let mut cache = CACHE.lock().unwrap();
+    if let Some((at, list)) = cache.as_ref() {
+        if at.elapsed() < CACHE_TTL {
+            return list.clone();
+        }
+    }
+    let list = load_containers();
+    *cache = Some((Instant::now(), list.clone()));
+    list
+}
+
+/// NSURLVolumeAvailableCapacityForImportantUsageKey of the boot volume, read through JXA
+/// (the same figure Finder shows as "Available").
+#[cfg(target_os = "macos")]
+fn available_for_important_usage() -> Option<u64> {
+    const SCRIPT: &str = "ObjC.import('Foundation');\
+        var key = $.NSURLVolumeAvailableCapacityForImportantUsageKey;\
+        $.NSURL.fileURLWithPath('/').resourceValuesForKeysError([key], null).objectForKey(key).js";
+    let out = std::process::Command::new("osascript").args(["-l", "JavaScript", "-e", SCRIPT]).output().ok()?;
+    String::from_utf8_lossy(&out.stdout).trim().parse::<f64>().ok().map(|n| n as u64)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn available_for_important_usage() -> Option<u64> {
+    None
+}
+
+/// Dates of Time Machine local snapshots on the boot volume.
+pub fn local_snapshot_dates() -> Vec<String> {
+    #[cfg(target_os = "macos")]
+    {
+        std::process::Command::new("tmutil")
+            .args(["listlocalsnapshotdates", "/"])
+            .output()
+            .map(|o| parse_snapshot_dates(&String::from_utf8_lossy(&o.stdout)))
+            .unwrap_or_default()
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        vec![]
+    }
+}
+
+/// Date lines of `tmutil listlocalsnapshotdates` ("2026-10-16-101500"), skipping the header.
+fn parse_snapshot_dates(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| l.starts_with(|c: char| c.is_ascii_digit()))
+        .collect()
+}
+
+/// Purgeable space and local snapshots of the boot volume (cached like `containers`).
+pub fn purgeable() -> Purgeable {
+    if let Some((at, p)) = PURGEABLE_CACHE.lock().unwrap().as_ref() {
+        if at.elapsed() < CACHE_TTL {
+            return p.clone();
+        }
+    }
+    let free = container_for_mount(&containers(), "/").map(|c| c.free_bytes);
+    let p = Purgeable {
+        purgeable_bytes: available_for_important_usage().zip(free).map(|(important, free)| important.saturating_sub(free)),
+        local_snapshots: local_snapshot_dates(),
+    };
+    *PURGEABLE_CACHE.lock().unwrap() = Some((Instant::now(), p.clone()));
+    p
+}
+
+/// Container holding the volume mounted at `mount_point`.
+pub fn container_for_mount(containers: &[ApfsContainer], mount_point: &str) -> Option<ApfsContainer> {
+    containers
+        .iter()
+        .find(|c| c.volumes.iter().any(|v| v.mount_point.as_deref() == Some(mount_point)))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_devices_map_to_their_volume() {
+        assert!(device_belongs_to_volume("/dev/disk3s1s1", "disk3s1"));
+        assert!(device_belongs_to_volume("disk3s5", "disk3s5"));
+        assert!(!device_belongs_to_volume("disk3s10", "disk3s1"));
+        assert!(!device_belongs_to_volume("disk3s1", "disk3s10"));
+    }
+
+    #[test]
+    fn snapshot_dates_skip_the_header() {
+        let out = "Snapshot dates for all disks:\n2026-10-15-093012\n2026-10-16-101500\n";
+        assert_eq!(parse_snapshot_dates(out), vec!["2026-10-15-093012", "2026-10-16-101500"]);
+    }
+
+    #[test]
+    fn parses_apfs_mount_lines() {
+        let out = "/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)\n\
+                   devfs on /dev (devfs, local, nobrowse)\n\
+                   /dev/disk3s5 on /System/Volumes/Data (apfs, local, journaled, nobrowse, protect)\n\
+                   /dev/disk5s1 on /Volumes/My Backup (apfs, local, nodev, nosuid, journaled)";
+        let mounts = parse_mounts(out);
+        assert_eq!(mounts.len(), 3);
+        assert_eq!(mounts[0], ("disk3s1s1".to_string(), "/".to_string()));
+        assert_eq!(mounts[2].1, "/Volumes/My Backup");
+    }
+}
@@ -0,0 +1,165 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppCopy {
+    pub path: String,
+    pub name: String,
+    /// CFBundleShortVersionString
+    pub version: Option<String>,
+    /// CFBundleVersion
+    pub build: Option<String>,
+    pub size_bytes: u64,
+    /// "applications" | "user_applications" | "downloads" | "desktop"
+    pub location: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateApp {
+    pub bundle_id: String,
+    pub name: String,
+    /// Newest version, preferring the one in /Applications
+    pub keep: AppCopy,
+    /// Older or stray copies; remove with `remove_app_copy_command`
+    pub others: Vec<AppCopy>,
+    pub reclaimable_bytes: u64,
+}
+
+/// Where copies of apps pile up, and how deep to look in each.
+fn search_locations(home: &Path) -> Vec<(PathBuf, &'static str, usize)> {
+    vec![
+        (PathBuf::from("/Applications"), "applications", 2),
+        (home.join("Applications"), "user_applications", 2),
+        (home.join("Downloads"), "downloads", 3),
+        (home.join("Desktop"), "desktop", 2),
+    ]
+}
+
+/// Numeric comparison of dotted versions ("1.10" > "1.9"); non-numeric parts count as 0.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<u64> {
+        v.split(|c: char| !c.is_ascii_digit()).filter(|p| !p.is_empty()).map(|p| p.parse().unwrap_or(0)).collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    (0..a.len().max(b.len()))
+        .map(|i| a.get(i).copied().unwrap_or(0).cmp(&b.get(i).copied().unwrap_or(0)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Better copy first: higher version, then higher build, then the one in /Applications.
+fn rank(a: &AppCopy, b: &AppCopy) -> Ordering {
+    let version = |c: &AppCopy| c.version.clone().unwrap_or_default();
+    let build = |c: &AppCopy| c.build.clone().unwrap_or_default();
+    compare_versions(&version(b), &version(a))
+        .then_with(|| compare_versions(&build(b), &build(a)))
+        .then_with(|| (b.location == "applications").cmp(&(a.location == "applications")))
+}
+
+fn group_copies(copies: Vec<(String, AppCopy)>) -> Vec<DuplicateApp> {
+    let mut by_id: HashMap<String, Vec<AppCopy>> = HashMap::new();
+    for (bundle_id, copy) in copies {
+        by_id.entry(bundle_id).or_default().push(copy);
+    }
+    let mut duplicates: Vec<DuplicateApp> = by_id
+        .into_iter()
+        .filter(|(_, copies)| copies.len() > 1)
+        .map(|(bundle_id, mut copies)| {
+            copies.sort_by(rank);
+            let keep = copies.remove(0);
+            DuplicateApp {
+                bundle_id,
+                name: keep.name.clone(),
+                reclaimable_bytes: copies.iter().map(|c| c.size_bytes).sum(),
+                keep,
+                others: copies,
+            }
+        })
+        .collect();
+    duplicates.sort_by_key(|d| std::cmp::Reverse(d.reclaimable_bytes));
+    duplicates
+}
+
+#[cfg(target_os = "macos")]
+fn read_copy(path: &Path, location: &str) -> Option<(String, AppCopy)> {
+    let file = std::fs::File::open(path.join("Contents/Info.plist")).ok()?;
+    let info: serde_json::Value = plist::from_reader(file).ok()?;
+    let field = |key: &str| info.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let bundle_id = field("CFBundleIdentifier")?;
+    Some((
+        bundle_id,
+        AppCopy {
+            path: path.to_string_lossy().to_string(),
+            name: path.file_stem().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            version: field("CFBundleShortVersionString"),
+            build: field("CFBundleVersion"),
+            size_bytes: super::dir_byte_size(path).allocated,
+            location: location.to_string(),
+        },
+    ))
+}
+
+/// Apps installed more than once under the same bundle id ("Zoom.app" and "zoom copy.app", or one
+/// in /Applications and another still in Downloads), with the copy worth keeping picked out.
+#[cfg(target_os = "macos")]
+pub fn scan_duplicate_apps() -> Vec<DuplicateApp> {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let mut copies = Vec::new();
+    for (root, location, depth) in search_locations(&home) {
+        // Apps bundled inside other apps (helpers, updaters) are never walked into
+        let walker = WalkDir::new(&root).min_depth(1).max_depth(depth).follow_links(false).into_iter().filter_entry(|e| {
+            !e.file_name().to_string_lossy().starts_with('.') && e.path().parent().and_then(|p| p.extension()).map(|x| x != "app").unwrap_or(true)
+        });
+        for entry in walker.filter_map(|e| e.ok()) {
+            if entry.file_type().is_dir() && entry.path().extension().is_some_and(|e| e == "app") {
+                copies.extend(read_copy(entry.path(), location));
+            }
+        }
+    }
+    let ctx = crate::mcp::context_store::ContextStore::load();
+    copies.retain(|(_, c)| !ctx.is_protected(&c.path));
+    group_copies(copies)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn scan_duplicate_apps() -> Vec<DuplicateApp> {
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copy(path: &str, version: &str, location: &str) -> (String, AppCopy) {
+        (
+            "us.zoom.xos".to_string(),
+            AppCopy {
+                path: path.to_string(),
+                name: "Zoom".to_string(),
+                version: Some(version.to_string()),
+                build: None,
+                size_bytes: 100,
+                location: location.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn keeps_the_newest_copy_preferring_applications() {
+        assert_eq!(compare_versions("5.10.1", "5.9.7"), Ordering::Greater);
+        assert_eq!(compare_versions("2.0", "2.0.0"), Ordering::Equal);
+
+        let groups = group_copies(vec![
+            copy("/Users/me/Downloads/zoom copy.app", "5.9.7", "downloads"),
+            copy("/Applications/zoom.us.app", "5.10.1", "applications"),
+            copy("/Users/me/Desktop/zoom.us.app", "5.10.1", "desktop"),
+        ]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].keep.path, "/Applications/zoom.us.app");
+        assert_eq!(groups[0].others.len(), 2);
+        assert_eq!(groups[0].reclaimable_bytes, 200);
+    }
+}
@@ -0,0 +1,246 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::mcp::context_store::{AutoCleanPolicy, ContextStore};
+use crate::mcp::file_index::index_files_measured;
+use crate::mcp::managed::ManagedPolicy;
+use crate::scanners::junk::{scan_junk_with_options, JunkScanOptions};
+use crate::scanners::{power, process, system_stats, trash};
+
+/// How often free space is checked.
+const CHECK_INTERVAL_SECS: u64 = 15 * 60;
+/// After a run, wait this long before cleaning again even if space is still low.
+const COOLDOWN_SECS: u64 = 6 * 3600;
+const TRASH_CATEGORY: &str = "Trash Bin";
+/// Categories auto-clean may remove without asking: things apps and the user already gave up on.
+pub const ALLOWED_CATEGORIES: [&str; 3] = ["User Caches", "User Logs", TRASH_CATEGORY];
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct AutoCleanReport {
+    pub free_bytes_before: u64,
+    pub categories: Vec<String>,
+    /// Removed paths, or the ones that would be when `dry_run`
+    pub paths: Vec<String>,
+    pub bytes_freed: u64,
+    pub dry_run: bool,
+    pub errors: Vec<String>,
+}
+
+fn free_bytes() -> u64 {
+    let (total, used) = system_stats::root_disk_usage();
+    total.saturating_sub(used)
+}
+
+fn is_due(policy: &AutoCleanPolicy, free: u64) -> bool {
+    policy.enabled && !policy.categories.is_empty() && free < policy.min_free_bytes
+}
+
+/// Top-level Trash entries trashed before `cutoff`; Finder's dot files are left alone.
+fn old_trash_entries(trash_dir: &Path, cutoff: SystemTime) -> Vec<PathBuf> {
+    std::fs::read_dir(trash_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| e.path())
+        .filter(|p| trash::trashed_at(p).is_some_and(|t| t < cutoff))
+        .collect()
+}
+
+/// Candidates from the approved categories that pass the same gates as confirm_delete: safe per the
+/// indexer, not excluded by the user or an administrator, and not open in a running app.
+fn candidates(home: &Path, policy: &AutoCleanPolicy, ctx: &ContextStore, managed: &ManagedPolicy) -> Vec<(String, u64)> {
+    let allowed = |path: &str| !managed.is_excluded(path) && !ctx.is_excluded(path) && crate::mcp::self_protect::check("Auto-clean", path).is_ok();
+    let categories: Vec<String> = policy
+        .categories
+        .iter()
+        .filter(|c| *c != TRASH_CATEGORY && ALLOWED_CATEGORIES.contains(&c.as_str()))
+        .cloned()
+        .collect();
+    let mut items: Vec<(String, u64)> = Vec::new();
+    if !categories.is_empty() {
+        let options = JunkScanOptions { only_categories: categories, group_by_directory: true, ..Default::default() };
+        let paths: Vec<String> = scan_junk_with_options(&home.to_string_lossy(), &options).items.into_iter().map(|i| i.path).collect();
+        items.extend(
+            index_files_measured(&paths, &crate::mcp::whitelist::Whitelist::for_store(ctx))
+                .into_iter()
+                .filter(|f| f.is_safe_to_delete && allowed(&f.path))
+                .map(|f| (f.path, f.size_bytes)),
+        );
+    }
+    if policy.categories.iter().any(|c| c == TRASH_CATEGORY) {
+        if let Some(cutoff) = trash::age_cutoff(policy.trash_min_age_days.max(1)) {
+            for entry in old_trash_entries(&home.join(".Trash"), cutoff) {
+                let path = entry.to_string_lossy().to_string();
+                if allowed(&path) {
+                    items.push((path, crate::mcp::dry_run::size_of(&entry)));
+                }
+            }
+        }
+    }
+    let paths: Vec<String> = items.iter().map(|(p, _)| p.clone()).collect();
+    let busy: std::collections::HashSet<String> = process::open_files(&paths).into_iter().map(|f| f.path).collect();
+    items.retain(|(p, _)| !busy.contains(p));
+    items
+}
+
+/// Category names in `categories` auto-clean refuses to handle.
+pub fn disallowed_categories(categories: &[String]) -> Vec<String> {
+    categories.iter().filter(|c| !ALLOWED_CATEGORIES.contains(&c.as_str())).cloned().collect()
+}
+
+/// Clean the policy's approved categories once, the way confirm_delete would: backed up when that
+/// is on, then staged or moved to the Trash, and recorded so the run can be undone. Only items
+/// already in the Trash are deleted for good, since there is nowhere further to move them.
+pub fn run(policy: &AutoCleanPolicy, dry_run: bool) -> AutoCleanReport {
+    let mut report = AutoCleanReport { free_bytes_before: free_bytes(), categories: policy.categories.clone(), dry_run, ..Default::default() };
+    let Some(home) = dirs::home_dir() else {
+        report.errors.push("Could not find home directory".to_string());
+        return report;
+    };
+    let ctx = ContextStore::load();
+    if ctx.unreadable {
+        // Without the stored protections every path would look unprotected
+        report.errors.push(format!("Auto-clean skipped: {} could not be read", ContextStore::store_path().display()));
+        return report;
+    }
+    let managed = ManagedPolicy::load();
+    let items = candidates(&home, policy, &ctx, &managed);
+    if dry_run {
+        report.bytes_freed = items.iter().map(|(_, size)| size).sum();
+        report.paths = items.into_iter().map(|(p, _)| p).collect();
+        return report;
+    }
+    let paths: Vec<String> = items.iter().map(|(p, _)| p.clone()).collect();
+    if let Err(e) = managed.check_destructive("Auto-clean", &paths).and_then(|_| ctx.check_protected("Auto-clean", &paths)) {
+        report.errors.push(e);
+        return report;
+    }
+    let trash_dir = home.join(".Trash");
+    let (emptied, cleaned): (Vec<_>, Vec<_>) = items.into_iter().partition(|(p, _)| Path::new(p).starts_with(&trash_dir));
+
+    // Archive first when backups are on; nothing is removed if that fails
+    if ctx.user_preferences.backup.enabled && !cleaned.is_empty() {
+        if let Err(e) = crate::mcp::backup::back_up(&cleaned) {
+            report.errors.push(format!("Backup failed, nothing was removed: {}", e));
+            return report;
+        }
+    }
+
+    let mut emptied_paths = Vec::new();
+    let mut emptied_bytes = 0;
+    for (path, _) in emptied {
+        match trash::remove_permanently(Path::new(&path)) {
+            Ok(size) => {
+                emptied_bytes += size;
+                emptied_paths.push(path);
+            }
+            Err(e) => report.errors.push(e),
+        }
+    }
+    if !emptied_paths.is_empty() {
+        ContextStore::load().record_deletion(emptied_paths.clone(), emptied_bytes);
+    }
+
+    // Recorded last, so undo_last_deletion_command puts this part back
+    let (removed, removed_bytes) = if ctx.user_preferences.staging.enabled {
+        let sizes: Vec<u64> = cleaned.iter().map(|(_, size)| *size).collect();
+        let paths: Vec<String> = cleaned.into_iter().map(|(p, _)| p).collect();
+        let (staged, errors) = crate::mcp::staging::stage_paths(&paths, &sizes);
+        report.errors.extend(errors);
+        let removed: Vec<String> = staged.iter().map(|i| i.original_path.clone()).collect();
+        let bytes = staged.iter().map(|i| i.size_bytes).sum();
+        if !removed.is_empty() {
+            let locations = staged.iter().map(|i| (i.original_path.clone(), i.staged_path.clone())).collect();
+            ContextStore::load().record_deletion_with_locations(removed.clone(), bytes, None, locations);
+        }
+        (removed, bytes)
+    } else {
+        let total = cleaned.iter().map(|(_, size)| size).sum();
+        let snapshot = if cleaned.is_empty() { None } else { tauri::async_runtime::block_on(crate::mcp::restore_point::before_cleanup(total)) };
+        let result = trash::trash_in_batches(&cleaned, |_| {});
+        report.errors.extend(result.errors);
+        if !result.removed.is_empty() {
+            let locations = trash::locate_trashed_all(&result.removed);
+            ContextStore::load().record_deletion_with_locations(result.removed.clone(), result.bytes_freed, snapshot, locations);
+        }
+        (result.removed, result.bytes_freed)
+    };
+
+    report.bytes_freed = emptied_bytes + removed_bytes;
+    report.paths = emptied_paths.into_iter().chain(removed).collect();
+    for path in &report.paths {
+        crate::scanners::space_lens::invalidate_cached_sizes(path);
+    }
+    report
+}
+
+fn notify(app: &AppHandle, report: &AutoCleanReport) {
+    if report.paths.is_empty() || power::should_suppress_alert("auto_clean") {
+        return;
+    }
+    let gb = report.bytes_freed as f64 / 1_073_741_824.0;
+    let body = if report.dry_run {
+        format!("Disk space is low. Auto-clean would free {:.1} GB from {}; dry run is on, so nothing was removed.", gb, report.categories.join(", "))
+    } else {
+        format!("Disk space was low, so Alto freed {:.1} GB from {}.", gb, report.categories.join(", "))
+    };
+    let _ = app.notification().builder().title("Auto-clean").body(&body).show();
+}
+
+/// Background check: when auto-clean is on and free space is below the threshold, clean the
+/// approved categories, tell the user, and emit "auto-clean-finished" with the report.
+pub fn start_auto_clean_thread(app: AppHandle) {
+    thread::spawn(move || {
+        let mut last_run: Option<Instant> = None;
+        loop {
+            thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECS));
+            if last_run.is_some_and(|t| t.elapsed().as_secs() < COOLDOWN_SECS) {
+                continue;
+            }
+            let policy = ContextStore::load().user_preferences.auto_clean;
+            if !is_due(&policy, free_bytes()) || power::should_defer_heavy_jobs() {
+                continue;
+            }
+            let report = run(&policy, crate::mcp::dry_run::enabled(None));
+            println!("🧹 Auto-clean freed {} bytes from {} item(s), {} error(s)", report.bytes_freed, report.paths.len(), report.errors.len());
+            notify(&app, &report);
+            let _ = app.emit("auto-clean-finished", &report);
+            last_run = Some(Instant::now());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_only_when_enabled_and_low_on_space() {
+        let policy = AutoCleanPolicy { enabled: true, min_free_bytes: 100, ..Default::default() };
+        assert!(is_due(&policy, 99));
+        assert!(!is_due(&policy, 100));
+        assert!(!is_due(&AutoCleanPolicy { enabled: false, ..policy.clone() }, 0));
+        assert!(!is_due(&AutoCleanPolicy { categories: vec![], ..policy }, 0));
+    }
+
+    #[test]
+    fn only_low_risk_categories_are_allowed() {
+        let asked = vec!["User Caches".to_string(), "Trash Bin".to_string(), "Screenshots".to_string()];
+        assert_eq!(disallowed_categories(&asked), vec!["Screenshots".to_string()]);
+    }
+
+    #[test]
+    fn old_trash_entries_skip_recent_and_hidden_items() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("old.txt"), b"x").unwrap();
+        std::fs::write(dir.path().join(".DS_Store"), b"x").unwrap();
+        let later = SystemTime::now() + Duration::from_secs(60);
+        assert_eq!(old_trash_entries(dir.path(), later), vec![dir.path().join("old.txt")]);
+        assert!(old_trash_entries(dir.path(), SystemTime::UNIX_EPOCH).is_empty());
+    }
+}
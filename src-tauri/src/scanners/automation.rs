@@ -0,0 +1,92 @@
+use serde::Serialize;
+use std::process::Command;
+
+/// AppleScript error raised when the user denied Automation (Apple Events) access.
+const ERR_AE_NOT_PERMITTED: &str = "-1743";
+/// AppleScript error raised when the user cancelled a dialog.
+const ERR_USER_CANCELED: &str = "-128";
+const ADMIN_CANCELLED: &str = "Administrator authorization was cancelled";
+
+pub const AUTOMATION_SETTINGS_URL: &str = "x-apple.systempreferences:com.apple.preference.security?Privacy_Automation";
+
+/// Automation access is missing; commands return it as `AltoError::AutomationPermission`.
+#[derive(Debug, Serialize)]
+pub struct AutomationPermissionError {
+    pub message: String,
+    pub settings_url: &'static str,
+}
+
+impl AutomationPermissionError {
+    pub fn new(action: &str) -> Self {
+        Self {
+            message: format!(
+                "{} needs Automation permission. Allow Alto in System Settings → Privacy & Security → Automation.",
+                action
+            ),
+            settings_url: AUTOMATION_SETTINGS_URL,
+        }
+    }
+}
+
+pub fn is_permission_denied(stderr: &str) -> bool {
+    stderr.contains(ERR_AE_NOT_PERMITTED)
+}
+
+/// Outcome of an `osascript` run that sends Apple Events to another app, separating a denied
+/// Automation permission from other failures.
+pub enum ScriptOutcome {
+    Ok(String),
+    PermissionDenied,
+    Failed(String),
+}
+
+fn quote_applescript(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Run `command` as root through macOS's administrator password dialog, with `prompt` shown when
+/// given. This isn't an Apple Event to another app, so -1743 doesn't apply; -128 means the user
+/// cancelled the dialog.
+pub fn run_admin_script(command: &str, prompt: Option<&str>) -> Result<String, String> {
+    let prompt = prompt.map(|p| format!(" with prompt {}", quote_applescript(p))).unwrap_or_default();
+    let script = format!("do shell script {}{} with administrator privileges", quote_applescript(command), prompt);
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(&script)
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+    let err = String::from_utf8_lossy(&output.stderr);
+    if err.contains(ERR_USER_CANCELED) {
+        Err(ADMIN_CANCELLED.to_string())
+    } else {
+        Err(err.trim().to_string())
+    }
+}
+
+/// Ask for an administrator's password in macOS's own dialog, showing `reason`. Ok only once an
+/// administrator authenticated; a cancelled dialog or a standard account is an error.
+pub fn authorize_admin(reason: &str) -> Result<(), String> {
+    run_admin_script("/usr/bin/true", Some(reason)).map(|_| ()).map_err(|e| {
+        if e == ADMIN_CANCELLED { e } else { format!("Administrator authorization failed: {}", e) }
+    })
+}
+
+pub fn run_osascript(script: &str) -> Result<ScriptOutcome, String> {
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if output.status.success() {
+        return Ok(ScriptOutcome::Ok(String::from_utf8_lossy(&output.stdout).to_string()));
+    }
+    let err = String::from_utf8_lossy(&output.stderr).to_string();
+    if is_permission_denied(&err) {
+        Ok(ScriptOutcome::PermissionDenied)
+    } else {
+        Ok(ScriptOutcome::Failed(err))
+    }
+}
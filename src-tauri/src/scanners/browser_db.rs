@@ -0,0 +1,362 @@
+use rusqlite::{params, Connection, OpenFlags};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Seconds between 1601-01-01 (Chromium timestamps) and the Unix epoch.
+const CHROMIUM_EPOCH_OFFSET: i64 = 11_644_473_600;
+/// Seconds between the Unix epoch and 2001-01-01 (Safari timestamps).
+const MAC_EPOCH_OFFSET: i64 = 978_307_200;
+
+/// What to remove from a browser database, instead of deleting the whole file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PrivacyCleanOptions {
+    /// Only remove history older than this many days; all of it when unset
+    pub history_older_than_days: Option<u32>,
+    /// Only remove cookies of these domains and their subdomains; all cookies when empty
+    pub cookie_domains: Vec<String>,
+    /// Keep cookies of sites with a saved password, so the user stays logged in there
+    pub keep_login_cookies: bool,
+}
+
+impl Default for PrivacyCleanOptions {
+    fn default() -> Self {
+        Self { history_older_than_days: None, cookie_domains: vec![], keep_login_cookies: true }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Schema {
+    ChromiumHistory,
+    ChromiumCookies,
+    SafariHistory,
+    FirefoxPlaces,
+    FirefoxCookies,
+}
+
+fn tables(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type = 'table'")?;
+    let names = stmt.query_map([], |row| row.get(0))?.collect();
+    names
+}
+
+fn detect(tables: &[String]) -> Option<Schema> {
+    let has = |name: &str| tables.iter().any(|t| t == name);
+    if has("visits") && has("urls") {
+        Some(Schema::ChromiumHistory)
+    } else if has("cookies") && has("meta") {
+        Some(Schema::ChromiumCookies)
+    } else if has("history_visits") && has("history_items") {
+        Some(Schema::SafariHistory)
+    } else if has("moz_places") && has("moz_historyvisits") {
+        Some(Schema::FirefoxPlaces)
+    } else if has("moz_cookies") {
+        Some(Schema::FirefoxCookies)
+    } else {
+        None
+    }
+}
+
+/// "accounts.google.com" -> "google.com"; keeps three labels for "bbc.co.uk"-style suffixes.
+fn site_of(host: &str) -> String {
+    let host = host.trim_start_matches('.').to_lowercase();
+    let labels: Vec<&str> = host.split('.').collect();
+    let keep = match labels.as_slice() {
+        [.., second, last] if last.len() == 2 && ["co", "com", "org", "net", "ac", "gov", "edu"].contains(second) => 3,
+        _ => 2,
+    };
+    labels[labels.len().saturating_sub(keep)..].join(".")
+}
+
+/// Whether a cookie set for `host` is removed: it belongs to one of `domains` (or any domain when
+/// empty) and not to a site in `keep_sites`.
+pub fn cookie_selected(host: &str, domains: &[String], keep_sites: &[String]) -> bool {
+    let host = host.trim_start_matches('.').to_lowercase();
+    if keep_sites.contains(&site_of(&host)) {
+        return false;
+    }
+    domains.is_empty()
+        || domains.iter().any(|d| {
+            let d = d.trim().trim_start_matches('.').to_lowercase();
+            host == d || host.ends_with(&format!(".{}", d))
+        })
+}
+
+/// "https://accounts.google.com:443/login" -> "google.com"
+fn origin_site(origin: &str) -> Option<String> {
+    origin.split("://").nth(1).and_then(|rest| rest.split(['/', ':']).next()).filter(|h| !h.is_empty()).map(site_of)
+}
+
+/// Sites with a saved password in the profile's "Login Data".
+fn chromium_login_sites(profile_dir: &Path) -> Vec<String> {
+    let Ok(conn) = Connection::open_with_flags(profile_dir.join("Login Data"), OpenFlags::SQLITE_OPEN_READ_ONLY) else {
+        return vec![];
+    };
+    let Ok(mut stmt) = conn.prepare("SELECT origin_url FROM logins WHERE blacklisted_by_user = 0") else {
+        return vec![];
+    };
+    let mut sites: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map(|rows| rows.flatten().filter_map(|origin| origin_site(&origin)).collect())
+        .unwrap_or_default();
+    sites.sort();
+    sites.dedup();
+    sites
+}
+
+/// Sites with a saved password in the Firefox profile's logins.json.
+fn firefox_login_sites(profile_dir: &Path) -> Vec<String> {
+    let logins: serde_json::Value = std::fs::read_to_string(profile_dir.join("logins.json"))
+        .ok()
+        .and_then(|d| serde_json::from_str(&d).ok())
+        .unwrap_or_default();
+    let mut sites: Vec<String> = logins
+        .get("logins")
+        .and_then(|l| l.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|login| login.get("hostname").and_then(|h| h.as_str()))
+        .filter_map(origin_site)
+        .collect();
+    sites.sort();
+    sites.dedup();
+    sites
+}
+
+fn cutoff_unix(days: Option<u32>) -> i64 {
+    match days {
+        Some(days) => chrono::Utc::now().timestamp() - days as i64 * 86400,
+        None => i64::MAX / 2_000_000,
+    }
+}
+
+/// Delete the rows picked by `pick(host)` from `table`, whose host column is `host_column`.
+fn delete_cookies(conn: &Connection, table: &str, host_column: &str, pick: impl Fn(&str) -> bool) -> rusqlite::Result<usize> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn.prepare(&format!("SELECT rowid, {} FROM {}", host_column, table))?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<rusqlite::Result<_>>()?;
+        rows
+    };
+    let mut delete = conn.prepare(&format!("DELETE FROM {} WHERE rowid = ?1", table))?;
+    let mut removed = 0;
+    for (rowid, _) in rows.iter().filter(|(_, host)| pick(host)) {
+        removed += delete.execute(params![rowid])?;
+    }
+    Ok(removed)
+}
+
+fn clean_schema(conn: &Connection, schema: Schema, tables: &[String], path: &Path, options: &PrivacyCleanOptions) -> rusqlite::Result<usize> {
+    let cutoff = cutoff_unix(options.history_older_than_days);
+    let has = |name: &str| tables.iter().any(|t| t == name);
+    match schema {
+        Schema::ChromiumHistory => {
+            let chromium_cutoff = (cutoff + CHROMIUM_EPOCH_OFFSET) * 1_000_000;
+            let mut removed = conn.execute("DELETE FROM visits WHERE visit_time < ?1", params![chromium_cutoff])?;
+            removed += conn.execute("DELETE FROM urls WHERE id NOT IN (SELECT url FROM visits)", [])?;
+            if has("downloads") {
+                removed += conn.execute("DELETE FROM downloads WHERE start_time < ?1", params![chromium_cutoff])?;
+            }
+            // Rows that point at removed visits, URLs or downloads would still show where the user went
+            let orphans = [
+                ("visit_source", "DELETE FROM visit_source WHERE id NOT IN (SELECT id FROM visits)"),
+                ("keyword_search_terms", "DELETE FROM keyword_search_terms WHERE url_id NOT IN (SELECT id FROM urls)"),
+                ("visited_links", "DELETE FROM visited_links WHERE link_url_id NOT IN (SELECT id FROM urls)"),
+                ("segments", "DELETE FROM segments WHERE url_id NOT IN (SELECT id FROM urls)"),
+                ("segment_usage", "DELETE FROM segment_usage WHERE segment_id NOT IN (SELECT id FROM segments)"),
+                ("content_annotations", "DELETE FROM content_annotations WHERE visit_id NOT IN (SELECT id FROM visits)"),
+                ("context_annotations", "DELETE FROM context_annotations WHERE visit_id NOT IN (SELECT id FROM visits)"),
+                ("downloads_url_chains", "DELETE FROM downloads_url_chains WHERE id NOT IN (SELECT id FROM downloads)"),
+                ("downloads_slices", "DELETE FROM downloads_slices WHERE download_id NOT IN (SELECT id FROM downloads)"),
+            ];
+            for (table, sql) in orphans {
+                if has(table) {
+                    conn.execute(sql, [])?;
+                }
+            }
+            Ok(removed)
+        }
+        Schema::SafariHistory => {
+            let mut removed = conn.execute("DELETE FROM history_visits WHERE visit_time < ?1", params![(cutoff - MAC_EPOCH_OFFSET) as f64])?;
+            removed += conn.execute("DELETE FROM history_items WHERE id NOT IN (SELECT history_item FROM history_visits)", [])?;
+            Ok(removed)
+        }
+        Schema::FirefoxPlaces => {
+            let mut removed = conn.execute("DELETE FROM moz_historyvisits WHERE visit_date < ?1", params![cutoff * 1_000_000])?;
+            // Bookmarked pages stay, only their visits go
+            removed += conn.execute(
+                "DELETE FROM moz_places WHERE id NOT IN (SELECT place_id FROM moz_historyvisits) AND id NOT IN (SELECT fk FROM moz_bookmarks WHERE fk IS NOT NULL)",
+                [],
+            )?;
+            // Kept places would still show how often and when they were visited
+            conn.execute(
+                "UPDATE moz_places SET visit_count = (SELECT COUNT(*) FROM moz_historyvisits WHERE place_id = moz_places.id),
+                 last_visit_date = (SELECT MAX(visit_date) FROM moz_historyvisits WHERE place_id = moz_places.id)",
+                [],
+            )?;
+            if has("moz_places_metadata") {
+                conn.execute("DELETE FROM moz_places_metadata WHERE created_at < ?1", params![cutoff * 1000])?;
+            }
+            // Hosts in moz_origins feed address bar autofill even after their pages are gone
+            let orphans = [
+                ("moz_inputhistory", "DELETE FROM moz_inputhistory WHERE place_id NOT IN (SELECT id FROM moz_places)"),
+                ("moz_places_metadata", "DELETE FROM moz_places_metadata WHERE place_id NOT IN (SELECT id FROM moz_places)"),
+                ("moz_annos", "DELETE FROM moz_annos WHERE place_id NOT IN (SELECT id FROM moz_places)"),
+                ("moz_origins", "DELETE FROM moz_origins WHERE id NOT IN (SELECT origin_id FROM moz_places WHERE origin_id IS NOT NULL)"),
+            ];
+            for (table, sql) in orphans {
+                if has(table) {
+                    conn.execute(sql, [])?;
+                }
+            }
+            Ok(removed)
+        }
+        Schema::FirefoxCookies => {
+            let keep = if options.keep_login_cookies { path.parent().map(firefox_login_sites).unwrap_or_default() } else { vec![] };
+            delete_cookies(conn, "moz_cookies", "host", |host| cookie_selected(host, &options.cookie_domains, &keep))
+        }
+        Schema::ChromiumCookies => {
+            // Newer Chromium keeps cookies in <profile>/Network/
+            let profile_dir = path.parent().map(|p| if p.ends_with("Network") { p.parent().unwrap_or(p) } else { p });
+            let keep = if options.keep_login_cookies { profile_dir.map(chromium_login_sites).unwrap_or_default() } else { vec![] };
+            delete_cookies(conn, "cookies", "host_key", |host| cookie_selected(host, &options.cookie_domains, &keep))
+        }
+    }
+}
+
+/// Whether `path` is a browser database that can be cleaned row by row.
+pub fn supports(path: &Path) -> bool {
+    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| tables(&conn))
+        .map(|t| detect(&t).is_some())
+        .unwrap_or(false)
+}
+
+/// Remove history and cookie rows from a browser database per `options`, then vacuum it. Returns
+/// `Ok(None)` when `path` isn't a database this knows, so the caller can delete the file instead.
+pub fn clean(path: &Path, options: &PrivacyCleanOptions) -> Result<Option<usize>, String> {
+    let Ok(mut conn) = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE) else { return Ok(None) };
+    let Ok(tables) = tables(&conn) else { return Ok(None) };
+    let Some(schema) = detect(&tables) else { return Ok(None) };
+    let fail = |e: rusqlite::Error| format!("{}: {}", path.display(), e);
+    conn.busy_timeout(std::time::Duration::from_secs(2)).map_err(fail)?;
+
+    let tx = conn.transaction().map_err(fail)?;
+    let removed = clean_schema(&tx, schema, &tables, path, options).map_err(fail)?;
+    tx.commit().map_err(fail)?;
+    // Deleted rows stay readable in free pages and the write-ahead log until both are rewritten
+    conn.execute_batch("VACUUM").map_err(fail)?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(())).map_err(fail)?;
+    Ok(Some(removed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_selected_cookies_but_keeps_logins() {
+        let dir = tempfile::tempdir().unwrap();
+        let logins = Connection::open(dir.path().join("Login Data")).unwrap();
+        logins
+            .execute_batch("CREATE TABLE logins (origin_url TEXT, blacklisted_by_user INTEGER); INSERT INTO logins VALUES ('https://accounts.google.com/', 0);")
+            .unwrap();
+        let cookies_path = dir.path().join("Cookies");
+        let conn = Connection::open(&cookies_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE meta (key TEXT, value TEXT); CREATE TABLE cookies (host_key TEXT, name TEXT);
+             INSERT INTO cookies VALUES ('.google.com', 'SID'), ('.doubleclick.net', 'IDE'), ('ads.tracker.com', 'uid'), ('news.example.org', 'pref');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let options = PrivacyCleanOptions { cookie_domains: vec!["doubleclick.net".into(), "tracker.com".into(), "google.com".into()], ..Default::default() };
+        assert_eq!(clean(&cookies_path, &options).unwrap(), Some(2));
+        let conn = Connection::open(&cookies_path).unwrap();
+        let left: Vec<String> = conn.prepare("SELECT host_key FROM cookies").unwrap().query_map([], |r| r.get(0)).unwrap().flatten().collect();
+        assert_eq!(left, vec![".google.com".to_string(), "news.example.org".to_string()]);
+    }
+
+    #[test]
+    fn removes_only_old_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("History");
+        let conn = Connection::open(&path).unwrap();
+        let now = (chrono::Utc::now().timestamp() + CHROMIUM_EPOCH_OFFSET) * 1_000_000;
+        let old = now - 40 * 86400 * 1_000_000;
+        conn.execute_batch("CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT); CREATE TABLE visits (id INTEGER PRIMARY KEY, url INTEGER, visit_time INTEGER);").unwrap();
+        conn.execute("INSERT INTO urls VALUES (1, 'https://old.example'), (2, 'https://new.example')", []).unwrap();
+        conn.execute("INSERT INTO visits VALUES (1, 1, ?1), (2, 2, ?2)", params![old, now]).unwrap();
+        drop(conn);
+
+        let options = PrivacyCleanOptions { history_older_than_days: Some(30), ..Default::default() };
+        assert_eq!(clean(&path, &options).unwrap(), Some(2));
+        assert_eq!(clean(&path, &PrivacyCleanOptions::default()).unwrap(), Some(2));
+        assert_eq!(clean(&dir.path().join("not-a-db.plist"), &PrivacyCleanOptions::default()).unwrap(), None);
+    }
+
+    #[test]
+    fn clears_chromium_history_side_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("History");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT); CREATE TABLE visits (id INTEGER PRIMARY KEY, url INTEGER, visit_time INTEGER);
+             CREATE TABLE visited_links (id INTEGER PRIMARY KEY, link_url_id INTEGER);
+             CREATE TABLE segments (id INTEGER PRIMARY KEY, url_id INTEGER); CREATE TABLE segment_usage (id INTEGER PRIMARY KEY, segment_id INTEGER);
+             CREATE TABLE content_annotations (visit_id INTEGER); CREATE TABLE context_annotations (visit_id INTEGER);
+             CREATE TABLE downloads (id INTEGER PRIMARY KEY, start_time INTEGER); CREATE TABLE downloads_url_chains (id INTEGER, url TEXT);
+             INSERT INTO urls VALUES (1, 'https://example.com'); INSERT INTO visits VALUES (1, 1, 1000);
+             INSERT INTO visited_links VALUES (1, 1); INSERT INTO segments VALUES (1, 1); INSERT INTO segment_usage VALUES (1, 1);
+             INSERT INTO content_annotations VALUES (1); INSERT INTO context_annotations VALUES (1);
+             INSERT INTO downloads VALUES (1, 1000); INSERT INTO downloads_url_chains VALUES (1, 'https://example.com/file.zip');",
+        )
+        .unwrap();
+        drop(conn);
+
+        assert_eq!(clean(&path, &PrivacyCleanOptions::default()).unwrap(), Some(3));
+        let conn = Connection::open(&path).unwrap();
+        for table in ["visited_links", "segments", "segment_usage", "content_annotations", "context_annotations", "downloads_url_chains"] {
+            let left: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0)).unwrap();
+            assert_eq!(left, 0, "{}", table);
+        }
+    }
+
+    #[test]
+    fn keeps_bookmarked_firefox_places() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("places.sqlite");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, visit_count INTEGER, last_visit_date INTEGER, origin_id INTEGER);
+             CREATE TABLE moz_historyvisits (id INTEGER PRIMARY KEY, place_id INTEGER, visit_date INTEGER);
+             CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, fk INTEGER);
+             CREATE TABLE moz_origins (id INTEGER PRIMARY KEY, host TEXT);
+             CREATE TABLE moz_places_metadata (id INTEGER PRIMARY KEY, place_id INTEGER, created_at INTEGER);
+             CREATE TABLE moz_annos (id INTEGER PRIMARY KEY, place_id INTEGER);
+             INSERT INTO moz_places VALUES (1, 'https://bookmarked.example', 5, 1000, 1), (2, 'https://visited.example', 1, 1000, 2);
+             INSERT INTO moz_historyvisits VALUES (1, 1, 1000), (2, 2, 1000);
+             INSERT INTO moz_bookmarks VALUES (1, 1), (2, NULL);
+             INSERT INTO moz_origins VALUES (1, 'bookmarked.example'), (2, 'visited.example');
+             INSERT INTO moz_places_metadata VALUES (1, 1, 1), (2, 2, 1);
+             INSERT INTO moz_annos VALUES (1, 1), (2, 2);",
+        )
+        .unwrap();
+        drop(conn);
+
+        assert_eq!(clean(&path, &PrivacyCleanOptions::default()).unwrap(), Some(3));
+        let conn = Connection::open(&path).unwrap();
+        let count = |table: &str| -> i64 { conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0)).unwrap() };
+        assert_eq!(count("moz_places"), 1);
+        assert_eq!((count("moz_origins"), count("moz_annos"), count("moz_places_metadata")), (1, 1, 0));
+        let (visits, last): (i64, Option<i64>) =
+            conn.query_row("SELECT visit_count, last_visit_date FROM moz_places", [], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        assert_eq!((visits, last), (0, None));
+    }
+
+    #[test]
+    fn groups_hosts_by_site() {
+        assert_eq!(site_of(".accounts.google.com"), "google.com");
+        assert_eq!(site_of("www.bbc.co.uk"), "bbc.co.uk");
+        assert!(!cookie_selected("mail.google.com", &[], &["google.com".to_string()]));
+    }
+}
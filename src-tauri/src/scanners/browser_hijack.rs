@@ -0,0 +1,342 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+use super::malware_signatures::SignatureSet;
+
+/// Chromium browsers checked: (name, policy domain / bundle id, data folder under Application
+/// Support, settings URL scheme).
+const CHROMIUM_BROWSERS: &[(&str, &str, &str, &str)] = &[
+    ("Google Chrome", "com.google.Chrome", "Google/Chrome", "chrome"),
+    ("Brave", "com.brave.Browser", "BraveSoftware/Brave-Browser", "brave"),
+    ("Microsoft Edge", "com.microsoft.Edge", "Microsoft Edge", "edge"),
+];
+
+/// Registrable domains of search engines a browser ships with or users commonly pick; anything
+/// else set as the default is worth a look.
+const KNOWN_SEARCH_DOMAINS: &[&str] = &[
+    "bing.com", "duckduckgo.com", "ecosia.org", "brave.com", "startpage.com", "baidu.com", "naver.com",
+    "qwant.com", "kagi.com", "perplexity.ai",
+];
+/// Engines with a domain per country (google.de, yandex.ru, yahoo.co.jp).
+const KNOWN_SEARCH_BRANDS: &[&str] = &["google", "yandex", "yahoo"];
+/// Query parameters that carry the search terms.
+const SEARCH_QUERY_KEYS: &[&str] = &["q", "p", "query", "search", "text", "wd"];
+
+/// Chromium policies adware sets to pin a homepage or search engine, or force an extension in.
+const HIJACK_POLICY_KEYS: &[&str] = &[
+    "HomepageLocation",
+    "NewTabPageLocation",
+    "RestoreOnStartupURLs",
+    "DefaultSearchProviderSearchURL",
+    "ExtensionInstallForcelist",
+];
+
+/// Chromium extension install locations set by policy or by another program rather than the user.
+const POLICY_LOCATIONS: &[u64] = &[7, 9];
+const EXTERNAL_LOCATIONS: &[u64] = &[2, 3, 6];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Remediation {
+    /// "open_settings" | "remove_extension" | "remove_policy"
+    pub action: String,
+    /// Settings URL, extension id or policy plist
+    pub target: String,
+    pub instructions: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BrowserFinding {
+    pub browser: String,
+    /// Profile folder ("Default", "Profile 1"); None for policies, which apply to every profile
+    pub profile: Option<String>,
+    /// "search_engine" | "homepage" | "startup_pages" | "policy" | "extension"
+    pub kind: String,
+    pub detail: String,
+    /// File the setting was read from
+    pub source: String,
+    pub remediation: Remediation,
+}
+
+fn host_of(url: &str) -> String {
+    let rest = url.split("://").nth(1).unwrap_or(url);
+    rest.split(['/', '?', '#', ':']).next().unwrap_or("").to_lowercase()
+}
+
+/// A country or generic ending after a brand: "com", "de", "co.uk", "com.br".
+fn is_brand_suffix(suffix: &str) -> bool {
+    match suffix.split('.').collect::<Vec<_>>().as_slice() {
+        [tld] => *tld == "com" || tld.len() == 2,
+        [second, tld] => (*second == "co" || *second == "com") && tld.len() == 2,
+        _ => false,
+    }
+}
+
+/// Whether `host` is one of the well-known engines or a subdomain of one.
+fn is_known_search_host(host: &str) -> bool {
+    let under = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+    KNOWN_SEARCH_DOMAINS.iter().any(|d| under(d))
+        || KNOWN_SEARCH_BRANDS.iter().any(|brand| {
+            // The brand must be the registrable label: google.de, www.google.co.uk, not google.evil-search.com
+            host.match_indices(&format!("{}.", brand))
+                .any(|(i, m)| (i == 0 || host[..i].ends_with('.')) && is_brand_suffix(&host[i + m.len()..]))
+        })
+}
+
+/// Whether `url` is a search page: a host or path segment starting with "search", a query
+/// parameter that carries search terms, or a `{searchTerms}` template.
+fn looks_like_search(url: &str) -> bool {
+    let lower = url.to_lowercase();
+    let rest = lower.split_once("://").map(|(_, r)| r).unwrap_or(&lower);
+    let (before_query, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let mut parts = before_query.split('#').next().unwrap_or("").split('/');
+    let host = parts.next().unwrap_or("");
+    host.split('.').any(|label| label.starts_with("search"))
+        || parts.any(|segment| segment.starts_with("search"))
+        || lower.contains("{searchterms}")
+        || query.split(['&', '#']).any(|pair| SEARCH_QUERY_KEYS.contains(&pair.split('=').next().unwrap_or("")))
+}
+
+/// Why `url` looks like a hijacked search or start page, if it does: a domain from the malware
+/// definitions, or a search page that isn't one of the well-known engines.
+fn hijack_reason(url: &str, signatures: &SignatureSet) -> Option<String> {
+    let host = host_of(url);
+    if host.is_empty() {
+        return None;
+    }
+    if let Some(sig) = signatures.match_domain(&host) {
+        return Some(format!("{} points to {}, a known {} domain", url, host, sig.name));
+    }
+    (!is_known_search_host(&host) && looks_like_search(url)).then(|| format!("{} is not a well-known search engine", host))
+}
+
+struct Profile<'a> {
+    browser: &'a str,
+    scheme: &'a str,
+    name: String,
+    source: String,
+}
+
+impl Profile<'_> {
+    fn finding(&self, kind: &str, detail: String, remediation: Remediation) -> BrowserFinding {
+        BrowserFinding {
+            browser: self.browser.to_string(),
+            profile: Some(self.name.clone()),
+            kind: kind.to_string(),
+            detail,
+            source: self.source.clone(),
+            remediation,
+        }
+    }
+
+    fn open_settings(&self, page: &str, what: &str) -> Remediation {
+        let url = format!("{}://settings/{}", self.scheme, page);
+        Remediation {
+            action: "open_settings".to_string(),
+            instructions: format!("Open {} in {} and reset the {}.", url, self.browser, what),
+            target: url,
+        }
+    }
+}
+
+/// Search engine, homepage and startup pages in a profile's `Preferences`.
+fn preference_findings(prefs: &Value, profile: &Profile, signatures: &SignatureSet) -> Vec<BrowserFinding> {
+    let mut findings = Vec::new();
+    let search = prefs.pointer("/default_search_provider_data/template_url_data/url").and_then(|v| v.as_str());
+    if let Some(reason) = search.and_then(|url| hijack_reason(url, signatures)) {
+        findings.push(profile.finding("search_engine", format!("Default search engine changed: {}", reason), profile.open_settings("search", "default search engine")));
+    }
+    let homepage = prefs.get("homepage").and_then(|v| v.as_str());
+    let homepage_used = prefs.get("homepage_is_newtabpage").and_then(|v| v.as_bool()) != Some(true);
+    if let Some(reason) = homepage.filter(|_| homepage_used).and_then(|url| hijack_reason(url, signatures)) {
+        findings.push(profile.finding("homepage", format!("Homepage changed: {}", reason), profile.open_settings("appearance", "home button page")));
+    }
+    // 4 = "Open a specific page or set of pages"
+    if prefs.pointer("/session/restore_on_startup").and_then(|v| v.as_u64()) == Some(4) {
+        let urls = prefs.pointer("/session/startup_urls").and_then(|v| v.as_array()).into_iter().flatten().filter_map(|u| u.as_str());
+        if let Some(reason) = urls.filter_map(|url| hijack_reason(url, signatures)).next() {
+            findings.push(profile.finding("startup_pages", format!("Startup pages changed: {}", reason), profile.open_settings("onStartup", "pages opened on startup")));
+        }
+    }
+    findings
+}
+
+/// Extensions in a profile's (Secure) Preferences that are known-bad, were forced in by policy, or
+/// came from outside the web store and take over the search engine or homepage.
+fn extension_findings(prefs: &Value, profile: &Profile, signatures: &SignatureSet) -> Vec<BrowserFinding> {
+    let Some(settings) = prefs.pointer("/extensions/settings").and_then(|v| v.as_object()) else {
+        return vec![];
+    };
+    let mut findings = Vec::new();
+    for (id, ext) in settings {
+        let name = ext.pointer("/manifest/name").and_then(|v| v.as_str()).unwrap_or(id);
+        let location = ext.get("location").and_then(|v| v.as_u64()).unwrap_or(0);
+        let from_store = ext.get("from_webstore").and_then(|v| v.as_bool()).unwrap_or(false);
+        let overrides = ext.pointer("/manifest/chrome_settings_overrides").is_some();
+        let reason = if let Some(sig) = signatures.match_extension_id(id) {
+            format!("known {} extension", sig.name)
+        } else if POLICY_LOCATIONS.contains(&location) {
+            "force-installed by a browser policy".to_string()
+        } else if overrides && !from_store && EXTERNAL_LOCATIONS.contains(&location) {
+            "installed by another program and overrides your search engine or homepage".to_string()
+        } else {
+            continue;
+        };
+        let remediation = if POLICY_LOCATIONS.contains(&location) {
+            Remediation {
+                action: "remove_policy".to_string(),
+                target: id.clone(),
+                instructions: "Remove the configuration profile or policy that forces it (see the policy findings), then remove the extension.".to_string(),
+            }
+        } else {
+            Remediation {
+                action: "remove_extension".to_string(),
+                target: id.clone(),
+                instructions: format!("Open {}://extensions in {} and remove \"{}\".", profile.scheme, profile.browser, name),
+            }
+        };
+        findings.push(profile.finding("extension", format!("Suspicious extension \"{}\" ({}): {}", name, id, reason), remediation));
+    }
+    findings
+}
+
+/// Hijack policies in one policy plist (already parsed) for the `domain` preferences domain.
+/// Policies from a configuration profile are only reported when they point somewhere suspicious,
+/// since IT departments set homepages and extensions that way too.
+fn policy_findings(browser: &str, domain: &str, policies: &Value, source: &str, signatures: &SignatureSet) -> Vec<BrowserFinding> {
+    let managed = source.starts_with("/Library/Managed Preferences");
+    let instructions = |key: &str| {
+        if managed {
+            "Installed by a configuration profile: remove it in System Settings > Privacy & Security > Profiles.".to_string()
+        } else {
+            format!("Run `defaults delete {} {}` in Terminal, then restart {}.", domain, key, browser)
+        }
+    };
+    HIJACK_POLICY_KEYS
+        .iter()
+        .filter_map(|key| {
+            let value = policies.get(*key)?;
+            let text = match value {
+                Value::Array(items) => items.iter().filter_map(|i| i.as_str()).collect::<Vec<_>>().join(", "),
+                other => other.as_str().map(|s| s.to_string()).unwrap_or_else(|| other.to_string()),
+            };
+            let suspicious = match *key {
+                "ExtensionInstallForcelist" => !managed || text.split(", ").any(|e| signatures.match_extension_id(e.split(';').next().unwrap_or(e)).is_some()),
+                _ => !managed || text.split(", ").any(|url| hijack_reason(url, signatures).is_some()),
+            };
+            suspicious.then(|| BrowserFinding {
+                browser: browser.to_string(),
+                profile: None,
+                kind: "policy".to_string(),
+                detail: format!("Policy {} forces {}", key, text),
+                source: source.to_string(),
+                remediation: Remediation { action: "remove_policy".to_string(), target: format!("{}:{}", source, key), instructions: instructions(key) },
+            })
+        })
+        .collect()
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// Policy plists Chromium reads on macOS: machine- and user-level managed preferences (written by
+/// configuration profiles) and the user's own preferences domain.
+#[cfg(target_os = "macos")]
+fn policy_files(home: &Path, domain: &str) -> Vec<std::path::PathBuf> {
+    let user = home.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    vec![
+        std::path::PathBuf::from(format!("/Library/Managed Preferences/{}.plist", domain)),
+        std::path::PathBuf::from(format!("/Library/Managed Preferences/{}/{}.plist", user, domain)),
+        home.join("Library/Preferences").join(format!("{}.plist", domain)),
+    ]
+}
+
+/// Changed search engines, forced homepages and suspicious extensions in Chrome, Brave and Edge.
+#[cfg(target_os = "macos")]
+pub fn scan(home: &Path, signatures: &SignatureSet) -> Vec<BrowserFinding> {
+    let mut findings = Vec::new();
+    for (browser, domain, folder, scheme) in CHROMIUM_BROWSERS {
+        for path in policy_files(home, domain) {
+            let policies: Option<Value> = std::fs::File::open(&path).ok().and_then(|f| plist::from_reader(f).ok());
+            if let Some(policies) = policies {
+                findings.extend(policy_findings(browser, domain, &policies, &path.to_string_lossy(), signatures));
+            }
+        }
+        let data_dir = home.join("Library/Application Support").join(folder);
+        for entry in std::fs::read_dir(&data_dir).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name != "Default" && !name.starts_with("Profile ") {
+                continue;
+            }
+            for file in ["Preferences", "Secure Preferences"] {
+                let path = entry.path().join(file);
+                let Some(prefs) = read_json(&path) else { continue };
+                let profile = Profile { browser, scheme, name: name.clone(), source: path.to_string_lossy().to_string() };
+                if file == "Preferences" {
+                    findings.extend(preference_findings(&prefs, &profile, signatures));
+                }
+                findings.extend(extension_findings(&prefs, &profile, signatures));
+            }
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn profile() -> Profile<'static> {
+        Profile { browser: "Google Chrome", scheme: "chrome", name: "Default".to_string(), source: "Preferences".to_string() }
+    }
+
+    #[test]
+    fn flags_hijacked_search_and_startup_pages() {
+        let signatures = SignatureSet::built_in();
+        let prefs = json!({
+            "default_search_provider_data": { "template_url_data": { "url": "https://searchbaron.com/?q={searchTerms}" } },
+            "homepage": "https://www.google.com/",
+            "homepage_is_newtabpage": false,
+            "session": { "restore_on_startup": 4, "startup_urls": ["https://search.example-results.net/home"] }
+        });
+        let findings = preference_findings(&prefs, &profile(), &signatures);
+        let kinds: Vec<&str> = findings.iter().map(|f| f.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["search_engine", "startup_pages"]);
+        assert_eq!(findings[0].remediation.target, "chrome://settings/search");
+
+        let clean = json!({ "default_search_provider_data": { "template_url_data": { "url": "https://duckduckgo.com/?q={searchTerms}" } } });
+        assert!(preference_findings(&clean, &profile(), &signatures).is_empty());
+    }
+
+    #[test]
+    fn matches_search_pages_and_engine_domains_exactly() {
+        let signatures = SignatureSet::built_in();
+        assert!(hijack_reason("https://research.university.edu/", &signatures).is_none());
+        assert!(hijack_reason("https://www.google.co.uk/search?q={searchTerms}", &signatures).is_none());
+        assert!(hijack_reason("https://search.yahoo.co.jp/search?p={searchTerms}", &signatures).is_none());
+        assert!(hijack_reason("https://google.evil-search.com/?q={searchTerms}", &signatures).is_some());
+        assert!(hijack_reason("https://notbing.com/results?q=cats", &signatures).is_some());
+        assert!(hijack_reason("https://portal.example.net/search/web", &signatures).is_some());
+    }
+
+    #[test]
+    fn flags_policy_and_overriding_extensions() {
+        let signatures = SignatureSet::built_in();
+        let prefs = json!({ "extensions": { "settings": {
+            "aaaa": { "location": 1, "from_webstore": true, "manifest": { "name": "Password Manager" } },
+            "bbbb": { "location": 7, "manifest": { "name": "Search Pro" } },
+            "cccc": { "location": 6, "from_webstore": false, "manifest": { "name": "Quick Search", "chrome_settings_overrides": {} } }
+        } } });
+        let findings = extension_findings(&prefs, &profile(), &signatures);
+        let ids: Vec<&str> = findings.iter().map(|f| f.remediation.target.as_str()).collect();
+        assert_eq!(ids, vec!["bbbb", "cccc"]);
+        assert_eq!(findings[0].remediation.action, "remove_policy");
+
+        let user_policy = json!({ "HomepageLocation": "https://www.example.com", "ShowHomeButton": true });
+        let found = policy_findings("Google Chrome", "com.google.Chrome", &user_policy, "/Users/jane/Library/Preferences/com.google.Chrome.plist", &signatures);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].detail.contains("HomepageLocation"));
+    }
+}
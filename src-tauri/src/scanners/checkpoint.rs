@@ -0,0 +1,77 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// On-disk checkpoint of a long-running scan, so it can resume after a crash or quit.
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope<T> {
+    scan_type: String,
+    updated_at: String,
+    state: T,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InterruptedScan {
+    pub scan_type: String, // "deep_scan" | "large_files"
+    pub updated_at: String,
+}
+
+fn checkpoint_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("checkpoints")
+}
+
+fn checkpoint_path(scan_type: &str) -> PathBuf {
+    checkpoint_dir().join(format!("{}.json", scan_type))
+}
+
+pub fn save<T: Serialize>(scan_type: &str, state: &T) {
+    let dir = checkpoint_dir();
+    let _ = std::fs::create_dir_all(&dir);
+    let envelope = Envelope {
+        scan_type: scan_type.to_string(),
+        updated_at: chrono::Local::now().to_rfc3339(),
+        state,
+    };
+    if let Ok(json) = serde_json::to_string(&envelope) {
+        // Write-then-rename so a crash mid-write never leaves a truncated checkpoint
+        let tmp = dir.join(format!("{}.json.tmp", scan_type));
+        if std::fs::write(&tmp, json).is_ok() {
+            let _ = std::fs::rename(&tmp, checkpoint_path(scan_type));
+        }
+    }
+}
+
+pub fn load<T: DeserializeOwned>(scan_type: &str) -> Option<T> {
+    let data = std::fs::read_to_string(checkpoint_path(scan_type)).ok()?;
+    serde_json::from_str::<Envelope<T>>(&data).ok().map(|e| e.state)
+}
+
+/// Remove a checkpoint once its scan completes (or the user discards it).
+pub fn clear(scan_type: &str) {
+    let _ = std::fs::remove_file(checkpoint_path(scan_type));
+}
+
+/// Scans that were checkpointed but never completed.
+pub fn list_interrupted() -> Vec<InterruptedScan> {
+    let mut scans = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(checkpoint_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map(|e| e != "json").unwrap_or(true) {
+                continue;
+            }
+            let data = match std::fs::read_to_string(&path) {
+                Ok(d) => d,
+                Err(_) => continue,
+            };
+            if let Ok(env) = serde_json::from_str::<Envelope<serde_json::Value>>(&data) {
+                scans.push(InterruptedScan {
+                    scan_type: env.scan_type,
+                    updated_at: env.updated_at,
+                });
+            }
+        }
+    }
+    scans
+}
@@ -0,0 +1,153 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+const MIN_SUGGEST_BYTES: u64 = 1024 * 1024 * 1024; // 1 GB materialized locally
+const STALE_AFTER_DAYS: u64 = 90;                  // Not touched for 3 months
+const MAX_FILES_PER_FOLDER: usize = 50_000;        // Cap per top-level folder
+const SCAN_TIMEOUT_SECS: u64 = 20;                 // Hard deadline across all clients
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SyncFolder {
+    pub name: String,
+    pub path: String,
+    /// Bytes actually stored on this Mac (online-only files count as 0)
+    pub local_bytes: u64,
+    /// Bytes the folder represents in the cloud
+    pub logical_bytes: u64,
+    /// Most recent modification inside the folder (unix seconds)
+    pub last_used: Option<i64>,
+    pub suggest_online_only: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SyncClient {
+    pub client: String, // "Dropbox" | "OneDrive" | "Google Drive"
+    pub root: String,
+    pub local_bytes: u64,
+    pub folders: Vec<SyncFolder>,
+    /// How to free space in this client without deleting anything from the cloud
+    pub action_hint: String,
+}
+
+/// Locate sync roots for the supported clients (classic home folders and File Provider locations).
+fn find_sync_roots(home: &Path) -> Vec<(&'static str, PathBuf)> {
+    let mut roots = Vec::new();
+
+    for (client, legacy) in [("Dropbox", "Dropbox"), ("OneDrive", "OneDrive"), ("Google Drive", "Google Drive")] {
+        let p = home.join(legacy);
+        if p.is_dir() {
+            roots.push((client, p));
+        }
+    }
+
+    // macOS 12.3+: File Provider based clients live under ~/Library/CloudStorage/<Client>-<account>
+    let cloud_storage = home.join("Library/CloudStorage");
+    if let Ok(entries) = fs::read_dir(&cloud_storage) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let client = if name.starts_with("Dropbox") {
+                "Dropbox"
+            } else if name.starts_with("OneDrive") {
+                "OneDrive"
+            } else if name.starts_with("GoogleDrive") {
+                "Google Drive"
+            } else {
+                continue;
+            };
+            let path = entry.path();
+            // Legacy ~/Dropbox is often a symlink into CloudStorage; don't report it twice
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if !roots.iter().any(|(_, r)| r.canonicalize().map(|c| c == canonical).unwrap_or(false)) {
+                roots.push((client, path));
+            }
+        }
+    }
+
+    roots
+}
+
+fn action_hint(client: &str) -> String {
+    match client {
+        "Dropbox" => "In Finder, right-click the folder and choose \"Make online-only\".".to_string(),
+        "OneDrive" => "In Finder, right-click the folder and choose \"Free Up Space\".".to_string(),
+        _ => "In Finder, right-click the folder and choose \"Remove Download\" (or \"Available offline\" off).".to_string(),
+    }
+}
+
+fn scan_folder(path: &Path, deadline: Instant) -> SyncFolder {
+    let mut local_bytes = 0u64;
+    let mut logical_bytes = 0u64;
+    let mut last_used: Option<i64> = None;
+
+    for entry in WalkDir::new(path).follow_links(false).into_iter().filter_map(|e| e.ok()).take(MAX_FILES_PER_FOLDER) {
+        if Instant::now() >= deadline {
+            break;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Ok(meta) = entry.metadata() {
+            // File Provider placeholders have no blocks allocated
+            local_bytes += super::allocated_bytes(&meta);
+            logical_bytes += meta.len();
+            let modified = meta.modified().ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64);
+            if modified > last_used {
+                last_used = modified;
+            }
+        }
+    }
+
+    let stale_cutoff = SystemTime::now()
+        .checked_sub(Duration::from_secs(STALE_AFTER_DAYS * 24 * 3600))
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    SyncFolder {
+        name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        path: path.to_string_lossy().to_string(),
+        local_bytes,
+        logical_bytes,
+        last_used,
+        suggest_online_only: local_bytes >= MIN_SUGGEST_BYTES && last_used.map(|t| t < stale_cutoff).unwrap_or(true),
+    }
+}
+
+/// Enumerate cloud sync clients and their locally materialized top-level folders.
+pub fn scan_cloud_sync() -> Vec<SyncClient> {
+    let home = match dirs::home_dir() {
+        Some(h) => h,
+        None => return Vec::new(),
+    };
+    let deadline = Instant::now() + Duration::from_secs(SCAN_TIMEOUT_SECS);
+    let mut clients = Vec::new();
+
+    for (client, root) in find_sync_roots(&home) {
+        let mut folders = Vec::new();
+        if let Ok(entries) = fs::read_dir(&root) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with('.') || !entry.path().is_dir() {
+                    continue;
+                }
+                folders.push(scan_folder(&entry.path(), deadline));
+            }
+        }
+        folders.sort_by_key(|f| std::cmp::Reverse(f.local_bytes));
+
+        clients.push(SyncClient {
+            client: client.to_string(),
+            root: root.to_string_lossy().to_string(),
+            local_bytes: folders.iter().map(|f| f.local_bytes).sum(),
+            folders,
+            action_hint: action_hint(client),
+        });
+    }
+
+    clients
+}
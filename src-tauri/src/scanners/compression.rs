@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime};
+use walkdir::WalkDir;
+
+use super::ByteSize;
+use crate::mcp::context_store::{path_is_excluded, ContextStore};
+use crate::mcp::drop_analysis::is_compressed;
+
+/// Folders smaller than this aren't worth an archive.
+const MIN_FOLDER_BYTES: u64 = 500 * 1024 * 1024;
+/// Nothing inside may have changed for this long.
+const MIN_UNTOUCHED_DAYS: u64 = 365;
+/// Candidates are at most this many levels below home; deeper folders show up through their parents.
+const MAX_CANDIDATE_DEPTH: usize = 4;
+const MAX_FILES_TO_SCAN: usize = 500_000;
+const SCAN_TIMEOUT_SECS: u64 = 90;
+/// Largest files sampled per folder to estimate how well it compresses.
+const SAMPLE_FILES: usize = 40;
+const SAMPLE_BYTES_PER_FILE: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompressionCandidate {
+    pub path: String,
+    pub size_bytes: u64,
+    pub file_count: usize,
+    /// unix seconds; newest modification anywhere inside
+    pub last_modified: i64,
+    pub estimated_archive_bytes: u64,
+    pub estimated_savings_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompressionReport {
+    pub candidates: Vec<CompressionCandidate>,
+    pub total_savings_bytes: u64,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    /// ditto: keeps extended attributes and resource forks, opens with a double-click
+    #[default]
+    Zip,
+    /// Smaller and faster, needs a tar with zstd support
+    TarZst,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompressProgress {
+    pub path: String,
+    /// "compressing" | "verifying" | "removing"
+    pub phase: String,
+    pub archive_bytes: u64,
+    pub estimated_archive_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompressResult {
+    pub archive: String,
+    pub original_bytes: u64,
+    pub archive_bytes: u64,
+    /// False when the original couldn't be moved to the Trash; the verified archive is kept either way
+    pub original_removed: bool,
+}
+
+#[derive(Default)]
+struct Pending {
+    bytes: u64,
+    files: usize,
+    newest: i64,
+}
+
+fn unix_secs(t: std::io::Result<SystemTime>) -> i64 {
+    t.ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Deflate ratio of the first bytes of the folder's largest files; already-compressed media counts as 1.
+fn estimate_archive_bytes(dir: &Path, size: u64) -> u64 {
+    let mut files: Vec<(PathBuf, u64)> = WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok().map(|m| (e.into_path(), m.len())))
+        .collect();
+    files.sort_by_key(|(_, len)| std::cmp::Reverse(*len));
+    let (mut sampled, mut compressed) = (0u64, 0u64);
+    for (path, _) in files.iter().take(SAMPLE_FILES) {
+        let mut buf = Vec::with_capacity(SAMPLE_BYTES_PER_FILE);
+        let Ok(file) = std::fs::File::open(path) else { continue };
+        if file.take(SAMPLE_BYTES_PER_FILE as u64).read_to_end(&mut buf).is_err() || buf.is_empty() {
+            continue;
+        }
+        sampled += buf.len() as u64;
+        if is_compressed(path) {
+            compressed += buf.len() as u64;
+            continue;
+        }
+        let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::fast());
+        let out = encoder.write_all(&buf).and_then(|_| encoder.finish()).map(|v| v.len() as u64);
+        compressed += out.unwrap_or(buf.len() as u64);
+    }
+    if sampled == 0 {
+        return size;
+    }
+    (size as f64 * compressed as f64 / sampled as f64) as u64
+}
+
+/// Folders over 500 MB in which nothing changed for a year (old projects, exports), topmost only,
+/// with the space an archive would save.
+pub fn find_compressible_folders() -> CompressionReport {
+    let _priority = super::priority::lower_scan_priority();
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let excluded = ContextStore::load().user_preferences.skipped_paths();
+    let now = unix_secs(Ok(SystemTime::now()));
+    let cutoff = now - (MIN_UNTOUCHED_DAYS * 86_400) as i64;
+    let deadline = Instant::now() + Duration::from_secs(SCAN_TIMEOUT_SECS);
+
+    // Children are yielded before their folder, so totals roll up as folders finish
+    let mut pending: HashMap<PathBuf, Pending> = HashMap::new();
+    let mut found: Vec<(PathBuf, Pending)> = Vec::new();
+    let mut truncated = false;
+    let walker = WalkDir::new(&home).follow_links(false).same_file_system(true).contents_first(true).into_iter().filter_entry(|e| {
+        let name = e.file_name().to_string_lossy();
+        e.depth() == 0
+            || !(name.starts_with('.')
+                || (e.depth() == 1 && name == "Library")
+                || super::space_lens::is_package(e.path())
+                || path_is_excluded(&e.path().to_string_lossy(), &excluded))
+    });
+    for (checked, entry) in walker.filter_map(|e| e.ok()).enumerate() {
+        if checked >= MAX_FILES_TO_SCAN || Instant::now() >= deadline {
+            truncated = true;
+            break;
+        }
+        let Ok(meta) = entry.metadata() else { continue };
+        let modified = unix_secs(meta.modified());
+        let parent = entry.path().parent().map(Path::to_path_buf).unwrap_or_default();
+        if entry.file_type().is_dir() {
+            let mut done = pending.remove(entry.path()).unwrap_or_default();
+            done.newest = done.newest.max(modified);
+            if entry.depth() > 0 {
+                let up = pending.entry(parent).or_default();
+                up.bytes += done.bytes;
+                up.files += done.files;
+                up.newest = up.newest.max(done.newest);
+            }
+            if entry.depth() > 0 && entry.depth() <= MAX_CANDIDATE_DEPTH && done.bytes >= MIN_FOLDER_BYTES && done.newest < cutoff {
+                found.push((entry.into_path(), done));
+            }
+        } else if entry.file_type().is_file() {
+            let up = pending.entry(parent).or_default();
+            up.bytes += ByteSize::of(&meta).allocated;
+            up.files += 1;
+            up.newest = up.newest.max(modified);
+        }
+    }
+
+    // Keep only the outermost qualifying folder; its subfolders are part of the same archive
+    let paths: Vec<PathBuf> = found.iter().map(|(p, _)| p.clone()).collect();
+    found.retain(|(p, _)| !paths.iter().any(|other| other != p && p.starts_with(other)));
+    let mut candidates: Vec<CompressionCandidate> = found
+        .into_iter()
+        .map(|(path, info)| {
+            let estimated = estimate_archive_bytes(&path, info.bytes);
+            CompressionCandidate {
+                path: path.to_string_lossy().to_string(),
+                size_bytes: info.bytes,
+                file_count: info.files,
+                last_modified: info.newest,
+                estimated_archive_bytes: estimated,
+                estimated_savings_bytes: info.bytes.saturating_sub(estimated),
+            }
+        })
+        .filter(|c| c.estimated_savings_bytes > 0)
+        .collect();
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.estimated_savings_bytes));
+    CompressionReport {
+        total_savings_bytes: candidates.iter().map(|c| c.estimated_savings_bytes).sum(),
+        candidates,
+        truncated,
+    }
+}
+
+fn archive_path(dir: &Path, format: ArchiveFormat) -> PathBuf {
+    let name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "Archive".to_string());
+    let ext = match format {
+        ArchiveFormat::Zip => "zip",
+        ArchiveFormat::TarZst => "tar.zst",
+    };
+    let parent = dir.parent().unwrap_or(dir);
+    std::iter::once(parent.join(format!("{}.{}", name, ext)))
+        .chain((2..).map(|n| parent.join(format!("{} {}.{}", name, n, ext))))
+        .find(|p| !p.exists())
+        .unwrap_or_else(|| parent.join(format!("{}.{}", name, ext)))
+}
+
+fn archive_command(dir: &Path, archive: &Path, format: ArchiveFormat) -> Command {
+    let parent = dir.parent().unwrap_or(dir);
+    let name = dir.file_name().unwrap_or_default();
+    match format {
+        ArchiveFormat::Zip => {
+            let mut cmd = Command::new("ditto");
+            cmd.args(["-c", "-k", "--sequesterRsrc", "--keepParent"]).arg(dir).arg(archive);
+            cmd
+        }
+        ArchiveFormat::TarZst => {
+            let mut cmd = Command::new("tar");
+            cmd.arg("--zstd").arg("-cf").arg(archive).arg("-C").arg(parent).arg(name);
+            cmd
+        }
+    }
+}
+
+/// Entry name of a `tar -tv` line: everything after the mode, links, owner, group, size and date.
+fn listing_name(line: &str) -> &str {
+    let mut rest = line;
+    for _ in 0..8 {
+        let trimmed = rest.trim_start();
+        rest = trimmed.find(char::is_whitespace).map(|i| &trimmed[i..]).unwrap_or("");
+    }
+    rest.trim_start()
+}
+
+/// Files and their total length, as listed by `tar -tv` (bsdtar reads zip archives too). Hard links
+/// count as files without adding length; the `__MACOSX/` resource-fork copies ditto adds are left out.
+fn parse_listing(listing: &str) -> (usize, u64) {
+    listing
+        .lines()
+        .filter(|l| (l.starts_with('-') || l.starts_with('h')) && !listing_name(l).starts_with("__MACOSX/"))
+        .map(|l| if l.starts_with('h') { 0 } else { l.split_whitespace().nth(4).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0) })
+        .fold((0, 0), |(n, total), size| (n + 1, total + size))
+}
+
+/// Device and inode, so hard links to one file can be told apart from copies.
+fn file_id(meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some((meta.dev(), meta.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = meta;
+        None
+    }
+}
+
+/// Regular files under `dir` with their total length counting hard-linked files once (how tar
+/// stores them) and counting every link (how zip does).
+fn source_totals(dir: &Path) -> (usize, u64, u64) {
+    let mut seen = std::collections::HashSet::new();
+    WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .fold((0, 0, 0), |(n, unique, total), m| {
+            let first = file_id(&m).map(|id| seen.insert(id)).unwrap_or(true);
+            (n + 1, unique + if first { m.len() } else { 0 }, total + m.len())
+        })
+}
+
+fn verify(dir: &Path, archive: &Path) -> Result<(), String> {
+    let out = Command::new("tar").arg("-tvf").arg(archive).output().map_err(|e| e.to_string())?;
+    if !out.status.success() {
+        return Err(format!("Archive could not be read back: {}", String::from_utf8_lossy(&out.stderr).trim()));
+    }
+    let (files, bytes) = parse_listing(&String::from_utf8_lossy(&out.stdout));
+    let (on_disk, unique, total) = source_totals(dir);
+    if files != on_disk || (bytes != unique && bytes != total) {
+        return Err(format!("Archive holds {} files ({} bytes) but the folder has {} ({} bytes)", files, bytes, on_disk, total));
+    }
+    Ok(())
+}
+
+/// Archive `dir` next to itself, check every file made it in, then move the folder to the Trash.
+/// A failed or unverifiable archive is deleted and the folder left untouched.
+pub fn compress_folder(dir: &Path, format: ArchiveFormat, on_progress: &dyn Fn(CompressProgress)) -> Result<CompressResult, String> {
+    if !dir.is_dir() {
+        return Err("Not a folder".to_string());
+    }
+    let _priority = super::priority::lower_scan_priority();
+    let original = super::dir_byte_size(dir).allocated;
+    let estimated = estimate_archive_bytes(dir, original);
+    let archive = archive_path(dir, format);
+    let path = dir.to_string_lossy().to_string();
+    let progress = |phase: &str, archive_bytes: u64| {
+        on_progress(CompressProgress {
+            path: path.clone(),
+            phase: phase.to_string(),
+            archive_bytes,
+            estimated_archive_bytes: estimated,
+        })
+    };
+    let archive_size = || std::fs::metadata(&archive).map(|m| m.len()).unwrap_or(0);
+    let discard = |e: String| {
+        let _ = std::fs::remove_file(&archive);
+        e
+    };
+
+    let mut child = archive_command(dir, &archive, format).spawn().map_err(|e| e.to_string())?;
+    // Neither tool reports progress; the archive's growth against the estimate stands in for it
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        progress("compressing", archive_size());
+        std::thread::sleep(Duration::from_millis(500));
+    };
+    if !status.success() {
+        return Err(discard(format!("Archiving failed ({})", status)));
+    }
+
+    progress("verifying", archive_size());
+    verify(dir, &archive).map_err(discard)?;
+
+    progress("removing", archive_size());
+    let original_removed = trash::delete(dir).is_ok();
+    if original_removed {
+        super::space_lens::invalidate_cached_sizes(&path);
+    }
+    Ok(CompressResult {
+        archive: archive.to_string_lossy().to_string(),
+        original_bytes: original,
+        archive_bytes: super::allocated_bytes(&std::fs::metadata(&archive).map_err(|e| e.to_string())?),
+        original_removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_counts_regular_files_only() {
+        let listing = "drwxr-xr-x  0 me staff       0 Jan  1  2024 Project/\n\
+                       -rw-r--r--  0 me staff    1200 Jan  1  2024 Project/a.txt\n\
+                       lrwxr-xr-x  0 me staff       0 Jan  1  2024 Project/link -> a.txt\n\
+                       -rw-r--r--  0 me staff      34 Jan  1  2024 Project/b.txt\n";
+        assert_eq!(parse_listing(listing), (2, 1234));
+    }
+
+    #[test]
+    fn listing_skips_resource_forks_and_counts_hard_links() {
+        let listing = "-rw-r--r--  0 me staff    1200 Jan  1  2024 Project/a.txt\n\
+                       hrw-r--r--  0 me staff       0 Jan  1  2024 Project/b.txt link to Project/a.txt\n\
+                       -rw-r--r--  0 me staff     163 Jan  1  2024 __MACOSX/Project/._a.txt\n";
+        assert_eq!(parse_listing(listing), (2, 1200));
+        assert_eq!(listing_name("-rw-r--r--  0 me staff  5 Jan  1  2024 My Folder/a b.txt"), "My Folder/a b.txt");
+    }
+
+    #[test]
+    fn text_is_estimated_smaller_than_it_is() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("log.txt"), "the same line again\n".repeat(10_000)).unwrap();
+        assert!(estimate_archive_bytes(dir.path(), 200_000) < 50_000);
+    }
+}
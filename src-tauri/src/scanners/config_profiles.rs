@@ -0,0 +1,148 @@
+use serde::Serialize;
+use serde_json::Value;
+
+pub const PROFILES_SETTINGS_URL: &str = "x-apple.systempreferences:com.apple.Profiles-Settings.extension";
+
+/// Preference domains of browsers whose settings adware locks down through a profile.
+const BROWSER_DOMAINS: &[&str] = &["com.google.Chrome", "com.apple.Safari", "com.brave.Browser", "com.microsoft.Edge", "org.mozilla.firefox"];
+
+/// An installed configuration profile and the settings in it that adware relies on.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigProfile {
+    pub identifier: String,
+    pub name: String,
+    pub organization: Option<String>,
+    /// "computer", or the user it's installed for
+    pub scope: String,
+    pub payload_types: Vec<String>,
+    pub installed_by_mdm: bool,
+    /// "DNS", "proxy", "web content filter", "browser policy (com.google.Chrome)"
+    pub risky_settings: Vec<String>,
+    /// Installed outside MDM and changes DNS, proxies or browser policies
+    pub suspicious: bool,
+}
+
+fn is_true(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Bool(b)) => *b,
+        Some(Value::String(s)) => s.eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
+
+fn risky_settings(payload: &Value) -> Vec<String> {
+    let kind = payload.get("PayloadType").and_then(|v| v.as_str()).unwrap_or("");
+    match kind {
+        "com.apple.dnsSettings.managed" => vec!["DNS".to_string()],
+        "com.apple.webcontent-filter" => vec!["web content filter".to_string()],
+        k if k.contains("proxy") => vec!["proxy".to_string()],
+        // Custom settings payloads hold one dictionary per preference domain
+        "com.apple.ManagedClient.preferences" => payload
+            .get("PayloadContent")
+            .and_then(|c| c.as_object())
+            .map(|domains| domains.keys().filter(|d| BROWSER_DOMAINS.contains(&d.as_str())).map(|d| format!("browser policy ({})", d)).collect())
+            .unwrap_or_default(),
+        k if BROWSER_DOMAINS.contains(&k) => vec![format!("browser policy ({})", k)],
+        _ => vec![],
+    }
+}
+
+/// Profiles from `profiles show -type configuration -output stdout-xml`, which groups them under
+/// "_computerlevel" and each user's name. A profile counts as MDM-installed when it carries the MDM
+/// payload itself, or the Mac is enrolled and the profile can't be removed by the user.
+fn parse_profiles(listing: &Value, mdm_enrolled: bool) -> Vec<ConfigProfile> {
+    let Some(groups) = listing.as_object() else { return vec![] };
+    let mut profiles = Vec::new();
+    for (group, entries) in groups {
+        let scope = if group == "_computerlevel" { "computer".to_string() } else { group.clone() };
+        for profile in entries.as_array().into_iter().flatten() {
+            let text = |key: &str| profile.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+            let payloads: Vec<&Value> = profile.get("ProfileItems").and_then(|v| v.as_array()).map(|items| items.iter().collect()).unwrap_or_default();
+            let payload_types: Vec<String> = payloads.iter().filter_map(|p| p.get("PayloadType").and_then(|v| v.as_str())).map(|s| s.to_string()).collect();
+            let installed_by_mdm = payload_types.iter().any(|t| t == "com.apple.mdm") || (mdm_enrolled && is_true(profile.get("ProfileRemovalDisallowed")));
+            let risky: Vec<String> = payloads.iter().flat_map(|p| risky_settings(p)).collect();
+            profiles.push(ConfigProfile {
+                identifier: text("ProfileIdentifier").unwrap_or_default(),
+                name: text("ProfileDisplayName").unwrap_or_else(|| "Unnamed profile".to_string()),
+                organization: text("ProfileOrganization"),
+                scope: scope.clone(),
+                payload_types,
+                installed_by_mdm,
+                suspicious: !installed_by_mdm && !risky.is_empty(),
+                risky_settings: risky,
+            });
+        }
+    }
+    profiles
+}
+
+#[cfg(target_os = "macos")]
+fn mdm_enrolled() -> bool {
+    std::process::Command::new("profiles")
+        .args(["status", "-type", "enrollment"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().any(|l| l.trim().starts_with("MDM enrollment: Yes")))
+        .unwrap_or(false)
+}
+
+/// Installed configuration profiles, suspicious ones first.
+#[cfg(target_os = "macos")]
+pub fn list_profiles() -> Vec<ConfigProfile> {
+    let Ok(out) = std::process::Command::new("profiles").args(["show", "-type", "configuration", "-output", "stdout-xml"]).output() else {
+        return vec![];
+    };
+    let listing: Value = plist::from_bytes(&out.stdout).unwrap_or(Value::Null);
+    let mut profiles = parse_profiles(&listing, mdm_enrolled());
+    profiles.sort_by_key(|p| !p.suspicious);
+    profiles
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn list_profiles() -> Vec<ConfigProfile> {
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flags_non_mdm_profiles_that_change_dns_or_browsers() {
+        let listing = json!({
+            "_computerlevel": [
+                {
+                    "ProfileIdentifier": "com.search.profile",
+                    "ProfileDisplayName": "Chrome Settings",
+                    "ProfileItems": [{
+                        "PayloadType": "com.apple.ManagedClient.preferences",
+                        "PayloadContent": { "com.google.Chrome": { "Forced": [] } }
+                    }]
+                },
+                {
+                    "ProfileIdentifier": "com.corp.mdm",
+                    "ProfileDisplayName": "Corp MDM",
+                    "ProfileItems": [{ "PayloadType": "com.apple.mdm" }, { "PayloadType": "com.apple.dnsSettings.managed" }]
+                },
+                {
+                    "ProfileIdentifier": "com.corp.wifi",
+                    "ProfileDisplayName": "Office Wi-Fi",
+                    "ProfileItems": [{ "PayloadType": "com.apple.wifi.managed" }]
+                }
+            ],
+            "jane": [{
+                "ProfileIdentifier": "com.vpn.dns",
+                "ProfileRemovalDisallowed": "TRUE",
+                "ProfileItems": [{ "PayloadType": "com.apple.dnsSettings.managed" }]
+            }]
+        });
+        let profiles = parse_profiles(&listing, false);
+        let suspicious: Vec<&str> = profiles.iter().filter(|p| p.suspicious).map(|p| p.identifier.as_str()).collect();
+        assert_eq!(suspicious, vec!["com.search.profile", "com.vpn.dns"]);
+        assert_eq!(profiles[0].risky_settings, vec!["browser policy (com.google.Chrome)".to_string()]);
+        assert_eq!(profiles[3].scope, "jane");
+
+        // On an enrolled Mac, a profile the user can't remove came from MDM
+        assert!(!parse_profiles(&listing, true).iter().any(|p| p.identifier == "com.vpn.dns" && p.suspicious));
+    }
+}
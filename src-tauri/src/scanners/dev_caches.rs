@@ -0,0 +1,274 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+use super::dir_byte_size;
+use crate::mcp::context_store::ContextStore;
+use crate::mcp::managed::ManagedPolicy;
+use crate::mcp::safety_gate;
+
+/// How a toolchain's cache is cleaned.
+enum CleanMethod {
+    /// Run the tool's own command (e.g. `go clean -modcache`); falls back to removing the
+    /// folders when the tool isn't installed anymore
+    Tool(&'static str, &'static [&'static [&'static str]]),
+    /// Move the folders to the Trash; the tool re-downloads or rebuilds them on demand
+    Remove,
+}
+
+struct Toolchain {
+    id: &'static str,
+    name: &'static str,
+    /// Relative to home
+    paths: &'static [&'static str],
+    clean: CleanMethod,
+    description: &'static str,
+}
+
+const TOOLCHAINS: &[Toolchain] = &[
+    Toolchain {
+        id: "cargo",
+        name: "Cargo registry",
+        // Like `cargo cache --autoclean`: extracted sources and .crate archives go, the index and git db stay
+        paths: &[".cargo/registry/cache", ".cargo/registry/src", ".cargo/git/checkouts"],
+        clean: CleanMethod::Remove,
+        description: "Downloaded crates and their extracted sources. Cargo fetches them again when needed.",
+    },
+    Toolchain {
+        id: "pip",
+        name: "pip",
+        paths: &["Library/Caches/pip"],
+        clean: CleanMethod::Tool("pip3", &[&["cache", "purge"]]),
+        description: "Cached wheels and downloads.",
+    },
+    Toolchain {
+        id: "go",
+        name: "Go",
+        paths: &["go/pkg/mod", "Library/Caches/go-build"],
+        clean: CleanMethod::Tool("go", &[&["clean", "-modcache"], &["clean", "-cache"]]),
+        description: "Module download cache and build cache.",
+    },
+    Toolchain {
+        id: "gradle",
+        name: "Gradle",
+        paths: &[".gradle/caches", ".gradle/wrapper/dists"],
+        clean: CleanMethod::Remove,
+        description: "Dependency and build caches plus downloaded Gradle distributions.",
+    },
+    Toolchain {
+        id: "cocoapods",
+        name: "CocoaPods",
+        paths: &["Library/Caches/CocoaPods"],
+        clean: CleanMethod::Tool("pod", &[&["cache", "clean", "--all"]]),
+        description: "Pod download cache.",
+    },
+    Toolchain {
+        id: "pnpm",
+        name: "pnpm",
+        paths: &["Library/pnpm/store", ".pnpm-store", ".local/share/pnpm/store"],
+        clean: CleanMethod::Tool("pnpm", &[&["store", "prune"]]),
+        description: "Packages no project references anymore are removed from the store.",
+    },
+];
+
+/// Id of the pseudo-toolchain for Rust `target/` build folders found under the project roots.
+const CARGO_TARGET_ID: &str = "cargo_target";
+const MAX_PROJECT_DEPTH: usize = 5;
+/// `target/` folders of projects edited within this many days are left for the next build.
+const CARGO_TARGET_STALE_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevCacheLocation {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevCache {
+    pub id: String,
+    pub toolchain: String,
+    pub description: String,
+    pub locations: Vec<DevCacheLocation>,
+    pub size_bytes: u64,
+    /// What cleaning does, e.g. "go clean -modcache" or "Delete folders"
+    pub clean_action: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevCacheCleanResult {
+    pub id: String,
+    pub bytes_freed: u64,
+    pub errors: Vec<String>,
+}
+
+/// GUI apps don't get the shell PATH; look where toolchains usually install their binaries.
+fn find_tool(name: &str) -> Option<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let dirs = [
+        PathBuf::from("/opt/homebrew/bin"),
+        PathBuf::from("/usr/local/bin"),
+        PathBuf::from("/usr/local/go/bin"),
+        PathBuf::from("/usr/bin"),
+        home.join(".cargo/bin"),
+        home.join("Library/pnpm"),
+        home.join(".local/bin"),
+    ];
+    dirs.iter().map(|d| d.join(name)).find(|p| p.is_file())
+}
+
+fn location(path: PathBuf) -> DevCacheLocation {
+    DevCacheLocation {
+        size_bytes: dir_byte_size(&path).allocated,
+        path: path.to_string_lossy().to_string(),
+    }
+}
+
+fn scan_toolchain(home: &Path, tc: &Toolchain, ctx: &ContextStore) -> DevCache {
+    let locations: Vec<DevCacheLocation> = tc
+        .paths
+        .iter()
+        .map(|rel| home.join(rel))
+        .filter(|p| p.is_dir() && !ctx.is_excluded(&p.to_string_lossy()))
+        .map(location)
+        .collect();
+    let clean_action = match &tc.clean {
+        CleanMethod::Tool(tool, runs) => runs.iter().map(|args| format!("{} {}", tool, args.join(" "))).collect::<Vec<_>>().join(" && "),
+        CleanMethod::Remove => "Move folders to the Trash".to_string(),
+    };
+    DevCache {
+        id: tc.id.to_string(),
+        toolchain: tc.name.to_string(),
+        description: tc.description.to_string(),
+        size_bytes: locations.iter().map(|l| l.size_bytes).sum(),
+        locations,
+        clean_action,
+    }
+}
+
+/// `target/` folders next to a Cargo.toml under the project roots, for projects not edited in
+/// `CARGO_TARGET_STALE_DAYS` days.
+fn cargo_target_dirs(ctx: &ContextStore) -> Vec<PathBuf> {
+    let cutoff = chrono::Utc::now().timestamp() - CARGO_TARGET_STALE_DAYS * 86400;
+    let mut found = Vec::new();
+    for root in super::node_modules::project_roots() {
+        let walker = WalkDir::new(&root)
+            .max_depth(MAX_PROJECT_DEPTH)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                e.depth() == 0 || (e.file_type().is_dir() && !name.starts_with('.') && name != "node_modules" && name != "target")
+            });
+        for entry in walker.filter_map(|e| e.ok()) {
+            let target = entry.path().join("target");
+            if entry.path().join("Cargo.toml").is_file()
+                && target.join("CACHEDIR.TAG").is_file()
+                && !ctx.is_excluded(&target.to_string_lossy())
+                && super::node_modules::project_last_modified(entry.path()) < cutoff
+            {
+                found.push(target);
+            }
+        }
+    }
+    found
+}
+
+fn scan_cargo_targets(ctx: &ContextStore) -> DevCache {
+    let mut locations: Vec<DevCacheLocation> = cargo_target_dirs(ctx).into_iter().map(location).collect();
+    locations.sort_by_key(|l| std::cmp::Reverse(l.size_bytes));
+    DevCache {
+        id: CARGO_TARGET_ID.to_string(),
+        toolchain: "Rust build output".to_string(),
+        description: format!("target/ folders of Rust projects not edited in {} days. Rebuilt by the next cargo build.", CARGO_TARGET_STALE_DAYS),
+        size_bytes: locations.iter().map(|l| l.size_bytes).sum(),
+        locations,
+        clean_action: "Move target folders to the Trash".to_string(),
+    }
+}
+
+/// Per-toolchain developer cache breakdown; toolchains with nothing on disk are left out.
+pub fn scan_dev_caches() -> Vec<DevCache> {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let ctx = ContextStore::load();
+    let mut caches: Vec<DevCache> = TOOLCHAINS.iter().map(|tc| scan_toolchain(&home, tc, &ctx)).collect();
+    caches.push(scan_cargo_targets(&ctx));
+    caches.retain(|c| !c.locations.is_empty());
+    caches.sort_by_key(|c| std::cmp::Reverse(c.size_bytes));
+    caches
+}
+
+/// Trash the cache's folders and record them, so the cleanup can be undone.
+fn remove_locations(cache: &DevCache, errors: &mut Vec<String>) {
+    let items: Vec<(String, u64)> = cache.locations.iter().map(|l| (l.path.clone(), l.size_bytes)).collect();
+    errors.extend(super::trash::trash_and_record(&items).errors);
+}
+
+/// Clean one toolchain's cache with its safe-clean semantics and report the space freed.
+pub fn clean_dev_cache(id: &str) -> Result<DevCacheCleanResult, String> {
+    let home = dirs::home_dir().ok_or("No home directory")?;
+    let ctx = ContextStore::load();
+    let before = if id == CARGO_TARGET_ID {
+        scan_cargo_targets(&ctx)
+    } else {
+        let tc = TOOLCHAINS.iter().find(|t| t.id == id).ok_or_else(|| format!("Unknown developer cache: {}", id))?;
+        scan_toolchain(&home, tc, &ctx)
+    };
+    let paths: Vec<String> = before.locations.iter().map(|l| l.path.clone()).collect();
+    safety_gate::check_paths("Developer cache cleanup", &paths, &ManagedPolicy::load(), &ctx)?;
+
+    let mut errors = Vec::new();
+    match TOOLCHAINS.iter().find(|t| t.id == id).map(|t| &t.clean) {
+        Some(CleanMethod::Tool(tool, runs)) => match find_tool(tool) {
+            Some(bin) => {
+                for args in runs.iter() {
+                    match Command::new(&bin).args(args.iter()).output() {
+                        Ok(o) if o.status.success() => {}
+                        Ok(o) => errors.push(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+                        Err(e) => errors.push(format!("{}: {}", tool, e)),
+                    }
+                }
+            }
+            // Tool uninstalled: its cache is orphaned, remove it directly
+            None => remove_locations(&before, &mut errors),
+        },
+        Some(CleanMethod::Remove) | None => remove_locations(&before, &mut errors),
+    }
+
+    let after: u64 = before
+        .locations
+        .iter()
+        .map(|l| dir_byte_size(Path::new(&l.path)).allocated)
+        .sum();
+    Ok(DevCacheCleanResult {
+        id: id.to_string(),
+        bytes_freed: before.size_bytes.saturating_sub(after),
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toolchain_ids_are_unique() {
+        let mut ids: Vec<&str> = TOOLCHAINS.iter().map(|t| t.id).collect();
+        ids.push(CARGO_TARGET_ID);
+        let count = ids.len();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), count);
+    }
+
+    #[test]
+    fn scans_only_existing_locations() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(home.path().join("Library/Caches/pip/wheels")).unwrap();
+        std::fs::write(home.path().join("Library/Caches/pip/wheels/a.whl"), vec![0u8; 4096]).unwrap();
+        let pip = TOOLCHAINS.iter().find(|t| t.id == "pip").unwrap();
+        let cache = scan_toolchain(home.path(), pip, &ContextStore::default());
+        assert_eq!(cache.locations.len(), 1);
+        assert!(cache.size_bytes > 0);
+        assert_eq!(cache.clean_action, "pip3 cache purge");
+    }
+}
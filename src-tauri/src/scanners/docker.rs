@@ -0,0 +1,219 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where the container VMs keep their disk images, relative to home.
+const VM_DISKS: &[(&str, &str)] = &[
+    ("Docker Desktop", "Library/Containers/com.docker.docker/Data/vms/0/data/Docker.raw"),
+    ("Docker Desktop", "Library/Containers/com.docker.docker/Data/vms/0/data/Docker.qcow2"),
+    ("OrbStack", "Library/Group Containers/HUAQ24HBR6.dev.orbstack/data/data.img"),
+    ("OrbStack", ".orbstack/data/data.img"),
+    ("Colima", ".colima/_lima/colima/diffdisk"),
+    ("Colima", ".colima/_lima/_disks/colima/datadisk"),
+    ("Colima", ".lima/colima/diffdisk"),
+];
+
+/// GUI apps don't inherit the shell PATH, so look in the usual install locations too.
+const DOCKER_BINARIES: &[&str] = &[
+    "/usr/local/bin/docker",
+    "/opt/homebrew/bin/docker",
+    "/Applications/Docker.app/Contents/Resources/bin/docker",
+    "/Applications/OrbStack.app/Contents/MacOS/xbin/docker",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VmDisk {
+    pub runtime: String,
+    pub path: String,
+    /// Apparent size; these are sparse files, so this is the VM's maximum
+    pub logical_bytes: u64,
+    /// Bytes the image actually occupies on disk
+    pub allocated_bytes: u64,
+}
+
+/// One row of `docker system df` (Images, Containers, Local Volumes, Build Cache).
+#[derive(Debug, Clone, Serialize)]
+pub struct DockerDfRow {
+    pub kind: String,
+    pub total_count: u64,
+    pub active: u64,
+    pub size_bytes: u64,
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DockerUsage {
+    pub installed: bool,
+    pub daemon_running: bool,
+    pub vm_disks: Vec<VmDisk>,
+    pub usage: Vec<DockerDfRow>,
+    pub reclaimable_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DockerPruneResult {
+    pub reclaimed_bytes: u64,
+    pub output: String,
+}
+
+fn docker_binary() -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = DOCKER_BINARIES.iter().map(PathBuf::from).collect();
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join(".orbstack/bin/docker"));
+        candidates.push(home.join(".docker/bin/docker"));
+    }
+    candidates.into_iter().find(|p| p.exists()).or_else(|| {
+        // Fall back to PATH (e.g. when launched from a terminal)
+        Command::new("docker").arg("--version").output().ok()
+            .filter(|o| o.status.success())
+            .map(|_| PathBuf::from("docker"))
+    })
+}
+
+pub(crate) fn vm_disks(home: &Path) -> Vec<VmDisk> {
+    VM_DISKS
+        .iter()
+        .filter_map(|(runtime, rel)| {
+            let path = home.join(rel);
+            let meta = std::fs::metadata(&path).ok()?;
+            Some(VmDisk {
+                runtime: runtime.to_string(),
+                path: path.to_string_lossy().to_string(),
+                logical_bytes: meta.len(),
+                allocated_bytes: super::allocated_bytes(&meta),
+            })
+        })
+        .collect()
+}
+
+/// Docker's human sizes ("1.03kB", "5.2GB", "0B") are decimal (go-units).
+fn parse_docker_size(s: &str) -> u64 {
+    let s = s.trim();
+    let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let Ok(value) = num.trim().parse::<f64>() else { return 0 };
+    let mult = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "tb" => 1e12,
+        "pb" => 1e15,
+        _ => return 0,
+    };
+    (value * mult) as u64
+}
+
+/// `docker system df --format json` prints one JSON object per line.
+fn parse_system_df(output: &str) -> Vec<DockerDfRow> {
+    let field_u64 = |v: &serde_json::Value, key: &str| -> u64 {
+        match v.get(key) {
+            Some(serde_json::Value::String(s)) => s.trim().parse().unwrap_or(0),
+            Some(n) => n.as_u64().unwrap_or(0),
+            None => 0,
+        }
+    };
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .map(|v| {
+            let text = |key: &str| v.get(key).and_then(|x| x.as_str()).unwrap_or("").to_string();
+            // "3.1GB (59%)"
+            let reclaimable = text("Reclaimable");
+            DockerDfRow {
+                kind: text("Type"),
+                total_count: field_u64(&v, "TotalCount"),
+                active: field_u64(&v, "Active"),
+                size_bytes: parse_docker_size(&text("Size")),
+                reclaimable_bytes: parse_docker_size(reclaimable.split(" (").next().unwrap_or("")),
+            }
+        })
+        .collect()
+}
+
+/// Disk used by Docker Desktop / OrbStack / Colima: VM disk images plus, when the daemon is up, `docker system df`.
+pub fn scan_docker() -> DockerUsage {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let vm_disks = vm_disks(&home);
+    let mut usage = DockerUsage {
+        installed: false,
+        daemon_running: false,
+        vm_disks,
+        usage: vec![],
+        reclaimable_bytes: 0,
+        errors: vec![],
+    };
+
+    let Some(docker) = docker_binary() else {
+        usage.installed = !usage.vm_disks.is_empty();
+        return usage;
+    };
+    usage.installed = true;
+
+    match Command::new(&docker).args(["system", "df", "--format", "json"]).output() {
+        Ok(o) if o.status.success() => {
+            usage.daemon_running = true;
+            usage.usage = parse_system_df(&String::from_utf8_lossy(&o.stdout));
+            usage.reclaimable_bytes = usage.usage.iter().map(|r| r.reclaimable_bytes).sum();
+        }
+        // Daemon not running: only the VM disk sizes are known
+        Ok(_) => {}
+        Err(e) => usage.errors.push(format!("docker: {}", e)),
+    }
+    usage
+}
+
+/// Run `docker system prune -f`, optionally removing all unused images (`-a`) and volumes.
+/// Note the VM disk image does not shrink on its own; Docker Desktop/OrbStack reclaim the space lazily.
+pub fn prune_docker(all_images: bool, volumes: bool) -> Result<DockerPruneResult, String> {
+    let docker = docker_binary().ok_or("Docker CLI not found")?;
+    let mut args = vec!["system", "prune", "-f"];
+    if all_images {
+        args.push("-a");
+    }
+    if volumes {
+        args.push("--volumes");
+    }
+    let output = Command::new(&docker).args(&args).output().map_err(|e| e.to_string())?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(if stderr.contains("Cannot connect") || stderr.contains("daemon") {
+            "Docker is not running. Start Docker Desktop, OrbStack or Colima and try again.".to_string()
+        } else {
+            stderr.trim().to_string()
+        });
+    }
+    let reclaimed_bytes = stdout
+        .lines()
+        .find_map(|l| l.strip_prefix("Total reclaimed space:"))
+        .map(parse_docker_size)
+        .unwrap_or(0);
+    Ok(DockerPruneResult { reclaimed_bytes, output: stdout })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_docker_sizes() {
+        assert_eq!(parse_docker_size("0B"), 0);
+        assert_eq!(parse_docker_size("1.5kB"), 1500);
+        assert_eq!(parse_docker_size(" 5.2GB"), 5_200_000_000);
+        assert_eq!(parse_docker_size("n/a"), 0);
+    }
+
+    #[test]
+    fn parses_system_df_json_lines() {
+        let out = r#"{"Active":"3","Reclaimable":"3.1GB (59%)","Size":"5.2GB","TotalCount":"12","Type":"Images"}
+{"Active":"0","Reclaimable":"512MB","Size":"512MB","TotalCount":"40","Type":"Build Cache"}"#;
+        let rows = parse_system_df(out);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].kind, "Images");
+        assert_eq!(rows[0].total_count, 12);
+        assert_eq!(rows[0].reclaimable_bytes, 3_100_000_000);
+        assert_eq!(rows[1].reclaimable_bytes, 512_000_000);
+    }
+}
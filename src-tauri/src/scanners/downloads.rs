@@ -0,0 +1,252 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use super::ByteSize;
+use crate::mcp::context_store::{path_is_excluded, ContextStore, DownloadRule, DownloadRuleAction};
+use crate::mcp::managed::ManagedPolicy;
+
+/// Files browsers are still writing.
+const PARTIAL_EXTENSIONS: &[&str] = &["crdownload", "download", "part", "partial", "opdownload"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedMove {
+    pub path: String,
+    pub destination: String,
+    pub rule: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleDownload {
+    pub path: String,
+    pub size_bytes: u64,
+    pub age_days: u64,
+    pub rule: String,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct OrganizeResult {
+    /// Moves done, or only planned when `dry_run`
+    pub moves: Vec<PlannedMove>,
+    /// Deletion candidates; removing them goes through confirm_delete
+    pub stale: Vec<StaleDownload>,
+    pub dry_run: bool,
+    pub errors: Vec<String>,
+}
+
+fn downloads_dir() -> Option<PathBuf> {
+    dirs::download_dir().or_else(|| dirs::home_dir().map(|h| h.join("Downloads")))
+}
+
+/// Subfolder names resolve inside Downloads; `~` and absolute paths are taken as is.
+fn resolve_destination(downloads: &Path, destination: &str) -> PathBuf {
+    match destination.strip_prefix('~') {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest.trim_start_matches('/')),
+        None if destination.starts_with('/') => PathBuf::from(destination),
+        None => downloads.join(destination),
+    }
+}
+
+/// First rule that applies to a file: same extension, old enough, and (for moves) still at the top of Downloads.
+fn matching_rule<'a>(rules: &'a [DownloadRule], ext: &str, age_days: u64, top_level: bool) -> Option<&'a DownloadRule> {
+    rules.iter().find(|r| {
+        r.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext))
+            && age_days >= r.min_age_days as u64
+            && (top_level || r.action == DownloadRuleAction::FlagStale)
+    })
+}
+
+fn age_days(meta: &std::fs::Metadata, now: SystemTime) -> u64 {
+    meta.created()
+        .or_else(|_| meta.modified())
+        .ok()
+        .and_then(|t| now.duration_since(t).ok())
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0)
+}
+
+/// `dest/name`, or `dest/name 2.ext`, `dest/name 3.ext`... when that's taken.
+fn free_name(dest: &Path, file_name: &str) -> PathBuf {
+    let candidate = dest.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let (stem, ext) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (file_name, String::new()),
+    };
+    (2..)
+        .map(|n| dest.join(format!("{} {}{}", stem, n, ext)))
+        .find(|p| !p.exists())
+        .unwrap_or(candidate)
+}
+
+/// Whether a browser is still writing `path`: an empty placeholder (Firefox creates one next to its
+/// `.part` file) or a file with a partial-download sibling such as `name.part` or Safari's
+/// `name.download` bundle.
+fn still_downloading(path: &Path, len: u64) -> bool {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else { return false };
+    len == 0 || PARTIAL_EXTENSIONS.iter().any(|ext| path.with_file_name(format!("{}.{}", name, ext)).exists())
+}
+
+fn plan(downloads: &Path, rules: &[DownloadRule], excluded: &[String], now: SystemTime) -> (Vec<PlannedMove>, Vec<StaleDownload>) {
+    let mut moves = Vec::new();
+    let mut stale = Vec::new();
+    // One level down so installers already sorted into a subfolder are still flagged when stale
+    let walker = WalkDir::new(downloads).min_depth(1).max_depth(2).follow_links(false).into_iter().filter_entry(|e| {
+        !e.file_name().to_string_lossy().starts_with('.') && !path_is_excluded(&e.path().to_string_lossy(), excluded)
+    });
+    for entry in walker.filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+        let ext = entry.path().extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        if PARTIAL_EXTENSIONS.contains(&ext.as_str()) {
+            continue;
+        }
+        let Ok(meta) = entry.metadata() else { continue };
+        if still_downloading(entry.path(), meta.len()) {
+            continue;
+        }
+        let age = age_days(&meta, now);
+        let Some(rule) = matching_rule(rules, &ext, age, entry.depth() == 1) else { continue };
+        let path = entry.path().to_string_lossy().to_string();
+        match rule.action {
+            DownloadRuleAction::Move => {
+                let dest = resolve_destination(downloads, &rule.destination);
+                // Already where the rule would put it
+                if entry.path().parent() != Some(dest.as_path()) {
+                    moves.push(PlannedMove { path, destination: dest.to_string_lossy().to_string(), rule: rule.name.clone() });
+                }
+            }
+            DownloadRuleAction::FlagStale => stale.push(StaleDownload {
+                path,
+                size_bytes: ByteSize::of(&meta).allocated,
+                age_days: age,
+                rule: rule.name.clone(),
+            }),
+        }
+    }
+    stale.sort_by_key(|s| std::cmp::Reverse(s.size_bytes));
+    (moves, stale)
+}
+
+fn apply(planned: &PlannedMove) -> Result<(), String> {
+    let src = Path::new(&planned.path);
+    let dest = Path::new(&planned.destination);
+    std::fs::create_dir_all(dest).map_err(|e| format!("{}: {}", planned.destination, e))?;
+    let name = src.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+    std::fs::rename(src, free_name(dest, name)).map_err(|e| format!("{}: {}", planned.path, e))
+}
+
+/// Sort ~/Downloads with the user's rules: move matching files into their folders and list stale
+/// files for deletion. Nothing is moved when `dry_run`.
+pub fn organize_downloads(dry_run: bool) -> OrganizeResult {
+    let Some(downloads) = downloads_dir() else {
+        return OrganizeResult { dry_run, errors: vec!["Could not find the Downloads folder".to_string()], ..Default::default() };
+    };
+    let prefs = ContextStore::load().user_preferences;
+    let (planned, stale) = plan(&downloads, &prefs.downloads.rules, &prefs.skipped_paths(), SystemTime::now());
+    if dry_run {
+        return OrganizeResult { moves: planned, stale, dry_run, errors: vec![] };
+    }
+    let policy = ManagedPolicy::load();
+    let mut result = OrganizeResult { stale, ..Default::default() };
+    for planned in planned {
+        let paths = [planned.path.clone(), planned.destination.clone()];
+        match policy.check_destructive("Move", &paths).and_then(|_| apply(&planned)) {
+            Ok(()) => result.moves.push(planned),
+            Err(e) => result.errors.push(e),
+        }
+    }
+    result
+}
+
+/// Watcher hook: apply the move rules to a file that just appeared at the top of Downloads.
+/// Returns where it was moved.
+pub fn organize_new_download(path: &Path) -> Option<PathBuf> {
+    let downloads = downloads_dir()?;
+    if path.parent() != Some(downloads.as_path()) || !path.is_file() {
+        return None;
+    }
+    let prefs = ContextStore::load().user_preferences;
+    if !prefs.downloads.auto_organize || path_is_excluded(&path.to_string_lossy(), &prefs.skipped_paths()) {
+        return None;
+    }
+    let policy = ManagedPolicy::load();
+    if policy.force_dry_run || policy.is_excluded(&path.to_string_lossy()) {
+        return None;
+    }
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    if PARTIAL_EXTENSIONS.contains(&ext.as_str()) || path.file_name()?.to_string_lossy().starts_with('.') {
+        return None;
+    }
+    if still_downloading(path, std::fs::metadata(path).ok()?.len()) {
+        return None;
+    }
+    let rule = matching_rule(&prefs.downloads.rules, &ext, 0, true).filter(|r| r.action == DownloadRuleAction::Move)?;
+    let dest = resolve_destination(&downloads, &rule.destination);
+    if policy.is_excluded(&dest.to_string_lossy()) {
+        return None;
+    }
+    std::fs::create_dir_all(&dest).ok()?;
+    let target = free_name(&dest, &path.file_name()?.to_string_lossy());
+    std::fs::rename(path, &target).ok().map(|_| target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::context_store::DownloadsPolicy;
+
+    #[test]
+    fn plans_moves_and_flags_stale_installers() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("photo.JPG"), b"x").unwrap();
+        std::fs::write(dir.path().join("setup.dmg"), b"x").unwrap();
+        std::fs::write(dir.path().join("movie.mp4.crdownload"), b"x").unwrap();
+        std::fs::create_dir(dir.path().join("Images")).unwrap();
+        std::fs::write(dir.path().join("Images/sorted.png"), b"x").unwrap();
+
+        let rules = DownloadsPolicy::default().rules;
+        let (moves, stale) = plan(dir.path(), &rules, &[], SystemTime::now());
+        let mut moved: Vec<(String, String)> = moves
+            .iter()
+            .map(|m| (Path::new(&m.path).file_name().unwrap().to_string_lossy().to_string(), m.rule.clone()))
+            .collect();
+        moved.sort();
+        assert_eq!(moved, vec![("photo.JPG".to_string(), "Images".to_string()), ("setup.dmg".to_string(), "Installers".to_string())]);
+        assert!(stale.is_empty());
+
+        // Two weeks later the installer is a deletion candidate instead
+        let later = SystemTime::now() + std::time::Duration::from_secs(15 * 86_400);
+        let (_, stale) = plan(dir.path(), &rules, &[], later);
+        assert_eq!(stale.len(), 1);
+        assert!(stale[0].path.ends_with("setup.dmg"));
+    }
+
+    #[test]
+    fn skips_files_a_browser_is_still_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        // Firefox: empty placeholder next to the .part file
+        std::fs::write(dir.path().join("report.pdf"), b"").unwrap();
+        std::fs::write(dir.path().join("report.pdf.part"), b"x").unwrap();
+        // Safari: the finished name next to its .download bundle
+        std::fs::write(dir.path().join("photo.jpg"), b"x").unwrap();
+        std::fs::create_dir(dir.path().join("photo.jpg.download")).unwrap();
+        std::fs::write(dir.path().join("setup.dmg"), b"x").unwrap();
+
+        assert!(still_downloading(&dir.path().join("report.pdf"), 0));
+        assert!(still_downloading(&dir.path().join("photo.jpg"), 1));
+        assert!(!still_downloading(&dir.path().join("setup.dmg"), 1));
+        let (moves, _) = plan(dir.path(), &DownloadsPolicy::default().rules, &[], SystemTime::now());
+        assert_eq!(moves.len(), 1);
+        assert!(moves[0].path.ends_with("setup.dmg"));
+    }
+
+    #[test]
+    fn free_name_does_not_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.pdf"), b"x").unwrap();
+        assert_eq!(free_name(dir.path(), "a.pdf"), dir.path().join("a 2.pdf"));
+        assert_eq!(free_name(dir.path(), "b.pdf"), dir.path().join("b.pdf"));
+    }
+}
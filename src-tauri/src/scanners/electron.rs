@@ -0,0 +1,107 @@
+use std::path::Path;
+
+/// Chromium cache folders an Electron app keeps in its Application Support folder. All are
+/// rebuilt on the next launch.
+const CACHE_SUBDIRS: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "DawnCache",
+    "DawnGraphiteCache",
+    "Service Worker/CacheStorage",
+    "Service Worker/ScriptCache",
+];
+
+/// Folders only a Chromium/Electron profile has; two of these mark the Electron layout.
+const PROFILE_MARKERS: &[&str] = &["Local Storage", "Session Storage", "IndexedDB", "Code Cache", "GPUCache", "blob_storage"];
+
+/// Apps whose caches already have dedicated junk templates.
+const HANDLED_APPS: &[&str] = &["Slack", "Discord", "Code"];
+
+/// One detected Electron app and the cache folders it has, relative to home.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElectronApp {
+    pub name: String,
+    pub cache_dirs: Vec<String>,
+}
+
+fn looks_like_electron(dir: &Path) -> bool {
+    PROFILE_MARKERS.iter().filter(|m| dir.join(m).is_dir()).count() >= 2
+}
+
+fn app_at(home: &Path, rel: &str, name: String) -> Option<ElectronApp> {
+    let dir = home.join(rel);
+    if !looks_like_electron(&dir) {
+        return None;
+    }
+    let cache_dirs: Vec<String> = CACHE_SUBDIRS
+        .iter()
+        .filter(|sub| dir.join(sub).is_dir())
+        .map(|sub| format!("{}/{}", rel, sub))
+        .collect();
+    (!cache_dirs.is_empty()).then_some(ElectronApp { name, cache_dirs })
+}
+
+/// Application Support folders with the Electron layout, one vendor level deep
+/// (e.g. "Notion", "Microsoft/Teams"), except apps with hard-coded templates.
+pub fn detect_electron_apps(home: &Path) -> Vec<ElectronApp> {
+    const ROOT: &str = "Library/Application Support";
+    let mut apps = Vec::new();
+    let Ok(entries) = std::fs::read_dir(home.join(ROOT)) else { return apps };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|n| !n.starts_with('.') && !HANDLED_APPS.contains(&n.as_str()))
+        .collect();
+    names.sort();
+
+    for name in names {
+        let rel = format!("{}/{}", ROOT, name);
+        if let Some(app) = app_at(home, &rel, name.clone()) {
+            apps.push(app);
+            continue;
+        }
+        // Vendor folders such as "Microsoft/Teams"
+        let Ok(children) = std::fs::read_dir(home.join(&rel)) else { continue };
+        for child in children.flatten().filter(|e| e.path().is_dir()) {
+            let child_name = child.file_name().to_string_lossy().to_string();
+            if let Some(app) = app_at(home, &format!("{}/{}", rel, child_name), child_name) {
+                apps.push(app);
+            }
+        }
+    }
+    apps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_electron_layout_and_skips_handled_apps() {
+        let home = tempfile::tempdir().unwrap();
+        let support = home.path().join("Library/Application Support");
+        for dir in [
+            "Notion/Cache",
+            "Notion/Code Cache",
+            "Notion/Local Storage",
+            "Microsoft/Teams/GPUCache",
+            "Microsoft/Teams/IndexedDB",
+            "Slack/Cache",
+            "Slack/Local Storage",
+            "Slack/Code Cache",
+            "SomeApp/Cache",
+        ] {
+            std::fs::create_dir_all(support.join(dir)).unwrap();
+        }
+        let apps = detect_electron_apps(home.path());
+        let names: Vec<&str> = apps.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["Teams", "Notion"]);
+        let notion = apps.iter().find(|a| a.name == "Notion").unwrap();
+        assert_eq!(
+            notion.cache_dirs,
+            vec!["Library/Application Support/Notion/Cache", "Library/Application Support/Notion/Code Cache"]
+        );
+    }
+}
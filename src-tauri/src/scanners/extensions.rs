@@ -36,6 +36,8 @@ pub fn scan_extensions() -> Vec<ExtensionItem> {
     // Note: Browser extensions are hidden in randomized profiles and require complex parsing of JSON manifests
     // For MVP transparency, we stick to Startup Items (Launch Agents) which are the "Extensions" that slow down boot.
 
+    let ctx = crate::mcp::context_store::ContextStore::load();
+    items.retain(|i| !ctx.is_protected(&i.path));
     items
 }
 
@@ -0,0 +1,298 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use super::automation::{run_osascript, AutomationPermissionError, ScriptOutcome};
+use crate::error::AltoError;
+
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "otc", "dfont"];
+/// Families not opened for this long are suggested for deactivation.
+pub const RARELY_USED_DAYS: u64 = 180;
+/// Third-party fonts fontd registers at login before the startup impact counts as medium / high.
+const STARTUP_FONTS_MEDIUM: usize = 300;
+const STARTUP_FONTS_HIGH: usize = 1000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FontFamily {
+    pub family: String,
+    /// "user" (~/Library/Fonts), "local" (/Library/Fonts, all users) or "system" (/System/Library/Fonts)
+    pub scope: String,
+    pub files: Vec<String>,
+    pub size_bytes: u64,
+    /// Most recent access of any file in the family, unix seconds
+    pub last_used: Option<i64>,
+    /// From Font Book; None when activation state was not queried
+    pub enabled: Option<bool>,
+    pub rarely_used: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FontStartupImpact {
+    pub user_fonts: usize,
+    pub local_fonts: usize,
+    pub total: usize,
+    /// "low" | "medium" | "high"
+    pub impact: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FontReport {
+    pub families: Vec<FontFamily>,
+    pub startup: FontStartupImpact,
+}
+
+fn font_dirs() -> Vec<(PathBuf, &'static str)> {
+    let mut dirs = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        dirs.push((home.join("Library/Fonts"), "user"));
+    }
+    dirs.push((PathBuf::from("/Library/Fonts"), "local"));
+    dirs.push((PathBuf::from("/System/Library/Fonts"), "system"));
+    dirs
+}
+
+fn font_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| {
+            p.extension()
+                .and_then(|x| x.to_str())
+                .map(|x| FONT_EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()))
+                .unwrap_or(false)
+        })
+}
+
+/// `len` bytes at `offset`.
+fn read_at<R: Read + Seek>(font: &mut R, offset: u64, len: usize) -> Option<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    font.seek(SeekFrom::Start(offset)).ok()?;
+    font.read_exact(&mut buf).ok()?;
+    Some(buf)
+}
+
+fn be16(b: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(b.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be32(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Family name from an OpenType/TrueType `name` table: the typographic family (name ID 16) when
+/// there is one, else the family (ID 1), the way Font Book groups fonts. Collections (.ttc) are
+/// read from their first font.
+fn family_from_name_table<R: Read + Seek>(font: &mut R) -> Option<String> {
+    let head = read_at(font, 0, 12)?;
+    let sfnt = if &head[..4] == b"ttcf" { be32(&head, 8)? as u64 } else { 0 };
+    let offset_table = read_at(font, sfnt, 12)?;
+    let num_tables = be16(&offset_table, 4)? as usize;
+    let directory = read_at(font, sfnt + 12, num_tables * 16)?;
+    let record = directory.chunks(16).find(|r| &r[..4] == b"name")?;
+    let table = read_at(font, be32(record, 8)? as u64, be32(record, 12)? as usize)?;
+
+    let count = be16(&table, 2)? as usize;
+    let strings = be16(&table, 4)? as usize;
+    let mut best: Option<(u8, String)> = None;
+    for i in 0..count {
+        let r = table.get(6 + i * 12..18 + i * 12)?;
+        let (platform, language, name_id) = (be16(r, 0)?, be16(r, 4)?, be16(r, 6)?);
+        let (len, at) = (be16(r, 8)? as usize, strings + be16(r, 10)? as usize);
+        let Some(bytes) = table.get(at..at + len) else { continue };
+        let text = match platform {
+            // Unicode and Windows names are UTF-16BE; Mac Roman is ASCII for the names that matter
+            0 | 3 => String::from_utf16_lossy(&bytes.chunks(2).filter_map(|c| be16(c, 0)).collect::<Vec<_>>()),
+            1 => bytes.iter().map(|&b| b as char).collect(),
+            _ => continue,
+        };
+        // Lower rank wins: typographic family before family, US English Windows names first
+        let rank = match name_id {
+            16 => 0,
+            1 => 4,
+            _ => continue,
+        } + match (platform, language) {
+            (3, 0x0409) => 0,
+            (3, _) => 1,
+            (0, _) => 2,
+            _ => 3,
+        };
+        if !text.trim().is_empty() && best.as_ref().map_or(true, |(b, _)| rank < *b) {
+            best = Some((rank, text.trim().to_string()));
+        }
+    }
+    best.map(|(_, name)| name)
+}
+
+/// Family name from the file name ("Roboto-BoldItalic.ttf" -> "Roboto"), for fonts without a
+/// readable name table (.dfont keeps it in a resource fork).
+fn family_from_file_name(path: &Path) -> String {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    stem.split('-').next().unwrap_or(&stem).trim().to_string()
+}
+
+/// The family Font Book lists the font under.
+fn family_from_file(path: &Path) -> String {
+    File::open(path)
+        .ok()
+        .and_then(|mut f| family_from_name_table(&mut f))
+        .unwrap_or_else(|| family_from_file_name(path))
+}
+
+fn accessed_secs(path: &Path) -> Option<i64> {
+    let t = std::fs::metadata(path).and_then(|m| m.accessed()).ok()?;
+    t.duration_since(SystemTime::UNIX_EPOCH).ok().map(|d| d.as_secs() as i64)
+}
+
+fn impact_level(total: usize) -> &'static str {
+    if total >= STARTUP_FONTS_HIGH {
+        "high"
+    } else if total >= STARTUP_FONTS_MEDIUM {
+        "medium"
+    } else {
+        "low"
+    }
+}
+
+/// Third-party fonts registered at login (user and /Library fonts; system fonts are preloaded either way).
+pub fn startup_impact() -> FontStartupImpact {
+    let count = |scope: &str| {
+        font_dirs()
+            .into_iter()
+            .filter(|(_, s)| *s == scope)
+            .map(|(dir, _)| font_files(&dir).count())
+            .sum::<usize>()
+    };
+    let (user_fonts, local_fonts) = (count("user"), count("local"));
+    let total = user_fonts + local_fonts;
+    FontStartupImpact { user_fonts, local_fonts, total, impact: impact_level(total).to_string() }
+}
+
+fn escape_applescript(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Names of families Font Book has deactivated. Launches Font Book.
+fn disabled_families() -> Result<Vec<String>, AltoError> {
+    match run_osascript("tell application \"Font Book\" to get name of every font family whose enabled is false")? {
+        ScriptOutcome::Ok(out) => Ok(out.trim().split(", ").filter(|s| !s.is_empty()).map(|s| s.to_string()).collect()),
+        ScriptOutcome::PermissionDenied => Err(AutomationPermissionError::new("Reading font activation").into()),
+        ScriptOutcome::Failed(err) => Err(err.into()),
+    }
+}
+
+/// Installed font families by scope. With `include_activation`, Font Book is asked which families are disabled.
+pub fn scan_fonts(include_activation: bool) -> Result<FontReport, AltoError> {
+    let disabled = if include_activation { Some(disabled_families()?) } else { None };
+    let cutoff = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64 - (RARELY_USED_DAYS * 24 * 3600) as i64)
+        .unwrap_or(0);
+
+    let mut families: BTreeMap<(String, &'static str), FontFamily> = BTreeMap::new();
+    for (dir, scope) in font_dirs() {
+        for path in font_files(&dir) {
+            let family = family_from_file(&path);
+            let entry = families.entry((family.clone(), scope)).or_insert_with(|| FontFamily {
+                enabled: disabled.as_ref().map(|d| !d.contains(&family)),
+                family,
+                scope: scope.to_string(),
+                files: vec![],
+                size_bytes: 0,
+                last_used: None,
+                rarely_used: false,
+            });
+            entry.size_bytes += std::fs::metadata(&path).map(|m| super::allocated_bytes(&m)).unwrap_or(0);
+            entry.last_used = entry.last_used.max(accessed_secs(&path));
+            entry.files.push(path.to_string_lossy().to_string());
+        }
+    }
+
+    let families = families
+        .into_values()
+        .map(|mut f| {
+            // System fonts can't be deactivated; never suggest them
+            f.rarely_used = f.scope != "system" && f.enabled != Some(false) && f.last_used.map(|t| t < cutoff).unwrap_or(false);
+            f
+        })
+        .collect();
+    Ok(FontReport { families, startup: startup_impact() })
+}
+
+/// Deactivate (not delete) families through Font Book, so they can be re-enabled later.
+/// Returns the number deactivated and per-family errors.
+pub fn deactivate_families(names: &[String]) -> Result<(usize, Vec<String>), AltoError> {
+    let mut done = 0usize;
+    let mut errors = Vec::new();
+    for name in names {
+        let script = format!(
+            "tell application \"Font Book\" to set enabled of font family \"{}\" to false",
+            escape_applescript(name)
+        );
+        match run_osascript(&script)? {
+            ScriptOutcome::Ok(_) => done += 1,
+            ScriptOutcome::PermissionDenied => return Err(AutomationPermissionError::new("Deactivating fonts").into()),
+            ScriptOutcome::Failed(err) => errors.push(format!("{}: {}", name, err.trim())),
+        }
+    }
+    Ok((done, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn family_name_from_file_name() {
+        assert_eq!(family_from_file(Path::new("/Library/Fonts/Roboto-BoldItalic.ttf")), "Roboto");
+        assert_eq!(family_from_file(Path::new("/Library/Fonts/Menlo.ttc")), "Menlo");
+    }
+
+    /// A font with only a `name` table holding the given (platform, language, name id, text) records.
+    fn font_with_names(names: &[(u16, u16, u16, &str)]) -> Vec<u8> {
+        let mut strings = Vec::new();
+        let mut records = Vec::new();
+        for (platform, language, id, text) in names {
+            let bytes: Vec<u8> = if *platform == 1 { text.bytes().collect() } else { text.encode_utf16().flat_map(|u| u.to_be_bytes()).collect() };
+            for v in [*platform, if *platform == 1 { 0 } else { 1 }, *language, *id, bytes.len() as u16, strings.len() as u16] {
+                records.extend(v.to_be_bytes());
+            }
+            strings.extend(bytes);
+        }
+        let mut table = Vec::new();
+        for v in [0u16, names.len() as u16, (6 + records.len()) as u16] {
+            table.extend(v.to_be_bytes());
+        }
+        table.extend(records);
+        table.extend(strings);
+
+        let mut font = vec![0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+        font.extend(b"name");
+        for v in [0u32, 28, table.len() as u32] {
+            font.extend(v.to_be_bytes());
+        }
+        font.extend(table);
+        font
+    }
+
+    #[test]
+    fn family_name_from_name_table() {
+        let font = font_with_names(&[(1, 0, 1, "Helvetica Neue Bold"), (3, 0x0409, 1, "Helvetica Neue Bold"), (3, 0x0409, 16, "Helvetica Neue")]);
+        assert_eq!(family_from_name_table(&mut std::io::Cursor::new(font)).as_deref(), Some("Helvetica Neue"));
+        let font = font_with_names(&[(1, 0, 1, "Menlo")]);
+        assert_eq!(family_from_name_table(&mut std::io::Cursor::new(font)).as_deref(), Some("Menlo"));
+        assert_eq!(family_from_name_table(&mut std::io::Cursor::new(b"not a font".to_vec())), None);
+    }
+
+    #[test]
+    fn startup_impact_levels() {
+        assert_eq!(impact_level(10), "low");
+        assert_eq!(impact_level(STARTUP_FONTS_MEDIUM), "medium");
+        assert_eq!(impact_level(5000), "high");
+    }
+}
@@ -0,0 +1,178 @@
+use serde::Serialize;
+use std::path::Path;
+
+/// Downloaded types Gatekeeper (or a code signature check) can say something about.
+const ASSESSABLE_EXT: &[&str] = &["app", "pkg", "mpkg", "dmg", "command", "sh"];
+
+/// What macOS knows about where a downloaded file came from and whether it may run.
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadAssessment {
+    pub path: String,
+    /// Has the com.apple.quarantine attribute browsers and mail clients set on downloads
+    pub quarantined: bool,
+    /// App that downloaded it ("Safari", "Google Chrome"), from the quarantine attribute
+    pub quarantine_agent: Option<String>,
+    /// "accepted" | "rejected" | "unsigned" | "invalid" | "signed" | "unknown"
+    pub gatekeeper: String,
+    /// spctl's `source=` line ("Notarized Developer ID", "no usable signature")
+    pub gatekeeper_source: Option<String>,
+    /// "low" | "medium" | "high"
+    pub risk: String,
+    pub reasons: Vec<String>,
+    /// Detection ratio from an earlier VirusTotal lookup of this file
+    pub virustotal: Option<super::virustotal::VirusTotalVerdict>,
+}
+
+/// Agent name from a com.apple.quarantine value ("0083;65a1b2c3;Safari;UUID").
+fn quarantine_agent(value: &str) -> Option<String> {
+    value.trim().split(';').nth(2).filter(|a| !a.is_empty()).map(|a| a.to_string())
+}
+
+/// Verdict and source from `spctl --assess -vv` output (it prints to stderr).
+fn parse_spctl(output: &str, accepted: bool) -> (String, Option<String>) {
+    let source = output.lines().find_map(|l| l.trim().strip_prefix("source=")).map(|s| s.to_string());
+    let verdict = if accepted {
+        "accepted"
+    } else if output.contains("rejected") {
+        "rejected"
+    } else {
+        "unknown"
+    };
+    (verdict.to_string(), source)
+}
+
+fn risk_of(quarantined: bool, gatekeeper: &str, source: Option<&str>) -> (String, Vec<String>) {
+    let mut reasons = Vec::new();
+    let mut risk = "low";
+    match gatekeeper {
+        "rejected" => {
+            risk = "high";
+            reasons.push(match source {
+                Some(s) => format!("Gatekeeper rejects it ({})", s),
+                None => "Gatekeeper rejects it".to_string(),
+            });
+        }
+        "unsigned" | "invalid" => {
+            risk = if quarantined { "high" } else { "medium" };
+            reasons.push(if gatekeeper == "unsigned" { "It is not code-signed" } else { "Its code signature is invalid" }.to_string());
+        }
+        _ => {}
+    }
+    if !quarantined {
+        if risk == "low" {
+            risk = "medium";
+        }
+        reasons.push("The download quarantine flag is missing, so macOS won't check it when opened".to_string());
+    }
+    (risk.to_string(), reasons)
+}
+
+/// Apps, installers, disk images and executables; other downloads aren't assessed.
+pub fn is_assessable(path: &Path) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    if ASSESSABLE_EXT.contains(&ext.as_str()) {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if ext.is_empty() {
+            return std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0);
+        }
+    }
+    false
+}
+
+#[cfg(target_os = "macos")]
+fn read_quarantine(path: &Path) -> Option<String> {
+    let out = std::process::Command::new("xattr").args(["-p", "com.apple.quarantine"]).arg(path).output().ok()?;
+    out.status.success().then(|| String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+/// Gatekeeper's verdict for apps, packages and disk images; a code signature check for bare executables.
+#[cfg(target_os = "macos")]
+fn gatekeeper_verdict(path: &Path) -> (String, Option<String>) {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let mut cmd = std::process::Command::new("spctl");
+    match ext.as_str() {
+        "app" => cmd.args(["--assess", "--type", "execute", "-vv"]),
+        "pkg" | "mpkg" => cmd.args(["--assess", "--type", "install", "-vv"]),
+        "dmg" => cmd.args(["--assess", "--type", "open", "--context", "context:primary-signature", "-vv"]),
+        _ => {
+            let signing = super::launch_items::code_signing(path);
+            return (if signing == "apple" || signing == "developer" { "signed".to_string() } else { signing }, None);
+        }
+    };
+    match cmd.arg(path).output() {
+        Ok(out) => parse_spctl(&String::from_utf8_lossy(&out.stderr), out.status.success()),
+        Err(_) => ("unknown".to_string(), None),
+    }
+}
+
+/// Quarantine and Gatekeeper status of a downloaded file.
+#[cfg(target_os = "macos")]
+pub fn assess(path: &Path) -> DownloadAssessment {
+    let quarantine = read_quarantine(path);
+    let (gatekeeper, source) = gatekeeper_verdict(path);
+    let (risk, reasons) = risk_of(quarantine.is_some(), &gatekeeper, source.as_deref());
+    DownloadAssessment {
+        path: path.to_string_lossy().to_string(),
+        quarantined: quarantine.is_some(),
+        quarantine_agent: quarantine.as_deref().and_then(quarantine_agent),
+        gatekeeper,
+        gatekeeper_source: source,
+        risk,
+        reasons,
+        virustotal: None,
+    }
+}
+
+/// Assess the apps, installers and executables in ~/Downloads (and one folder down), riskiest first.
+#[cfg(target_os = "macos")]
+pub fn assess_downloads(home: &Path) -> Vec<DownloadAssessment> {
+    use rayon::prelude::*;
+    let paths: Vec<std::path::PathBuf> = walkdir::WalkDir::new(home.join("Downloads"))
+        .min_depth(1)
+        .max_depth(2)
+        .into_iter()
+        // Apps are assessed as a whole, not walked into
+        .filter_entry(|e| e.path().parent().and_then(|p| p.extension()).map(|x| x != "app").unwrap_or(true))
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|p| is_assessable(p))
+        .collect();
+    let rank = |risk: &str| match risk {
+        "high" => 0,
+        "medium" => 1,
+        _ => 2,
+    };
+    let mut assessed: Vec<DownloadAssessment> = paths.par_iter().map(|p| assess(p)).collect();
+    assessed.sort_by_key(|a| rank(&a.risk));
+    assessed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quarantine_and_spctl_output() {
+        assert_eq!(quarantine_agent("0083;65a1b2c3;Safari;6E5C1A2B-0000-4F4F-8D8D-1234567890AB\n").as_deref(), Some("Safari"));
+        assert_eq!(quarantine_agent("0081;65a1b2c3;;"), None);
+
+        let (verdict, source) = parse_spctl("/Users/jane/Downloads/Tool.app: rejected\nsource=no usable signature\n", false);
+        assert_eq!((verdict.as_str(), source.as_deref()), ("rejected", Some("no usable signature")));
+        let (verdict, source) = parse_spctl("/Applications/Safari.app: accepted\nsource=Notarized Developer ID\n", true);
+        assert_eq!((verdict.as_str(), source.as_deref()), ("accepted", Some("Notarized Developer ID")));
+    }
+
+    #[test]
+    fn rates_rejected_unsigned_and_unquarantined_downloads() {
+        assert_eq!(risk_of(true, "accepted", None).0, "low");
+        assert_eq!(risk_of(true, "rejected", Some("no usable signature")).0, "high");
+        assert_eq!(risk_of(true, "unsigned", None).0, "high");
+        let (risk, reasons) = risk_of(false, "accepted", None);
+        assert_eq!(risk, "medium");
+        assert_eq!(reasons.len(), 1);
+    }
+}
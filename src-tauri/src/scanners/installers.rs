@@ -0,0 +1,386 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use super::ByteSize;
+
+/// What an installer was found to contain, remembered per file until it changes.
+#[derive(Debug, Clone, Default)]
+struct Contents {
+    /// (bundle id, app file name, version) of apps inside a disk image or package payload
+    apps: Vec<(Option<String>, String, Option<String>)>,
+    /// Component package identifiers of a .pkg
+    package_ids: Vec<String>,
+}
+
+lazy_static::lazy_static! {
+    static ref CONTENTS_CACHE: Mutex<HashMap<(String, i128), Contents>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledMatch {
+    /// Installed app, or the package receipt id for packages without an app
+    pub installed: String,
+    pub installed_version: Option<String>,
+    pub installer_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstallerInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    /// "dmg" | "pkg"
+    pub kind: String,
+    /// Set when what the installer contains is already on this Mac
+    pub already_installed: Option<InstalledMatch>,
+}
+
+/// Installed apps by bundle id and by lowercase name, with their versions.
+#[derive(Default)]
+pub struct InstalledApps {
+    by_id: HashMap<String, (String, Option<String>)>,
+    by_name: HashMap<String, (String, Option<String>)>,
+    receipts: Vec<String>,
+}
+
+/// (bundle id, file name, short version) from an app's Info.plist.
+#[cfg(target_os = "macos")]
+fn app_info(app: &Path) -> Option<(Option<String>, String, Option<String>)> {
+    let name = app.file_name()?.to_string_lossy().to_string();
+    let info: Option<serde_json::Value> = std::fs::File::open(app.join("Contents/Info.plist")).ok().and_then(|f| plist::from_reader(f).ok());
+    let field = |key: &str| info.as_ref()?.get(key)?.as_str().map(|s| s.to_string());
+    Some((field("CFBundleIdentifier"), name, field("CFBundleShortVersionString")))
+}
+
+#[cfg(target_os = "macos")]
+fn apps_in(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(depth)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| e.path().parent().and_then(|p| p.extension()).map(|x| x != "app").unwrap_or(true))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir() && e.path().extension().is_some_and(|x| x == "app"))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+impl InstalledApps {
+    #[cfg(target_os = "macos")]
+    pub fn load() -> Self {
+        let mut installed = InstalledApps::default();
+        let home = dirs::home_dir().unwrap_or_default();
+        for dir in [PathBuf::from("/Applications"), home.join("Applications")] {
+            for app in apps_in(&dir, 2) {
+                let Some((id, name, version)) = app_info(&app) else { continue };
+                let entry = (app.to_string_lossy().to_string(), version);
+                if let Some(id) = id {
+                    installed.by_id.insert(id, entry.clone());
+                }
+                installed.by_name.insert(name.to_lowercase(), entry);
+            }
+        }
+        installed.receipts = std::process::Command::new("pkgutil")
+            .arg("--pkgs")
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(|l| l.trim().to_string()).collect())
+            .unwrap_or_default();
+        installed
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn load() -> Self {
+        InstalledApps::default()
+    }
+
+    fn find(&self, contents: &Contents) -> Option<InstalledMatch> {
+        for (id, name, version) in &contents.apps {
+            let hit = id.as_ref().and_then(|id| self.by_id.get(id)).or_else(|| self.by_name.get(&name.to_lowercase()));
+            if let Some((path, installed_version)) = hit {
+                return Some(InstalledMatch {
+                    installed: path.clone(),
+                    installed_version: installed_version.clone(),
+                    installer_version: version.clone(),
+                });
+            }
+        }
+        // Packages that install no app (drivers, CLI tools) still leave a receipt
+        let id = contents.package_ids.iter().find(|id| self.receipts.contains(id))?;
+        Some(InstalledMatch { installed: id.clone(), installed_version: None, installer_version: None })
+    }
+}
+
+/// First mount point among a disk image's `system-entities`.
+#[cfg(target_os = "macos")]
+fn entity_mount(image: &serde_json::Value) -> Option<PathBuf> {
+    image
+        .get("system-entities")?
+        .as_array()?
+        .iter()
+        .find_map(|e| e.get("mount-point")?.as_str().map(PathBuf::from))
+}
+
+/// Mount point from `hdiutil attach -plist` output.
+#[cfg(target_os = "macos")]
+fn mount_point(attach_plist: &[u8]) -> Option<PathBuf> {
+    entity_mount(&plist::from_bytes(attach_plist).ok()?)
+}
+
+/// Where `dmg` is mounted already, from `hdiutil info -plist` output: the user opened it, or an
+/// installer is running from it.
+#[cfg(target_os = "macos")]
+fn existing_mount(info_plist: &[u8], dmg: &Path) -> Option<PathBuf> {
+    let info: serde_json::Value = plist::from_bytes(info_plist).ok()?;
+    let dmg = dmg.canonicalize().unwrap_or_else(|_| dmg.to_path_buf());
+    info.get("images")?
+        .as_array()?
+        .iter()
+        .filter(|image| image.get("image-path").and_then(|p| p.as_str()).is_some_and(|p| Path::new(p) == dmg))
+        .find_map(entity_mount)
+}
+
+/// List the apps at the top level of a disk image. An image the user already mounted is read where
+/// it is and left attached; otherwise Alto attaches it read-only and out of sight, and detaches
+/// only that mount afterwards.
+#[cfg(target_os = "macos")]
+fn dmg_contents(dmg: &Path) -> Contents {
+    use std::process::{Command, Stdio};
+    let apps_at = |mount: &Path| apps_in(mount, 1).iter().filter_map(|a| app_info(a)).collect();
+    let info = Command::new("hdiutil").args(["info", "-plist"]).output().map(|o| o.stdout).unwrap_or_default();
+    if let Some(mount) = existing_mount(&info, dmg) {
+        return Contents { apps: apps_at(&mount), package_ids: vec![] };
+    }
+    // A license agreement prompt reads stdin; with none the attach fails instead of hanging
+    let Ok(out) = Command::new("hdiutil")
+        .args(["attach", "-nobrowse", "-readonly", "-noautoopen", "-noverify", "-plist"])
+        .arg(dmg)
+        .stdin(Stdio::null())
+        .output()
+    else {
+        return Contents::default();
+    };
+    let Some(mount) = mount_point(&out.stdout) else { return Contents::default() };
+    let apps = apps_at(&mount);
+    // Nothing else knows about this hidden mount, so forcing is only a fallback if Spotlight holds it
+    let detached = Command::new("hdiutil").args(["detach", "-quiet"]).arg(&mount).status().is_ok_and(|s| s.success());
+    if !detached {
+        let _ = Command::new("hdiutil").args(["detach", "-quiet", "-force"]).arg(&mount).status();
+    }
+    Contents { apps, package_ids: vec![] }
+}
+
+/// Package ids of the pkg-info (PackageInfo) and pkg-ref (Distribution) elements in a package's XML.
+fn package_identifiers(xml: &str) -> Vec<String> {
+    xml.split("<pkg-info")
+        .skip(1)
+        .chain(xml.split("<pkg-ref").skip(1))
+        .filter_map(|tag| {
+            let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+            let start = tag.find("identifier=\"").or_else(|| tag.find("id=\""))?;
+            let rest = &tag[start..];
+            let value = &rest[rest.find('"')? + 1..];
+            Some(value[..value.find('"')?].to_string())
+        })
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// Apps in the payload (`pkgutil --payload-files`) and the component ids from the package's XML.
+#[cfg(target_os = "macos")]
+fn pkg_contents(pkg: &Path) -> Contents {
+    use std::process::Command;
+    let payload = Command::new("pkgutil").arg("--payload-files").arg(pkg).output().map(|o| String::from_utf8_lossy(&o.stdout).to_string()).unwrap_or_default();
+    let mut names: Vec<String> = payload
+        .lines()
+        .filter_map(|l| l.trim_start_matches("./").split('/').find(|c| c.ends_with(".app")).map(|c| c.to_string()))
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let mut package_ids = Vec::new();
+    if let Ok(dir) = scratch_dir() {
+        let listing = Command::new("xar").arg("-tf").arg(pkg).output().map(|o| String::from_utf8_lossy(&o.stdout).to_string()).unwrap_or_default();
+        let xml_files: Vec<&str> = listing.lines().filter(|l| *l == "Distribution" || l.ends_with("/PackageInfo") || *l == "PackageInfo").collect();
+        if !xml_files.is_empty() && Command::new("xar").arg("-xf").arg(pkg).arg("-C").arg(&dir).args(&xml_files).status().is_ok() {
+            for file in &xml_files {
+                if let Ok(xml) = std::fs::read_to_string(dir.join(file)) {
+                    package_ids.extend(package_identifiers(&xml));
+                }
+            }
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    package_ids.sort();
+    package_ids.dedup();
+    Contents { apps: names.into_iter().map(|n| (None, n, None)).collect(), package_ids }
+}
+
+#[cfg(target_os = "macos")]
+fn scratch_dir() -> std::io::Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("alto-pkg-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+#[cfg(target_os = "macos")]
+fn contents_of(path: &Path, kind: &str) -> Contents {
+    match kind {
+        "dmg" => dmg_contents(path),
+        _ => pkg_contents(path),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn contents_of(_path: &Path, _kind: &str) -> Contents {
+    Contents::default()
+}
+
+fn installer_kind(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()?.to_lowercase().as_str() {
+        "dmg" => Some("dmg"),
+        "pkg" | "mpkg" => Some("pkg"),
+        _ => None,
+    }
+}
+
+/// Whether `path` really is a disk image or flat installer package, judged by its contents rather
+/// than its name: a UDIF trailer ("koly") or ISO 9660 descriptor for .dmg/.iso, a xar header for .pkg.
+pub fn is_installer_image(path: &Path) -> bool {
+    use std::io::{Read, Seek, SeekFrom};
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let Ok(meta) = std::fs::symlink_metadata(path) else { return false };
+    if !meta.is_file() {
+        return false;
+    }
+    let Ok(mut file) = std::fs::File::open(path) else { return false };
+    let mut has_magic = |at: SeekFrom, magic: &[u8]| {
+        let mut buf = vec![0u8; magic.len()];
+        file.seek(at).is_ok() && file.read_exact(&mut buf).is_ok() && buf == magic
+    };
+    match ext.as_str() {
+        "dmg" | "iso" => (meta.len() >= 512 && has_magic(SeekFrom::End(-512), b"koly")) || has_magic(SeekFrom::Start(0x8001), b"CD001"),
+        "pkg" | "mpkg" => has_magic(SeekFrom::Start(0), b"xar!"),
+        _ => false,
+    }
+}
+
+/// What's installed from the disk image or package at `path`, if anything.
+/// Contents are cached per file and modification time, so repeat scans don't re-mount images.
+pub fn match_installed(path: &Path, installed: &InstalledApps) -> Option<InstalledMatch> {
+    let kind = installer_kind(path)?;
+    let meta = std::fs::metadata(path).ok()?;
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as i128)
+        .unwrap_or(0);
+    let key = (path.to_string_lossy().to_string(), mtime);
+    let cached = CONTENTS_CACHE.lock().unwrap().get(&key).cloned();
+    let contents = cached.unwrap_or_else(|| {
+        let contents = contents_of(path, kind);
+        CONTENTS_CACHE.lock().unwrap().insert(key, contents.clone());
+        contents
+    });
+    installed.find(&contents)
+}
+
+/// Reason shown on junk items for installers whose app is already installed.
+pub fn installed_reason(m: &InstalledMatch) -> String {
+    let name = Path::new(&m.installed).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| m.installed.clone());
+    format!("Already installed ({}) — safe to delete", name)
+}
+
+/// Disk images and packages in Downloads, tagged when what they install is already on this Mac.
+pub fn scan_installers() -> Vec<InstallerInfo> {
+    let _priority = super::priority::lower_scan_priority();
+    let Some(downloads) = dirs::download_dir().or_else(|| dirs::home_dir().map(|h| h.join("Downloads"))) else { return vec![] };
+    let installed = InstalledApps::load();
+    let mut installers: Vec<InstallerInfo> = walkdir::WalkDir::new(&downloads)
+        .max_depth(2)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        // Flat packages are files; bundle-style .pkg/.mpkg are folders
+        .filter(|e| installer_kind(e.path()).is_some())
+        .filter_map(|e| {
+            let kind = installer_kind(e.path())?;
+            let size = if e.file_type().is_dir() { super::dir_byte_size(e.path()) } else { ByteSize::of(&e.metadata().ok()?) };
+            Some(InstallerInfo {
+                path: e.path().to_string_lossy().to_string(),
+                size_bytes: size.allocated,
+                kind: kind.to_string(),
+                already_installed: match_installed(e.path(), &installed),
+            })
+        })
+        .collect();
+    let ctx = crate::mcp::context_store::ContextStore::load();
+    installers.retain(|i| !ctx.is_protected(&i.path));
+    installers.sort_by_key(|i| std::cmp::Reverse(i.size_bytes));
+    installers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_package_identifiers_from_xml() {
+        let distribution = r#"<installer-gui-script><pkg-ref id="com.example.app.pkg" version="1.2"/>
+            <pkg-ref id="com.example.app.pkg">#app.pkg</pkg-ref></installer-gui-script>"#;
+        let info = r#"<pkg-info format-version="2" identifier="com.example.driver" version="3.0">"#;
+        let mut ids = package_identifiers(distribution);
+        ids.extend(package_identifiers(info));
+        ids.dedup();
+        assert_eq!(ids, vec!["com.example.app.pkg", "com.example.driver"]);
+    }
+
+    #[test]
+    fn matches_by_bundle_id_then_name_then_receipt() {
+        let mut installed = InstalledApps::default();
+        installed.by_id.insert("us.zoom.xos".into(), ("/Applications/zoom.us.app".into(), Some("6.0".into())));
+        installed.by_name.insert("slack.app".into(), ("/Applications/Slack.app".into(), None));
+        installed.receipts.push("com.example.driver".into());
+
+        let dmg = Contents { apps: vec![(Some("us.zoom.xos".into()), "Zoom.app".into(), Some("5.9".into()))], package_ids: vec![] };
+        let m = installed.find(&dmg).unwrap();
+        assert_eq!(m.installed, "/Applications/zoom.us.app");
+        assert_eq!(installed_reason(&m), "Already installed (zoom.us.app) — safe to delete");
+
+        let pkg = Contents { apps: vec![(None, "Slack.app".into(), None)], package_ids: vec![] };
+        assert_eq!(installed.find(&pkg).unwrap().installed, "/Applications/Slack.app");
+        let driver = Contents { apps: vec![], package_ids: vec!["com.example.driver".into()] };
+        assert_eq!(installed.find(&driver).unwrap().installed, "com.example.driver");
+        assert!(installed.find(&Contents::default()).is_none());
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn finds_images_the_user_mounted() {
+        let info = br#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict><key>images</key><array>
+<dict><key>image-path</key><string>/Users/jane/Downloads/Zoom.dmg</string>
+<key>system-entities</key><array><dict><key>dev-entry</key><string>/dev/disk4s1</string>
+<key>mount-point</key><string>/Volumes/Zoom</string></dict></array></dict>
+</array></dict></plist>"#;
+        assert_eq!(existing_mount(info, Path::new("/Users/jane/Downloads/Zoom.dmg")), Some(PathBuf::from("/Volumes/Zoom")));
+        assert_eq!(existing_mount(info, Path::new("/Users/jane/Downloads/Slack.dmg")), None);
+    }
+
+    #[test]
+    fn recognizes_images_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut udif = vec![0u8; 4096];
+        udif[4096 - 512..4096 - 508].copy_from_slice(b"koly");
+        std::fs::write(dir.path().join("App.dmg"), &udif).unwrap();
+        std::fs::write(dir.path().join("Setup.pkg"), b"xar!\x00\x1c").unwrap();
+        std::fs::write(dir.path().join("Renamed.dmg"), vec![0u8; 4096]).unwrap();
+        assert!(is_installer_image(&dir.path().join("App.dmg")));
+        assert!(is_installer_image(&dir.path().join("Setup.pkg")));
+        assert!(!is_installer_image(&dir.path().join("Renamed.dmg")));
+        assert!(!is_installer_image(dir.path()));
+    }
+}
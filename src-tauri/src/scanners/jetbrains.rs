@@ -0,0 +1,208 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::dir_byte_size;
+use crate::mcp::context_store::ContextStore;
+use crate::mcp::managed::ManagedPolicy;
+use crate::mcp::safety_gate;
+
+/// Per-version folders live under these (relative to home), named like "IntelliJIdea2024.1".
+const JETBRAINS_ROOTS: &[(&str, &str)] = &[
+    ("Library/Caches/JetBrains", "caches"),
+    ("Library/Application Support/JetBrains", "config"),
+    ("Library/Logs/JetBrains", "logs"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JetBrainsFolder {
+    /// "caches" | "config" | "logs"
+    pub kind: String,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JetBrainsVersion {
+    /// Folder name shared by all of the version's folders, e.g. "PyCharm2023.2"
+    pub data_dir_name: String,
+    pub version: String,
+    pub installed: bool,
+    pub folders: Vec<JetBrainsFolder>,
+    pub size_bytes: u64,
+    /// What cleaning removes: only caches for installed IDEs, caches and logs for uninstalled ones
+    pub clean_action: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JetBrainsProduct {
+    pub product: String,
+    pub versions: Vec<JetBrainsVersion>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JetBrainsCleanResult {
+    pub data_dir_name: String,
+    pub bytes_freed: u64,
+    pub errors: Vec<String>,
+}
+
+/// "IntelliJIdea2024.1" -> ("IntelliJIdea", "2024.1"). Folders without a year.minor suffix
+/// (Toolbox, consentOptions, ...) aren't IDE versions.
+fn split_data_dir_name(name: &str) -> Option<(&str, &str)> {
+    let idx = name.find(|c: char| c.is_ascii_digit())?;
+    let (product, version) = name.split_at(idx);
+    let (year, minor) = version.split_once('.')?;
+    let valid = !product.is_empty()
+        && year.len() == 4
+        && year.chars().all(|c| c.is_ascii_digit())
+        && !minor.is_empty()
+        && minor.chars().all(|c| c.is_ascii_digit());
+    valid.then_some((product, version))
+}
+
+/// Data directory names of installed IDEs, from each app's product-info.json
+/// (/Applications, ~/Applications and Toolbox-managed installs).
+fn installed_data_dirs(home: &Path) -> HashSet<String> {
+    let search = [
+        (PathBuf::from("/Applications"), 1),
+        (home.join("Applications"), 2),
+        (home.join("Library/Application Support/JetBrains/Toolbox/apps"), 5),
+    ];
+    let mut installed = HashSet::new();
+    for (dir, depth) in search {
+        for entry in WalkDir::new(&dir).max_depth(depth).into_iter().filter_map(|e| e.ok()) {
+            if entry.path().extension().map(|x| x != "app").unwrap_or(true) {
+                continue;
+            }
+            let info = entry.path().join("Contents/Resources/product-info.json");
+            let Ok(text) = std::fs::read_to_string(&info) else { continue };
+            let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) else { continue };
+            if let Some(name) = json.get("dataDirectoryName").and_then(|n| n.as_str()) {
+                installed.insert(name.to_string());
+            }
+        }
+    }
+    installed
+}
+
+fn scan_in(home: &Path, installed: &HashSet<String>, ctx: &ContextStore) -> Vec<JetBrainsProduct> {
+    let mut versions: BTreeMap<String, (String, JetBrainsVersion)> = BTreeMap::new();
+    for (root, kind) in JETBRAINS_ROOTS {
+        let Ok(entries) = std::fs::read_dir(home.join(root)) else { continue };
+        for entry in entries.flatten().filter(|e| e.path().is_dir()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some((product, version)) = split_data_dir_name(&name) else { continue };
+            let path = entry.path();
+            if ctx.is_excluded(&path.to_string_lossy()) {
+                continue;
+            }
+            let is_installed = installed.contains(&name);
+            let (_, v) = versions.entry(name.clone()).or_insert_with(|| {
+                (
+                    product.to_string(),
+                    JetBrainsVersion {
+                        data_dir_name: name.clone(),
+                        version: version.to_string(),
+                        installed: is_installed,
+                        folders: vec![],
+                        size_bytes: 0,
+                        clean_action: if is_installed {
+                            "Move caches to the Trash (re-indexed on next launch)".to_string()
+                        } else {
+                            "Move caches and logs of this uninstalled version to the Trash; settings only when selected".to_string()
+                        },
+                    },
+                )
+            });
+            let size_bytes = dir_byte_size(&path).allocated;
+            v.size_bytes += size_bytes;
+            v.folders.push(JetBrainsFolder {
+                kind: kind.to_string(),
+                path: path.to_string_lossy().to_string(),
+                size_bytes,
+            });
+        }
+    }
+
+    let mut products: BTreeMap<String, JetBrainsProduct> = BTreeMap::new();
+    for (_, (product, version)) in versions {
+        let entry = products.entry(product.clone()).or_insert_with(|| JetBrainsProduct {
+            product,
+            versions: vec![],
+            size_bytes: 0,
+        });
+        entry.size_bytes += version.size_bytes;
+        entry.versions.push(version);
+    }
+    let mut products: Vec<JetBrainsProduct> = products.into_values().collect();
+    products.sort_by_key(|p| std::cmp::Reverse(p.size_bytes));
+    products
+}
+
+/// JetBrains IDE folders grouped by product and version, flagging versions that are no longer installed.
+pub fn scan_jetbrains() -> Vec<JetBrainsProduct> {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    scan_in(&home, &installed_data_dirs(&home), &ContextStore::load())
+}
+
+/// Clean one IDE version: caches only when it's still installed, caches and logs otherwise.
+/// Settings folders (keymaps, code styles, plugins) only go when listed in `settings`. Everything
+/// removed goes to the Trash and is recorded for undo.
+pub fn clean_jetbrains_version(data_dir_name: &str, settings: &[String]) -> Result<JetBrainsCleanResult, String> {
+    let version = scan_jetbrains()
+        .into_iter()
+        .flat_map(|p| p.versions)
+        .find(|v| v.data_dir_name == data_dir_name)
+        .ok_or_else(|| format!("No JetBrains folders found for {}", data_dir_name))?;
+    let items: Vec<(String, u64)> = version
+        .folders
+        .iter()
+        .filter(|f| match f.kind.as_str() {
+            "caches" => true,
+            "config" => settings.contains(&f.path),
+            _ => !version.installed,
+        })
+        .map(|f| (f.path.clone(), f.size_bytes))
+        .collect();
+    let paths: Vec<String> = items.iter().map(|(p, _)| p.clone()).collect();
+    safety_gate::check_paths("JetBrains cleanup", &paths, &ManagedPolicy::load(), &ContextStore::load())?;
+
+    let result = super::trash::trash_and_record(&items);
+    Ok(JetBrainsCleanResult { data_dir_name: data_dir_name.to_string(), bytes_freed: result.bytes_freed, errors: result.errors })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_product_and_version() {
+        assert_eq!(split_data_dir_name("IntelliJIdea2024.1"), Some(("IntelliJIdea", "2024.1")));
+        assert_eq!(split_data_dir_name("PyCharmCE2023.2"), Some(("PyCharmCE", "2023.2")));
+        assert_eq!(split_data_dir_name("Toolbox"), None);
+        assert_eq!(split_data_dir_name("consentOptions"), None);
+    }
+
+    #[test]
+    fn groups_versions_and_flags_uninstalled() {
+        let home = tempfile::tempdir().unwrap();
+        for dir in [
+            "Library/Caches/JetBrains/GoLand2023.1",
+            "Library/Caches/JetBrains/GoLand2024.2",
+            "Library/Application Support/JetBrains/GoLand2023.1",
+            "Library/Application Support/JetBrains/Toolbox",
+        ] {
+            std::fs::create_dir_all(home.path().join(dir)).unwrap();
+        }
+        let installed: HashSet<String> = ["GoLand2024.2".to_string()].into_iter().collect();
+        let products = scan_in(home.path(), &installed, &ContextStore::default());
+        assert_eq!(products.len(), 1);
+        let old = products[0].versions.iter().find(|v| v.version == "2023.1").unwrap();
+        assert!(!old.installed);
+        assert_eq!(old.folders.len(), 2);
+        assert!(products[0].versions.iter().any(|v| v.version == "2024.2" && v.installed));
+    }
+}
@@ -1,10 +1,18 @@
-use super::{ScanResult, ScannedItem};
+use super::{dir_byte_size, ByteSize, ScanResult, ScannedItem};
+use super::priority::ScanPriorityGuard;
+use crate::mcp::context_store::{path_is_excluded, path_is_protected, ContextStore, ScanProfile};
+use crate::mcp::file_index::index_file_with;
+use crate::mcp::whitelist::Whitelist;
+use crate::mcp::path_norm::{nfc, path_within};
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
+use rayon::prelude::*;
 
 const MAX_DEPTH: u32 = 8;              // Was 50 — deep enough for app caches, not for crawling the entire FS
 const MAX_FILES_PER_DIR: usize = 500; // Cap per template to avoid millions-of-files hangs
+const MIN_FILES_PER_DIR: usize = 50;  // Floor for the adaptive per-template cap
 const MAX_TOTAL_FILES: usize = 5_000; // Global cap across all templates
 const SCAN_TIMEOUT_SECS: u64 = 25;   // Hard deadline: give up after 25s, return what we have
 
@@ -36,6 +44,11 @@ const JUNK_TEMPLATES: &[&str] = &[
     "Library/Application Support/Discord/Cache",
     "Library/Application Support/Discord/Code Cache",
 
+    // Cloud Sync Caches (safe to clear, the clients re-download on demand)
+    "Dropbox/.dropbox.cache",
+    "Library/Containers/com.microsoft.OneDrive-mac/Data/Library/Caches",
+    "Library/Caches/com.google.drivefs",
+
     // Development Junk
     ".npm/_cacache",
     ".yarn/cache",
@@ -53,6 +66,8 @@ const JUNK_TEMPLATES: &[&str] = &[
     "Downloads",
 
     // CMM-level categories
+    "Library/iTunes/iPhone Software Updates", // iOS restore images (.ipsw)
+    "Library/iTunes/iPad Software Updates",
     "Library/Caches/com.apple.SoftwareUpdate", // Old Updates
     "Library/Caches/com.apple.Safari/Localization", // Language Files (Safari localization cache)
 ];
@@ -88,7 +103,8 @@ const JUNK_TEMPLATES: &[&str] = &[
 
 fn category_name(tpl: &str) -> &'static str {
     // Shared Logic
-    if tpl.contains("Chrome") { "Chrome Cache" }
+    if tpl.contains("dropbox.cache") || tpl.contains("OneDrive") || tpl.contains("drivefs") { "Cloud Sync Cache" }
+    else if tpl.contains("Chrome") { "Chrome Cache" }
     else if tpl.contains("Brave") { "Brave Cache" }
     else if tpl.contains("Firefox") { "Firefox Cache" }
     else if tpl.contains("Slack") { "Slack Cache" }
@@ -107,6 +123,7 @@ fn category_name(tpl: &str) -> &'static str {
     else if tpl.contains("Saved Application State") { "App State" }
     else if tpl.contains("Desktop") { "Screenshots" }
     else if tpl.contains("Downloads") { "Old Installers" }
+    else if tpl.contains("Software Updates") { "iOS Software Updates" }
     else if tpl.contains("SoftwareUpdate") { "Old Updates" }
     else if tpl.contains("Localization") { "Language Files" }
     
@@ -118,134 +135,510 @@ fn category_name(tpl: &str) -> &'static str {
     else { "User Caches" }
 }
 
-fn is_whitelisted(file_name: &str) -> bool {
-    // Files we should NEVER delete automatically, even in cache folders
-    let whitelist = [
-        ".DS_Store",
-        "localized",
-        "Icon\r",
-        ".lock",
-        "settings.json",
-        "config.json",
-        "User Data", // Protect Chrome User Data root if scanned
-        "bookmarks", // Protect bookmarks
-        "Login Data", // Protect saved passwords
-        "desktop.ini", // Windows system file
-        "ntuser.dat", // Windows registry
-    ];
-    whitelist.contains(&file_name)
+/// What a junk scan leaves alone: the user's excluded and protected paths and the skip patterns.
+struct SkipRules {
+    excluded: Vec<String>,
+    protected: Vec<String>,
+    whitelist: Whitelist,
+}
+
+impl SkipRules {
+    fn excludes(&self, path: &str) -> bool {
+        path_is_excluded(path, &self.excluded)
+    }
+
+    /// Whether removing `path` as a whole would take a protected path with it.
+    fn holds_protected(&self, path: &str) -> bool {
+        path_is_protected(path, &self.protected)
+    }
+
+    /// Whitelisted files plus browser databases that must never be cleaned as junk.
+    fn protects(&self, path: &Path) -> bool {
+        let name = path.file_name().map(|n| nfc(&n.to_string_lossy()).to_lowercase()).unwrap_or_default();
+        self.whitelist.matches(&path.to_string_lossy()) || ["cookies", "history"].contains(&name.as_str())
+    }
+}
+
+/// Restrictions applied to a junk scan, usually taken from a named scan profile.
+#[derive(Debug, Clone, Default)]
+pub struct JunkScanOptions {
+    /// Template prefixes (relative to home) to include; empty means all templates
+    pub scopes: Vec<String>,
+    pub excluded_categories: Vec<String>,
+    /// Only these categories are scanned; empty means all
+    pub only_categories: Vec<String>,
+    pub max_total_files: Option<usize>,
+    pub timeout_secs: Option<u64>,
+    /// Report each app folder under cache roots as one directory item instead of its files
+    pub group_by_directory: bool,
+    /// Skip Trash items trashed more recently than this; None uses UserPrefs
+    pub trash_min_age_days: Option<u32>,
+}
+
+impl JunkScanOptions {
+    pub fn from_profile(profile: &ScanProfile) -> Self {
+        Self {
+            scopes: profile.scopes.clone(),
+            excluded_categories: profile.excluded_categories.clone(),
+            only_categories: Vec::new(),
+            max_total_files: profile.max_files,
+            timeout_secs: profile.timeout_secs,
+            group_by_directory: false,
+            trash_min_age_days: None,
+        }
+    }
+
+    fn in_scope(&self, tpl: &str) -> bool {
+        self.scopes.is_empty() || self.scopes.iter().any(|s| {
+            let s = s.trim_matches('/');
+            path_within(tpl, s) || path_within(s, tpl)
+        })
+    }
+
+    fn allows_category(&self, category: &str) -> bool {
+        !self.excluded_categories.iter().any(|c| c == category)
+            && (self.only_categories.is_empty() || self.only_categories.iter().any(|c| c == category))
+    }
 }
 
 pub fn scan_junk(home: &str) -> ScanResult {
-    let home = Path::new(home);
+    scan_junk_with_options(home, &JunkScanOptions::default())
+}
+
+/// How far a template walk got before returning.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TemplateStatus {
+    Missing,
+    Complete,
+    /// Stopped at the per-template cap
+    Capped,
+    /// Stopped (or never started) because the deadline or global budget ran out
+    Interrupted,
+}
+
+/// Per-template cap scaled by the share of the time budget still left: templates that start
+/// early may list up to twice the base cap, late ones shrink towards MIN_FILES_PER_DIR.
+fn adaptive_dir_cap(remaining: Duration, total: Duration) -> usize {
+    if total.is_zero() {
+        return MIN_FILES_PER_DIR;
+    }
+    let share = (remaining.as_secs_f64() / total.as_secs_f64()).clamp(0.0, 1.0);
+    ((MAX_FILES_PER_DIR as f64 * 2.0 * share) as usize).max(MIN_FILES_PER_DIR)
+}
+
+const STALE_INSTALLER_CACHE_DAYS: u64 = 30;
+
+/// Full "Install macOS *.app" bundles in /Applications (12+ GB each) and installer cache
+/// files untouched for a month. These live outside home, so they aren't junk templates.
+#[cfg(target_os = "macos")]
+fn scan_system_installers(excluded: &[String], deadline: Instant, budget: &AtomicUsize) -> Vec<ScannedItem> {
     let mut items = Vec::new();
-    let errors = Vec::new();
-    let mut total_size_bytes = 0u64;
-    let mut total_files_scanned = 0usize;
-    let deadline = Instant::now() + Duration::from_secs(SCAN_TIMEOUT_SECS);
+    let mut push = |path: &Path, size: ByteSize, category: &str, is_directory: bool| -> bool {
+        if budget.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1)).is_err() {
+            return false;
+        }
+        items.push(ScannedItem {
+            path: path.to_string_lossy().to_string(),
+            size_bytes: size.allocated,
+            logical_bytes: size.logical,
+            allocated_bytes: size.allocated,
+            category_name: category.to_string(),
+            is_directory,
+            accessed_date: None,
+            file_category: None,
+            reason: None,
+        });
+        true
+    };
 
-    'outer: for tpl in JUNK_TEMPLATES {
-        // Hard deadline: if we've been scanning longer than SCAN_TIMEOUT_SECS, stop
+    if let Ok(entries) = fs::read_dir("/Applications") {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            if !name.starts_with("Install macOS") || !name.ends_with(".app") || path_is_excluded(&path.to_string_lossy(), excluded) {
+                continue;
+            }
+            if Instant::now() >= deadline {
+                return items;
+            }
+            let size = dir_byte_size(&path);
+            if size.logical > 0 && !push(&path, size, "macOS Installers", true) {
+                return items;
+            }
+        }
+    }
+
+    let stale_before = std::time::SystemTime::now() - Duration::from_secs(STALE_INSTALLER_CACHE_DAYS * 24 * 3600);
+    let cache = Path::new("/Library/Caches/com.apple.installer");
+    for entry in walkdir::WalkDir::new(cache).max_depth(MAX_DEPTH as usize).into_iter().filter_map(|e| e.ok()) {
         if Instant::now() >= deadline {
-            eprintln!("⚠️ Junk scan timeout reached after {} seconds. Returning partial results.", SCAN_TIMEOUT_SECS);
             break;
         }
+        if !entry.file_type().is_file() || path_is_excluded(&entry.path().to_string_lossy(), excluded) {
+            continue;
+        }
+        let meta = match entry.metadata() {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        let stale = meta.modified().map(|t| t < stale_before).unwrap_or(false);
+        if stale && meta.len() > 0 && !push(entry.path(), ByteSize::of(&meta), "macOS Installers", false) {
+            break;
+        }
+    }
+    items
+}
 
-        let full = home.join(tpl);
-        if !full.exists() {
+/// Roots whose direct children are per-app folders that can be trashed as a whole.
+const GROUPABLE_ROOTS: &[&str] = &["Library/Caches", "Library/Logs"];
+
+/// One item per child folder of a cache root (aggregate size). Folders holding a whitelisted
+/// file can't be removed wholesale, so their files are listed individually instead.
+fn scan_grouped_root(
+    full: &Path,
+    tpl: &str,
+    skip: &SkipRules,
+    deadline: Instant,
+    budget: &AtomicUsize,
+    dir_cap: usize,
+) -> (Vec<ScannedItem>, TemplateStatus) {
+    let mut items = Vec::new();
+    let mut status = TemplateStatus::Complete;
+    let category = category_name(tpl);
+    let entries = match fs::read_dir(full) {
+        Ok(e) => e,
+        Err(_) => return (items, TemplateStatus::Missing),
+    };
+
+    for entry in entries.flatten() {
+        if Instant::now() >= deadline {
+            return (items, TemplateStatus::Interrupted);
+        }
+        let path = entry.path();
+        let path_str = path.to_string_lossy().to_string();
+        if skip.protects(&path) || skip.excludes(&path_str) {
             continue;
         }
 
-        // Special handling & depth control
-        let (depth, is_desktop) = if tpl == &"Desktop" {
-             (1, true)
-        } else if tpl == &"Desktop/screenshots" {
-             (2, false)
+        let mut files = Vec::new();
+        let mut dir_size = ByteSize::default();
+        let mut groupable = !skip.holds_protected(&path_str);
+        // No depth limit: a grouped folder is trashed whole, so its size has to cover all of it
+        let walker = walkdir::WalkDir::new(&path)
+            .into_iter()
+            .filter_entry(|e| !skip.excludes(&e.path().to_string_lossy()));
+        for e in walker.flatten() {
+            if Instant::now() >= deadline {
+                status = TemplateStatus::Interrupted;
+                break;
+            }
+            if !e.file_type().is_file() {
+                continue;
+            }
+            if skip.protects(e.path()) {
+                groupable = false;
+                continue;
+            }
+            let size = e.metadata().map(|m| ByteSize::of(&m)).unwrap_or_default();
+            dir_size += size;
+            if size.logical > 0 {
+                if files.len() < dir_cap {
+                    files.push((e.path().to_string_lossy().to_string(), size));
+                } else if !groupable {
+                    status = TemplateStatus::Capped;
+                }
+            }
+        }
+        if status == TemplateStatus::Interrupted {
+            // Only part of this folder was measured; don't report it with a short size
+            return (items, status);
+        }
+        if dir_size.logical == 0 {
+            continue;
+        }
+
+        let candidates: Vec<(String, ByteSize, bool)> = if groupable {
+            vec![(path_str, dir_size, path.is_dir())]
         } else {
-             (MAX_DEPTH as usize, false)
+            files.into_iter().map(|(p, size)| (p, size, false)).collect()
         };
+        for (path, size, is_directory) in candidates {
+            if budget.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1)).is_err() {
+                return (items, TemplateStatus::Interrupted);
+            }
+            items.push(ScannedItem {
+                path,
+                size_bytes: size.allocated,
+                logical_bytes: size.logical,
+                allocated_bytes: size.allocated,
+                category_name: category.to_string(),
+                is_directory,
+                accessed_date: None,
+                file_category: None,
+                reason: None,
+            });
+        }
+    }
+    (items, status)
+}
 
-        let walker = walkdir::WalkDir::new(&full)
-            .max_depth(depth)
-            .into_iter();
+/// Walk a single template. `budget` is the global file cap shared by all worker threads.
+fn scan_template(
+    home: &Path,
+    tpl: &str,
+    options: &JunkScanOptions,
+    skip: &SkipRules,
+    deadline: Instant,
+    budget: &AtomicUsize,
+    time_budget: Duration,
+) -> (Vec<ScannedItem>, TemplateStatus) {
+    let mut items = Vec::new();
+    let full = home.join(tpl);
+    if !full.exists() {
+        return (items, TemplateStatus::Missing);
+    }
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() || budget.load(Ordering::Relaxed) == 0 {
+        return (items, TemplateStatus::Interrupted);
+    }
+    let dir_cap = adaptive_dir_cap(remaining, time_budget);
+    if options.group_by_directory && GROUPABLE_ROOTS.contains(&tpl) {
+        return scan_grouped_root(&full, tpl, skip, deadline, budget, dir_cap);
+    }
 
-        let mut dir_file_count = 0usize;
+    // Special handling & depth control
+    let (depth, is_desktop) = if tpl == "Desktop" {
+         (1, true)
+    } else if tpl == "Desktop/screenshots" {
+         (2, false)
+    } else {
+         (MAX_DEPTH as usize, false)
+    };
 
-        for entry in walker {
-            // Deadline and global cap checks inside inner loop
-            if Instant::now() >= deadline || total_files_scanned >= MAX_TOTAL_FILES {
-                break 'outer;
+    // Age is judged on the top-level Trash entry: files inside a trashed folder keep their own times
+    let trash_cutoff = if tpl == ".Trash" {
+        options.trash_min_age_days.and_then(super::trash::age_cutoff)
+    } else {
+        None
+    };
+    // One Spotlight query for the whole Desktop instead of a lookup per file
+    let captures = is_desktop.then(|| super::screenshots::ScreenCaptures::in_folder(&full));
+    let walker = walkdir::WalkDir::new(&full)
+        .max_depth(depth)
+        .into_iter()
+        .filter_entry(|e| {
+            if let Some(cutoff) = trash_cutoff {
+                if e.depth() == 1 && super::trash::trashed_at(e.path()).map(|t| t >= cutoff).unwrap_or(true) {
+                    return false;
+                }
             }
-            // Per-directory cap
-            if dir_file_count >= MAX_FILES_PER_DIR {
-                break;
+            !skip.excludes(&e.path().to_string_lossy())
+        });
+
+    let mut dir_file_count = 0usize;
+    let mut status = TemplateStatus::Complete;
+
+    for entry in walker {
+        // Deadline and global cap checks inside inner loop
+        if Instant::now() >= deadline || budget.load(Ordering::Relaxed) == 0 {
+            status = TemplateStatus::Interrupted;
+            break;
+        }
+        // Per-directory cap
+        if dir_file_count >= dir_cap {
+            status = TemplateStatus::Capped;
+            break;
+        }
+
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("Error scanning {}: {}", full.display(), e);
+                continue;
             }
+        };
+        let path = entry.path();
+
+        if !path.is_file() {
+            continue;
+        }
 
-            let entry = match entry {
-                Ok(e) => e,
-                Err(e) => {
-                    eprintln!("Error scanning {}: {}", full.display(), e);
+        if path.file_name().and_then(|n| n.to_str()).is_some() {
+            if skip.protects(path) {
+                continue;
+            }
+            if captures.as_ref().is_some_and(|c| !c.contains(path)) {
+                continue;
+            }
+            if tpl.contains("Downloads") {
+                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+                // Archives may hold anything; only disk images and packages are offered
+                if !["dmg", "pkg", "mpkg", "iso"].contains(&ext.as_str()) {
                     continue;
                 }
-            };
-            let path = entry.path();
+            }
+        }
 
-            if !path.is_file() {
+        let meta = match fs::metadata(path) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+
+        let size = ByteSize::of(&meta);
+        if size.logical > 0 {
+            let cat = if tpl.contains("Downloads") {
+                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+                if ext == "dmg" || ext == "iso" {
+                    "Unused Disk Images"
+                } else {
+                    category_name(tpl)
+                }
+            } else {
+                category_name(tpl)
+            };
+            if !options.allows_category(cat) {
                 continue;
             }
+            // Claim one slot of the shared budget; another worker may have taken the last one
+            if budget.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1)).is_err() {
+                status = TemplateStatus::Interrupted;
+                break;
+            }
+            items.push(ScannedItem {
+                path: path.to_string_lossy().to_string(),
+                size_bytes: size.allocated,
+                logical_bytes: size.logical,
+                allocated_bytes: size.allocated,
+                category_name: cat.to_string(),
+                is_directory: false,
+                accessed_date: None,
+                file_category: None,
+                reason: None,
+            });
+            dir_file_count += 1;
+        }
+    }
+    (items, status)
+}
 
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if is_whitelisted(name) {
-                    continue;
+pub fn scan_junk_with_options(home: &str, options: &JunkScanOptions) -> ScanResult {
+    let home = Path::new(home);
+    let errors = Vec::new();
+    let max_total_files = options.max_total_files.unwrap_or(MAX_TOTAL_FILES);
+    let timeout_secs = options.timeout_secs.unwrap_or(SCAN_TIMEOUT_SECS);
+    let time_budget = Duration::from_secs(timeout_secs);
+    let deadline = Instant::now() + time_budget;
+    let prefs = ContextStore::load().user_preferences;
+    let skip = SkipRules {
+        whitelist: Whitelist::new(&prefs.always_skip_patterns),
+        excluded: prefs.skipped_paths(),
+        protected: prefs.protected_paths.clone(),
+    };
+    let budget = AtomicUsize::new(max_total_files);
+    let scan_mode = prefs.scan_mode;
+    let options = &JunkScanOptions {
+        trash_min_age_days: Some(options.trash_min_age_days.unwrap_or(prefs.trash_min_age_days)),
+        ..options.clone()
+    };
+
+    // Templates are walked on rayon workers; results keep template order
+    let per_template: Vec<(&str, Vec<ScannedItem>, TemplateStatus)> = JUNK_TEMPLATES
+        .par_iter()
+        .filter(|tpl| options.in_scope(tpl) && options.allows_category(category_name(tpl)))
+        .map(|tpl| {
+            let _priority = ScanPriorityGuard::for_mode(scan_mode);
+            let (items, status) = scan_template(home, tpl, options, &skip, deadline, &budget, time_budget);
+            (*tpl, items, status)
+        })
+        .collect();
+    if Instant::now() >= deadline {
+        eprintln!("⚠️ Junk scan timeout reached after {} seconds. Returning partial results.", timeout_secs);
+    }
+
+    let mut truncated = false;
+    let mut scanned_templates = Vec::new();
+    let mut skipped_templates = Vec::new();
+    let mut items: Vec<ScannedItem> = Vec::new();
+    for (tpl, tpl_items, status) in per_template {
+        match status {
+            TemplateStatus::Missing => {}
+            TemplateStatus::Complete => scanned_templates.push(tpl.to_string()),
+            TemplateStatus::Capped => {
+                truncated = true;
+                scanned_templates.push(tpl.to_string());
+            }
+            TemplateStatus::Interrupted => {
+                truncated = true;
+                skipped_templates.push(tpl.to_string());
+            }
+        }
+        items.extend(tpl_items);
+    }
+
+    // Electron apps without a template of their own (Notion, Figma, Obsidian, Teams, ...)
+    #[cfg(target_os = "macos")]
+    {
+        let targets: Vec<(String, String)> = super::electron::detect_electron_apps(home)
+            .into_iter()
+            .flat_map(|app| {
+                let category = format!("{} Cache", app.name);
+                app.cache_dirs.into_iter().map(move |dir| (dir, category.clone()))
+            })
+            .filter(|(dir, category)| {
+                // "Electron App Cache" switches all of them off; picking one app's category still finds it
+                options.in_scope(dir)
+                    && options.allows_category(category)
+                    && !options.excluded_categories.iter().any(|c| c == "Electron App Cache")
+            })
+            .collect();
+        let electron: Vec<(String, Vec<ScannedItem>, TemplateStatus)> = targets
+            .into_par_iter()
+            .map(|(dir, category)| {
+                let _priority = ScanPriorityGuard::for_mode(scan_mode);
+                let (mut found, status) = scan_template(home, &dir, options, &skip, deadline, &budget, time_budget);
+                for item in &mut found {
+                    item.category_name = category.clone();
                 }
-                if name.eq_ignore_ascii_case("Cookies") || name.eq_ignore_ascii_case("History") {
-                    continue;
+                (dir, found, status)
+            })
+            .collect();
+        for (dir, found, status) in electron {
+            match status {
+                TemplateStatus::Missing => {}
+                TemplateStatus::Complete => scanned_templates.push(dir),
+                TemplateStatus::Capped => {
+                    truncated = true;
+                    scanned_templates.push(dir);
                 }
-                if is_desktop && !name.starts_with("Screenshot") {
-                    continue;
-                }
-                if tpl.contains("Downloads") {
-                    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
-                    if !["dmg", "pkg", "iso", "zip", "tar", "gz", "7z", "rar"].contains(&ext.as_str()) {
-                        continue;
-                    }
+                TemplateStatus::Interrupted => {
+                    truncated = true;
+                    skipped_templates.push(dir);
                 }
             }
+            items.extend(found);
+        }
+    }
 
-            let meta = match fs::metadata(path) {
-                Ok(m) => m,
-                Err(_) => continue,
-            };
+    let mut total_size_bytes: u64 = items.iter().map(|i| i.size_bytes).sum();
+    let mut total_files_scanned = items.len();
 
-            let size = meta.len();
-            if size > 0 {
-                let cat = if tpl.contains("Downloads") {
-                    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
-                    if ext == "dmg" || ext == "iso" {
-                        "Unused Disk Images"
-                    } else {
-                        category_name(tpl)
-                    }
-                } else {
-                    category_name(tpl)
-                };
-                items.push(ScannedItem {
-                    path: path.to_string_lossy().to_string(),
-                    size_bytes: size,
-                    category_name: cat.to_string(),
-                    is_directory: false,
-                    accessed_date: None,
-                });
-                total_size_bytes += size;
-                dir_file_count += 1;
-                total_files_scanned += 1;
-            }
+    #[cfg(target_os = "macos")]
+    {
+        if options.scopes.is_empty() && options.allows_category("macOS Installers") && Instant::now() < deadline {
+            let installers = scan_system_installers(&skip.excluded, deadline, &budget);
+            total_size_bytes += installers.iter().map(|i| i.size_bytes).sum::<u64>();
+            total_files_scanned += installers.len();
+            items.extend(installers);
         }
     }
 
     #[cfg(target_os = "macos")]
     {
-        if total_files_scanned < MAX_TOTAL_FILES && Instant::now() < deadline {
+        if total_files_scanned < max_total_files
+            && Instant::now() < deadline
+            && options.in_scope("Library/Preferences")
+            && options.allows_category("Broken Preferences")
+        {
             let prefs_dir = home.join("Library/Preferences");
             if prefs_dir.exists() {
                 if let Ok(entries) = fs::read_dir(&prefs_dir) {
@@ -253,19 +646,23 @@ pub fn scan_junk(home: &str) -> ScanResult {
                         let p = entry.path();
                         if p.is_file()
                             && p.extension().map(|e| e == "plist").unwrap_or(false)
-                            && total_files_scanned < MAX_TOTAL_FILES
+                            && total_files_scanned < max_total_files
                         {
-                            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                            let size = entry.metadata().map(|m| ByteSize::of(&m)).unwrap_or_default();
                             let path_str = p.to_string_lossy().to_string();
-                            if is_broken_plist(&p) {
+                            if !skip.excludes(&path_str) && is_broken_plist(&p) {
                                 items.push(ScannedItem {
                                     path: path_str,
-                                    size_bytes: size,
+                                    size_bytes: size.allocated,
+                                    logical_bytes: size.logical,
+                                    allocated_bytes: size.allocated,
                                     category_name: "Broken Preferences".to_string(),
                                     is_directory: false,
                                     accessed_date: None,
+                                    file_category: None,
+                                    reason: None,
                                 });
-                                total_size_bytes += size;
+                                total_size_bytes += size.allocated;
                                 total_files_scanned += 1;
                             }
                         }
@@ -275,15 +672,44 @@ pub fn scan_junk(home: &str) -> ScanResult {
         }
     }
 
+    // Installers whose app is already installed are the easiest call for the user
+    if Instant::now() < deadline && items.iter().any(|i| i.category_name == "Old Installers" || i.category_name == "Unused Disk Images") {
+        let installed = super::installers::InstalledApps::load();
+        for item in items.iter_mut().filter(|i| i.category_name == "Old Installers" || i.category_name == "Unused Disk Images") {
+            if Instant::now() >= deadline {
+                break;
+            }
+            if let Some(m) = super::installers::match_installed(Path::new(&item.path), &installed) {
+                item.reason = Some(super::installers::installed_reason(&m));
+            }
+        }
+    }
+
+    // Only surface what confirm_delete will actually accept, with the safety gate's verdict attached
+    let before = items.len();
+    items.retain_mut(|item| {
+        let indexed = index_file_with(&item.path, &skip.whitelist);
+        item.file_category = Some(indexed.category);
+        // A scanner's own reason ("Already installed") says more than the category's
+        item.reason = item.reason.take().or(Some(indexed.reason));
+        indexed.is_safe_to_delete
+    });
+    if items.len() != before {
+        total_size_bytes = items.iter().map(|i| i.size_bytes).sum();
+    }
+
     ScanResult {
         items,
         total_size_bytes,
         errors,
+        truncated,
+        scanned_templates,
+        skipped_templates,
     }
 }
 
 #[cfg(target_os = "macos")]
-fn is_broken_plist(path: &Path) -> bool {
+pub(crate) fn is_broken_plist(path: &Path) -> bool {
     use std::io::Read;
     let mut f = match fs::File::open(path) {
         Ok(f) => f,
@@ -309,6 +735,46 @@ mod tests {
     fn test_category_name_mapping() {
         assert_eq!(category_name("Library/Caches/com.google.Chrome"), "Chrome Cache");
         assert_eq!(category_name(".Trash"), "Trash Bin");
+        assert_eq!(category_name("Dropbox/.dropbox.cache"), "Cloud Sync Cache");
+    }
+
+    #[test]
+    fn test_profile_scopes_and_exclusions() {
+        let opts = JunkScanOptions {
+            scopes: vec!["Library/Developer/".to_string()],
+            excluded_categories: vec!["Trash Bin".to_string()],
+            ..Default::default()
+        };
+        assert!(opts.in_scope("Library/Developer/Xcode/DerivedData"));
+        assert!(!opts.in_scope("Library/Caches"));
+        assert!(!opts.allows_category("Trash Bin"));
+        assert!(JunkScanOptions::default().in_scope(".Trash"));
+
+        let only = JunkScanOptions { only_categories: vec!["Xcode Data".to_string()], ..Default::default() };
+        assert!(only.allows_category("Xcode Data"));
+        assert!(!only.allows_category("User Caches"));
+    }
+
+    #[test]
+    fn test_folder_holding_a_protected_path_is_not_grouped() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let caches = temp_dir.path().join("Caches");
+        for dir in ["com.example.app/keep", "com.example.other"] {
+            fs::create_dir_all(caches.join(dir)).unwrap();
+        }
+        fs::write(caches.join("com.example.app/blob"), b"cache").unwrap();
+        fs::write(caches.join("com.example.app/keep/notes"), b"mine").unwrap();
+        fs::write(caches.join("com.example.other/blob"), b"cache").unwrap();
+
+        let keep = caches.join("com.example.app/keep").to_string_lossy().to_string();
+        let skip = SkipRules { excluded: vec![keep.clone()], protected: vec![keep], whitelist: Whitelist::new(&[]) };
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let (items, _) = scan_grouped_root(&caches, "Library/Caches", &skip, deadline, &AtomicUsize::new(100), 100);
+        let paths: Vec<&str> = items.iter().map(|i| i.path.as_str()).collect();
+        assert!(paths.contains(&caches.join("com.example.other").to_str().unwrap()));
+        assert!(paths.contains(&caches.join("com.example.app/blob").to_str().unwrap()));
+        assert!(!paths.contains(&caches.join("com.example.app").to_str().unwrap()));
+        assert!(!paths.iter().any(|p| p.ends_with("notes")));
     }
 
     #[test]
@@ -340,4 +806,66 @@ mod tests {
         assert!(!paths.iter().any(|p| p.contains(".DS_Store")), "Should NOT list .DS_Store");
         assert!(!paths.iter().any(|p| p.contains("Cookies")), "Should NOT list Cookies");
     }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_screenshots_and_disk_images_pass_the_gate() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path();
+        fs::create_dir_all(home.join("Desktop")).unwrap();
+        fs::create_dir_all(home.join("Downloads")).unwrap();
+        let shot = home.join("Desktop/Screenshot 2024-05-01 at 10.00.00.png");
+        fs::write(&shot, b"png").unwrap();
+        std::process::Command::new("xattr").args(["-w", "com.apple.metadata:kMDItemIsScreenCapture", "1"]).arg(&shot).status().unwrap();
+        fs::write(home.join("Desktop/Holiday.png"), b"png").unwrap();
+        let mut udif = vec![0u8; 4096];
+        udif[4096 - 512..4096 - 508].copy_from_slice(b"koly");
+        fs::write(home.join("Downloads/App.dmg"), &udif).unwrap();
+        fs::write(home.join("Downloads/Photos.zip"), b"PK").unwrap();
+
+        let opts = JunkScanOptions { scopes: vec!["Desktop".to_string(), "Downloads".to_string()], ..Default::default() };
+        let result = scan_junk_with_options(home.to_str().unwrap(), &opts);
+        let category = |name: &str| result.items.iter().find(|i| i.path.ends_with(name)).map(|i| i.category_name.as_str());
+        assert_eq!(category("Screenshot 2024-05-01 at 10.00.00.png"), Some("Screenshots"));
+        assert_eq!(category("App.dmg"), Some("Unused Disk Images"));
+        assert_eq!(category("Holiday.png"), None);
+        assert_eq!(category("Photos.zip"), None);
+    }
+
+    #[test]
+    fn test_adaptive_dir_cap_shrinks_with_time() {
+        let total = Duration::from_secs(20);
+        assert_eq!(adaptive_dir_cap(total, total), MAX_FILES_PER_DIR * 2);
+        assert_eq!(adaptive_dir_cap(Duration::from_secs(10), total), MAX_FILES_PER_DIR);
+        assert_eq!(adaptive_dir_cap(Duration::ZERO, total), MIN_FILES_PER_DIR);
+    }
+
+    #[test]
+    fn test_grouped_cache_folders() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let home = temp_dir.path();
+        let app_cache = home.join("Library/Caches/com.example.app");
+        let guarded = home.join("Library/Caches/com.example.guarded");
+        fs::create_dir_all(app_cache.join("nested")).unwrap();
+        fs::create_dir_all(&guarded).unwrap();
+        fs::write(app_cache.join("a.bin"), b"aaaa").unwrap();
+        fs::write(app_cache.join("nested/b.bin"), b"bb").unwrap();
+        let deep = app_cache.join("1/2/3/4/5/6/7/8/9/10");
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(deep.join("d.bin"), b"ddd").unwrap();
+        fs::write(guarded.join("settings.json"), b"{}").unwrap();
+        fs::write(guarded.join("c.bin"), b"c").unwrap();
+
+        let opts = JunkScanOptions { group_by_directory: true, ..Default::default() };
+        let result = scan_junk_with_options(home.to_str().unwrap(), &opts);
+
+        let app = result.items.iter().find(|i| i.path.ends_with("com.example.app")).expect("grouped folder");
+        assert!(app.is_directory);
+        // Everything that trashing the folder removes, however deep
+        assert_eq!(app.logical_bytes, 9);
+        assert_eq!(app.size_bytes, app.allocated_bytes);
+        // A folder holding a whitelisted file is never offered as a whole
+        assert!(!result.items.iter().any(|i| i.path.ends_with("com.example.guarded")));
+        assert!(result.items.iter().any(|i| i.path.ends_with("c.bin") && !i.is_directory));
+    }
 }
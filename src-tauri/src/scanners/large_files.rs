@@ -1,18 +1,131 @@
-use super::{ScanResult, ScannedItem};
+use super::{checkpoint, ByteSize, ScanResult, ScannedItem};
+use crate::mcp::context_store::{path_is_excluded, ContextStore};
+use crate::mcp::path_norm::path_key;
 use walkdir::{WalkDir, DirEntry};
 use sysinfo::Disks;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
 const MIN_SIZE_BYTES: u64 = 50 * 1024 * 1024; // 50 MB
 const MAX_FILES_TO_SCAN: usize = 50_000;      // Cap to avoid hanging on massive disks
 const SCAN_TIMEOUT_SECS: u64 = 30;           // Hard deadline
+const CHECKPOINT_EVERY_FILES: usize = 5_000;  // Persist progress this often
+const PROGRESS_EVERY_FILES: usize = 1_000;    // Emit a progress update this often
+const PROGRESS_TOP_ITEMS: usize = 50;         // Largest files sent with each progress update
+
+pub const CHECKPOINT_NAME: &str = "large_files";
+
+/// What to look for, e.g. "videos over 1 GB in my home folder not opened in a year".
+/// Every field is optional; the default is files over 50 MB on all mounted disks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LargeFilesQuery {
+    pub min_size_bytes: Option<u64>,
+    /// Folders to search (`~` allowed); empty means every mounted disk
+    pub roots: Vec<String>,
+    /// Result categories to keep ("Movies", "Archives", "Music", "Pictures", "Documents", "Virtual Machines", "Other")
+    pub categories: Vec<String>,
+    /// File extensions to keep, without the dot
+    pub extensions: Vec<String>,
+    /// Only files not opened for at least this many days
+    pub not_accessed_days: Option<u32>,
+    /// Include iCloud files that aren't downloaded (tagged, they take no local space); skipped by default
+    pub include_cloud_only: bool,
+    /// Also search network shares when `roots` is empty; skipped by default
+    pub include_network_volumes: bool,
+}
+
+impl LargeFilesQuery {
+    fn min_size(&self) -> u64 {
+        self.min_size_bytes.unwrap_or(MIN_SIZE_BYTES)
+    }
+
+    fn matches_type(&self, ext: &str, category: &str) -> bool {
+        (self.categories.is_empty() || self.categories.iter().any(|c| c.eq_ignore_ascii_case(category)))
+            && (self.extensions.is_empty()
+                || self.extensions.iter().any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext)))
+    }
+
+    /// Unix seconds a file must not have been accessed since.
+    fn accessed_cutoff(&self) -> Option<i64> {
+        self.not_accessed_days.map(|days| {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            now - days as i64 * 24 * 3600
+        })
+    }
+
+    /// Search roots: the given folders, or every mounted disk (network shares only on request).
+    fn search_roots(&self, disks: &mut Disks) -> Vec<std::path::PathBuf> {
+        if self.roots.is_empty() {
+            disks.refresh_list();
+            return disks
+                .list()
+                .iter()
+                .filter(|d| {
+                    self.include_network_volumes
+                        || !super::system_stats::is_network_file_system(&d.file_system().to_string_lossy())
+                })
+                .map(|d| d.mount_point().to_owned())
+                .collect();
+        }
+        let home = dirs::home_dir().unwrap_or_default();
+        self.roots
+            .iter()
+            .map(|r| match r.strip_prefix('~') {
+                Some(rest) => home.join(rest.trim_start_matches('/')),
+                None => std::path::PathBuf::from(r),
+            })
+            .collect()
+    }
+}
+
+fn category_for(ext: &str) -> &'static str {
+    match ext.to_lowercase().as_str() {
+        "mp4" | "mov" | "mkv" | "avi" | "wmv" | "flv" | "webm" | "m4v" => "Movies",
+        "zip" | "dmg" | "iso" | "tar" | "gz" | "pkg" | "rar" | "7z" => "Archives",
+        "mp3" | "wav" | "flac" | "aac" | "alac" | "m4a" => "Music",
+        "jpg" | "png" | "heic" | "raw" | "tiff" | "jpeg" | "webp" => "Pictures",
+        "pdf" | "doc" | "docx" | "ppt" | "pptx" | "xls" | "xlsx" | "txt" | "md" => "Documents",
+        _ => "Other",
+    }
+}
+
+/// Progress persisted during the scan so it can resume after a crash or quit.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LargeFilesCheckpoint {
+    pub completed_mounts: Vec<String>,
+    pub items: Vec<ScannedItem>,
+    /// The query the interrupted scan was running
+    #[serde(default)]
+    pub query: LargeFilesQuery,
+}
 
 // Lazy static for system info to reuse
 lazy_static::lazy_static! {
     static ref DISKS_REFRESH: Mutex<Disks> = Mutex::new(Disks::new_with_refreshed_list());
 }
 
+/// Set by `cancel_scan`; the running scan stops at the next file and returns what it has.
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Stop the running large file scan early. Its partial result is returned as truncated.
+pub fn cancel_scan() {
+    CANCELLED.store(true, Ordering::Relaxed);
+}
+
+/// The `limit` largest items so far, largest first.
+fn top_items(items: &[ScannedItem], limit: usize) -> Vec<ScannedItem> {
+    let mut top: Vec<&ScannedItem> = items.iter().collect();
+    top.sort_by_key(|i| std::cmp::Reverse(i.size_bytes));
+    top.into_iter().take(limit).cloned().collect()
+}
+
 fn is_ignored(entry: &DirEntry) -> bool {
     let file_name = entry.file_name().to_string_lossy();
     if file_name.starts_with('.') {
@@ -51,33 +164,134 @@ fn is_ignored(entry: &DirEntry) -> bool {
     false
 }
 
-pub fn scan_large_files(_home: &str) -> ScanResult {
-    let mut items = Vec::new();
-    let errors = Vec::new();
+fn progress_update(
+    mount: &str,
+    files_checked: usize,
+    items: &[ScannedItem],
+    expected_files: Option<usize>,
+    start: Instant,
+    deadline: Instant,
+) -> LargeFilesProgress {
+    let (percent, eta_secs) = match expected_files {
+        Some(expected) => {
+            let done = (files_checked as f64 / expected as f64).min(0.99);
+            let elapsed = start.elapsed().as_secs_f64();
+            let eta = if done > 0.0 { elapsed / done - elapsed } else { 0.0 };
+            // Never promise longer than the hard deadline allows
+            let eta = eta.min(deadline.saturating_duration_since(Instant::now()).as_secs_f64());
+            (Some((done * 100.0) as u8), Some(eta.round() as u64))
+        }
+        None => (None, None),
+    };
+    LargeFilesProgress {
+        mount_point: mount.to_string(),
+        files_checked,
+        items_found: items.len(),
+        percent,
+        eta_secs,
+        top_items: top_items(items, PROGRESS_TOP_ITEMS),
+    }
+}
+
+pub fn scan_large_files(home: &str) -> ScanResult {
+    scan_large_files_resumable(home, None)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeFilesProgress {
+    pub mount_point: String,
+    pub files_checked: usize,
+    pub items_found: usize,
+    /// None until a previous run has measured this Mac's mounts
+    pub percent: Option<u8>,
+    pub eta_secs: Option<u64>,
+    /// Largest files found so far, so results show up long before the scan ends
+    pub top_items: Vec<ScannedItem>,
+}
+
+/// Scan for large files, skipping mount points already finished in `resume` (and reusing its query).
+pub fn scan_large_files_resumable(home: &str, resume: Option<LargeFilesCheckpoint>) -> ScanResult {
+    let query = resume.as_ref().map(|r| r.query.clone()).unwrap_or_default();
+    scan_large_files_with_progress(home, &query, resume, &|_| {})
+}
+
+pub fn scan_large_files_with_progress(
+    _home: &str,
+    query: &LargeFilesQuery,
+    resume: Option<LargeFilesCheckpoint>,
+    on_progress: &dyn Fn(LargeFilesProgress),
+) -> ScanResult {
+    let _priority = super::priority::lower_scan_priority();
+    let start = Instant::now();
+    CANCELLED.store(false, Ordering::Relaxed);
+    let ctx = ContextStore::load();
+    let mut samples = Vec::new();
+    let mut state = resume.unwrap_or_default();
+    state.query = query.clone();
+    let min_size = query.min_size();
+    let accessed_cutoff = query.accessed_cutoff();
+    let mut items = std::mem::take(&mut state.items);
+    let mut seen: HashSet<String> = items.iter().map(|i| path_key(&i.path)).collect();
+    let mut errors = Vec::new();
     let mut total_files_checked = 0usize;
+    let mut truncated = false;
     let deadline = Instant::now() + Duration::from_secs(SCAN_TIMEOUT_SECS);
+    let excluded = ctx.user_preferences.skipped_paths();
     
-    // Refresh disks
-    let mut disks_lock = DISKS_REFRESH.lock().unwrap();
-    disks_lock.refresh_list();
+    let disks = query.search_roots(&mut DISKS_REFRESH.lock().unwrap());
 
-    let disks: Vec<_> = disks_lock.list().iter().map(|d| d.mount_point().to_owned()).collect();
+    // Files expected across all pending mounts, from previous runs (capped like the scan itself)
+    let history = |mount: &str| ctx.scan_throughput.get(&format!("{}/{}", CHECKPOINT_NAME, mount)).cloned();
+    let pending: Vec<String> = disks.iter()
+        .map(|d| d.to_string_lossy().to_string())
+        .filter(|m| !state.completed_mounts.contains(m))
+        .collect();
+    let expected_files = if pending.iter().all(|m| history(m).is_some()) {
+        let total: u64 = pending.iter().filter_map(|m| history(m)).map(|t| t.files).sum();
+        Some((total as usize).clamp(1, MAX_FILES_TO_SCAN))
+    } else {
+        None
+    };
 
     'outer: for mount_point in disks {
+        let mount_str = mount_point.to_string_lossy().to_string();
+        if state.completed_mounts.contains(&mount_str) {
+            continue;
+        }
+        if !mount_point.is_dir() {
+            errors.push(format!("Not a folder: {}", mount_str));
+            continue;
+        }
+
+        let mount_start = Instant::now();
+        let mount_files_start = total_files_checked;
+
         // Prepare walker
         let walker = WalkDir::new(&mount_point)
             .follow_links(false)
             .same_file_system(true)
             .into_iter()
-            .filter_entry(|e| !is_ignored(e));
+            .filter_entry(|e| !is_ignored(e) && !path_is_excluded(&e.path().to_string_lossy(), &excluded));
 
         for entry in walker {
             // Global safety checks
+            if CANCELLED.load(Ordering::Relaxed) {
+                truncated = true;
+                break 'outer;
+            }
             if Instant::now() >= deadline || total_files_checked >= MAX_FILES_TO_SCAN {
                 eprintln!("⚠️ Large files scan hit limit (time or file count). Returning partial results.");
+                truncated = true;
                 break 'outer;
             }
             total_files_checked += 1;
+            if total_files_checked % CHECKPOINT_EVERY_FILES == 0 {
+                state.items = items.clone();
+                checkpoint::save(CHECKPOINT_NAME, &state);
+            }
+            if total_files_checked % PROGRESS_EVERY_FILES == 0 {
+                on_progress(progress_update(&mount_str, total_files_checked, &items, expected_files, start, deadline));
+            }
 
             let entry = match entry {
                 Ok(e) => e,
@@ -88,39 +302,75 @@ pub fn scan_large_files(_home: &str) -> ScanResult {
                 continue;
             }
 
-            let len = match entry.metadata() {
-                Ok(m) => m.len(),
-                Err(_) => 0,
+            let (size, dataless) = match entry.metadata() {
+                Ok(m) => (ByteSize::of(&m), super::is_dataless(&m)),
+                Err(_) => (ByteSize::default(), false),
             };
+            if dataless && !query.include_cloud_only {
+                continue;
+            }
 
-            if len >= MIN_SIZE_BYTES {
+            if size.logical >= min_size {
                 let path = entry.path();
-                let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("Other");
-                let category = match ext.to_lowercase().as_str() {
-                    "mp4" | "mov" | "mkv" | "avi" | "wmv" | "flv" | "webm" | "m4v" => "Movies",
-                    "zip" | "dmg" | "iso" | "tar" | "gz" | "pkg" | "rar" | "7z" => "Archives",
-                    "mp3" | "wav" | "flac" | "aac" | "alac" | "m4a" => "Music",
-                    "jpg" | "png" | "heic" | "raw" | "tiff" | "jpeg" | "webp" => "Pictures",
-                    "pdf" | "doc" | "docx" | "ppt" | "pptx" | "xls" | "xlsx" | "txt" | "md" => "Documents",
-                    _ => "Other",
-                };
+                let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+                let vm = super::vm_images::vm_kind(path);
+                let category = if vm.is_some() { "Virtual Machines" } else { category_for(ext) };
+                if !query.matches_type(ext, category) {
+                    continue;
+                }
 
                 let accessed_date = entry.metadata().ok()
                     .and_then(|m| m.accessed().ok())
                     .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
                     .map(|d| d.as_secs() as i64);
+                if let Some(cutoff) = accessed_cutoff {
+                    if accessed_date.map(|t| t > cutoff).unwrap_or(true) {
+                        continue;
+                    }
+                }
+                if !seen.insert(path_key(&path.to_string_lossy())) {
+                    continue;
+                }
 
                 items.push(ScannedItem {
                     path: path.to_string_lossy().to_string(),
-                    size_bytes: len,
-                    category_name: category.to_string(),
+                    size_bytes: size.allocated,
+                    logical_bytes: size.logical,
+                    allocated_bytes: size.allocated,
+                    category_name: if dataless { "iCloud Only".to_string() } else { category.to_string() },
                     is_directory: false,
                     accessed_date,
+                    file_category: None,
+                    reason: if dataless {
+                        Some("Stored in iCloud only: takes no space on this Mac, deleting it frees nothing".to_string())
+                    } else {
+                        vm.map(|k| format!("{} virtual machine disk; see Virtual Machines to compact or delete it", k.label()))
+                    },
                 });
             }
         }
+
+        samples.push((
+            format!("{}/{}", CHECKPOINT_NAME, mount_str),
+            (total_files_checked - mount_files_start) as u64,
+            0,
+            mount_start.elapsed().as_secs_f64(),
+        ));
+        state.completed_mounts.push(mount_str);
+        state.items = items.clone();
+        checkpoint::save(CHECKPOINT_NAME, &state);
     }
 
+    if truncated {
+        // Cut short by the deadline, the file cap or a cancel: keep what's done so it can resume
+        state.items = items.clone();
+        checkpoint::save(CHECKPOINT_NAME, &state);
+    } else {
+        checkpoint::clear(CHECKPOINT_NAME);
+    }
+    // Only fully walked mounts are recorded, so a truncated run doesn't understate file counts
+    ContextStore::load().record_throughput(&samples);
+
     // Sort by size descending
     items.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes));
 
@@ -130,5 +380,43 @@ pub fn scan_large_files(_home: &str) -> ScanResult {
         items,
         total_size_bytes: total_size,
         errors,
+        truncated,
+        scanned_templates: Vec::new(),
+        skipped_templates: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_filters_by_category_and_extension() {
+        let videos = LargeFilesQuery { categories: vec!["movies".to_string()], ..Default::default() };
+        assert!(videos.matches_type("MOV", category_for("MOV")));
+        assert!(!videos.matches_type("zip", category_for("zip")));
+
+        let isos = LargeFilesQuery { extensions: vec![".iso".to_string()], ..Default::default() };
+        assert!(isos.matches_type("iso", "Archives"));
+        assert!(!isos.matches_type("dmg", "Archives"));
+        assert_eq!(LargeFilesQuery::default().min_size(), MIN_SIZE_BYTES);
+    }
+
+    #[test]
+    fn progress_carries_largest_items_first() {
+        let item = |path: &str, size: u64| ScannedItem {
+            path: path.to_string(),
+            size_bytes: size,
+            logical_bytes: size,
+            allocated_bytes: size,
+            category_name: "Other".to_string(),
+            is_directory: false,
+            accessed_date: None,
+            file_category: None,
+            reason: None,
+        };
+        let items = vec![item("/a", 1), item("/b", 3), item("/c", 2)];
+        let top: Vec<String> = top_items(&items, 2).into_iter().map(|i| i.path).collect();
+        assert_eq!(top, vec!["/b", "/c"]);
     }
 }
@@ -0,0 +1,196 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Launch agents/daemons at or above this score are reported as threats.
+pub const THREAT_SCORE: u8 = 50;
+
+/// Folders where a legitimate launch item never keeps its executable.
+const TEMP_DIRS: &[&str] = &["/tmp", "/private/tmp", "/var", "/private/var"];
+
+/// The parts of a launch agent/daemon plist the heuristics look at.
+#[derive(Debug, Clone, Default)]
+pub struct LaunchItem {
+    pub plist_path: PathBuf,
+    pub label: Option<String>,
+    /// `Program`, or the first of `ProgramArguments`
+    pub program: Option<PathBuf>,
+    pub arguments: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchItemRisk {
+    pub plist_path: String,
+    pub label: Option<String>,
+    pub program: Option<String>,
+    /// "apple" | "developer" | "unsigned" | "invalid" | "unknown"
+    pub signing: String,
+    /// 0 (nothing unusual) to 100
+    pub risk_score: u8,
+    pub reasons: Vec<String>,
+    /// Detection ratio from an earlier VirusTotal lookup of `program`
+    pub virustotal: Option<super::virustotal::VirusTotalVerdict>,
+}
+
+impl LaunchItem {
+    #[cfg(target_os = "macos")]
+    pub fn read(plist_path: &Path) -> Option<Self> {
+        let info: serde_json::Value = plist::from_reader(std::fs::File::open(plist_path).ok()?).ok()?;
+        let arguments: Vec<String> = info
+            .get("ProgramArguments")
+            .and_then(|v| v.as_array())
+            .map(|args| args.iter().filter_map(|a| a.as_str()).map(|a| a.to_string()).collect())
+            .unwrap_or_default();
+        let program = info.get("Program").and_then(|v| v.as_str()).or(arguments.first().map(|a| a.as_str())).map(PathBuf::from);
+        Some(Self {
+            plist_path: plist_path.to_path_buf(),
+            label: info.get("Label").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            program,
+            arguments,
+        })
+    }
+}
+
+/// Who signed `path`, from `codesign -dvv`: "apple" for Apple's own code, "developer" for any
+/// other valid signature, "unsigned", or "invalid" when the signature doesn't check out.
+#[cfg(target_os = "macos")]
+pub fn code_signing(path: &Path) -> String {
+    let Ok(out) = std::process::Command::new("codesign").arg("-dvv").arg(path).output() else {
+        return "unknown".to_string();
+    };
+    let details = String::from_utf8_lossy(&out.stderr);
+    let signing = if details.contains("not signed at all") {
+        "unsigned"
+    } else if !out.status.success() {
+        "invalid"
+    } else if details.lines().any(|l| l == "Authority=Software Signing") {
+        "apple"
+    } else {
+        "developer"
+    };
+    signing.to_string()
+}
+
+/// A long run of base64 text, or a pipeline that decodes some ("echo ... | base64 -D | sh").
+fn looks_base64(arg: &str) -> bool {
+    let lower = arg.to_lowercase();
+    if ["base64 -d", "base64 --decode", "b64decode", "frombase64string"].iter().any(|m| lower.contains(m)) {
+        return true;
+    }
+    arg.split_whitespace().any(|token| {
+        token.len() >= 40
+            && !token.starts_with('/')
+            && token.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '='))
+            && token.chars().any(|c| c.is_ascii_uppercase())
+            && token.chars().any(|c| c.is_ascii_lowercase())
+            && token.chars().any(|c| c.is_ascii_digit())
+    })
+}
+
+fn in_temp_dir(path: &Path) -> bool {
+    TEMP_DIRS.iter().any(|d| path.starts_with(d))
+}
+
+/// Inside a dot folder in the user's Library ("~/Library/.hidden/agent").
+fn in_hidden_library_dir(path: &Path, home: &Path) -> bool {
+    path.strip_prefix(home.join("Library"))
+        .map(|rest| rest.parent().is_some_and(|p| p.components().any(|c| c.as_os_str().to_string_lossy().starts_with('.'))))
+        .unwrap_or(false)
+}
+
+/// Score a launch item from what it runs and how `program` is signed.
+pub fn assess(item: &LaunchItem, signing: &str, home: &Path) -> LaunchItemRisk {
+    let mut score: u32 = 0;
+    let mut reasons = Vec::new();
+    let mut flag = |points: u32, reason: &str| {
+        score += points;
+        reasons.push(reason.to_string());
+    };
+
+    // Scripts are started through an interpreter, so their path is one of the arguments
+    let paths: Vec<&Path> = item.program.iter().map(|p| p.as_path()).chain(item.arguments.iter().skip(1).filter(|a| a.starts_with('/')).map(Path::new)).collect();
+    if paths.iter().any(|p| in_temp_dir(p)) {
+        flag(40, "Runs from a temporary folder (/tmp or /var)");
+    }
+    if paths.iter().any(|p| in_hidden_library_dir(p, home)) {
+        flag(40, "Runs from a hidden folder in ~/Library");
+    }
+    match signing {
+        "unsigned" => flag(30, "The executable is not code-signed"),
+        "invalid" => flag(30, "The executable's code signature is invalid"),
+        _ => {}
+    }
+    if item.arguments.iter().any(|a| looks_base64(a)) {
+        flag(35, "Its arguments contain base64-encoded data");
+    }
+    if item.label.as_deref().is_some_and(|l| l.starts_with("com.apple.")) && signing != "apple" {
+        flag(50, "Its label imitates Apple (com.apple.*) but the executable isn't Apple's");
+    }
+
+    LaunchItemRisk {
+        plist_path: item.plist_path.to_string_lossy().to_string(),
+        label: item.label.clone(),
+        program: item.program.as_ref().map(|p| p.to_string_lossy().to_string()),
+        signing: signing.to_string(),
+        risk_score: score.min(100) as u8,
+        reasons,
+        virustotal: None,
+    }
+}
+
+/// Read and score every plist in `dirs`, riskiest first.
+#[cfg(target_os = "macos")]
+pub fn scan(dirs: &[PathBuf], home: &Path) -> Vec<LaunchItemRisk> {
+    let mut items: Vec<LaunchItemRisk> = dirs
+        .iter()
+        .flat_map(|dir| std::fs::read_dir(dir).into_iter().flatten().flatten())
+        .filter(|e| e.path().extension().is_some_and(|x| x == "plist"))
+        .filter_map(|e| LaunchItem::read(&e.path()))
+        .map(|item| {
+            let signing = match &item.program {
+                Some(p) if p.exists() => code_signing(p),
+                _ => "unknown".to_string(),
+            };
+            assess(&item, &signing, home)
+        })
+        .collect();
+    items.sort_by_key(|i| std::cmp::Reverse(i.risk_score));
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(label: &str, args: &[&str]) -> LaunchItem {
+        LaunchItem {
+            plist_path: PathBuf::from(format!("/Library/LaunchAgents/{}.plist", label)),
+            label: Some(label.to_string()),
+            program: args.first().map(PathBuf::from),
+            arguments: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn scores_suspicious_launch_items() {
+        let home = Path::new("/Users/jane");
+        let clean = assess(&item("com.google.keystone.agent", &["/Library/Google/Keystone/Agent"]), "developer", home);
+        assert_eq!(clean.risk_score, 0);
+
+        let fake_apple = assess(&item("com.apple.updates", &["/Users/jane/Library/.sys/updater"]), "unsigned", home);
+        assert_eq!(fake_apple.risk_score, 100);
+        assert_eq!(fake_apple.reasons.len(), 3);
+
+        let dropper = assess(&item("com.helper.x", &["/bin/sh", "-c", "echo ZWNobyBoZWxsbyB3b3JsZDsgY3VybCBodHRwOi8vZXhhbXBsZS5jb20= | base64 -D | sh"]), "apple", home);
+        assert_eq!(dropper.risk_score, 35);
+
+        let tmp = assess(&item("com.helper.y", &["/bin/bash", "/private/tmp/run.sh"]), "apple", home);
+        assert!(tmp.risk_score >= 40);
+    }
+
+    #[test]
+    fn base64_detection_ignores_paths_and_words() {
+        assert!(!looks_base64("/Applications/Some App.app/Contents/MacOS/SomeAppLauncherHelperTool"));
+        assert!(!looks_base64("--enable-automatic-background-updates-for-everyone"));
+        assert!(looks_base64("aGVsbG8gd29ybGQgdGhpcyBpcyBhIGxvbmcgYmFzZTY0IHN0cmluZw=="));
+    }
+}
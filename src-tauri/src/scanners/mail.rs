@@ -10,48 +10,200 @@ pub struct MailAttachment {
     pub size_bytes: u64,
 }
 
-pub fn scan_mail_attachments() -> Vec<MailAttachment> {
-    let mut attachments = Vec::new();
+/// Where Mail saves a copy of each attachment the user opens. Attachments inside ~/Library/Mail are
+/// left out: for POP and On My Mac mailboxes they are the only copy.
+fn attachment_roots() -> Vec<PathBuf> {
     let home = home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    vec![home.join("Library/Containers/com.apple.mail/Data/Library/Mail Downloads")]
+}
 
-    // Common locations for Apple Mail downloads/attachments
-    let paths_to_search = vec![
-        home.join("Library/Containers/com.apple.mail/Data/Library/Mail Downloads"),
-        home.join("Library/Mail"), 
-    ];
+/// Whether `path` is a file `scan_mail_attachments` would report, so clean_mail_command can't be
+/// pointed at anything else.
+pub fn is_attachment_path(path: &str) -> bool {
+    attachment_roots().iter().any(|root| Path::new(path).starts_with(root) && Path::new(path) != root)
+}
 
-    for root in paths_to_search {
+pub fn scan_mail_attachments() -> Vec<MailAttachment> {
+    let mut attachments = Vec::new();
+
+    for root in attachment_roots() {
         if !root.exists() { continue; }
 
         for entry in WalkDir::new(&root).into_iter().filter_map(|e| e.ok()) {
             let path = entry.path();
-            
-            // Heuristic: If we are in 'Mail Downloads', everything is fair game.
-            // If in 'Library/Mail', strictly look for folders named "Attachments"
-            let is_download = path.to_string_lossy().contains("Mail Downloads");
-            let is_attachment_folder = path.to_string_lossy().contains("/Attachments/");
-            
-            if path.is_file() && (is_download || is_attachment_folder) {
+            if path.is_file() && is_attachment_path(&path.to_string_lossy()) {
                 if let Ok(metadata) = path.metadata() {
                     attachments.push(MailAttachment {
                         path: path.to_string_lossy().to_string(),
                         name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
-                        size_bytes: metadata.len(),
+                        size_bytes: super::allocated_bytes(&metadata),
                     });
                 }
             }
         }
     }
 
+    let ctx = crate::mcp::context_store::ContextStore::load();
+    attachments.retain(|a| !ctx.is_protected(&a.path));
     attachments
 }
 
-pub fn clean_mail_attachments(paths: Vec<String>) -> Result<(), String> {
-    for path_str in paths {
-        let path = Path::new(&path_str);
-        if path.exists() {
-             std::fs::remove_file(path).map_err(|e| e.to_string())?;
-        }
+/// Envelope Index past this size is worth a rebuild regardless of mailbox size.
+const ENVELOPE_INDEX_LARGE_BYTES: u64 = 1024 * 1024 * 1024;
+/// ... or when it is this large relative to the mail it indexes.
+const ENVELOPE_INDEX_MAX_RATIO: f64 = 0.25;
+/// Share of free (unused) SQLite pages above which the index counts as fragmented.
+const ENVELOPE_INDEX_MAX_FREE: f64 = 0.30;
+
+#[derive(Serialize, Debug)]
+pub struct MailboxUsage {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MailAccountUsage {
+    /// Account folder name (a UUID for most account types)
+    pub id: String,
+    pub size_bytes: u64,
+    pub mailboxes: Vec<MailboxUsage>,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct MailStoreReport {
+    /// e.g. ~/Library/Mail/V10; None when Mail was never set up or access is denied
+    pub mail_dir: Option<String>,
+    /// Envelope Index plus its -wal/-shm files
+    pub envelope_index_bytes: u64,
+    /// Fraction of the index's SQLite pages on the freelist
+    pub envelope_index_free_ratio: f64,
+    pub accounts: Vec<MailAccountUsage>,
+    pub total_mail_bytes: u64,
+    /// Suggest the "Rebuild Mail Index" maintenance task
+    pub suggest_rebuild: bool,
+    pub reason: Option<String>,
+}
+
+fn dir_size(path: &Path) -> u64 {
+    super::dir_byte_size(path).allocated
+}
+
+/// Newest ~/Library/Mail/V* directory.
+fn mail_version_dir(home: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(home.join("Library/Mail"))
+        .ok()?
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let version: u32 = name.strip_prefix('V')?.parse().ok()?;
+            Some((version, e.path()))
+        })
+        .max_by_key(|(v, _)| *v)
+        .map(|(_, p)| p)
+}
+
+/// Free-page ratio from the SQLite header: page count at offset 28, freelist count at 36.
+fn sqlite_free_ratio(header: &[u8]) -> f64 {
+    if header.len() < 40 || !header.starts_with(b"SQLite format 3\0") {
+        return 0.0;
+    }
+    let be = |o: usize| u32::from_be_bytes([header[o], header[o + 1], header[o + 2], header[o + 3]]) as f64;
+    let pages = be(28);
+    if pages == 0.0 { 0.0 } else { be(36) / pages }
+}
+
+fn rebuild_reason(index_bytes: u64, mail_bytes: u64, free_ratio: f64) -> Option<String> {
+    if index_bytes >= ENVELOPE_INDEX_LARGE_BYTES {
+        Some("The mail index is over 1 GB.".to_string())
+    } else if mail_bytes > 0 && index_bytes as f64 > mail_bytes as f64 * ENVELOPE_INDEX_MAX_RATIO {
+        Some("The mail index is unusually large compared to your mailboxes.".to_string())
+    } else if free_ratio > ENVELOPE_INDEX_MAX_FREE {
+        Some(format!("{:.0}% of the mail index is unused space.", free_ratio * 100.0))
+    } else {
+        None
+    }
+}
+
+/// Read-only analysis of Apple Mail's store: Envelope Index size/fragmentation and per-account mailbox sizes.
+pub fn analyze_mail_store() -> MailStoreReport {
+    let home = home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let Some(mail_dir) = mail_version_dir(&home) else { return MailStoreReport::default() };
+
+    let mail_data = mail_dir.join("MailData");
+    let envelope_index_bytes = ["Envelope Index", "Envelope Index-wal", "Envelope Index-shm"]
+        .iter()
+        .filter_map(|f| std::fs::metadata(mail_data.join(f)).ok())
+        .map(|m| m.len())
+        .sum();
+    let mut header = [0u8; 100];
+    let envelope_index_free_ratio = std::fs::File::open(mail_data.join("Envelope Index"))
+        .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut header))
+        .map(|_| sqlite_free_ratio(&header))
+        .unwrap_or(0.0);
+
+    let mut accounts: Vec<MailAccountUsage> = std::fs::read_dir(&mail_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false) && e.file_name() != "MailData")
+                .map(|e| {
+                    let mut mailboxes: Vec<MailboxUsage> = WalkDir::new(e.path())
+                        .into_iter()
+                        .filter_map(|m| m.ok())
+                        .filter(|m| m.file_type().is_dir() && m.path().extension().map(|x| x == "mbox").unwrap_or(false))
+                        // Nested mailboxes are counted inside their parent
+                        .filter(|m| !m.path().parent().map(|p| p.to_string_lossy().contains(".mbox")).unwrap_or(false))
+                        .map(|m| MailboxUsage {
+                            name: m.path().file_stem().unwrap_or_default().to_string_lossy().to_string(),
+                            path: m.path().to_string_lossy().to_string(),
+                            size_bytes: dir_size(m.path()),
+                        })
+                        .collect();
+                    mailboxes.sort_by_key(|m| std::cmp::Reverse(m.size_bytes));
+                    MailAccountUsage {
+                        id: e.file_name().to_string_lossy().to_string(),
+                        size_bytes: mailboxes.iter().map(|m| m.size_bytes).sum(),
+                        mailboxes,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    accounts.sort_by_key(|a| std::cmp::Reverse(a.size_bytes));
+
+    let total_mail_bytes = accounts.iter().map(|a| a.size_bytes).sum();
+    let reason = rebuild_reason(envelope_index_bytes, total_mail_bytes, envelope_index_free_ratio);
+    MailStoreReport {
+        mail_dir: Some(mail_dir.to_string_lossy().to_string()),
+        envelope_index_bytes,
+        envelope_index_free_ratio,
+        accounts,
+        total_mail_bytes,
+        suggest_rebuild: reason.is_some(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqlite_header_free_ratio() {
+        let mut header = vec![0u8; 100];
+        header[..16].copy_from_slice(b"SQLite format 3\0");
+        header[28..32].copy_from_slice(&100u32.to_be_bytes());
+        header[36..40].copy_from_slice(&40u32.to_be_bytes());
+        assert!((sqlite_free_ratio(&header) - 0.4).abs() < 1e-9);
+        assert_eq!(sqlite_free_ratio(b"not a database"), 0.0);
+    }
+
+    #[test]
+    fn rebuild_suggested_for_oversized_or_fragmented_index() {
+        assert!(rebuild_reason(2 * ENVELOPE_INDEX_LARGE_BYTES, 0, 0.0).is_some());
+        assert!(rebuild_reason(400_000_000, 1_000_000_000, 0.0).is_some());
+        assert!(rebuild_reason(50_000_000, 1_000_000_000, 0.5).is_some());
+        assert!(rebuild_reason(50_000_000, 1_000_000_000, 0.05).is_none());
     }
-    Ok(())
 }
@@ -8,6 +8,8 @@ pub struct MaintenanceTask {
     pub description: String,
     pub command: String,
     pub requires_sudo: bool,
+    /// Part of the task runs through the privileged helper (before `command`)
+    pub uses_helper: bool,
 }
 
 pub fn get_tasks() -> Vec<MaintenanceTask> {
@@ -18,6 +20,7 @@ pub fn get_tasks() -> Vec<MaintenanceTask> {
             description: "Resets the DNS cache to fix network issues.".to_string(),
             command: "dscacheutil -flushcache; sudo killall -HUP mDNSResponder".to_string(),
             requires_sudo: true,
+            uses_helper: false,
         },
         MaintenanceTask {
             id: "free_ram".to_string(),
@@ -25,6 +28,7 @@ pub fn get_tasks() -> Vec<MaintenanceTask> {
             description: "Purges inactive memory to speed up the system.".to_string(),
             command: "sudo purge".to_string(),
             requires_sudo: true,
+            uses_helper: false,
         },
         MaintenanceTask {
             id: "reindex_spotlight".to_string(),
@@ -32,6 +36,7 @@ pub fn get_tasks() -> Vec<MaintenanceTask> {
             description: "Rebuilds the search index to fix Spotlight issues.".to_string(),
             command: "sudo mdutil -E /".to_string(),
             requires_sudo: true,
+            uses_helper: false,
         },
         MaintenanceTask {
             id: "repair_disk_perms".to_string(),
@@ -39,6 +44,7 @@ pub fn get_tasks() -> Vec<MaintenanceTask> {
             description: "Verifies and repairs file permissions on the main volume.".to_string(),
             command: "diskutil resetUserPermissions / `id -u`".to_string(),
             requires_sudo: false, // User mode
+            uses_helper: false,
         },
         MaintenanceTask {
             id: "clear_font_cache".to_string(),
@@ -46,6 +52,15 @@ pub fn get_tasks() -> Vec<MaintenanceTask> {
             description: "Removes font cache files to fix rendering glitches.".to_string(),
             command: "atsutil databases -remove".to_string(),
             requires_sudo: true,
+            uses_helper: false,
+        },
+        MaintenanceTask {
+            id: "clear_user_font_cache".to_string(),
+            name: "Clear My Font Cache".to_string(),
+            description: "Removes only your user's font caches; no admin password needed.".to_string(),
+            command: "atsutil databases -removeUser".to_string(),
+            requires_sudo: false,
+            uses_helper: false,
         },
         MaintenanceTask {
             id: "rebuild_launch_services".to_string(),
@@ -53,6 +68,39 @@ pub fn get_tasks() -> Vec<MaintenanceTask> {
             description: "Rebuilds the Launch Services database so apps open correctly.".to_string(),
             command: "/System/Library/Frameworks/CoreServices.framework/Frameworks/LaunchServices.framework/Support/lsregister -kill -r -domain local -domain system -domain user".to_string(),
             requires_sudo: false,
+            uses_helper: false,
+        },
+        MaintenanceTask {
+            id: "rebuild_mail_index".to_string(),
+            name: "Rebuild Mail Index".to_string(),
+            description: "Quits Mail and sets its Envelope Index aside; Mail rebuilds a compact index on next launch.".to_string(),
+            command: "osascript -e 'tell application \"Mail\" to quit'; sleep 3; for f in ~/Library/Mail/V*/MailData/Envelope\\ Index*; do case \"$f\" in *.old) continue;; esac; [ -e \"$f\" ] && mv \"$f\" \"$f.old\"; done; true".to_string(),
+            requires_sudo: false,
+            uses_helper: false,
+        },
+        MaintenanceTask {
+            id: "clear_quicklook_cache".to_string(),
+            name: "Clear QuickLook Thumbnails".to_string(),
+            description: "Clears cached QuickLook thumbnails and reloads preview generators.".to_string(),
+            command: "qlmanage -r cache; qlmanage -r".to_string(),
+            requires_sudo: false,
+            uses_helper: false,
+        },
+        MaintenanceTask {
+            id: "reset_icon_services".to_string(),
+            name: "Rebuild Icon Cache".to_string(),
+            description: "Resets Icon Services so Finder and the Dock stop showing wrong or generic icons.".to_string(),
+            command: "killall Dock && killall Finder".to_string(),
+            requires_sudo: false,
+            uses_helper: true,
+        },
+        MaintenanceTask {
+            id: "fix_finder_previews".to_string(),
+            name: "Fix Finder Previews".to_string(),
+            description: "Clears QuickLook thumbnails and rebuilds the icon cache when Finder shows wrong icons or previews.".to_string(),
+            command: "qlmanage -r cache && qlmanage -r && killall Dock && killall Finder".to_string(),
+            requires_sudo: false,
+            uses_helper: true,
         },
     ]
 }
@@ -60,21 +108,8 @@ pub fn get_tasks() -> Vec<MaintenanceTask> {
 #[cfg(target_os = "macos")]
 fn run_task_impl(task: &MaintenanceTask) -> Result<String, String> {
     if task.requires_sudo {
-        // Use AppleScript to show GUI password prompt for sudo
-        let script = format!(
-            "do shell script \"{}\" with administrator privileges",
-            task.command.replace('"', "\\\"").replace('\\', "\\\\")
-        );
-        let output = Command::new("osascript")
-            .arg("-e")
-            .arg(&script)
-            .output()
-            .map_err(|e| e.to_string())?;
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
-        } else {
-            Err(String::from_utf8_lossy(&output.stderr).to_string())
-        }
+        // macOS's own password dialog; cancelling it is an error, not a missing permission
+        super::automation::run_admin_script(&task.command, None)
     } else {
         let output = Command::new("sh")
             .arg("-c")
@@ -103,8 +138,19 @@ fn run_task_impl(task: &MaintenanceTask) -> Result<String, String> {
     }
 }
 
-pub fn run_task(id: &str) -> Result<String, String> {
-    let tasks = get_tasks();
-    let task = tasks.iter().find(|t| t.id == id).ok_or("Task not found")?;
-    run_task_impl(task)
+pub async fn run_task(id: &str) -> Result<String, String> {
+    let task = get_tasks().into_iter().find(|t| t.id == id).ok_or("Task not found")?;
+    if task.uses_helper {
+        if !crate::helper_client::ensure_helper_installed().await {
+            return Err("Failed to install execution helper".to_string());
+        }
+        let res = crate::helper_client::send_command(crate::helper_client::Command::ResetIconServices).await
+            .map_err(|e| format!("Helper communication failed: {}", e))?;
+        if !res.success {
+            return Err(res.message);
+        }
+    }
+    tokio::task::spawn_blocking(move || run_task_impl(&task))
+        .await
+        .map_err(|e| e.to_string())?
 }
@@ -1,22 +1,57 @@
+use rayon::prelude::*;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::fs;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Serialize)]
+#[cfg(target_os = "macos")]
+use super::launch_items::LaunchItem;
+use super::browser_hijack::BrowserFinding;
+use super::config_profiles::ConfigProfile;
+use super::gatekeeper::DownloadAssessment;
+use super::launch_items::LaunchItemRisk;
+use super::malware_signatures::SignatureSet;
+use super::xprotect::XProtectStatus;
+
+/// Bigger files are not hashed; droppers and agents are small.
+const MAX_HASH_BYTES: u64 = 512 * 1024 * 1024;
+/// Downloads newer than this are hashed.
+const RECENT_DOWNLOAD_DAYS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
 pub struct MalwareResult {
     pub threats_found: Vec<String>,
     pub status: String,
+    /// Version of the signature definitions the scan used
+    pub definitions_version: u64,
+    /// Executables whose SHA-256 is a known-bad hash
+    pub hash_matches: Vec<HashMatch>,
+    /// Whether the definitions carry any SHA-256 hashes. The built-in set ships none, so the hash
+    /// check stays off until a definitions update brings some
+    pub hash_scan_active: bool,
+    /// Every launch agent/daemon with its risk score, riskiest first
+    pub launch_items: Vec<LaunchItemRisk>,
+    /// Hijacked search engines, forced homepages and suspicious extensions, each with its fix
+    pub browser_findings: Vec<BrowserFinding>,
+    /// Installed configuration profiles; `suspicious` ones set DNS, proxies or browser policies outside MDM
+    pub config_profiles: Vec<ConfigProfile>,
+    /// Quarantine and Gatekeeper status of apps, installers and executables in ~/Downloads
+    pub downloads: Vec<DownloadAssessment>,
+    /// XProtect/MRT versions and whether Apple's background scans and updates are on
+    pub xprotect: XProtectStatus,
+    /// Findings left out of `threats_found` because XProtect already remediated them
+    pub remediated_by_apple: Vec<String>,
 }
 
-const SUSPICIOUS_FILES_MACOS: &[&str] = &[
-    "com.genieo.engine.plist",
-    "com.searchbar.plist",
-    "com.mackeeper.MacKeeper.HLP.plist",
-    "com.zeobit.MacKeeper.Helper.plist",
-    "com.vsearch.agent.plist",
-    "com.adware.analyser.plist",
-    "com.pcv.hlpramc.plist",
-    "com.spigot.ApplicationManager.plist",
-];
+#[derive(Debug, Clone, Serialize)]
+pub struct HashMatch {
+    pub path: String,
+    pub sha256: String,
+    /// Name of the signature that lists the hash
+    pub signature: String,
+    /// "launch_item" | "user_applications" | "downloads"
+    pub location: String,
+}
 
 #[cfg(target_os = "windows")]
 const SUSPICIOUS_FILES_WINDOWS: &[&str] = &[
@@ -29,17 +64,151 @@ const SUSPICIOUS_FILES_WINDOWS: &[&str] = &[
     "miner.exe",
 ];
 
+/// Bundle ids of the apps in /Applications and ~/Applications.
+#[cfg(target_os = "macos")]
+fn installed_bundle_ids(home: &std::path::Path) -> Vec<(String, std::path::PathBuf)> {
+    let mut apps = Vec::new();
+    for dir in [std::path::PathBuf::from("/Applications"), home.join("Applications")] {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if !path.extension().is_some_and(|e| e == "app") {
+                continue;
+            }
+            let info: Option<serde_json::Value> = fs::File::open(path.join("Contents/Info.plist")).ok().and_then(|f| plist::from_reader(f).ok());
+            if let Some(id) = info.as_ref().and_then(|i| i.get("CFBundleIdentifier")).and_then(|v| v.as_str()) {
+                apps.push((id.to_string(), path));
+            }
+        }
+    }
+    apps
+}
+
+/// Lowercase hex SHA-256 of a file's contents.
+pub fn sha256_file(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+/// Mach-O (thin or universal) or a script/binary with an execute bit.
+fn is_executable(path: &Path, meta: &fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if meta.permissions().mode() & 0o111 != 0 {
+            return true;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = meta;
+    let mut magic = [0u8; 4];
+    let read = fs::File::open(path).and_then(|mut f| std::io::Read::read_exact(&mut f, &mut magic));
+    read.is_ok() && matches!(magic, [0xcf, 0xfa, 0xed, 0xfe] | [0xce, 0xfa, 0xed, 0xfe] | [0xca, 0xfe, 0xba, 0xbe])
+}
+
+/// Executables in the places adware persists: what launch agents/daemons run, apps in
+/// ~/Applications, and executables, installers and apps downloaded in the last month.
+#[cfg(target_os = "macos")]
+fn persistence_executables(home: &Path, launch_dirs: &[PathBuf]) -> Vec<(PathBuf, &'static str)> {
+    let mut found = Vec::new();
+    for dir in launch_dirs {
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            if let Some(program) = LaunchItem::read(&entry.path()).and_then(|i| i.program) {
+                found.push((program, "launch_item"));
+            }
+        }
+    }
+    let app_binaries = |app: &Path| -> Vec<PathBuf> {
+        fs::read_dir(app.join("Contents/MacOS")).into_iter().flatten().flatten().map(|e| e.path()).collect()
+    };
+    for entry in fs::read_dir(home.join("Applications")).into_iter().flatten().flatten() {
+        found.extend(app_binaries(&entry.path()).into_iter().map(|p| (p, "user_applications")));
+    }
+    let cutoff = std::time::SystemTime::now() - std::time::Duration::from_secs(RECENT_DOWNLOAD_DAYS * 86400);
+    let downloads = walkdir::WalkDir::new(home.join("Downloads")).max_depth(2).into_iter().filter_entry(|e| {
+        // Apps are looked into below rather than walked
+        e.depth() == 0 || e.path().parent().and_then(|p| p.extension()).map(|x| x != "app").unwrap_or(true)
+    });
+    for entry in downloads.filter_map(|e| e.ok()) {
+        let recent = entry.metadata().ok().and_then(|m| m.modified().ok()).is_some_and(|t| t >= cutoff);
+        let path = entry.path();
+        if !recent || entry.depth() == 0 {
+            continue;
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("app") => found.extend(app_binaries(path).into_iter().map(|p| (p, "downloads"))),
+            Some("pkg" | "dmg") => found.push((path.to_path_buf(), "downloads")),
+            _ if entry.file_type().is_file() => found.push((path.to_path_buf(), "downloads")),
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Hash each candidate and report the ones listed in `signatures`. Candidates that aren't regular
+/// executables (or installer images) or are too large are skipped.
+fn hash_matches(signatures: &SignatureSet, candidates: Vec<(PathBuf, &'static str)>) -> Vec<HashMatch> {
+    let mut seen = std::collections::HashSet::new();
+    let candidates: Vec<_> = candidates.into_iter().filter(|(p, _)| seen.insert(p.clone())).collect();
+    candidates
+        .into_par_iter()
+        .filter_map(|(path, location)| {
+            let meta = fs::metadata(&path).ok()?;
+            let installer = path.extension().is_some_and(|e| e == "pkg" || e == "dmg");
+            if !meta.is_file() || meta.len() > MAX_HASH_BYTES || !(installer || is_executable(&path, &meta)) {
+                return None;
+            }
+            let sha256 = sha256_file(&path)?;
+            let sig = signatures.match_sha256(&sha256)?;
+            Some(HashMatch { path: path.to_string_lossy().to_string(), sha256, signature: sig.name.clone(), location: location.to_string() })
+        })
+        .collect()
+}
+
+/// Store a scan in the ContextStore; returns the threats the previous scan didn't report.
+pub fn record(result: &MalwareResult, scheduled: bool) -> Vec<String> {
+    crate::mcp::context_store::ContextStore::load().record_malware_scan(result.threats_found.clone(), result.definitions_version, scheduled)
+}
+
+/// Scan run by the scheduler: the result is stored and emitted as "malware-scan-finished", and the
+/// user is notified about threats a previous scan didn't report. Threats stay pending until a
+/// notification was actually shown, so ones found in quiet mode are announced on a later run.
+pub fn run_scheduled_scan(app: &tauri::AppHandle) {
+    use tauri::Emitter;
+    use tauri_plugin_notification::NotificationExt;
+
+    let result = scan_malware();
+    let new = record(&result, true);
+    println!("[Malware] Scheduled scan: {} threat(s), {} new", result.threats_found.len(), new.len());
+    let mut ctx = crate::mcp::context_store::ContextStore::load();
+    let pending = &ctx.pending_malware_alerts;
+    if !pending.is_empty() && !super::power::should_suppress_alert("malware") {
+        let body = if pending.len() == 1 { pending[0].clone() } else { format!("{} and {} more", pending[0], pending.len() - 1) };
+        if app.notification().builder().title("New threat found").body(&body).show().is_ok() {
+            ctx.clear_malware_alerts();
+        }
+    }
+    let _ = app.emit("malware-scan-finished", &result);
+}
+
 pub fn scan_malware() -> MalwareResult {
     let mut threats = Vec::new();
-    
+    let mut matches = Vec::new();
+    let mut launch_items = Vec::new();
+    let mut browser_findings = Vec::new();
+    let mut config_profiles = Vec::new();
+    let mut downloads = Vec::new();
+    let signatures = super::malware_signatures::load();
+
     // --- macOS Scan ---
     #[cfg(target_os = "macos")]
     {
         let home = dirs::home_dir().unwrap_or(std::path::PathBuf::from("/"));
         let scan_paths = [
             home.join("Library/LaunchAgents"),
-            std::path::PathBuf::from("/Library/LaunchAgents"),
-            std::path::PathBuf::from("/Library/LaunchDaemons"),
+            PathBuf::from("/Library/LaunchAgents"),
+            PathBuf::from("/Library/LaunchDaemons"),
         ];
     
         for dir in &scan_paths {
@@ -47,8 +216,8 @@ pub fn scan_malware() -> MalwareResult {
                 for entry in entries.flatten() {
                     let path = entry.path();
                     if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        if SUSPICIOUS_FILES_MACOS.contains(&name) {
-                            threats.push(format!("Known Adware/PUP found: {} in {:?}", name, dir));
+                        if let Some(sig) = signatures.match_file_name(name) {
+                            threats.push(format!("Known Adware/PUP found: {} ({}) in {:?}", name, sig.name, dir));
                         }
                         if name.starts_with('.') {
                              threats.push(format!("Suspicious hidden file in LaunchAgent: {:?}", path));
@@ -57,6 +226,47 @@ pub fn scan_malware() -> MalwareResult {
                 }
             }
         }
+
+        launch_items.extend(super::launch_items::scan(&scan_paths, &home));
+        for item in launch_items.iter().filter(|i| i.risk_score >= super::launch_items::THREAT_SCORE) {
+            threats.push(format!("Suspicious launch item (risk {}): {} — {}", item.risk_score, item.plist_path, item.reasons.join("; ")));
+        }
+
+        for (sig, path) in signatures.existing_paths(&home) {
+            threats.push(format!("Known Adware/PUP found: {} at {:?}", sig.name, path));
+        }
+        for (bundle_id, path) in installed_bundle_ids(&home) {
+            if let Some(sig) = signatures.match_bundle_id(&bundle_id) {
+                threats.push(format!("Known Adware/PUP app: {} ({}) at {:?}", sig.name, bundle_id, path));
+            }
+        }
+
+        browser_findings.extend(super::browser_hijack::scan(&home, &signatures));
+        for finding in &browser_findings {
+            threats.push(format!("Browser hijack in {}: {}", finding.browser, finding.detail));
+        }
+
+        config_profiles.extend(super::config_profiles::list_profiles());
+        for profile in config_profiles.iter().filter(|p| p.suspicious) {
+            threats.push(format!(
+                "Configuration profile \"{}\" ({}) changes {} and wasn't installed by MDM. Remove it in System Settings > Privacy & Security > Profiles.",
+                profile.name,
+                profile.identifier,
+                profile.risky_settings.join(", ")
+            ));
+        }
+
+        downloads.extend(super::gatekeeper::assess_downloads(&home));
+        for item in downloads.iter().filter(|d| d.risk == "high") {
+            threats.push(format!("Risky download: {} — {}", item.path, item.reasons.join("; ")));
+        }
+
+        if signatures.has_hashes() {
+            matches = hash_matches(&signatures, persistence_executables(&home, &scan_paths));
+            for m in &matches {
+                threats.push(format!("Known malware file: {} ({}, SHA-256 match) at {:?}", m.signature, m.location, m.path));
+            }
+        }
     }
 
     // --- Windows Scan ---
@@ -90,14 +300,73 @@ pub fn scan_malware() -> MalwareResult {
         }
     }
 
-    let status = if threats.is_empty() {
+    if crate::mcp::context_store::ContextStore::load().user_preferences.virustotal.enabled {
+        let verdicts = super::virustotal::cached_verdicts();
+        for item in &mut downloads {
+            item.virustotal = verdicts.get(&item.path).cloned();
+        }
+        for item in &mut launch_items {
+            item.virustotal = item.program.as_ref().and_then(|p| verdicts.get(p)).cloned();
+        }
+    }
+
+    let xprotect = super::xprotect::status();
+    let (threats, remediated_by_apple) = super::xprotect::skip_remediated(threats, &xprotect.remediated_paths);
+
+    let mut status = if threats.is_empty() {
         "Your System is safe. No threats found.".to_string()
     } else {
         format!("Found {} potential threats.", threats.len())
     };
+    if !remediated_by_apple.is_empty() {
+        status.push_str(&format!(" {} more already removed by XProtect.", remediated_by_apple.len()));
+    }
+    let hash_scan_active = signatures.has_hashes();
+    if !hash_scan_active && cfg!(target_os = "macos") {
+        status.push_str(" Files aren't checked against known-bad hashes until the malware definitions are updated.");
+    }
 
     MalwareResult {
         threats_found: threats,
         status,
+        definitions_version: signatures.version,
+        hash_matches: matches,
+        hash_scan_active,
+        launch_items,
+        browser_findings,
+        config_profiles,
+        downloads,
+        xprotect,
+        remediated_by_apple,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_executables_and_reports_the_matching_signature() {
+        let dir = tempfile::tempdir().unwrap();
+        let dropper = dir.path().join("Installer");
+        fs::write(&dropper, b"abc").unwrap();
+        let text = dir.path().join("notes.txt");
+        fs::write(&text, b"abc").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&dropper, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(sha256_file(&dropper).as_deref(), Some(abc));
+        let set: SignatureSet = serde_json::from_value(serde_json::json!({
+            "version": 2,
+            "signatures": [{ "name": "Test.Dropper", "sha256": [abc.to_uppercase()] }]
+        }))
+        .unwrap();
+        let found = hash_matches(&set, vec![(dropper.clone(), "downloads"), (text, "downloads"), (dropper, "downloads")]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].signature, "Test.Dropper");
     }
 }
@@ -0,0 +1,225 @@
+use minisign_verify::{PublicKey, Signature as Minisig};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const BUILT_IN_JSON: &str = include_str!("../data/malware_signatures.json");
+const DEFINITIONS_URL: &str = "https://github.com/Adiuk24/alto-AI_mac_cleaner/releases/latest/download/malware_signatures.json";
+/// Definitions are signed with the same minisign key as app updates (tauri.conf.json).
+const DEFINITIONS_PUBLIC_KEY: &str = "RWTjut4xs07AuthITPmHlSkbYHjEOriHn3y/UVJVCHnM9hh/eJ5VVoIC";
+const FETCH_TIMEOUT_SECS: &str = "30";
+
+/// One known piece of malware or adware and the traces it leaves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Signature {
+    pub name: String,
+    /// Launch agent/daemon file names ("com.genieo.engine.plist")
+    #[serde(default)]
+    pub file_names: Vec<String>,
+    /// Files or folders it installs; `~` is the home folder
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub bundle_ids: Vec<String>,
+    /// Lowercase hex SHA-256 of its executables
+    #[serde(default)]
+    pub sha256: Vec<String>,
+    /// Domains it sets as search engine or homepage ("searchbaron.com" also matches subdomains)
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// Chromium extension ids
+    #[serde(default)]
+    pub extension_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureSet {
+    /// Increases with every published update; older files are never installed over newer ones
+    pub version: u64,
+    #[serde(default)]
+    pub published: Option<String>,
+    pub signatures: Vec<Signature>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DefinitionsStatus {
+    pub version: u64,
+    pub published: Option<String>,
+    pub signature_count: usize,
+    pub hash_count: usize,
+    /// "built_in" | "downloaded"
+    pub source: String,
+    /// Set by an update that installed newer definitions
+    pub updated: bool,
+}
+
+impl SignatureSet {
+    pub fn built_in() -> Self {
+        serde_json::from_str(BUILT_IN_JSON).expect("bundled malware_signatures.json is valid")
+    }
+
+    /// The signature that names this launch agent/daemon file, if any.
+    pub fn match_file_name(&self, name: &str) -> Option<&Signature> {
+        self.signatures.iter().find(|s| s.file_names.iter().any(|f| f == name))
+    }
+
+    pub fn match_bundle_id(&self, bundle_id: &str) -> Option<&Signature> {
+        self.signatures.iter().find(|s| s.bundle_ids.iter().any(|b| b == bundle_id))
+    }
+
+    /// False for the built-in definitions, which list no hashes; only an update can turn the hash scan on.
+    pub fn has_hashes(&self) -> bool {
+        self.signatures.iter().any(|s| !s.sha256.is_empty())
+    }
+
+    /// The signature listing this SHA-256 (hex, any case).
+    pub fn match_sha256(&self, sha256: &str) -> Option<&Signature> {
+        self.signatures.iter().find(|s| s.sha256.iter().any(|h| h.eq_ignore_ascii_case(sha256)))
+    }
+
+    pub fn match_domain(&self, host: &str) -> Option<&Signature> {
+        self.signatures.iter().find(|s| s.domains.iter().any(|d| host == d || host.ends_with(&format!(".{}", d))))
+    }
+
+    pub fn match_extension_id(&self, id: &str) -> Option<&Signature> {
+        self.signatures.iter().find(|s| s.extension_ids.iter().any(|e| e == id))
+    }
+
+    /// Known install locations that exist on this Mac, with the signature they belong to.
+    pub fn existing_paths(&self, home: &Path) -> Vec<(&Signature, PathBuf)> {
+        let mut found = Vec::new();
+        for sig in &self.signatures {
+            for raw in &sig.paths {
+                let path = match raw.strip_prefix("~/") {
+                    Some(rest) => home.join(rest),
+                    None => PathBuf::from(raw),
+                };
+                if path.symlink_metadata().is_ok() {
+                    found.push((sig, path));
+                }
+            }
+        }
+        found
+    }
+
+    fn status(&self, source: &str, updated: bool) -> DefinitionsStatus {
+        DefinitionsStatus {
+            version: self.version,
+            published: self.published.clone(),
+            signature_count: self.signatures.len(),
+            hash_count: self.signatures.iter().map(|s| s.sha256.len()).sum(),
+            source: source.to_string(),
+            updated,
+        }
+    }
+}
+
+fn store_path() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("malware_signatures.json")
+}
+
+fn signature_path(store: &Path) -> PathBuf {
+    store.with_extension("json.minisig")
+}
+
+/// Parse definitions only if `minisig` is a valid signature of `json` by `public_key`.
+fn verify(json: &[u8], minisig: &str, public_key: &str) -> Result<SignatureSet, String> {
+    let key = PublicKey::from_base64(public_key).map_err(|e| format!("Bad definitions key: {}", e))?;
+    let sig = Minisig::decode(minisig).map_err(|e| format!("Bad definitions signature: {}", e))?;
+    key.verify(json, &sig, false).map_err(|e| format!("Definitions signature check failed: {}", e))?;
+    serde_json::from_slice(json).map_err(|e| format!("Invalid definitions file: {}", e))
+}
+
+/// Downloaded definitions, re-verified on every load, so a file edited on disk is ignored.
+fn load_downloaded() -> Option<SignatureSet> {
+    let store = store_path();
+    let json = std::fs::read(&store).ok()?;
+    let minisig = std::fs::read_to_string(signature_path(&store)).ok()?;
+    match verify(&json, &minisig, DEFINITIONS_PUBLIC_KEY) {
+        Ok(set) => Some(set),
+        Err(e) => {
+            eprintln!("[Malware] Ignoring downloaded definitions: {}", e);
+            None
+        }
+    }
+}
+
+/// The newest valid definitions: downloaded ones when they beat the bundled set.
+pub fn load() -> SignatureSet {
+    load_with_source().0
+}
+
+fn load_with_source() -> (SignatureSet, &'static str) {
+    let built_in = SignatureSet::built_in();
+    match load_downloaded() {
+        Some(set) if set.version > built_in.version => (set, "downloaded"),
+        _ => (built_in, "built_in"),
+    }
+}
+
+pub fn status() -> DefinitionsStatus {
+    let (set, source) = load_with_source();
+    set.status(source, false)
+}
+
+fn fetch(url: &str) -> Result<Vec<u8>, String> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "--max-time", FETCH_TIMEOUT_SECS, url])
+        .output()
+        .map_err(|e| format!("Could not run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Download of {} failed: {}", url, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(output.stdout)
+}
+
+/// Download the published definitions and their signature, and install them if the signature
+/// checks out and they are newer than what is already in use.
+pub fn update() -> Result<DefinitionsStatus, String> {
+    let json = fetch(DEFINITIONS_URL)?;
+    let minisig = String::from_utf8(fetch(&format!("{}.minisig", DEFINITIONS_URL))?).map_err(|_| "Definitions signature is not text".to_string())?;
+    let downloaded = verify(&json, &minisig, DEFINITIONS_PUBLIC_KEY)?;
+    let (current, source) = load_with_source();
+    if downloaded.version <= current.version {
+        return Ok(current.status(source, false));
+    }
+    let store = store_path();
+    if let Some(dir) = store.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    // Signature first: a crash in between leaves a pair that fails verification, not unsigned data
+    std::fs::write(signature_path(&store), &minisig).map_err(|e| e.to_string())?;
+    let tmp = store.with_extension("json.tmp");
+    std::fs::write(&tmp, &json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &store).map_err(|e| e.to_string())?;
+    Ok(downloaded.status("downloaded", true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_definitions_parse_and_match() {
+        let set = SignatureSet::built_in();
+        assert!(set.version >= 1);
+        assert_eq!(set.match_file_name("com.genieo.engine.plist").map(|s| s.name.as_str()), Some("Genieo"));
+        assert_eq!(set.match_bundle_id("com.zeobit.MacKeeper").map(|s| s.name.as_str()), Some("MacKeeper"));
+        assert!(set.match_file_name("com.apple.Finder.plist").is_none());
+    }
+
+    #[test]
+    fn unsigned_definitions_are_rejected() {
+        assert!(verify(BUILT_IN_JSON.as_bytes(), "not a signature", DEFINITIONS_PUBLIC_KEY).is_err());
+    }
+
+    #[test]
+    fn existing_paths_expand_home() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(home.path().join("Library/Application Support/Spigot")).unwrap();
+        let set = SignatureSet::built_in();
+        let found = set.existing_paths(home.path());
+        assert!(found.iter().any(|(s, p)| s.name == "Spigot" && p.ends_with("Application Support/Spigot")));
+    }
+}
@@ -1,25 +1,134 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::mcp::file_index::FileCategory;
 
-#[derive(Debug, Clone, Serialize)]
+/// Byte accounting policy shared by all scanners. `logical` is the file length (`len()`);
+/// `allocated` is what the file really occupies on disk (st_blocks * 512), which differs for
+/// sparse, APFS-compressed, cloud-evicted and very small files. Anything presented as
+/// "reclaimable" uses `allocated`, so totals reconcile with Finder and Disk Utility.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ByteSize {
+    pub logical: u64,
+    pub allocated: u64,
+}
+
+impl ByteSize {
+    pub fn of(meta: &std::fs::Metadata) -> Self {
+        Self { logical: meta.len(), allocated: allocated_bytes(meta) }
+    }
+}
+
+impl std::ops::AddAssign for ByteSize {
+    fn add_assign(&mut self, other: Self) {
+        self.logical += other.logical;
+        self.allocated += other.allocated;
+    }
+}
+
+impl std::ops::Add for ByteSize {
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl std::iter::Sum for ByteSize {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut acc, s| {
+            acc += s;
+            acc
+        })
+    }
+}
+
+/// On-disk size of a file (st_blocks * 512); `len()` where block counts aren't available.
+pub fn allocated_bytes(meta: &std::fs::Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        meta.blocks() * 512
+    }
+    #[cfg(not(unix))]
+    {
+        meta.len()
+    }
+}
+
+/// True for files evicted by iCloud Drive (or another File Provider): the name and logical size
+/// are local but the contents aren't, so deleting them frees nothing and reading them downloads.
+pub fn is_dataless(meta: &std::fs::Metadata) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::macos::fs::MetadataExt;
+        const SF_DATALESS: u32 = 0x4000_0000;
+        meta.st_flags() & SF_DATALESS != 0
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = meta;
+        false
+    }
+}
+
+/// Logical and allocated size of every regular file under `path` (symlinks not followed).
+pub fn dir_byte_size(path: &Path) -> ByteSize {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| ByteSize::of(&m))
+        .sum()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScannedItem {
     pub path: String,
+    /// Reclaimable size; equal to `allocated_bytes`
     pub size_bytes: u64,
+    #[serde(default)]
+    pub logical_bytes: u64,
+    #[serde(default)]
+    pub allocated_bytes: u64,
     pub category_name: String,
     pub is_directory: bool,
     pub accessed_date: Option<i64>,
+    /// Safety classification from the MCP file index (junk scan only)
+    #[serde(default)]
+    pub file_category: Option<FileCategory>,
+    /// Why the item is considered safe to remove
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ScanResult {
     pub items: Vec<ScannedItem>,
     pub total_size_bytes: u64,
     pub errors: Vec<String>,
+    /// True when a deadline or file cap cut the scan short, so `items` is partial
+    pub truncated: bool,
+    /// Templates walked (possibly up to the per-template cap)
+    pub scanned_templates: Vec<String>,
+    /// Templates that were not (fully) walked because time or the global cap ran out
+    pub skipped_templates: Vec<String>,
 }
 
 pub mod junk;
 pub mod large_files;
 pub mod space_lens;
 pub mod malware;
+pub mod malware_signatures;
+pub mod launch_items;
+pub mod browser_hijack;
+pub mod config_profiles;
+pub mod gatekeeper;
+pub mod virustotal;
+pub mod quarantine;
+pub mod xprotect;
+pub mod browser_db;
+pub mod safari_data;
 pub mod speed;
 pub mod scheduler;
 pub mod system_stats;
@@ -33,3 +142,33 @@ pub mod maintenance;
 pub mod privacy;
 pub mod monitor;
 pub mod process;
+pub mod power;
+pub mod storage;
+pub mod cloud_sync;
+pub mod checkpoint;
+pub mod automation;
+pub mod priority;
+pub mod trash;
+pub mod xcode;
+pub mod pref_panes;
+pub mod docker;
+pub mod fonts;
+pub mod node_modules;
+pub mod dev_caches;
+pub mod apfs;
+pub mod vm_images;
+pub mod jetbrains;
+pub mod android;
+pub mod electron;
+pub mod old_files;
+pub mod size_index;
+pub mod space_lens_history;
+pub mod similar_photos;
+pub mod system_data;
+pub mod screenshots;
+pub mod downloads;
+pub mod compression;
+pub mod app_versions;
+pub mod installers;
+pub mod mover;
+pub mod auto_clean;
@@ -3,6 +3,8 @@ use std::time::Duration;
 use tauri::AppHandle;
 use tauri_plugin_notification::NotificationExt;
 use crate::scanners::system_stats::get_stats;
+use crate::scanners::power;
+use crate::scanners::process;
 
 pub fn start_monitor_thread(app: AppHandle) {
     thread::spawn(move || {
@@ -15,6 +17,8 @@ pub fn start_monitor_thread(app: AppHandle) {
             thread::sleep(Duration::from_secs(10));
 
             let stats = get_stats();
+            // Keep the shared process snapshot warm for privacy/uninstaller checks
+            process::refresh();
             
             // --- CPU MONITOR ---
             // Alert if CPU > 85% for 3 consecutive checks (30s)
@@ -24,7 +28,7 @@ pub fn start_monitor_thread(app: AppHandle) {
                 high_cpu_counter = 0;
             }
 
-            if high_cpu_counter >= 3 {
+            if high_cpu_counter >= 3 && !power::should_suppress_alert("high_cpu") {
                 if last_cpu_alert.elapsed().as_secs() > 3600 { // Cooldown 1 hour
                     let _ = app.notification()
                         .builder()
@@ -39,7 +43,7 @@ pub fn start_monitor_thread(app: AppHandle) {
             // --- RAM MONITOR ---
             // Alert if RAM > 90% full
             let ram_percent = (stats.memory_used as f64 / stats.memory_total as f64) * 100.0;
-            if ram_percent > 90.0 {
+            if ram_percent > 90.0 && !power::should_suppress_alert("memory_full") {
                  if last_ram_alert.elapsed().as_secs() > 3600 {
                     let _ = app.notification()
                         .builder()
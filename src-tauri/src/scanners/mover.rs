@@ -0,0 +1,165 @@
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::Read;
+use std::path::Path;
+use walkdir::WalkDir;
+
+const CHUNK: usize = 1024 * 1024;
+/// Bytes copied between progress reports
+const PROGRESS_EVERY: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MoveProgress {
+    /// Item being moved
+    pub path: String,
+    pub items_done: usize,
+    pub items_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+/// Counts copied bytes across a whole move and reports every `PROGRESS_EVERY`.
+pub struct Progress<'a> {
+    pub bytes_done: u64,
+    reported: u64,
+    on_bytes: &'a mut dyn FnMut(u64),
+}
+
+impl<'a> Progress<'a> {
+    pub fn new(on_bytes: &'a mut dyn FnMut(u64)) -> Self {
+        Self { bytes_done: 0, reported: 0, on_bytes }
+    }
+
+    fn add(&mut self, n: u64) {
+        self.bytes_done += n;
+        if self.bytes_done - self.reported >= PROGRESS_EVERY {
+            self.reported = self.bytes_done;
+            (self.on_bytes)(self.bytes_done);
+        }
+    }
+}
+
+/// Logical size of a file or of everything in a folder.
+pub fn total_len(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn checksum(path: &Path) -> Result<(u64, u64), String> {
+    let mut file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut hasher = DefaultHasher::new();
+    let mut buf = vec![0u8; CHUNK];
+    let mut len = 0u64;
+    loop {
+        let n = file.read(&mut buf).map_err(|e| format!("{}: {}", path.display(), e))?;
+        if n == 0 {
+            return Ok((len, hasher.finish()));
+        }
+        hasher.write(&buf[..n]);
+        len += n as u64;
+    }
+}
+
+/// Copy one regular file with `fs::copy` (which keeps extended attributes and clones on APFS), then
+/// checksum both sides and compare. Sockets, FIFOs and devices are refused rather than read.
+fn copy_file_verified(src: &Path, dest: &Path, progress: &mut Progress) -> Result<(), String> {
+    let err = |e: std::io::Error| format!("{}: {}", src.display(), e);
+    let meta = fs::symlink_metadata(src).map_err(err)?;
+    if !meta.file_type().is_file() {
+        return Err(format!("{} is not a regular file and can't be copied", src.display()));
+    }
+    fs::copy(src, dest).map_err(|e| format!("{}: {}", dest.display(), e))?;
+    if let Ok(modified) = meta.modified() {
+        if let Ok(output) = File::options().write(true).open(dest) {
+            let _ = output.set_modified(modified);
+        }
+    }
+    let original = checksum(src)?;
+    if checksum(dest)? != original {
+        return Err(format!("Copy of {} does not match the original", src.display()));
+    }
+    progress.add(original.0);
+    Ok(())
+}
+
+fn copy_tree(src: &Path, dest: &Path, progress: &mut Progress) -> Result<(), String> {
+    for entry in WalkDir::new(src).follow_links(false).sort_by_file_name() {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let rel = entry.path().strip_prefix(src).map_err(|e| e.to_string())?;
+        let target = if rel.as_os_str().is_empty() { dest.to_path_buf() } else { dest.join(rel) };
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            fs::create_dir(&target).map_err(|e| format!("{}: {}", target.display(), e))?;
+        } else if file_type.is_symlink() {
+            let link = fs::read_link(entry.path()).map_err(|e| e.to_string())?;
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&link, &target).map_err(|e| format!("{}: {}", target.display(), e))?;
+            #[cfg(not(unix))]
+            return Err(format!("Can't copy symlink {} to {}", entry.path().display(), link.display()));
+        } else {
+            copy_file_verified(entry.path(), &target, progress)?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy `src` (file or folder) to `dest` for a move across volumes, verifying every file.
+/// A failed copy is removed again; the original is never touched here.
+pub fn copy_verified(src: &Path, dest: &Path, progress: &mut Progress) -> Result<(), String> {
+    if fs::symlink_metadata(dest).is_ok() {
+        return Err(format!("{} already exists", dest.display()));
+    }
+    let result = copy_tree(src, dest, progress);
+    if result.is_err() {
+        let _ = if dest.is_dir() { fs::remove_dir_all(dest) } else { fs::remove_file(dest) };
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_folders_and_verifies_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("Project");
+        fs::create_dir_all(src.join("assets")).unwrap();
+        fs::write(src.join("readme.md"), b"hello").unwrap();
+        fs::write(src.join("assets/logo.png"), vec![7u8; 3 * CHUNK + 5]).unwrap();
+
+        let mut on_bytes = |_: u64| {};
+        let mut progress = Progress::new(&mut on_bytes);
+        let dest = dir.path().join("Moved");
+        copy_verified(&src, &dest, &mut progress).unwrap();
+        assert_eq!(progress.bytes_done, total_len(&src));
+        assert_eq!(fs::read(dest.join("assets/logo.png")).unwrap(), fs::read(src.join("assets/logo.png")).unwrap());
+
+        // Never merges into or overwrites an existing destination
+        assert!(copy_verified(&src, &dest, &mut progress).is_err());
+        assert!(dest.join("readme.md").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_to_copy_sockets() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("Project");
+        fs::create_dir_all(&src).unwrap();
+        let _listener = std::os::unix::net::UnixListener::bind(src.join("agent.sock")).unwrap();
+
+        let mut on_bytes = |_: u64| {};
+        let mut progress = Progress::new(&mut on_bytes);
+        let dest = dir.path().join("Moved");
+        assert!(copy_verified(&src, &dest, &mut progress).is_err());
+        assert!(!dest.exists());
+    }
+}
@@ -0,0 +1,140 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+use crate::mcp::context_store::ContextStore;
+
+/// Project roots (relative to home) searched when the user hasn't configured any.
+const DEFAULT_PROJECT_ROOTS: &[&str] = &["Projects", "dev", "Developer", "code", "src", "workspace", "repos", "GitHub"];
+/// Projects untouched for this long count as stale unless the caller asks otherwise.
+pub const DEFAULT_STALE_MONTHS: u32 = 3;
+/// How deep below a project root projects are looked for (e.g. ~/dev/org/monorepo/packages/app).
+const MAX_PROJECT_DEPTH: usize = 6;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleNodeModules {
+    pub project_name: String,
+    pub project_path: String,
+    pub node_modules_path: String,
+    pub size_bytes: u64,
+    /// Newest modification of the project's own files (node_modules excluded), unix seconds
+    pub last_modified: i64,
+}
+
+/// Configured project roots, or the common defaults that exist under home.
+pub fn project_roots() -> Vec<PathBuf> {
+    let configured = ContextStore::load().user_preferences.project_roots;
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    if !configured.is_empty() {
+        return configured
+            .iter()
+            .map(|r| match r.strip_prefix("~/") {
+                Some(rest) => home.join(rest),
+                None => PathBuf::from(r),
+            })
+            .collect();
+    }
+    DEFAULT_PROJECT_ROOTS.iter().map(|r| home.join(r)).filter(|p| p.is_dir()).collect()
+}
+
+fn is_skipped_dir(name: &str) -> bool {
+    name.starts_with('.') || matches!(name, "node_modules" | "target" | "build" | "dist" | "Pods" | "vendor")
+}
+
+fn to_secs(t: SystemTime) -> i64 {
+    t.duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+/// Newest mtime among the project's own files, two levels deep (enough to catch source edits
+/// without walking large trees). node_modules, build output and dot-folders are ignored.
+pub fn project_last_modified(project: &Path) -> i64 {
+    WalkDir::new(project)
+        .max_depth(2)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !is_skipped_dir(&e.file_name().to_string_lossy()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.depth() > 0)
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .map(to_secs)
+        .max()
+        .unwrap_or(0)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    super::dir_byte_size(path).allocated
+}
+
+fn package_name(project: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(project.join("package.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&text).ok()?;
+    json.get("name")?.as_str().map(|s| s.to_string())
+}
+
+/// node_modules folders whose project hasn't been modified in `stale_months` months, largest first.
+pub fn find_stale_node_modules(stale_months: u32) -> Vec<StaleNodeModules> {
+    let ctx = ContextStore::load();
+    let cutoff = to_secs(
+        SystemTime::now()
+            .checked_sub(Duration::from_secs(stale_months as u64 * 30 * 24 * 3600))
+            .unwrap_or(SystemTime::UNIX_EPOCH),
+    );
+
+    let mut found = Vec::new();
+    for root in project_roots() {
+        let walker = WalkDir::new(&root)
+            .max_depth(MAX_PROJECT_DEPTH)
+            .into_iter()
+            .filter_entry(|e| {
+                e.depth() == 0 || (e.file_type().is_dir() && !is_skipped_dir(&e.file_name().to_string_lossy()))
+            });
+        for entry in walker.filter_map(|e| e.ok()) {
+            let project = entry.path();
+            let node_modules = project.join("node_modules");
+            if !node_modules.is_dir() || !project.join("package.json").is_file() {
+                continue;
+            }
+            if ctx.is_excluded(&node_modules.to_string_lossy()) {
+                continue;
+            }
+            let last_modified = project_last_modified(project);
+            if last_modified >= cutoff {
+                continue;
+            }
+            found.push(StaleNodeModules {
+                project_name: package_name(project)
+                    .unwrap_or_else(|| project.file_name().unwrap_or_default().to_string_lossy().to_string()),
+                project_path: project.to_string_lossy().to_string(),
+                node_modules_path: node_modules.to_string_lossy().to_string(),
+                size_bytes: dir_size(&node_modules),
+                last_modified,
+            });
+        }
+    }
+    found.sort_by_key(|f| std::cmp::Reverse(f.size_bytes));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_and_hidden_dirs_are_skipped() {
+        assert!(is_skipped_dir("node_modules"));
+        assert!(is_skipped_dir(".git"));
+        assert!(is_skipped_dir("dist"));
+        assert!(!is_skipped_dir("packages"));
+    }
+
+    #[test]
+    fn last_modified_ignores_node_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("package.json"), "{}").unwrap();
+        let own = project_last_modified(dir.path());
+        std::thread::sleep(Duration::from_millis(1100));
+        std::fs::create_dir(dir.path().join("node_modules")).unwrap();
+        std::fs::write(dir.path().join("node_modules/x.js"), "").unwrap();
+        assert_eq!(project_last_modified(dir.path()), own);
+    }
+}
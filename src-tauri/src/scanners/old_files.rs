@@ -0,0 +1,268 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use walkdir::WalkDir;
+
+use super::ByteSize;
+use crate::mcp::context_store::{path_is_excluded, ContextStore};
+use crate::mcp::whitelist::Whitelist;
+
+const MIN_SIZE_BYTES: u64 = 100 * 1024 * 1024; // 100 MB
+const MAX_FILES_TO_SCAN: usize = 200_000;
+const SCAN_TIMEOUT_SECS: u64 = 60;
+/// Unused-for tiers, in months; a file lands in the largest one it qualifies for.
+const AGE_TIERS_MONTHS: [u32; 3] = [24, 12, 6];
+const SECS_PER_MONTH: i64 = 30 * 24 * 3600;
+
+/// What counts as "big and old". Defaults: over 100 MB, anywhere in the home folder.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OldFilesQuery {
+    pub min_size_bytes: Option<u64>,
+    /// Folders to search (`~` allowed); empty means the home folder
+    pub roots: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OldFile {
+    pub path: String,
+    pub size_bytes: u64,
+    /// unix seconds; the later of the access time and Spotlight's "last opened" date
+    pub last_opened: i64,
+    /// 6, 12 or 24
+    pub unused_months: u32,
+    /// "move" | "compress" | "trash"; "move" goes through move_paths_command to an external disk
+    pub suggested_actions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OldFileGroup {
+    pub folder: String,
+    pub files: Vec<OldFile>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgeTierTotal {
+    pub unused_months: u32,
+    pub file_count: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OldFilesReport {
+    pub groups: Vec<OldFileGroup>,
+    pub tiers: Vec<AgeTierTotal>,
+    pub total_bytes: u64,
+    /// Mounted external disks, as destinations for moving files off this Mac
+    pub external_volumes: Vec<String>,
+    pub truncated: bool,
+}
+
+fn unix_secs(t: std::io::Result<SystemTime>) -> Option<i64> {
+    t.ok()?.duration_since(SystemTime::UNIX_EPOCH).ok().map(|d| d.as_secs() as i64)
+}
+
+/// Spotlight's kMDItemLastUsedDate, which Finder and Launch Services keep even where atime is stale.
+#[cfg(target_os = "macos")]
+fn spotlight_last_used(path: &Path) -> Option<i64> {
+    let out = std::process::Command::new("mdls")
+        .args(["-raw", "-name", "kMDItemLastUsedDate"])
+        .arg(path)
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    chrono::DateTime::parse_from_str(text.trim(), "%Y-%m-%d %H:%M:%S %z").ok().map(|d| d.timestamp())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn spotlight_last_used(_path: &Path) -> Option<i64> {
+    None
+}
+
+fn unused_tier(last_opened: i64, now: i64) -> Option<u32> {
+    let months = (now - last_opened) / SECS_PER_MONTH;
+    AGE_TIERS_MONTHS.iter().copied().find(|t| months >= *t as i64)
+}
+
+/// "trash" only when the indexer calls the file safe to delete; confirm_delete would send anything
+/// else (documents, media: most of what this finds) to the review queue instead.
+fn suggested_actions(path: &Path, whitelist: &Whitelist) -> Vec<String> {
+    let mut actions = vec!["move".to_string()];
+    if !crate::mcp::drop_analysis::is_compressed(path) {
+        actions.push("compress".to_string());
+    }
+    if crate::mcp::file_index::index_file_with(&path.to_string_lossy(), whitelist).is_safe_to_delete {
+        actions.push("trash".to_string());
+    }
+    actions
+}
+
+fn external_volumes() -> Vec<String> {
+    // The boot volume shows up in /Volumes as a symlink to /
+    std::fs::read_dir("/Volumes")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+                .map(|e| e.path().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn search_roots(query: &OldFilesQuery, home: &Path) -> Vec<PathBuf> {
+    if query.roots.is_empty() {
+        return vec![home.to_path_buf()];
+    }
+    query
+        .roots
+        .iter()
+        .map(|r| match r.strip_prefix('~') {
+            Some(rest) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(r),
+        })
+        .collect()
+}
+
+fn group_by_folder(files: Vec<OldFile>) -> Vec<OldFileGroup> {
+    let mut groups: BTreeMap<String, OldFileGroup> = BTreeMap::new();
+    for file in files {
+        let folder = Path::new(&file.path).parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+        let group = groups
+            .entry(folder.clone())
+            .or_insert_with(|| OldFileGroup { folder, files: vec![], size_bytes: 0 });
+        group.size_bytes += file.size_bytes;
+        group.files.push(file);
+    }
+    let mut groups: Vec<OldFileGroup> = groups.into_values().collect();
+    for g in &mut groups {
+        g.files.sort_by_key(|f| std::cmp::Reverse(f.size_bytes));
+    }
+    groups.sort_by_key(|g| std::cmp::Reverse(g.size_bytes));
+    groups
+}
+
+fn scan_in(roots: &[PathBuf], min_size: u64, excluded: &[String], whitelist: &Whitelist, now: i64) -> (Vec<OldFile>, bool) {
+    let deadline = Instant::now() + Duration::from_secs(SCAN_TIMEOUT_SECS);
+    let mut files = Vec::new();
+    let mut checked = 0usize;
+    for root in roots {
+        let walker = WalkDir::new(root).follow_links(false).same_file_system(true).into_iter().filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            // Package contents are managed by the owning app
+            e.depth() == 0
+                || !(name.starts_with('.')
+                    || name == "Library"
+                    || name == "node_modules"
+                    || super::space_lens::is_package(e.path())
+                    || path_is_excluded(&e.path().to_string_lossy(), excluded))
+        });
+        for entry in walker.filter_map(|e| e.ok()) {
+            checked += 1;
+            if checked >= MAX_FILES_TO_SCAN || Instant::now() >= deadline {
+                return (files, true);
+            }
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else { continue };
+            let size = ByteSize::of(&meta);
+            // iCloud-only files take no local space
+            if size.logical < min_size || super::is_dataless(&meta) {
+                continue;
+            }
+            let local = [unix_secs(meta.accessed()), unix_secs(meta.modified())].into_iter().flatten().max().unwrap_or(0);
+            // Cheap check first; Spotlight is only asked about files that already look old
+            if unused_tier(local, now).is_none() {
+                continue;
+            }
+            let last_opened = local.max(spotlight_last_used(entry.path()).unwrap_or(0));
+            let Some(unused_months) = unused_tier(last_opened, now) else { continue };
+            files.push(OldFile {
+                path: entry.path().to_string_lossy().to_string(),
+                size_bytes: size.allocated,
+                last_opened,
+                unused_months,
+                suggested_actions: suggested_actions(entry.path(), whitelist),
+            });
+        }
+    }
+    (files, false)
+}
+
+/// Files over a size threshold not opened for 6, 12 or 24 months, grouped by folder, largest first.
+pub fn scan_old_files(query: &OldFilesQuery) -> OldFilesReport {
+    let _priority = super::priority::lower_scan_priority();
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let ctx = ContextStore::load();
+    let excluded = ctx.user_preferences.skipped_paths();
+    let now = unix_secs(Ok(SystemTime::now())).unwrap_or(0);
+    let (files, truncated) = scan_in(
+        &search_roots(query, &home),
+        query.min_size_bytes.unwrap_or(MIN_SIZE_BYTES),
+        &excluded,
+        &Whitelist::for_store(&ctx),
+        now,
+    );
+
+    let tiers = AGE_TIERS_MONTHS
+        .iter()
+        .rev()
+        .map(|&months| {
+            let in_tier = files.iter().filter(|f| f.unused_months == months);
+            AgeTierTotal {
+                unused_months: months,
+                file_count: in_tier.clone().count(),
+                size_bytes: in_tier.map(|f| f.size_bytes).sum(),
+            }
+        })
+        .collect();
+    OldFilesReport {
+        total_bytes: files.iter().map(|f| f.size_bytes).sum(),
+        groups: group_by_folder(files),
+        tiers,
+        external_volumes: external_volumes(),
+        truncated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_land_in_the_largest_tier_they_qualify_for() {
+        let now = 1_000 * SECS_PER_MONTH;
+        assert_eq!(unused_tier(now - 3 * SECS_PER_MONTH, now), None);
+        assert_eq!(unused_tier(now - 7 * SECS_PER_MONTH, now), Some(6));
+        assert_eq!(unused_tier(now - 13 * SECS_PER_MONTH, now), Some(12));
+        assert_eq!(unused_tier(now - 30 * SECS_PER_MONTH, now), Some(24));
+    }
+
+    #[test]
+    fn offers_trash_only_where_the_safety_gate_allows_it() {
+        let whitelist = Whitelist::default();
+        let movie = suggested_actions(Path::new("/Users/jane/Movies/Wedding.mov"), &whitelist);
+        assert_eq!(movie, vec!["move".to_string(), "compress".to_string()]);
+        let cache = suggested_actions(Path::new("/Users/jane/Library/Caches/com.example.app/blob.bin"), &whitelist);
+        assert!(cache.contains(&"trash".to_string()));
+    }
+
+    #[test]
+    fn groups_by_parent_folder() {
+        let file = |path: &str, size: u64| OldFile {
+            path: path.to_string(),
+            size_bytes: size,
+            last_opened: 0,
+            unused_months: 24,
+            suggested_actions: vec![],
+        };
+        let groups = group_by_folder(vec![file("/a/x.mov", 1), file("/b/y.mov", 5), file("/a/z.mov", 2)]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].folder, "/b");
+        assert_eq!(groups[1].size_bytes, 3);
+        assert_eq!(groups[1].files[0].path, "/a/z.mov");
+    }
+}
@@ -0,0 +1,88 @@
+use serde::Serialize;
+use crate::mcp::context_store::ContextStore;
+
+/// Current macOS power/attention state used to throttle background work.
+#[derive(Debug, Clone, Copy, Serialize, Default)]
+pub struct PowerState {
+    pub low_power_mode: bool,
+    pub focus_active: bool,
+}
+
+#[cfg(target_os = "macos")]
+fn low_power_mode_enabled() -> bool {
+    // `pmset -g` prints a " lowpowermode         1" line when Low Power Mode is on
+    let output = match std::process::Command::new("pmset").arg("-g").output() {
+        Ok(o) => o,
+        Err(_) => return false,
+    };
+    String::from_utf8_lossy(&output.stdout).lines().any(|line| {
+        let mut parts = line.split_whitespace();
+        parts.next() == Some("lowpowermode") && parts.next() == Some("1")
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn focus_active() -> bool {
+    // Active Focus modes (incl. Do Not Disturb) are stored as assertion records.
+    // Reading this file requires Full Disk Access; without it we assume no Focus.
+    let home = match dirs::home_dir() {
+        Some(h) => h,
+        None => return false,
+    };
+    let path = home.join("Library/DoNotDisturb/DB/Assertions.json");
+    let data = match std::fs::read_to_string(path) {
+        Ok(d) => d,
+        Err(_) => return false,
+    };
+    let v: serde_json::Value = serde_json::from_str(&data).unwrap_or(serde_json::Value::Null);
+    v.get("data")
+        .and_then(|d| d.as_array())
+        .map(|entries| {
+            entries.iter().any(|e| {
+                e.get("storeAssertionRecords")
+                    .and_then(|r| r.as_array())
+                    .map(|r| !r.is_empty())
+                    .unwrap_or(false)
+            })
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn low_power_mode_enabled() -> bool {
+    false
+}
+
+#[cfg(not(target_os = "macos"))]
+fn focus_active() -> bool {
+    false
+}
+
+pub fn current_state() -> PowerState {
+    PowerState {
+        low_power_mode: low_power_mode_enabled(),
+        focus_active: focus_active(),
+    }
+}
+
+/// True when the user's quiet-mode policy says Alto should currently hold back.
+fn is_quiet(ctx: &ContextStore, state: PowerState) -> bool {
+    let policy = &ctx.user_preferences.quiet_mode;
+    (policy.respect_low_power && state.low_power_mode) || (policy.respect_focus && state.focus_active)
+}
+
+/// Whether an alert of the given type (e.g. "high_cpu", "app_installed") should be held back right now.
+pub fn should_suppress_alert(alert_type: &str) -> bool {
+    let ctx = ContextStore::load();
+    let policy = &ctx.user_preferences.quiet_mode;
+    if !policy.suppressed_alerts.iter().any(|a| a == alert_type) {
+        return false;
+    }
+    is_quiet(&ctx, current_state())
+}
+
+/// Whether scheduler jobs flagged as heavy should be postponed right now.
+pub fn should_defer_heavy_jobs() -> bool {
+    let ctx = ContextStore::load();
+    is_quiet(&ctx, current_state())
+}
@@ -0,0 +1,245 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::helper_client::{self, Command};
+
+/// Third-party panes that stopped working long ago; flagged even if something from the vendor is still installed.
+const LEGACY_PANES: &[(&str, &str)] = &[
+    ("com.adobe.flashplayerpreferences", "Adobe Flash Player reached end of life in 2020"),
+    ("com.oracle.java.JavaControlPanel", "Java Control Panel for a plug-in browsers no longer load"),
+    ("org.perian.Perian", "Perian is a QuickTime 7 component, unsupported since macOS 10.15"),
+    ("com.growl.prefpanel", "Growl was replaced by Notification Center"),
+    ("net.telestream.flip4mac", "Flip4Mac is a QuickTime 7 component, unsupported since macOS 10.15"),
+];
+
+const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrefPaneItem {
+    pub path: String,
+    pub name: String,
+    pub bundle_id: Option<String>,
+    pub size_bytes: u64,
+    /// "user" (~/Library/PreferencePanes) or "system" (/Library/PreferencePanes)
+    pub location: String,
+    /// No installed app from the same vendor
+    pub orphaned: bool,
+    /// Cannot load in System Settings on this Mac (32-bit/PowerPC only, missing binary, known dead product)
+    pub incompatible: bool,
+    /// Loads only through Rosetta on Apple Silicon
+    pub needs_rosetta: bool,
+    pub reason: String,
+}
+
+fn pane_dirs() -> Vec<(PathBuf, &'static str)> {
+    let mut dirs = vec![(PathBuf::from("/Library/PreferencePanes"), "system")];
+    if let Some(home) = dirs::home_dir() {
+        dirs.push((home.join("Library/PreferencePanes"), "user"));
+    }
+    dirs
+}
+
+#[cfg(target_os = "macos")]
+fn info_plist(bundle: &Path) -> Option<serde_json::Value> {
+    plist::from_file(bundle.join("Contents/Info.plist")).ok()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn info_plist(_bundle: &Path) -> Option<serde_json::Value> {
+    None
+}
+
+fn plist_str(info: &Option<serde_json::Value>, key: &str) -> Option<String> {
+    info.as_ref()?.get(key)?.as_str().map(|s| s.to_string())
+}
+
+/// "com.adobe.flashplayerpreferences" -> "com.adobe"
+fn vendor_prefix(bundle_id: &str) -> String {
+    bundle_id.split('.').take(2).collect::<Vec<_>>().join(".").to_lowercase()
+}
+
+/// Vendor prefixes of every installed app, to tell whether a pane's parent app is gone.
+fn installed_vendor_prefixes() -> HashSet<String> {
+    let mut roots = vec![PathBuf::from("/Applications"), PathBuf::from("/System/Applications")];
+    if let Some(home) = dirs::home_dir() {
+        roots.push(home.join("Applications"));
+    }
+    roots
+        .iter()
+        .flat_map(|root| {
+            WalkDir::new(root)
+                .max_depth(2)
+                .into_iter()
+                .filter_map(|e| e.ok())
+        })
+        .filter(|e| e.path().extension().map(|x| x == "app").unwrap_or(false))
+        .filter_map(|e| plist_str(&info_plist(e.path()), "CFBundleIdentifier"))
+        .map(|id| vendor_prefix(&id))
+        .collect()
+}
+
+/// CPU types in a Mach-O (thin or universal) binary header.
+fn macho_cpu_types(header: &[u8]) -> Vec<u32> {
+    if header.len() < 8 {
+        return vec![];
+    }
+    let be = |b: &[u8]| u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+    let le = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+    match be(&header[0..4]) {
+        // Universal binary: big-endian fat_header followed by fat_arch entries (20 bytes, or 32 for fat64)
+        magic @ (0xcafe_babe | 0xcafe_babf) => {
+            let entry = if magic == 0xcafe_babf { 32 } else { 20 };
+            let count = be(&header[4..8]) as usize;
+            // Java class files share the 0xcafebabe magic; a real fat header has only a handful of slices
+            if count > 16 {
+                return vec![];
+            }
+            (0..count)
+                .filter_map(|i| header.get(8 + i * entry..12 + i * entry))
+                .map(be)
+                .collect()
+        }
+        // Thin little-endian Mach-O, 32 or 64 bit
+        0xcefa_edfe | 0xcffa_edfe => vec![le(&header[4..8])],
+        _ => vec![],
+    }
+}
+
+fn executable_cpu_types(bundle: &Path, info: &Option<serde_json::Value>) -> Option<Vec<u32>> {
+    let exe_name = plist_str(info, "CFBundleExecutable")?;
+    let mut header = vec![0u8; 4096];
+    let mut file = std::fs::File::open(bundle.join("Contents/MacOS").join(exe_name)).ok()?;
+    let n = file.read(&mut header).ok()?;
+    header.truncate(n);
+    Some(macho_cpu_types(&header))
+}
+
+fn inspect_pane(path: &Path, location: &str, installed: &HashSet<String>) -> PrefPaneItem {
+    let info = info_plist(path);
+    let bundle_id = plist_str(&info, "CFBundleIdentifier");
+    let name = plist_str(&info, "CFBundleName")
+        .unwrap_or_else(|| path.file_stem().unwrap_or_default().to_string_lossy().to_string());
+
+    let mut reasons = Vec::new();
+    let mut incompatible = false;
+    let mut needs_rosetta = false;
+
+    if let Some((_, why)) = bundle_id.as_deref().and_then(|id| LEGACY_PANES.iter().find(|(known, _)| known.eq_ignore_ascii_case(id))) {
+        incompatible = true;
+        reasons.push(why.to_string());
+    }
+    match executable_cpu_types(path, &info) {
+        None => {
+            incompatible = true;
+            reasons.push("Pane executable is missing or unreadable".to_string());
+        }
+        Some(types) if !types.is_empty() => {
+            if !types.contains(&CPU_TYPE_X86_64) && !types.contains(&CPU_TYPE_ARM64) {
+                incompatible = true;
+                reasons.push("32-bit or PowerPC only; macOS no longer loads it".to_string());
+            } else if cfg!(target_arch = "aarch64") && !types.contains(&CPU_TYPE_ARM64) {
+                needs_rosetta = true;
+                reasons.push("Intel-only; opens in a separate window through Rosetta".to_string());
+            }
+        }
+        Some(_) => {}
+    }
+
+    let orphaned = match &bundle_id {
+        Some(id) => !installed.contains(&vendor_prefix(id)),
+        None => true,
+    };
+    if orphaned {
+        reasons.push("No app from this vendor is installed".to_string());
+    }
+
+    PrefPaneItem {
+        path: path.to_string_lossy().to_string(),
+        name,
+        bundle_id,
+        size_bytes: super::dir_byte_size(path).allocated,
+        location: location.to_string(),
+        orphaned,
+        incompatible,
+        needs_rosetta,
+        reason: reasons.join(". "),
+    }
+}
+
+/// Third-party preference panes in /Library and ~/Library, with orphan and compatibility flags.
+pub fn scan_pref_panes() -> Vec<PrefPaneItem> {
+    let installed = installed_vendor_prefixes();
+    let mut items = Vec::new();
+    for (dir, location) in pane_dirs() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map(|x| x == "prefPane").unwrap_or(false) {
+                items.push(inspect_pane(&path, location, &installed));
+            }
+        }
+    }
+    let ctx = crate::mcp::context_store::ContextStore::load();
+    items.retain(|i| !ctx.is_protected(&i.path));
+    items.sort_by_key(|i| (!(i.orphaned || i.incompatible), i.name.to_lowercase()));
+    items
+}
+
+/// Remove a pane; falls back to the root helper for /Library/PreferencePanes.
+pub async fn remove_pref_pane(path_str: String) -> Result<(), String> {
+    let path = Path::new(&path_str);
+    let in_pane_dir = path.extension().map(|x| x == "prefPane").unwrap_or(false)
+        && pane_dirs().iter().any(|(dir, _)| path.parent() == Some(dir.as_path()));
+    if !in_pane_dir {
+        return Err("Not a preference pane".to_string());
+    }
+    if !path.exists() {
+        return Err("Path does not exist".to_string());
+    }
+
+    if trash::delete(path).is_ok() {
+        return Ok(());
+    }
+
+    if !helper_client::ensure_helper_installed().await {
+        return Err("Failed to install execution helper".to_string());
+    }
+    let res = helper_client::send_command(Command::DeletePath { path: path_str }).await
+        .map_err(|e| format!("Helper communication failed: {}", e))?;
+    if res.success {
+        Ok(())
+    } else {
+        Err(res.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_fat_and_thin_macho_headers() {
+        let mut fat = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 2];
+        let mut x86 = vec![0u8; 20];
+        x86[..4].copy_from_slice(&CPU_TYPE_X86_64.to_be_bytes());
+        let mut arm = vec![0u8; 20];
+        arm[..4].copy_from_slice(&CPU_TYPE_ARM64.to_be_bytes());
+        fat.extend(x86);
+        fat.extend(arm);
+        assert_eq!(macho_cpu_types(&fat), vec![CPU_TYPE_X86_64, CPU_TYPE_ARM64]);
+
+        let mut thin = vec![0xcf, 0xfa, 0xed, 0xfe];
+        thin.extend(7u32.to_le_bytes());
+        assert_eq!(macho_cpu_types(&thin), vec![7]);
+    }
+
+    #[test]
+    fn vendor_prefix_is_first_two_components() {
+        assert_eq!(vendor_prefix("com.Adobe.flashplayerpreferences"), "com.adobe");
+        assert_eq!(vendor_prefix("local"), "local");
+    }
+}
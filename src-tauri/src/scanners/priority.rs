@@ -0,0 +1,74 @@
+use crate::mcp::context_store::{ContextStore, ScanMode};
+
+#[cfg(target_os = "macos")]
+mod ffi {
+    use std::os::raw::{c_int, c_uint, c_void};
+
+    pub const IOPOL_TYPE_DISK: c_int = 0;
+    pub const IOPOL_SCOPE_THREAD: c_int = 1;
+    pub const IOPOL_THROTTLE: c_int = 3;
+    pub const QOS_CLASS_UTILITY: c_uint = 0x11;
+
+    extern "C" {
+        pub fn getiopolicy_np(iotype: c_int, scope: c_int) -> c_int;
+        pub fn setiopolicy_np(iotype: c_int, scope: c_int, policy: c_int) -> c_int;
+        pub fn pthread_self() -> *mut c_void;
+        pub fn pthread_get_qos_class_np(thread: *mut c_void, qos: *mut c_uint, relpri: *mut c_int) -> c_int;
+        pub fn pthread_set_qos_class_self_np(qos: c_uint, relpri: c_int) -> c_int;
+    }
+}
+
+/// Lowers the current thread's CPU QoS and disk IO priority while alive (in "background" scan mode),
+/// restoring the previous values on drop — scan threads come from shared pools and get reused.
+pub struct ScanPriorityGuard {
+    #[cfg(target_os = "macos")]
+    previous: Option<(std::os::raw::c_int, std::os::raw::c_uint, std::os::raw::c_int)>,
+}
+
+impl ScanPriorityGuard {
+    pub fn for_mode(mode: ScanMode) -> Self {
+        #[cfg(target_os = "macos")]
+        {
+            if mode == ScanMode::Background {
+                // SAFETY: plain libSystem calls on the current thread with valid out-pointers
+                unsafe {
+                    let io = ffi::getiopolicy_np(ffi::IOPOL_TYPE_DISK, ffi::IOPOL_SCOPE_THREAD);
+                    let mut qos = 0;
+                    let mut relpri = 0;
+                    ffi::pthread_get_qos_class_np(ffi::pthread_self(), &mut qos, &mut relpri);
+                    ffi::setiopolicy_np(ffi::IOPOL_TYPE_DISK, ffi::IOPOL_SCOPE_THREAD, ffi::IOPOL_THROTTLE);
+                    ffi::pthread_set_qos_class_self_np(ffi::QOS_CLASS_UTILITY, 0);
+                    return Self { previous: Some((io, qos, relpri)) };
+                }
+            }
+            Self { previous: None }
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = mode;
+            Self {}
+        }
+    }
+}
+
+impl Drop for ScanPriorityGuard {
+    fn drop(&mut self) {
+        #[cfg(target_os = "macos")]
+        if let Some((io, qos, relpri)) = self.previous {
+            // SAFETY: see above
+            unsafe {
+                if io >= 0 {
+                    ffi::setiopolicy_np(ffi::IOPOL_TYPE_DISK, ffi::IOPOL_SCOPE_THREAD, io);
+                }
+                if qos != 0 {
+                    ffi::pthread_set_qos_class_self_np(qos, relpri);
+                }
+            }
+        }
+    }
+}
+
+/// Apply the user's scan mode to the calling thread for as long as the guard lives.
+pub fn lower_scan_priority() -> ScanPriorityGuard {
+    ScanPriorityGuard::for_mode(ContextStore::load().user_preferences.scan_mode)
+}
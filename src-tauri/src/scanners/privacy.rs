@@ -1,5 +1,6 @@
 use serde::Serialize;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::fs;
 
 #[derive(Debug, Serialize, Clone)]
@@ -10,6 +11,53 @@ pub struct PrivacyItem {
     pub path: String,
     pub size_bytes: u64,
     pub description: String,
+    /// Browser profile the data belongs to ("Work", "Person 1"); none for Safari
+    pub profile: Option<String>,
+}
+
+/// Browsers the privacy cleaner knows about: (display name, bundle id, data folder marker in paths).
+const BROWSERS: &[(&str, &str, &str)] = &[
+    ("Google Chrome", "com.google.Chrome", "Google/Chrome"),
+    ("Safari", "com.apple.Safari", "Safari"),
+    ("Brave", "com.brave.Browser", "BraveSoftware"),
+    ("Arc", "company.thebrowser.Browser", "Application Support/Arc/"),
+    ("Microsoft Edge", "com.microsoft.edgemac", "Application Support/Microsoft Edge/"),
+    ("Opera", "com.operasoftware.Opera", "Application Support/com.operasoftware.Opera/"),
+    ("Vivaldi", "com.vivaldi.Vivaldi", "Application Support/Vivaldi/"),
+    ("Chromium", "org.chromium.Chromium", "Application Support/Chromium/"),
+    ("Firefox", "org.mozilla.firefox", "Firefox"),
+];
+
+/// Chromium-based browsers: (display name, data folder under ~/Library/Application Support).
+const CHROMIUM_BROWSERS: &[(&str, &str)] = &[
+    ("Google Chrome", "Google/Chrome"),
+    ("Brave", "BraveSoftware/Brave-Browser"),
+    ("Arc", "Arc/User Data"),
+    ("Microsoft Edge", "Microsoft Edge"),
+    ("Opera", "com.operasoftware.Opera"),
+    ("Vivaldi", "Vivaldi"),
+    ("Chromium", "Chromium"),
+];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RunningBrowser {
+    pub name: String,
+    pub bundle_id: String,
+    pub pids: Vec<u32>,
+}
+
+/// Supported browsers that are currently running, matched by bundle id of the main executable.
+pub fn running_browsers() -> Vec<RunningBrowser> {
+    BROWSERS.iter()
+        .filter_map(|(name, bundle_id, _)| {
+            let pids: Vec<u32> = crate::scanners::process::find_by_bundle_id(bundle_id).iter().map(|p| p.pid).collect();
+            if pids.is_empty() {
+                None
+            } else {
+                Some(RunningBrowser { name: name.to_string(), bundle_id: bundle_id.to_string(), pids })
+            }
+        })
+        .collect()
 }
 
 pub fn scan_privacy() -> Vec<PrivacyItem> {
@@ -17,12 +65,23 @@ pub fn scan_privacy() -> Vec<PrivacyItem> {
     let home = dirs::home_dir().unwrap();
     let library = home.join("Library");
 
-    // 1. Google Chrome
-    let chrome_base = library.join("Application Support/Google/Chrome/Default");
-    if chrome_base.exists() {
-        check_browser_file(&mut items, &chrome_base, "History", "Google Chrome", "Browsing History");
-        check_browser_file(&mut items, &chrome_base, "Cookies", "Google Chrome", "Tracking Cookies");
-        check_browser_file(&mut items, &chrome_base, "Login Data", "Google Chrome", "Saved Passwords");
+    // 1. Chromium browsers, every profile
+    for (browser, folder) in CHROMIUM_BROWSERS {
+        for (profile_dir, profile) in chromium_profiles(&library.join("Application Support").join(folder)) {
+            let folder = profile_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+            // Cookies moved into Network/ in Chrome 96
+            let cookies = [profile_dir.join("Network/Cookies"), profile_dir.join("Cookies")].into_iter().find(|p| p.exists());
+            for (path, data_type, desc) in [
+                (Some(profile_dir.join("History")), "History", "Browsing History"),
+                (cookies, "Cookies", "Tracking Cookies"),
+                (Some(profile_dir.join("Login Data")), "Login Data", "Saved Passwords"),
+            ] {
+                if let Some(path) = path {
+                    let id = format!("{}_{}_{}", browser, folder, data_type);
+                    push_item(&mut items, &path, id, browser, data_type, desc, Some(&profile));
+                }
+            }
+        }
     }
 
     // 2. Safari
@@ -30,58 +89,165 @@ pub fn scan_privacy() -> Vec<PrivacyItem> {
     if safari_base.exists() {
         check_browser_file(&mut items, &safari_base, "History.db", "Safari", "Browsing History");
         check_browser_file(&mut items, &safari_base, "LastSession.plist", "Safari", "Last Session Data");
-        // Safari Cookies happen in ~/Library/Cookies/Cookies.binarycookies usually, but let's stick to base
+    }
+    if let Some(cookies) = super::safari_data::cookies_file(&library) {
+        let desc = match super::safari_data::cookie_site_count(&cookies) {
+            Some(sites) => format!("Cookies from {} sites", sites),
+            None => "Tracking Cookies".to_string(),
+        };
+        push_item(&mut items, &cookies, "Safari_Cookies".to_string(), "Safari", "Cookies", &desc, None);
+    }
+    let website_data = super::safari_data::website_data_dir(&library);
+    for site in super::safari_data::site_data(&library) {
+        // Per-site folders are named by hash under each top-level site, so only the whole relative path is unique
+        let relative = site.path.strip_prefix(&website_data).or_else(|_| site.path.strip_prefix(&library)).unwrap_or(&site.path);
+        let id = format!("Safari_{}_{}", site.kind, relative.to_string_lossy());
+        push_item(&mut items, &site.path, id, "Safari", site.kind, &site.host, None);
     }
 
-    // 3. Brave Browser
-    let brave_base = library.join("Application Support/BraveSoftware/Brave-Browser/Default");
-    if brave_base.exists() {
-        check_browser_file(&mut items, &brave_base, "History", "Brave", "Browsing History");
-        check_browser_file(&mut items, &brave_base, "Cookies", "Brave", "Tracking Cookies");
+    // 3. Firefox: every profile, with its cache kept under ~/Library/Caches
+    let firefox_dir = library.join("Application Support/Firefox");
+    let firefox_names = firefox_profile_names(&firefox_dir);
+    for profile in fs::read_dir(firefox_dir.join("Profiles")).into_iter().flatten().flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+        let folder = profile.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let name = firefox_names.get(&folder).cloned().unwrap_or_else(|| folder.clone());
+        let cache = library.join("Caches/Firefox/Profiles").join(&folder).join("cache2");
+        for (path, data_type, desc) in [
+            (profile.join("places.sqlite"), "History", "Browsing History"),
+            (profile.join("cookies.sqlite"), "Cookies", "Tracking Cookies"),
+            (cache, "Cache", "Cached Web Content"),
+        ] {
+            push_item(&mut items, &path, format!("Firefox_{}_{}", folder, data_type), "Firefox", data_type, desc, Some(&name));
+        }
     }
 
+    let ctx = crate::mcp::context_store::ContextStore::load();
+    items.retain(|i| !ctx.is_protected(&i.path));
     items
 }
 
-fn check_browser_file(items: &mut Vec<PrivacyItem>, base: &Path, filename: &str, browser: &str, desc: &str) {
-    let path = base.join(filename);
-    if path.exists() {
-        if let Ok(meta) = fs::metadata(&path) {
-            items.push(PrivacyItem {
-                id: format!("{}_{}", browser, filename),
-                browser: browser.to_string(),
-                data_type: filename.to_string(),
-                path: path.to_string_lossy().to_string(),
-                size_bytes: meta.len(),
-                description: desc.to_string(),
-            });
+/// Profile folders in a Chromium data folder with their display names from "Local State". Opera
+/// keeps its main profile in the data folder itself and extra ones in `_side_profiles`.
+fn chromium_profiles(data_dir: &Path) -> Vec<(PathBuf, String)> {
+    let local_state: serde_json::Value = fs::read_to_string(data_dir.join("Local State"))
+        .ok()
+        .and_then(|d| serde_json::from_str(&d).ok())
+        .unwrap_or_default();
+    let display_name = |folder: &str| {
+        local_state
+            .pointer(&format!("/profile/info_cache/{}/name", folder.replace('~', "~0").replace('/', "~1")))
+            .and_then(|n| n.as_str())
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| folder.to_string())
+    };
+
+    let mut profiles = Vec::new();
+    if data_dir.join("Preferences").exists() {
+        profiles.push((data_dir.to_path_buf(), "Default".to_string()));
+    }
+    let subfolders = fs::read_dir(data_dir).into_iter().flatten().chain(fs::read_dir(data_dir.join("_side_profiles")).into_iter().flatten()).flatten();
+    for entry in subfolders {
+        let folder = entry.file_name().to_string_lossy().to_string();
+        let is_profile = folder == "Default" || folder.starts_with("Profile ") || entry.path().parent().is_some_and(|p| p.ends_with("_side_profiles"));
+        if is_profile && entry.path().join("Preferences").exists() {
+            profiles.push((entry.path(), display_name(&folder)));
         }
     }
+    profiles.sort();
+    profiles
 }
 
-pub fn clean_privacy_item(path_str: &str) -> Result<(), String> {
-    let path = Path::new(path_str);
-    
-    // Safety Check: Is browser running?
-    if path_str.contains("Chrome") && crate::scanners::process::is_process_running("Google Chrome") {
-        return Err("Please close Google Chrome to clean this item.".to_string());
+/// Profile folder -> name, from Firefox's profiles.ini ("Path=Profiles/abcd.default-release").
+fn firefox_profile_names(firefox_dir: &Path) -> HashMap<String, String> {
+    let ini = fs::read_to_string(firefox_dir.join("profiles.ini")).unwrap_or_default();
+    let mut names = HashMap::new();
+    let mut name = None;
+    for line in ini.lines().map(|l| l.trim()) {
+        if line.starts_with('[') {
+            name = None;
+        } else if let Some(n) = line.strip_prefix("Name=") {
+            name = Some(n.to_string());
+        } else if let (Some(path), Some(n)) = (line.strip_prefix("Path="), &name) {
+            names.insert(path.rsplit('/').next().unwrap_or(path).to_string(), n.clone());
+        }
     }
-    if path_str.contains("Safari") && crate::scanners::process::is_process_running("Safari") {
-        // Safari might be "Safari" or "Safari Technology Preview"
-        return Err("Please close Safari to clean this item.".to_string());
+    names
+}
+
+fn check_browser_file(items: &mut Vec<PrivacyItem>, base: &Path, filename: &str, browser: &str, desc: &str) {
+    push_item(items, &base.join(filename), format!("{}_{}", browser, filename), browser, filename, desc, None);
+}
+
+fn push_item(items: &mut Vec<PrivacyItem>, path: &Path, id: String, browser: &str, data_type: &str, desc: &str, profile: Option<&str>) {
+    if let Ok(meta) = fs::metadata(path) {
+        items.push(PrivacyItem {
+            id,
+            browser: browser.to_string(),
+            data_type: data_type.to_string(),
+            path: path.to_string_lossy().to_string(),
+            size_bytes: if meta.is_dir() { super::mover::total_len(path) } else { meta.len() },
+            description: desc.to_string(),
+            profile: profile.map(|p| p.to_string()),
+        });
     }
-    if path_str.contains("Brave") && crate::scanners::process::is_process_running("Brave Browser") {
-        return Err("Please close Brave to clean this item.".to_string());
+}
+
+/// Bundle id of the browser whose data lives at `path_str`, if it's one we know.
+pub fn browser_for_path(path_str: &str) -> Option<(&'static str, &'static str)> {
+    BROWSERS.iter()
+        // Before Safari was sandboxed its cookies lived in ~/Library/Cookies
+        .find(|(name, _, marker)| path_str.contains(marker) || (*name == "Safari" && path_str.ends_with("Cookies.binarycookies")))
+        .map(|(name, bundle_id, _)| (*name, *bundle_id))
+}
+
+/// Clean a browser data file. History and cookie databases have just the rows picked by `options`
+/// removed and are vacuumed, Safari's cookie file loses the cookies of `options.cookie_domains`;
+/// other files and folders (session data, saved passwords, site data) are moved to the Trash.
+pub fn clean_privacy_item(path_str: &str, options: &super::browser_db::PrivacyCleanOptions) -> Result<(), String> {
+    let path = Path::new(path_str);
+    
+    // Safety Check: Is the browser owning this data running?
+    if let Some((name, bundle_id)) = browser_for_path(path_str) {
+        if crate::scanners::process::is_bundle_id_running(bundle_id) {
+            return Err(format!("Please close {} to clean this item.", name));
+        }
     }
 
     if path.exists() {
-        // For SQLite DBs (History, Cookies), deleting the file is the nuclear option.
-        // It clears everything.
-        // Ideally we'd use rusqlite to execute "DELETE FROM ...", but that requires locking.
-        // For v2.2 MVP, we delete the file (Chrome/Safari will recreate empty on restart).
-        // WARNING: This logs user out of sites (Cookies) or clears all history.
-        
+        // Safari keeps logins in the keychain, so there's no way to tell login cookies apart here
+        if path_str.ends_with(".binarycookies") && !options.cookie_domains.is_empty() {
+            let removed = super::safari_data::remove_cookies(path, &options.cookie_domains)?;
+            println!("[Privacy] Removed {} cookie(s) from {}", removed, path_str);
+            return Ok(());
+        }
+        if let Some(rows) = super::browser_db::clean(path, options)? {
+            println!("[Privacy] Removed {} row(s) from {}", rows, path_str);
+            return Ok(());
+        }
         trash::delete(path).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_chromium_and_firefox_profile_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let chrome = dir.path().join("Google/Chrome");
+        for folder in ["Default", "Profile 2", "System Profile"] {
+            fs::create_dir_all(chrome.join(folder)).unwrap();
+            fs::write(chrome.join(folder).join("Preferences"), "{}").unwrap();
+        }
+        fs::write(chrome.join("Local State"), r#"{"profile":{"info_cache":{"Default":{"name":"Personal"},"Profile 2":{"name":"Work"}}}}"#).unwrap();
+        let names: Vec<String> = chromium_profiles(&chrome).into_iter().map(|(_, name)| name).collect();
+        assert_eq!(names, vec!["Personal".to_string(), "Work".to_string()]);
+
+        let firefox = dir.path().join("Firefox");
+        fs::create_dir_all(&firefox).unwrap();
+        fs::write(firefox.join("profiles.ini"), "[Profile0]\nName=default-release\nIsRelative=1\nPath=Profiles/x1y2.default-release\n").unwrap();
+        assert_eq!(firefox_profile_names(&firefox).get("x1y2.default-release").map(|s| s.as_str()), Some("default-release"));
+    }
+}
@@ -1,13 +1,279 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
 use sysinfo::System;
 
-pub fn is_process_running(name_substr: &str) -> bool {
-    let mut sys = System::new_all();
-    sys.refresh_processes();
+/// Lookups reuse the shared snapshot if it is younger than this.
+const MAX_SNAPSHOT_AGE: Duration = Duration::from_secs(5);
 
-    for (_pid, process) in sys.processes() {
-        if process.name().to_lowercase().contains(&name_substr.to_lowercase()) {
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub exe: Option<String>,
+    /// Bundle id when this is the main executable of an .app (helpers nested in Frameworks don't count)
+    pub bundle_id: Option<String>,
+}
+
+struct Snapshot {
+    processes: Vec<ProcessInfo>,
+    taken_at: Instant,
+}
+
+lazy_static::lazy_static! {
+    // One System reused for every refresh instead of System::new_all() per query
+    static ref SYSTEM: Mutex<System> = Mutex::new(System::new());
+    static ref SNAPSHOT: RwLock<Option<Snapshot>> = RwLock::new(None);
+    // Info.plist lookups keyed by .app path; bundles rarely change while Alto runs
+    static ref BUNDLE_IDS: Mutex<HashMap<PathBuf, Option<String>>> = Mutex::new(HashMap::new());
+}
+
+/// `/X.app/Contents/MacOS/<exe>` -> `/X.app`
+fn main_bundle_of(exe: &Path) -> Option<&Path> {
+    let macos = exe.parent()?;
+    let contents = macos.parent()?;
+    let bundle = contents.parent()?;
+    let is_main = macos.file_name()? == "MacOS"
+        && contents.file_name()? == "Contents"
+        && bundle.extension()? == "app";
+    if is_main { Some(bundle) } else { None }
+}
+
+#[cfg(target_os = "macos")]
+fn bundle_id_for_exe(exe: &Path) -> Option<String> {
+    let bundle = main_bundle_of(exe)?;
+    BUNDLE_IDS.lock().unwrap()
+        .entry(bundle.to_path_buf())
+        .or_insert_with(|| crate::scanners::uninstaller::get_bundle_id(bundle))
+        .clone()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn bundle_id_for_exe(_exe: &Path) -> Option<String> {
+    None
+}
+
+/// Re-read the process table into the shared snapshot.
+pub fn refresh() {
+    let processes: Vec<ProcessInfo> = {
+        let mut sys = SYSTEM.lock().unwrap();
+        sys.refresh_processes();
+        sys.processes()
+            .iter()
+            .map(|(pid, p)| ProcessInfo {
+                pid: pid.as_u32(),
+                name: p.name().to_string(),
+                exe: p.exe().map(|e| e.to_string_lossy().to_string()),
+                bundle_id: p.exe().and_then(bundle_id_for_exe),
+            })
+            .collect()
+    };
+    *SNAPSHOT.write().unwrap() = Some(Snapshot { processes, taken_at: Instant::now() });
+}
+
+fn with_snapshot<T>(f: impl Fn(&[ProcessInfo]) -> T) -> T {
+    let fresh = SNAPSHOT.read().unwrap()
+        .as_ref()
+        .map(|s| s.taken_at.elapsed() < MAX_SNAPSHOT_AGE)
+        .unwrap_or(false);
+    if !fresh {
+        refresh();
+    }
+    let guard = SNAPSHOT.read().unwrap();
+    f(guard.as_ref().map(|s| s.processes.as_slice()).unwrap_or(&[]))
+}
+
+/// Processes whose executable lives inside `bundle_path` (e.g. "/Applications/Slack.app").
+pub fn find_by_bundle_path(bundle_path: &str) -> Vec<ProcessInfo> {
+    let bundle = Path::new(bundle_path);
+    with_snapshot(|procs| {
+        procs.iter()
+            .filter(|p| p.exe.as_ref().map(|e| Path::new(e).starts_with(bundle)).unwrap_or(false))
+            .cloned()
+            .collect()
+    })
+}
+
+pub fn is_bundle_running(bundle_path: &str) -> bool {
+    !find_by_bundle_path(bundle_path).is_empty()
+}
+
+/// Main app processes with the given bundle id (e.g. "com.google.Chrome").
+pub fn find_by_bundle_id(bundle_id: &str) -> Vec<ProcessInfo> {
+    with_snapshot(|procs| procs.iter().filter(|p| p.bundle_id.as_deref() == Some(bundle_id)).cloned().collect())
+}
+
+pub fn is_bundle_id_running(bundle_id: &str) -> bool {
+    !find_by_bundle_id(bundle_id).is_empty()
+}
+
+const QUIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuitResult {
+    pub bundle_id: String,
+    pub was_running: bool,
+    /// True once no process with the bundle id is left
+    pub quit: bool,
+    /// A SIGKILL was needed after the graceful quit timed out
+    pub forced: bool,
+}
+
+/// Wait until no main process with `bundle_id` remains, re-reading the process table each poll.
+fn wait_for_exit(bundle_id: &str, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        refresh();
+        if !is_bundle_id_running(bundle_id) {
             return true;
         }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(QUIT_POLL_INTERVAL);
+    }
+}
+
+fn signal(pids: &[ProcessInfo], sig: &str) {
+    for p in pids {
+        let _ = std::process::Command::new("kill").arg(sig).arg(p.pid.to_string()).status();
+    }
+}
+
+/// Ask an app to quit (as if the user chose Quit), wait up to `timeout`, then kill it if `force` is set.
+/// Blocking — call from a worker thread.
+pub fn quit_app(bundle_id: &str, force: bool, timeout: Duration) -> Result<QuitResult, String> {
+    refresh();
+    let running = find_by_bundle_id(bundle_id);
+    let mut result = QuitResult { bundle_id: bundle_id.to_string(), was_running: !running.is_empty(), quit: true, forced: false };
+    if running.is_empty() {
+        return Ok(result);
+    }
+
+    // Graceful quit lets the app flush its databases; SIGTERM when Apple Events aren't allowed
+    let script = format!("tell application id \"{}\" to quit", bundle_id.replace('"', ""));
+    match super::automation::run_osascript(&script) {
+        Ok(super::automation::ScriptOutcome::Ok(_)) => {}
+        _ => signal(&running, "-TERM"),
+    }
+
+    result.quit = wait_for_exit(bundle_id, timeout);
+    if !result.quit && force {
+        signal(&find_by_bundle_id(bundle_id), "-KILL");
+        result.forced = true;
+        result.quit = wait_for_exit(bundle_id, Duration::from_secs(2));
+    }
+    Ok(result)
+}
+
+/// Start an app again by bundle id (used after cleaning its data).
+pub fn relaunch_app(bundle_id: &str) -> Result<(), String> {
+    std::process::Command::new("open")
+        .args(["-g", "-b", bundle_id])
+        .status()
+        .map_err(|e| e.to_string())
+        .and_then(|s| if s.success() { Ok(()) } else { Err(format!("Could not relaunch {}", bundle_id)) })
+}
+
+/// A file about to be deleted that a running process has open.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenFile {
+    /// The path asked about (a folder when something inside it is open)
+    pub path: String,
+    pub pid: u32,
+    pub process: String,
+    /// The app the process belongs to, so the user can be asked to quit it
+    pub app_name: Option<String>,
+    pub bundle_id: Option<String>,
+}
+
+/// `lsof -F pcn` output as (pid, command, file name) for every open file.
+fn parse_lsof(output: &str) -> Vec<(u32, String, String)> {
+    let mut files = Vec::new();
+    let mut pid = 0u32;
+    let mut command = String::new();
+    for line in output.lines() {
+        let (tag, value) = line.split_at(line.len().min(1));
+        match tag {
+            "p" => pid = value.parse().unwrap_or(0),
+            "c" => command = value.to_string(),
+            "n" if value.starts_with('/') => files.push((pid, command.clone(), value.to_string())),
+            _ => {}
+        }
+    }
+    files
+}
+
+/// Which of `candidates` an open file pins: the file itself, a folder containing it, or the SQLite
+/// database whose -wal/-shm/-journal it is (deleting one half of a live database corrupts it).
+fn pinned_candidate<'a>(open: &str, candidates: &'a std::collections::HashSet<String>) -> Option<&'a String> {
+    let base = ["-wal", "-shm", "-journal"].iter().find_map(|s| open.strip_suffix(s));
+    std::iter::once(open)
+        .chain(base)
+        .find_map(|p| candidates.get(p))
+        .or_else(|| Path::new(open).ancestors().skip(1).find_map(|p| candidates.get(p.to_str()?)))
+}
+
+/// The outermost .app a process runs from ("Slack Helper" lives inside Slack.app).
+fn owning_app(exe: &Path) -> Option<&Path> {
+    exe.ancestors().filter(|p| p.extension().is_some_and(|e| e == "app")).last()
+}
+
+/// Files among `paths` (or inside them) that running processes have open, from one `lsof` pass.
+/// Empty when lsof isn't available.
+pub fn open_files(paths: &[String]) -> Vec<OpenFile> {
+    if paths.is_empty() {
+        return vec![];
+    }
+    let Ok(out) = std::process::Command::new("lsof").args(["-w", "-n", "-P", "-F", "pcn"]).output() else {
+        return vec![];
+    };
+    let candidates: std::collections::HashSet<String> = paths.iter().cloned().collect();
+    let exes: HashMap<u32, Option<String>> = with_snapshot(|procs| procs.iter().map(|p| (p.pid, p.exe.clone())).collect());
+    let mut found: Vec<OpenFile> = Vec::new();
+    for (pid, process, name) in parse_lsof(&String::from_utf8_lossy(&out.stdout)) {
+        let Some(path) = pinned_candidate(&name, &candidates) else { continue };
+        if found.iter().any(|f| &f.path == path && f.pid == pid) {
+            continue;
+        }
+        let app = exes.get(&pid).cloned().flatten().and_then(|exe| owning_app(Path::new(&exe)).map(Path::to_path_buf));
+        #[cfg(target_os = "macos")]
+        let bundle_id = app.as_deref().and_then(crate::scanners::uninstaller::get_bundle_id);
+        #[cfg(not(target_os = "macos"))]
+        let bundle_id = None;
+        found.push(OpenFile {
+            path: path.clone(),
+            pid,
+            process,
+            app_name: app.as_deref().and_then(|a| a.file_stem()).map(|n| n.to_string_lossy().to_string()),
+            bundle_id,
+        });
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_files_pinned_by_open_handles() {
+        let lsof = "p412\ncSlack Helper\nn/Users/me/Library/Caches/com.tinyspeck.slackmacgap/Cache.db-wal\nncount=3\np99\ncbash\nn/dev/ttys001\n";
+        let open = parse_lsof(lsof);
+        assert_eq!(open.len(), 2);
+        assert_eq!((open[0].0, open[0].1.as_str()), (412, "Slack Helper"));
+
+        let set = |paths: &[&str]| -> std::collections::HashSet<String> { paths.iter().map(|p| p.to_string()).collect() };
+        let database = set(&["/Users/me/Library/Caches/com.tinyspeck.slackmacgap/Cache.db"]);
+        let folder = set(&["/Users/me/Library/Caches/com.tinyspeck.slackmacgap"]);
+        assert!(pinned_candidate(&open[0].2, &database).is_some());
+        assert!(pinned_candidate(&open[0].2, &folder).is_some());
+        assert!(pinned_candidate(&open[0].2, &set(&["/Users/me/Library/Caches/com.tinyspeck.slack"])).is_none());
+        assert!(pinned_candidate(&open[1].2, &folder).is_none());
+
+        let helper = Path::new("/Applications/Slack.app/Contents/Frameworks/Slack Helper.app/Contents/MacOS/Slack Helper");
+        assert_eq!(owning_app(helper), Some(Path::new("/Applications/Slack.app")));
     }
-    false
 }
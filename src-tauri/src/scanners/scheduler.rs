@@ -4,8 +4,13 @@ use std::time::Duration;
 use chrono::Local;
 use cron::Schedule;
 use std::str::FromStr;
+use tauri::{AppHandle, Emitter};
 
 use serde::{Deserialize, Serialize};
+use crate::scanners::{malware, power};
+
+/// Jobs with this task type run the malware scan here rather than in the UI.
+pub const MALWARE_SCAN_TASK: &str = "malware_scan";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Job {
@@ -13,6 +18,12 @@ pub struct Job {
     pub schedule: String, // Cron expression
     pub task_type: String,
     pub next_run: Option<i64>,
+    /// Heavy jobs (deep scans, cleanups) are deferred while Low Power Mode / Focus is active.
+    #[serde(default)]
+    pub heavy: bool,
+    /// Scan profile the job runs with (None = default scopes)
+    #[serde(default)]
+    pub profile: Option<String>,
 }
 
 pub struct Scheduler {
@@ -47,7 +58,7 @@ impl Scheduler {
         }
     }
 
-    pub fn new() -> Self {
+    pub fn new(app: AppHandle) -> Self {
         let jobs: Arc<Mutex<Vec<Job>>> = Arc::new(Mutex::new(Self::load_jobs()));
         let jobs_clone = jobs.clone();
 
@@ -56,20 +67,44 @@ impl Scheduler {
             loop {
                 thread::sleep(Duration::from_secs(60)); // Check every minute
                 let mut jobs_lock = jobs_clone.lock().unwrap();
-                let _now = Local::now();
+                let now = Local::now().timestamp();
+                let mut changed = false;
 
                 for job in jobs_lock.iter_mut() {
-                    // Simple cron check logic would go here
-                    // For now, we just print
-                    println!("Checking job: {} - {}", job.id, job.task_type);
-                    
-                    if let Ok(schedule) = Schedule::from_str(&job.schedule) {
-                       if let Some(next) = schedule.upcoming(Local).next() {
-                           let _next_timestamp = next.timestamp();
-                           // Logic to trigger task execution would go here
-                           // involving emitting an event to frontend
-                       }
+                    let schedule = match Schedule::from_str(&job.schedule) {
+                        Ok(s) => s,
+                        Err(_) => continue,
+                    };
+                    let next = match job.next_run {
+                        Some(t) => t,
+                        None => {
+                            job.next_run = schedule.upcoming(Local).next().map(|n| n.timestamp());
+                            changed = true;
+                            continue;
+                        }
+                    };
+                    if now < next {
+                        continue;
+                    }
+
+                    // Job is due. Heavy jobs stay due (retried next minute) while the Mac is in a quiet state.
+                    if job.heavy && power::should_defer_heavy_jobs() {
+                        println!("[Scheduler] Deferring heavy job {} ({}): Low Power Mode or Focus active", job.id, job.task_type);
+                        continue;
                     }
+
+                    println!("[Scheduler] Running job: {} - {}", job.id, job.task_type);
+                    let _ = app.emit("scheduled-task", job.clone());
+                    if job.task_type == MALWARE_SCAN_TASK {
+                        let app = app.clone();
+                        thread::spawn(move || malware::run_scheduled_scan(&app));
+                    }
+                    job.next_run = schedule.upcoming(Local).next().map(|n| n.timestamp());
+                    changed = true;
+                }
+
+                if changed {
+                    Self::save_jobs(&jobs_lock);
                 }
             }
         });
@@ -77,7 +112,7 @@ impl Scheduler {
         Scheduler { jobs }
     }
 
-    pub fn add_job(&self, schedule: String, task_type: String) -> String {
+    pub fn add_job(&self, schedule: String, task_type: String, heavy: bool, profile: Option<String>) -> String {
         let mut jobs = self.jobs.lock().unwrap();
         let id = uuid::Uuid::new_v4().to_string();
         
@@ -86,6 +121,8 @@ impl Scheduler {
             schedule,
             task_type,
             next_run: None,
+            heavy,
+            profile,
         });
 
         Self::save_jobs(&jobs);
@@ -0,0 +1,330 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use super::ByteSize;
+use crate::mcp::context_store::{path_is_excluded, ContextStore};
+
+/// Age buckets (upper bound in days, label); anything older lands in "Older".
+const AGE_BUCKETS: [(u64, &str); 3] = [(7, "Last 7 days"), (30, "Last 30 days"), (90, "Last 90 days")];
+/// Folders searched when Spotlight has nothing (volume not indexed, or not macOS).
+const FALLBACK_FOLDERS: [&str; 4] = ["Desktop", "Documents", "Downloads", "Pictures"];
+/// Files passed to one mdls call.
+#[cfg(target_os = "macos")]
+const MDLS_BATCH: usize = 200;
+/// File types `screencapture` writes.
+const CAPTURE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "heic", "tiff", "pdf", "mov"];
+/// Extended attribute `screencapture` sets on every capture; Spotlight's kMDItemIsScreenCapture comes from it.
+#[cfg(target_os = "macos")]
+const CAPTURE_XATTR: &str = "com.apple.metadata:kMDItemIsScreenCapture";
+
+#[cfg(target_os = "macos")]
+mod ffi {
+    use std::os::raw::{c_char, c_int, c_void};
+
+    pub const XATTR_NOFOLLOW: c_int = 0x0001;
+
+    extern "C" {
+        pub fn getxattr(path: *const c_char, name: *const c_char, value: *mut c_void, size: usize, position: u32, options: c_int) -> isize;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Screenshot {
+    pub path: String,
+    pub size_bytes: u64,
+    /// unix seconds
+    pub created: i64,
+    pub age_days: u64,
+    /// "2560x1600" for full-screen captures, "Window" / "Selection" otherwise, "Unknown" without metadata
+    pub screen: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenshotGroup {
+    pub label: String,
+    pub count: usize,
+    pub size_bytes: u64,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenshotsReport {
+    /// Newest first
+    pub screenshots: Vec<Screenshot>,
+    pub by_age: Vec<ScreenshotGroup>,
+    pub by_screen: Vec<ScreenshotGroup>,
+    pub total_bytes: u64,
+    /// Where "move to a Screenshots folder" puts files by default
+    pub default_folder: String,
+}
+
+/// ~/Pictures/Screenshots; created on first move.
+pub fn default_folder() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("/")).join("Pictures").join("Screenshots")
+}
+
+/// Every file Spotlight marks as a screen capture (kMDItemIsScreenCapture), under the home folder.
+#[cfg(target_os = "macos")]
+fn spotlight_screenshots(home: &Path) -> Vec<PathBuf> {
+    std::process::Command::new("mdfind")
+        .arg("-onlyin")
+        .arg(home)
+        .arg("kMDItemIsScreenCapture == 1")
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(PathBuf::from).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn spotlight_screenshots(_home: &Path) -> Vec<PathBuf> {
+    vec![]
+}
+
+fn is_capture_type(path: &Path) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    CAPTURE_EXTENSIONS.contains(&ext.as_str())
+}
+
+/// Whether `path` carries the marker `screencapture` sets, rather than a (localized) "Screenshot"
+/// name. Read in-process, so checking thousands of files starts no processes.
+#[cfg(target_os = "macos")]
+pub fn has_capture_marker(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    let (Ok(c_path), Ok(name)) = (CString::new(path.as_os_str().as_bytes()), CString::new(CAPTURE_XATTR)) else {
+        return false;
+    };
+    // SAFETY: both strings are NUL-terminated; a null buffer of size 0 only asks for the value's length
+    unsafe { ffi::getxattr(c_path.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0, 0, ffi::XATTR_NOFOLLOW) >= 0 }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn has_capture_marker(path: &Path) -> bool {
+    path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("Screenshot"))
+}
+
+/// A screenshot or screen recording, verified by the capture marker.
+fn is_screen_capture(path: &Path) -> bool {
+    is_capture_type(path) && path.is_file() && has_capture_marker(path)
+}
+
+/// The folder picked in the Screenshot app's Options menu, when the user changed it.
+#[cfg(target_os = "macos")]
+fn capture_location() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    let prefs: serde_json::Value = plist::from_file(home.join("Library/Preferences/com.apple.screencapture.plist")).ok()?;
+    let location = prefs.get("location")?.as_str()?;
+    Some(match location.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(location),
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn capture_location() -> Option<PathBuf> {
+    None
+}
+
+/// A verified capture where screenshots pile up: the Desktop, the folder screencapture saves to,
+/// or the Screenshots folder Alto moves them into. Captures filed anywhere else count as the
+/// user's documents.
+pub fn is_verified_screenshot(path: &Path) -> bool {
+    if !is_screen_capture(path) {
+        return false;
+    }
+    let on_desktop = path.ancestors().skip(1).any(|a| a.file_name().is_some_and(|n| n.eq_ignore_ascii_case("Desktop")));
+    on_desktop || path.starts_with(default_folder()) || capture_location().is_some_and(|l| path.starts_with(l))
+}
+
+/// Screenshots and screen recordings in one folder, from a single Spotlight query. Files Spotlight
+/// hasn't indexed (unindexed volume, a capture taken seconds ago) are checked for the marker instead.
+pub struct ScreenCaptures {
+    indexed: HashSet<PathBuf>,
+}
+
+impl ScreenCaptures {
+    pub fn in_folder(dir: &Path) -> Self {
+        Self { indexed: spotlight_screenshots(dir).into_iter().collect() }
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        is_capture_type(path) && (self.indexed.contains(path) || has_capture_marker(path))
+    }
+}
+
+/// One Spotlight attribute for many files; `mdls -raw` separates the values with NUL in argument order.
+#[cfg(target_os = "macos")]
+fn mdls_values(attribute: &str, paths: &[PathBuf]) -> Vec<Option<String>> {
+    let mut values = Vec::with_capacity(paths.len());
+    for batch in paths.chunks(MDLS_BATCH) {
+        let out = std::process::Command::new("mdls")
+            .args(["-raw", "-name", attribute])
+            .args(batch)
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+            .unwrap_or_default();
+        let mut parsed: Vec<Option<String>> = out
+            .split('\0')
+            .map(|v| Some(v.trim().trim_matches('"').to_string()).filter(|v| !v.is_empty() && v != "(null)"))
+            .collect();
+        // A failed call or unexpected output leaves the batch without metadata rather than misaligned
+        parsed.resize(batch.len(), None);
+        values.extend(parsed);
+    }
+    values
+}
+
+#[cfg(target_os = "macos")]
+fn screen_labels(paths: &[PathBuf]) -> Vec<String> {
+    let kinds = mdls_values("kMDItemScreenCaptureType", paths);
+    let widths = mdls_values("kMDItemPixelWidth", paths);
+    let heights = mdls_values("kMDItemPixelHeight", paths);
+    (0..paths.len())
+        .map(|i| screen_label(kinds[i].as_deref(), widths[i].as_deref(), heights[i].as_deref()))
+        .collect()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn screen_labels(paths: &[PathBuf]) -> Vec<String> {
+    vec!["Unknown".to_string(); paths.len()]
+}
+
+/// Full-screen captures are grouped by resolution, which tells displays apart.
+fn screen_label(kind: Option<&str>, width: Option<&str>, height: Option<&str>) -> String {
+    match kind {
+        Some("window") => "Window".to_string(),
+        Some("selection") => "Selection".to_string(),
+        _ => match (width, height) {
+            (Some(w), Some(h)) => format!("{}x{}", w, h),
+            _ => "Unknown".to_string(),
+        },
+    }
+}
+
+fn age_label(age_days: u64) -> &'static str {
+    AGE_BUCKETS.iter().find(|(max, _)| age_days < *max).map(|(_, label)| *label).unwrap_or("Older")
+}
+
+fn group_by<'a>(shots: &'a [Screenshot], key: impl Fn(&'a Screenshot) -> String) -> Vec<ScreenshotGroup> {
+    let mut groups: BTreeMap<String, ScreenshotGroup> = BTreeMap::new();
+    for shot in shots {
+        let label = key(shot);
+        let group = groups
+            .entry(label.clone())
+            .or_insert_with(|| ScreenshotGroup { label, count: 0, size_bytes: 0, paths: vec![] });
+        group.count += 1;
+        group.size_bytes += shot.size_bytes;
+        group.paths.push(shot.path.clone());
+    }
+    groups.into_values().collect()
+}
+
+/// Screenshots and screen recordings anywhere in the home folder, grouped by age and by screen.
+/// Found through Spotlight; common folders are walked when Spotlight returns nothing.
+pub fn scan_screenshots() -> ScreenshotsReport {
+    let _priority = super::priority::lower_scan_priority();
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let excluded = ContextStore::load().user_preferences.skipped_paths();
+
+    let mut paths = spotlight_screenshots(&home);
+    if paths.is_empty() {
+        paths = FALLBACK_FOLDERS
+            .iter()
+            .flat_map(|f| WalkDir::new(home.join(f)).max_depth(3).follow_links(false).into_iter().filter_map(|e| e.ok()))
+            .filter(|e| e.file_type().is_file() && is_capture_type(e.path()) && has_capture_marker(e.path()))
+            .map(|e| e.into_path())
+            .collect();
+    }
+    paths.retain(|p| p.is_file() && !path_is_excluded(&p.to_string_lossy(), &excluded));
+
+    let now = SystemTime::now();
+    let labels = screen_labels(&paths);
+    let mut screenshots: Vec<Screenshot> = paths
+        .iter()
+        .zip(labels)
+        .filter_map(|(path, screen)| {
+            let meta = std::fs::metadata(path).ok()?;
+            let created_at = meta.created().or_else(|_| meta.modified()).ok()?;
+            Some(Screenshot {
+                path: path.to_string_lossy().to_string(),
+                size_bytes: ByteSize::of(&meta).allocated,
+                created: created_at.duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0),
+                age_days: now.duration_since(created_at).map(|d| d.as_secs() / 86_400).unwrap_or(0),
+                screen,
+            })
+        })
+        .collect();
+    screenshots.sort_by_key(|s| std::cmp::Reverse(s.created));
+
+    let mut by_age = group_by(&screenshots, |s| age_label(s.age_days).to_string());
+    let order = |label: &str| AGE_BUCKETS.iter().position(|(_, l)| *l == label).unwrap_or(AGE_BUCKETS.len());
+    by_age.sort_by_key(|g| order(&g.label));
+    let mut by_screen = group_by(&screenshots, |s| s.screen.clone());
+    by_screen.sort_by_key(|g| std::cmp::Reverse(g.size_bytes));
+
+    ScreenshotsReport {
+        total_bytes: screenshots.iter().map(|s| s.size_bytes).sum(),
+        screenshots,
+        by_age,
+        by_screen,
+        default_folder: default_folder().to_string_lossy().to_string(),
+    }
+}
+
+/// Paths of screenshots at least `days` old, for the bulk "trash older than" action.
+pub fn older_than(report: &ScreenshotsReport, days: u64) -> Vec<String> {
+    report.screenshots.iter().filter(|s| s.age_days >= days).map(|s| s.path.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_screens_and_ages() {
+        assert_eq!(screen_label(Some("display"), Some("2560"), Some("1600")), "2560x1600");
+        assert_eq!(screen_label(Some("window"), Some("800"), Some("600")), "Window");
+        assert_eq!(screen_label(None, None, None), "Unknown");
+        assert_eq!(age_label(0), "Last 7 days");
+        assert_eq!(age_label(29), "Last 30 days");
+        assert_eq!(age_label(400), "Older");
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn old_desktop_screenshots_get_trashed() {
+        use crate::mcp::file_index::index_file_with;
+        use crate::mcp::whitelist::Whitelist;
+
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(home.path().join("Desktop")).unwrap();
+        let (old, recent) = (home.path().join("Desktop/Screenshot old.png"), home.path().join("Desktop/Screenshot new.png"));
+        for shot in [&old, &recent] {
+            std::fs::write(shot, b"png").unwrap();
+            std::process::Command::new("xattr").args(["-w", CAPTURE_XATTR, "1"]).arg(shot).status().unwrap();
+        }
+        let shot = |path: &Path, age_days| Screenshot {
+            path: path.to_string_lossy().to_string(),
+            size_bytes: 3,
+            created: 0,
+            age_days,
+            screen: "Unknown".to_string(),
+        };
+        let screenshots = vec![shot(&recent, 2), shot(&old, 40)];
+        let report = ScreenshotsReport { total_bytes: 6, by_age: vec![], by_screen: vec![], default_folder: String::new(), screenshots };
+
+        // The same gate and trash step confirm_delete applies
+        let paths = older_than(&report, 30);
+        assert_eq!(paths, vec![old.to_string_lossy().to_string()]);
+        let whitelist = Whitelist::new(&[]);
+        let items: Vec<(String, u64)> = paths.iter().filter(|p| index_file_with(p, &whitelist).is_safe_to_delete).map(|p| (p.clone(), 3)).collect();
+        assert_eq!(items.len(), 1);
+        let result = super::super::trash::trash_in_batches(&items, |_| {});
+        assert_eq!(result.removed.len(), 1);
+        assert!(!old.exists());
+        assert!(recent.exists());
+    }
+}
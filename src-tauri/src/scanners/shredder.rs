@@ -43,8 +43,12 @@ fn overwrite_file(path: &Path) -> Result<(), String> {
 
 // Secure delete: Overwrite with 3 passes then rename then delete
 pub fn shred_path(path_str: &str) -> Result<(), String> {
-    let path = Path::new(path_str);
-    
+    crate::mcp::self_protect::check("Shredder", path_str)?;
+    shred(Path::new(path_str))
+}
+
+/// Shred without the self-protection check, for Alto's own quarantine vault.
+pub(crate) fn shred(path: &Path) -> Result<(), String> {
     if !path.exists() {
         return Err("Path does not exist".to_string());
     }
@@ -67,3 +71,15 @@ pub fn shred_path(path_str: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_to_shred_alto_data() {
+        let dir = dirs::home_dir().unwrap().join(".alto");
+        let err = shred_path(&dir.join("context.json").to_string_lossy()).unwrap_err();
+        assert!(err.contains("belongs to Alto"));
+    }
+}
@@ -0,0 +1,383 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use walkdir::WalkDir;
+
+use crate::mcp::context_store::{path_is_excluded, ContextStore};
+
+/// Folders searched, relative to home. Photos libraries inside them are packages and skipped.
+const PHOTO_FOLDERS: &[&str] = &["Pictures", "Downloads", "Desktop"];
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "heic", "heif", "tif", "tiff", "gif", "bmp", "webp", "dng", "cr2", "cr3", "nef", "arw"];
+/// Files larger than this aren't thumbnailed (panoramas, huge TIFF scans).
+const MAX_IMAGE_BYTES: u64 = 200 * 1024 * 1024;
+const MIN_IMAGE_BYTES: u64 = 16 * 1024;
+const MAX_IMAGES: usize = 20_000;
+const SCAN_TIMEOUT_SECS: u64 = 300;
+/// Hashes are computed from a HASH_SIDE x HASH_SIDE grayscale thumbnail.
+const HASH_SIDE: usize = 32;
+/// pHash bits that may differ for two images to count as near-duplicates.
+const NEAR_DUPLICATE_DISTANCE: u32 = 8;
+/// Shots this close in time and this similar are treated as one burst.
+const BURST_WINDOW_SECS: i64 = 10;
+const BURST_DISTANCE: u32 = 18;
+const PROGRESS_EVERY: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarPhoto {
+    pub path: String,
+    pub size_bytes: u64,
+    /// unix seconds
+    pub modified: i64,
+    /// The copy suggested for keeping (the largest file, usually the best quality)
+    pub keep: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarPhotoGroup {
+    /// "near_duplicate" | "burst"
+    pub kind: String,
+    pub photos: Vec<SimilarPhoto>,
+    /// Bytes freed by removing every photo except the one marked `keep`
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarPhotosReport {
+    pub groups: Vec<SimilarPhotoGroup>,
+    pub images_checked: usize,
+    pub reclaimable_bytes: u64,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarPhotosProgress {
+    pub images_found: usize,
+    pub images_hashed: usize,
+}
+
+/// Hashes from earlier runs, keyed by path; reused while size and mtime are unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedHash {
+    size: u64,
+    modified: i64,
+    phash: u64,
+}
+
+struct Candidate {
+    path: PathBuf,
+    size: u64,
+    modified: i64,
+}
+
+fn cache_path() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("photo_hashes.json")
+}
+
+fn load_cache() -> HashMap<String, CachedHash> {
+    std::fs::read_to_string(cache_path())
+        .ok()
+        .and_then(|d| serde_json::from_str(&d).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &HashMap<String, CachedHash>) {
+    let path = cache_path();
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string(cache) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .map(|x| IMAGE_EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Decode a PNG into HASH_SIDE x HASH_SIDE luminance values, box-averaging if it's larger.
+fn png_gray(bytes: &[u8]) -> Option<Vec<f64>> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0u8; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).ok()?;
+    let (w, h) = (info.width as usize, info.height as usize);
+    if w == 0 || h == 0 {
+        return None;
+    }
+    let channels = info.color_type.samples();
+    let luma = |x: usize, y: usize| -> f64 {
+        let i = (y * w + x) * channels;
+        match channels {
+            1 | 2 => buf[i] as f64,
+            _ => 0.299 * buf[i] as f64 + 0.587 * buf[i + 1] as f64 + 0.114 * buf[i + 2] as f64,
+        }
+    };
+    let mut out = vec![0.0; HASH_SIDE * HASH_SIDE];
+    for ty in 0..HASH_SIDE {
+        for tx in 0..HASH_SIDE {
+            let (x0, x1) = (tx * w / HASH_SIDE, ((tx + 1) * w / HASH_SIDE).max(tx * w / HASH_SIDE + 1).min(w));
+            let (y0, y1) = (ty * h / HASH_SIDE, ((ty + 1) * h / HASH_SIDE).max(ty * h / HASH_SIDE + 1).min(h));
+            let mut sum = 0.0;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    sum += luma(x, y);
+                }
+            }
+            out[ty * HASH_SIDE + tx] = sum / ((x1 - x0) * (y1 - y0)) as f64;
+        }
+    }
+    Some(out)
+}
+
+/// A tiny grayscale thumbnail. On macOS `sips` does the decoding (JPEG, HEIC, RAW, ...) and
+/// writes a HASH_SIDE-pixel PNG, so full-size images are never loaded into Alto's memory.
+#[cfg(target_os = "macos")]
+fn thumbnail(path: &Path) -> Option<Vec<f64>> {
+    let out = std::env::temp_dir().join(format!("alto-thumb-{}.png", uuid::Uuid::new_v4()));
+    let side = HASH_SIDE.to_string();
+    let status = std::process::Command::new("sips")
+        .args(["-s", "format", "png", "-z", &side, &side])
+        .arg(path)
+        .arg("--out")
+        .arg(&out)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .ok()?;
+    let bytes = std::fs::read(&out).ok();
+    let _ = std::fs::remove_file(&out);
+    if !status.success() {
+        return None;
+    }
+    png_gray(&bytes?)
+}
+
+/// Without sips only PNGs can be decoded.
+#[cfg(not(target_os = "macos"))]
+fn thumbnail(path: &Path) -> Option<Vec<f64>> {
+    if path.extension().map(|x| !x.eq_ignore_ascii_case("png")).unwrap_or(true) {
+        return None;
+    }
+    png_gray(&std::fs::read(path).ok()?)
+}
+
+/// DCT-based perceptual hash: the top-left 8x8 low frequencies, each compared to the median of the AC terms.
+fn phash(gray: &[f64]) -> u64 {
+    let n = HASH_SIDE;
+    let cos: Vec<f64> = (0..8 * n)
+        .map(|i| {
+            let (u, x) = (i / n, i % n);
+            ((2 * x + 1) as f64 * u as f64 * std::f64::consts::PI / (2 * n) as f64).cos()
+        })
+        .collect();
+    let mut coeffs = Vec::with_capacity(64);
+    for u in 0..8 {
+        for v in 0..8 {
+            let mut sum = 0.0;
+            for y in 0..n {
+                for x in 0..n {
+                    sum += gray[y * n + x] * cos[u * n + y] * cos[v * n + x];
+                }
+            }
+            coeffs.push(sum);
+        }
+    }
+    let mut ac: Vec<f64> = coeffs[1..].to_vec();
+    ac.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let median = ac[ac.len() / 2];
+    coeffs.iter().enumerate().filter(|(_, c)| **c > median).fold(0u64, |h, (i, _)| h | (1 << i))
+}
+
+fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut i = i;
+    while parent[i] != root {
+        let next = parent[i];
+        parent[i] = root;
+        i = next;
+    }
+    root
+}
+
+/// Union images that look alike, or that were shot seconds apart and look similar.
+fn cluster(hashed: &[(Candidate, u64)]) -> Vec<SimilarPhotoGroup> {
+    let mut parent: Vec<usize> = (0..hashed.len()).collect();
+    for i in 0..hashed.len() {
+        for j in i + 1..hashed.len() {
+            let d = distance(hashed[i].1, hashed[j].1);
+            let same_moment = (hashed[i].0.modified - hashed[j].0.modified).abs() <= BURST_WINDOW_SECS
+                && hashed[i].0.path.parent() == hashed[j].0.path.parent();
+            if d <= NEAR_DUPLICATE_DISTANCE || (same_moment && d <= BURST_DISTANCE) {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                parent[a] = b;
+            }
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..hashed.len() {
+        let root = find(&mut parent, i);
+        members.entry(root).or_default().push(i);
+    }
+    let mut groups: Vec<SimilarPhotoGroup> = members
+        .into_values()
+        .filter(|m| m.len() > 1)
+        .map(|mut m| {
+            m.sort_by_key(|&i| std::cmp::Reverse(hashed[i].0.size));
+            let times: Vec<i64> = m.iter().map(|&i| hashed[i].0.modified).collect();
+            let span = times.iter().max().unwrap_or(&0) - times.iter().min().unwrap_or(&0);
+            let photos: Vec<SimilarPhoto> = m
+                .iter()
+                .enumerate()
+                .map(|(rank, &i)| SimilarPhoto {
+                    path: hashed[i].0.path.to_string_lossy().to_string(),
+                    size_bytes: hashed[i].0.size,
+                    modified: hashed[i].0.modified,
+                    keep: rank == 0,
+                })
+                .collect();
+            SimilarPhotoGroup {
+                kind: if span <= BURST_WINDOW_SECS * photos.len() as i64 { "burst" } else { "near_duplicate" }.to_string(),
+                reclaimable_bytes: photos.iter().filter(|p| !p.keep).map(|p| p.size_bytes).sum(),
+                photos,
+            }
+        })
+        .collect();
+    groups.sort_by_key(|g| std::cmp::Reverse(g.reclaimable_bytes));
+    groups
+}
+
+fn candidates(home: &Path, excluded: &[String]) -> (Vec<Candidate>, bool) {
+    let mut found = Vec::new();
+    for folder in PHOTO_FOLDERS {
+        let walker = WalkDir::new(home.join(folder)).follow_links(false).into_iter().filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0
+                || !(name.starts_with('.')
+                    || super::space_lens::is_package(e.path())
+                    || path_is_excluded(&e.path().to_string_lossy(), excluded))
+        });
+        for entry in walker.filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() || !is_image(entry.path()) {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else { continue };
+            // Cloud-only files would be downloaded just to be hashed
+            if meta.len() < MIN_IMAGE_BYTES || meta.len() > MAX_IMAGE_BYTES || super::is_dataless(&meta) {
+                continue;
+            }
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            found.push(Candidate { path: entry.into_path(), size: meta.len(), modified });
+            if found.len() >= MAX_IMAGES {
+                return (found, true);
+            }
+        }
+    }
+    (found, false)
+}
+
+/// Near-duplicate photos and burst shots in Pictures, Downloads and Desktop, grouped by perceptual
+/// hash and sorted by reclaimable size.
+pub fn scan_similar_photos(on_progress: &(dyn Fn(SimilarPhotosProgress) + Sync)) -> SimilarPhotosReport {
+    let _priority = super::priority::lower_scan_priority();
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let excluded = ContextStore::load().user_preferences.skipped_paths();
+    let (found, mut truncated) = candidates(&home, &excluded);
+    let images_found = found.len();
+    on_progress(SimilarPhotosProgress { images_found, images_hashed: 0 });
+
+    let cache = load_cache();
+    let deadline = Instant::now() + Duration::from_secs(SCAN_TIMEOUT_SECS);
+    let hashed_count = AtomicUsize::new(0);
+    let results: Vec<(Candidate, Option<u64>)> = super::size_index::in_pool(|| {
+        found
+            .into_par_iter()
+            .map(|c| {
+                let key = c.path.to_string_lossy().to_string();
+                let hash = match cache.get(&key) {
+                    Some(h) if h.size == c.size && h.modified == c.modified => Some(h.phash),
+                    _ if Instant::now() >= deadline => None,
+                    _ => thumbnail(&c.path).map(|g| phash(&g)),
+                };
+                let done = hashed_count.fetch_add(1, Ordering::Relaxed) + 1;
+                if done % PROGRESS_EVERY == 0 {
+                    on_progress(SimilarPhotosProgress { images_found, images_hashed: done });
+                }
+                (c, hash)
+            })
+            .collect()
+    });
+    truncated |= Instant::now() >= deadline;
+
+    let hashed: Vec<(Candidate, u64)> = results.into_iter().filter_map(|(c, h)| h.map(|h| (c, h))).collect();
+    let new_cache: HashMap<String, CachedHash> = hashed
+        .iter()
+        .map(|(c, h)| (c.path.to_string_lossy().to_string(), CachedHash { size: c.size, modified: c.modified, phash: *h }))
+        .collect();
+    save_cache(&new_cache);
+
+    let groups = cluster(&hashed);
+    SimilarPhotosReport {
+        images_checked: hashed.len(),
+        reclaimable_bytes: groups.iter().map(|g| g.reclaimable_bytes).sum(),
+        groups,
+        truncated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic noise texture in 0..200, offset by `shift`.
+    fn texture(shift: f64) -> Vec<f64> {
+        let mut seed = 42u32;
+        (0..HASH_SIDE * HASH_SIDE)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                ((seed >> 16) % 200) as f64 + shift
+            })
+            .collect()
+    }
+
+    #[test]
+    fn similar_images_hash_close_and_different_ones_far() {
+        let a = phash(&texture(0.0));
+        let brighter = phash(&texture(30.0));
+        let mirrored: Vec<f64> =
+            texture(0.0).chunks(HASH_SIDE).flat_map(|r| r.iter().rev().copied().collect::<Vec<_>>()).collect();
+        assert!(distance(a, brighter) <= NEAR_DUPLICATE_DISTANCE);
+        assert!(distance(a, phash(&mirrored)) > NEAR_DUPLICATE_DISTANCE);
+    }
+
+    #[test]
+    fn keeps_the_largest_copy() {
+        let c = |p: &str, size: u64, modified: i64| Candidate { path: PathBuf::from(p), size, modified };
+        let hashed = vec![(c("/p/a.jpg", 100, 0), 0u64), (c("/p/b.jpg", 300, 5000), 1u64), (c("/p/c.jpg", 50, 0), u64::MAX)];
+        let groups = cluster(&hashed);
+        assert_eq!(groups.len(), 1);
+        assert!(groups[0].photos[0].keep && groups[0].photos[0].path == "/p/b.jpg");
+        assert_eq!(groups[0].reclaimable_bytes, 100);
+        assert_eq!(groups[0].kind, "near_duplicate");
+    }
+}
@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use rayon::prelude::*;
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use super::ByteSize;
+
+/// One directory's own contents. Totals are rebuilt from these, so a change deep in the tree only
+/// rescans the folders on its path.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DirRecord {
+    /// Directory mtime (unix nanos); changes when entries are added, removed or renamed
+    mtime: i128,
+    files_logical: u64,
+    files_allocated: u64,
+    /// Names of subdirectories (symlinks excluded)
+    subdirs: Vec<String>,
+    total_logical: u64,
+    total_allocated: u64,
+}
+
+#[derive(Default)]
+struct SizeIndex {
+    loaded: bool,
+    changed: bool,
+    dirs: HashMap<String, DirRecord>,
+    /// Totals confirmed since the watcher started; trusted without touching the disk
+    verified: HashSet<String>,
+}
+
+/// Directory walks share at most this many threads.
+const MAX_WALK_THREADS: usize = 8;
+
+lazy_static::lazy_static! {
+    static ref INDEX: Mutex<SizeIndex> = Mutex::new(SizeIndex::default());
+    static ref POOL: Option<rayon::ThreadPool> = rayon::ThreadPoolBuilder::new()
+        .num_threads(std::thread::available_parallelism().map(|n| n.get().min(MAX_WALK_THREADS)).unwrap_or(4))
+        .thread_name(|i| format!("alto-walk-{}", i))
+        .build()
+        .ok();
+}
+
+/// Set once FSEvents are being delivered for the home folder; until then every total is re-checked by mtime.
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+fn index_path() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("size_index.json")
+}
+
+fn mtime_of(meta: &std::fs::Metadata) -> i128 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as i128)
+        .unwrap_or(0)
+}
+
+impl SizeIndex {
+    fn ensure_loaded(&mut self) {
+        if self.loaded {
+            return;
+        }
+        self.loaded = true;
+        if let Ok(data) = std::fs::read_to_string(index_path()) {
+            self.dirs = serde_json::from_str(&data).unwrap_or_default();
+        }
+    }
+
+    fn forget(&mut self, key: &str) {
+        let prefix = format!("{}/", key.trim_end_matches('/'));
+        self.dirs.retain(|p, _| p != key && !p.starts_with(&prefix));
+        self.verified.retain(|p| p != key && !p.starts_with(&prefix));
+    }
+
+    /// Something changed at `path`: its folder must be re-read and every ancestor's total re-summed.
+    fn invalidate(&mut self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            self.dirs.remove(parent.to_string_lossy().as_ref());
+        }
+        for ancestor in path.ancestors() {
+            self.verified.remove(ancestor.to_string_lossy().as_ref());
+        }
+    }
+}
+
+fn lock(index: &Mutex<SizeIndex>) -> MutexGuard<'_, SizeIndex> {
+    index.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Size of `path`, re-reading only folders whose mtime changed (or, with `trust_verified`,
+/// only folders FSEvents reported). Subfolders are measured in parallel; the lock is never held
+/// across disk access.
+fn measure(index: &Mutex<SizeIndex>, path: &Path, trust_verified: bool) -> ByteSize {
+    let key = path.to_string_lossy().to_string();
+    let known = {
+        let idx = lock(index);
+        if let Some(r) = idx.dirs.get(&key).filter(|_| trust_verified && idx.verified.contains(&key)) {
+            return ByteSize { logical: r.total_logical, allocated: r.total_allocated };
+        }
+        idx.dirs.get(&key).cloned()
+    };
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        lock(index).forget(&key);
+        return ByteSize::default();
+    };
+    let mtime = mtime_of(&meta);
+
+    let (mut record, reread) = match known {
+        Some(r) if r.mtime == mtime => (r, false),
+        previous => {
+            let fresh = read_dir_record(path, mtime);
+            // Folders that disappeared take their whole subtree out of the index
+            if let Some(previous) = previous {
+                let mut idx = lock(index);
+                for name in previous.subdirs.iter().filter(|s| !fresh.subdirs.contains(s)) {
+                    idx.forget(&path.join(name).to_string_lossy());
+                }
+            }
+            (fresh, true)
+        }
+    };
+
+    let files = ByteSize { logical: record.files_logical, allocated: record.files_allocated };
+    let total = files
+        + record
+            .subdirs
+            .par_iter()
+            .map(|name| measure(index, &path.join(name), trust_verified))
+            .sum::<ByteSize>();
+
+    let mut idx = lock(index);
+    if reread || record.total_logical != total.logical || record.total_allocated != total.allocated {
+        record.total_logical = total.logical;
+        record.total_allocated = total.allocated;
+        idx.changed = true;
+    }
+    idx.dirs.insert(key.clone(), record);
+    idx.verified.insert(key);
+    total
+}
+
+fn read_dir_record(path: &Path, mtime: i128) -> DirRecord {
+    let mut record = DirRecord { mtime, ..Default::default() };
+    let Ok(entries) = std::fs::read_dir(path) else { return record };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else { continue };
+        if file_type.is_dir() {
+            record.subdirs.push(entry.file_name().to_string_lossy().to_string());
+        } else if file_type.is_file() {
+            if let Ok(meta) = entry.metadata() {
+                let size = ByteSize::of(&meta);
+                record.files_logical += size.logical;
+                record.files_allocated += size.allocated;
+            }
+        }
+    }
+    record
+}
+
+/// Recursive size of the directory at `path`, from the index where it's still current.
+/// Same accounting as `dir_byte_size` (regular files only, symlinks not followed).
+pub fn dir_size(path: &Path) -> ByteSize {
+    lock(&INDEX).ensure_loaded();
+    in_pool(|| measure(&INDEX, path, WATCHING.load(Ordering::Relaxed)))
+}
+
+/// Run `f` on the bounded pool used for directory walks, so parallel measuring never takes every core.
+pub fn in_pool<R: Send>(f: impl FnOnce() -> R + Send) -> R {
+    match POOL.as_ref() {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
+}
+
+/// Recursive sizes (allocated bytes) of `root` and its subfolders down to `max_depth` levels,
+/// brought up to date first.
+pub fn subtree_sizes(root: &Path, max_depth: usize) -> HashMap<String, u64> {
+    let mut sizes = HashMap::new();
+    dir_size(root);
+    let index = lock(&INDEX);
+    let mut stack = vec![(root.to_path_buf(), 0usize)];
+    while let Some((dir, depth)) = stack.pop() {
+        let Some(record) = index.dirs.get(dir.to_string_lossy().as_ref()) else { continue };
+        sizes.insert(dir.to_string_lossy().to_string(), record.total_allocated);
+        if depth < max_depth {
+            stack.extend(record.subdirs.iter().map(|name| (dir.join(name), depth + 1)));
+        }
+    }
+    sizes
+}
+
+/// Drop what the index knows at and above `path`, e.g. after Alto deleted it.
+pub fn invalidate(path: &Path) {
+    let mut index = lock(&INDEX);
+    index.forget(&path.to_string_lossy());
+    index.invalidate(path);
+}
+
+/// Write the index to ~/.alto if it changed since the last save.
+pub fn save() {
+    let mut index = lock(&INDEX);
+    if !index.changed {
+        return;
+    }
+    let path = index_path();
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string(&index.dirs) {
+        // Write-then-rename so a crash mid-write never leaves a truncated index
+        let tmp = path.with_extension("json.tmp");
+        if std::fs::write(&tmp, json).is_ok() && std::fs::rename(&tmp, &path).is_ok() {
+            index.changed = false;
+        }
+    }
+}
+
+/// Keep the home folder's index current in the background: FSEvents invalidate changed folders,
+/// and the whole tree is measured once at startup so the first Space Lens scan is instant.
+pub fn start_background_indexing() {
+    let Some(home) = dirs::home_dir() else { return };
+    std::thread::spawn(move || {
+        #[cfg(target_os = "macos")]
+        let _watcher = {
+            use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+            let alto_dir = home.join(".alto");
+            let watcher = RecommendedWatcher::new(
+                move |res: notify::Result<notify::Event>| {
+                    let Ok(event) = res else { return };
+                    let mut index = lock(&INDEX);
+                    for path in event.paths.iter().filter(|p| !p.starts_with(&alto_dir)) {
+                        index.invalidate(path);
+                    }
+                },
+                Config::default(),
+            )
+            .and_then(|mut w| w.watch(&home, RecursiveMode::Recursive).map(|_| w));
+            match watcher {
+                Ok(w) => {
+                    WATCHING.store(true, Ordering::Relaxed);
+                    Some(w)
+                }
+                Err(e) => {
+                    eprintln!("[SizeIndex] Failed to watch home folder: {}", e);
+                    None
+                }
+            }
+        };
+
+        {
+            let _priority = super::priority::lower_scan_priority();
+            dir_size(&home);
+        }
+        save();
+        super::space_lens_history::record_snapshot_if_due();
+
+        // The watcher lives as long as this thread; persist periodically so a crash loses little
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(300));
+            save();
+            super::space_lens_history::record_snapshot_if_due();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totals_match_a_full_walk_and_follow_changes() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("a/b")).unwrap();
+        std::fs::write(root.path().join("a/one.bin"), vec![1u8; 1000]).unwrap();
+        std::fs::write(root.path().join("a/b/two.bin"), vec![1u8; 3000]).unwrap();
+
+        let index = Mutex::new(SizeIndex { loaded: true, ..Default::default() });
+        assert_eq!(measure(&index, root.path(), true), super::super::dir_byte_size(root.path()));
+
+        std::fs::remove_dir_all(root.path().join("a/b")).unwrap();
+        lock(&index).invalidate(&root.path().join("a/b"));
+        assert_eq!(measure(&index, root.path(), true).logical, 1000);
+        assert!(!lock(&index).dirs.contains_key(root.path().join("a/b").to_string_lossy().as_ref()));
+    }
+}
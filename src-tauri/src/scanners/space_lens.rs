@@ -1,20 +1,119 @@
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
-use walkdir::WalkDir;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use super::{size_index, ByteSize};
+
+/// Directory extensions macOS presents as a single file (apps, libraries, documents, VM bundles).
+const PACKAGE_EXTENSIONS: &[&str] = &[
+    "app", "appex", "framework", "bundle", "plugin", "kext", "xpc", "photoslibrary", "musiclibrary",
+    "tvlibrary", "imovielibrary", "fcpbundle", "logicx", "band", "pages", "numbers", "key", "rtfd",
+    "xcarchive", "dsym", "pvm", "utm", "vmwarevm", "sparsebundle",
+];
+
+/// Folder sizes computed by recent scans are reused for this long when the user drills in.
+const SIZE_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+lazy_static::lazy_static! {
+    /// Folder path -> size and when it was measured, filled by every scan.
+    static ref SIZE_CACHE: Mutex<HashMap<String, (ByteSize, Instant)>> = Mutex::new(HashMap::new());
+}
+
+/// True for package directories, which the tree shows as one leaf instead of descending into.
+pub fn is_package(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .map(|x| PACKAGE_EXTENSIONS.contains(&x.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+        && path.is_dir()
+}
+
+fn cache_size(path: &str, size: ByteSize) {
+    if let Ok(mut cache) = SIZE_CACHE.lock() {
+        cache.insert(path.to_string(), (size, Instant::now()));
+    }
+}
+
+fn cached_size(path: &str) -> Option<ByteSize> {
+    let cache = SIZE_CACHE.lock().ok()?;
+    cache.get(path).filter(|(_, at)| at.elapsed() < SIZE_CACHE_TTL).map(|(size, _)| *size)
+}
+
+/// Forget cached sizes at, below and above `path` (its ancestors' totals include it), e.g. after it was deleted.
+pub fn invalidate_cached_sizes(path: &str) {
+    size_index::invalidate(Path::new(path));
+    if let Ok(mut cache) = SIZE_CACHE.lock() {
+        let target = Path::new(path);
+        cache.retain(|p, _| {
+            let cached = Path::new(p);
+            !(cached.starts_with(target) || target.starts_with(cached))
+        });
+    }
+}
 
 #[derive(Debug, Serialize, Clone)]
 pub struct FileNode {
     pub name: String,
     pub path: String,
+    /// On-disk size (same as `allocated_bytes`), used for layout and totals
     pub size: u64,
+    pub logical_bytes: u64,
+    pub allocated_bytes: u64,
     pub children: Option<Vec<FileNode>>, // None if file, Some if dir
     pub is_dir: bool,
+    /// Package directory (.app, .photoslibrary, ...) shown as a leaf with its total size
+    pub is_package: bool,
+}
+
+/// Flattened tree for the webview: nodes point at their parent by index and carry only their
+/// own name, so full paths aren't repeated for every node. The root is `nodes[0]`.
+#[derive(Debug, Serialize)]
+pub struct CompactTree {
+    pub root_path: String,
+    pub nodes: Vec<CompactNode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompactNode {
+    pub name: String,
+    pub size: u64,
+    pub parent: Option<u32>,
+    pub is_dir: bool,
+    /// False when the directory's children were not scanned (depth limit) — same as `children: None`
+    pub expanded: bool,
+    pub is_package: bool,
+}
+
+/// Pre-order flattening, so a parent always precedes its children.
+pub fn compact_tree(root: &FileNode) -> CompactTree {
+    let mut nodes = Vec::new();
+    let mut stack: Vec<(&FileNode, Option<u32>)> = vec![(root, None)];
+    while let Some((node, parent)) = stack.pop() {
+        let id = nodes.len() as u32;
+        nodes.push(CompactNode {
+            name: node.name.clone(),
+            size: node.size,
+            parent,
+            is_dir: node.is_dir,
+            expanded: node.children.is_some(),
+            is_package: node.is_package,
+        });
+        if let Some(children) = &node.children {
+            // Reversed so children come out in their (size-sorted) order
+            for child in children.iter().rev() {
+                stack.push((child, Some(id)));
+            }
+        }
+    }
+    CompactTree { root_path: root.path.clone(), nodes }
 }
 
 pub fn scan_space_lens(path: &str, depth_limit: u32) -> FileNode {
     let root = Path::new(path);
-    scan_node(root, 0, depth_limit)
+    size_index::in_pool(|| scan_node(root, 0, depth_limit))
 }
 
 fn scan_node(path: &Path, current_depth: u32, depth_limit: u32) -> FileNode {
@@ -27,13 +126,16 @@ fn scan_node(path: &Path, current_depth: u32, depth_limit: u32) -> FileNode {
     
     // Check if it's a directory
     if !path.is_dir() {
-        let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let size = fs::symlink_metadata(path).map(|m| ByteSize::of(&m)).unwrap_or_default();
         return FileNode {
             name,
             path: path_str,
-            size,
+            size: size.allocated,
+            logical_bytes: size.logical,
+            allocated_bytes: size.allocated,
             children: None,
             is_dir: false,
+            is_package: false,
         };
     }
 
@@ -42,51 +144,128 @@ fn scan_node(path: &Path, current_depth: u32, depth_limit: u32) -> FileNode {
     // Optimization: If we have reached the depth limit, we stop building the tree structure
     // and just calculate the size of this directory efficiently using WalkDir.
     // This avoids allocating FileNodes for the entire subtree.
-    if current_depth >= depth_limit {
-        let size = get_dir_size(path);
+    // Packages are one item to the user: sized as a whole, never descended into.
+    let package = is_package(path);
+    if current_depth >= depth_limit || package {
+        let size = size_index::dir_size(path);
+        cache_size(&path_str, size);
         return FileNode {
             name,
             path: path_str,
-            size,
+            size: size.allocated,
+            logical_bytes: size.logical,
+            allocated_bytes: size.allocated,
             children: None, // Logic: we stopped here
             is_dir: true,
+            is_package: package,
         };
     }
 
-    // If within depth limit, we scan children recursively
-    let mut total_size = 0;
-    let mut children_nodes = Vec::new();
-
-    if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries.filter_map(|e| e.ok()) {
-            let child_path = entry.path();
-            let child_node = scan_node(&child_path, current_depth + 1, depth_limit);
-            
-            // Only add child size if it's valid (already calculated inside child_node)
-            total_size += child_node.size;
-            children_nodes.push(child_node);
-        }
-    }
-    
+    // If within depth limit, we scan children recursively; sibling subtrees in parallel
+    let child_paths: Vec<PathBuf> = fs::read_dir(path)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    let mut children_nodes: Vec<FileNode> = child_paths
+        .par_iter()
+        .map(|child_path| scan_node(child_path, current_depth + 1, depth_limit))
+        .collect();
+    let total_size: ByteSize = children_nodes
+        .iter()
+        .map(|c| ByteSize { logical: c.logical_bytes, allocated: c.allocated_bytes })
+        .sum();
+
     // Sort children by size desc
     children_nodes.sort_by(|a, b| b.size.cmp(&a.size));
+    cache_size(&path_str, total_size);
 
     FileNode {
         name,
         path: path_str,
-        size: total_size,
+        size: total_size.allocated,
+        logical_bytes: total_size.logical,
+        allocated_bytes: total_size.allocated,
         children: Some(children_nodes),
         is_dir: true,
+        is_package: false,
     }
 }
 
-/// efficiently calculates directory size without building a tree
-fn get_dir_size(path: &Path) -> u64 {
-    WalkDir::new(path)
+
+/// One level of the tree at `path`: its direct children, with folder sizes from the cache where a
+/// recent scan measured them. Used when the user clicks into a folder instead of rescanning.
+pub fn expand_node(path: &str) -> FileNode {
+    let dir = Path::new(path);
+    let mut children: Vec<FileNode> = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect::<Vec<_>>())
+        .unwrap_or_default()
         .into_iter()
-        .filter_map(|entry| entry.ok())
-        .filter_map(|entry| entry.metadata().ok())
-        .filter(|metadata| metadata.is_file())
-        .map(|m| m.len())
-        .sum()
+        .map(|child| {
+            let child_str = child.to_string_lossy().to_string();
+            match cached_size(&child_str) {
+                Some(size) if child.is_dir() => FileNode {
+                    name: child.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                    path: child_str,
+                    size: size.allocated,
+                    logical_bytes: size.logical,
+                    allocated_bytes: size.allocated,
+                    children: None,
+                    is_dir: true,
+                    is_package: is_package(&child),
+                },
+                _ => scan_node(&child, 0, 0),
+            }
+        })
+        .collect();
+    children.sort_by_key(|c| std::cmp::Reverse(c.size));
+
+    let total: ByteSize = children
+        .iter()
+        .map(|c| ByteSize { logical: c.logical_bytes, allocated: c.allocated_bytes })
+        .sum();
+    cache_size(path, total);
+    FileNode {
+        name: dir.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        path: path.to_string(),
+        size: total.allocated,
+        logical_bytes: total.logical,
+        allocated_bytes: total.allocated,
+        children: Some(children),
+        is_dir: true,
+        is_package: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_uses_cached_folder_sizes() {
+        let root = tempfile::tempdir().unwrap();
+        let sub = root.path().join("sub");
+        fs::create_dir(&sub).unwrap();
+        fs::write(sub.join("a.bin"), vec![1u8; 4096]).unwrap();
+        let sub_str = sub.to_string_lossy().to_string();
+
+        let fake = ByteSize { logical: 1, allocated: 123 };
+        cache_size(&sub_str, fake);
+        let node = expand_node(&root.path().to_string_lossy());
+        assert_eq!(node.children.as_ref().unwrap()[0].size, 123);
+
+        invalidate_cached_sizes(&root.path().to_string_lossy());
+        let node = expand_node(&root.path().to_string_lossy());
+        assert_eq!(node.children.as_ref().unwrap()[0].logical_bytes, 4096);
+    }
+
+    #[test]
+    fn packages_are_leaves_with_their_total_size() {
+        let root = tempfile::tempdir().unwrap();
+        let binary = root.path().join("Tool.app/Contents/MacOS");
+        fs::create_dir_all(&binary).unwrap();
+        fs::write(binary.join("Tool"), vec![1u8; 2048]).unwrap();
+        let tree = scan_space_lens(&root.path().to_string_lossy(), 4);
+        let app = &tree.children.as_ref().unwrap()[0];
+        assert!(app.is_package && app.children.is_none());
+        assert_eq!(app.logical_bytes, 2048);
+    }
 }
@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::size_index;
+
+/// Levels below the home folder kept per snapshot; deeper changes show up in their ancestors.
+const SNAPSHOT_DEPTH: usize = 4;
+/// Folders smaller than this are left out of snapshots.
+const MIN_RECORDED_BYTES: u64 = 10 * 1024 * 1024;
+/// At most one automatic snapshot per this many seconds.
+const SNAPSHOT_INTERVAL_SECS: i64 = 24 * 3600;
+const MAX_SNAPSHOTS: usize = 90;
+/// Changes listed in a diff, largest first.
+const MAX_DIFF_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Snapshot {
+    taken_at: String,
+    root: String,
+    /// Folder path -> allocated bytes
+    sizes: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotInfo {
+    /// Unix seconds; pass to `space_lens_diff_command`
+    pub id: String,
+    pub taken_at: String,
+    pub root: String,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirChange {
+    pub path: String,
+    pub from_bytes: u64,
+    pub to_bytes: u64,
+    /// Positive when the folder grew
+    pub delta_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpaceLensDiff {
+    pub from: SnapshotInfo,
+    pub to: SnapshotInfo,
+    pub total_delta_bytes: i64,
+    pub changes: Vec<DirChange>,
+}
+
+fn history_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("space_lens_history")
+}
+
+fn snapshot_ids() -> Vec<i64> {
+    let mut ids: Vec<i64> = std::fs::read_dir(history_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.path().file_stem()?.to_str()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    ids.sort();
+    ids
+}
+
+fn load(id: i64) -> Option<Snapshot> {
+    let data = std::fs::read_to_string(history_dir().join(format!("{}.json", id))).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+fn info(id: &str, snapshot: &Snapshot) -> SnapshotInfo {
+    SnapshotInfo {
+        id: id.to_string(),
+        taken_at: snapshot.taken_at.clone(),
+        root: snapshot.root.clone(),
+        total_bytes: snapshot.sizes.get(&snapshot.root).copied().unwrap_or(0),
+    }
+}
+
+fn current(root: &Path) -> Snapshot {
+    let mut sizes = size_index::subtree_sizes(root, SNAPSHOT_DEPTH);
+    let root_str = root.to_string_lossy().to_string();
+    sizes.retain(|p, size| *size >= MIN_RECORDED_BYTES || *p == root_str);
+    Snapshot { taken_at: chrono::Local::now().to_rfc3339(), root: root_str, sizes }
+}
+
+/// Take a snapshot of the home folder's sizes unless one was taken within the last day.
+/// Sizes come from the directory-size index, so this is cheap once it is warm.
+pub fn record_snapshot_if_due() {
+    let Some(home) = dirs::home_dir() else { return };
+    let now = chrono::Utc::now().timestamp();
+    let ids = snapshot_ids();
+    if ids.last().map(|last| now - last < SNAPSHOT_INTERVAL_SECS).unwrap_or(false) {
+        return;
+    }
+    let dir = history_dir();
+    let _ = std::fs::create_dir_all(&dir);
+    if let Ok(json) = serde_json::to_string(&current(&home)) {
+        let _ = std::fs::write(dir.join(format!("{}.json", now)), json);
+    }
+    for old in ids.iter().rev().skip(MAX_SNAPSHOTS - 1) {
+        let _ = std::fs::remove_file(dir.join(format!("{}.json", old)));
+    }
+}
+
+/// Stored snapshots, oldest first.
+pub fn list_snapshots() -> Vec<SnapshotInfo> {
+    snapshot_ids()
+        .into_iter()
+        .filter_map(|id| load(id).map(|s| info(&id.to_string(), &s)))
+        .collect()
+}
+
+fn diff_sizes(from: &HashMap<String, u64>, to: &HashMap<String, u64>) -> Vec<DirChange> {
+    let paths: HashSet<&String> = from.keys().chain(to.keys()).collect();
+    let mut changes: Vec<DirChange> = paths
+        .into_iter()
+        .filter_map(|path| {
+            // Folders below the size floor in one snapshot count as empty there
+            let from_bytes = from.get(path).copied().unwrap_or(0);
+            let to_bytes = to.get(path).copied().unwrap_or(0);
+            let delta_bytes = to_bytes as i64 - from_bytes as i64;
+            (delta_bytes.unsigned_abs() >= MIN_RECORDED_BYTES).then(|| DirChange {
+                path: path.clone(),
+                from_bytes,
+                to_bytes,
+                delta_bytes,
+            })
+        })
+        .collect();
+    changes.sort_by_key(|c| std::cmp::Reverse(c.delta_bytes.unsigned_abs()));
+    changes.truncate(MAX_DIFF_ENTRIES);
+    changes
+}
+
+/// Which folders grew or shrank between snapshot `from` and snapshot `to` (current sizes when `to` is None).
+pub fn diff(from: &str, to: Option<&str>) -> Result<SpaceLensDiff, String> {
+    let parse = |id: &str| id.parse::<i64>().map_err(|_| format!("Invalid snapshot id: {}", id));
+    let from_snapshot = load(parse(from)?).ok_or_else(|| format!("Snapshot not found: {}", from))?;
+    let (to_id, to_snapshot) = match to {
+        Some(id) => (id.to_string(), load(parse(id)?).ok_or_else(|| format!("Snapshot not found: {}", id))?),
+        None => ("now".to_string(), current(Path::new(&from_snapshot.root))),
+    };
+    if to_snapshot.root != from_snapshot.root {
+        return Err("Snapshots are of different folders".to_string());
+    }
+    let from_info = info(from, &from_snapshot);
+    let to_info = info(&to_id, &to_snapshot);
+    Ok(SpaceLensDiff {
+        total_delta_bytes: to_info.total_bytes as i64 - from_info.total_bytes as i64,
+        changes: diff_sizes(&from_snapshot.sizes, &to_snapshot.sizes),
+        from: from_info,
+        to: to_info,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lists_growth_and_shrinkage_largest_first() {
+        const MB: u64 = 1024 * 1024;
+        let from: HashMap<String, u64> =
+            [("/h/Movies", 100 * MB), ("/h/Downloads", 50 * MB), ("/h/Docs", 20 * MB)].map(|(p, s)| (p.to_string(), s)).into();
+        let to: HashMap<String, u64> =
+            [("/h/Movies", 140 * MB), ("/h/Docs", 21 * MB), ("/h/Xcode", 30 * MB)].map(|(p, s)| (p.to_string(), s)).into();
+        let changes = diff_sizes(&from, &to);
+        let summary: Vec<(&str, i64)> = changes.iter().map(|c| (c.path.as_str(), c.delta_bytes / MB as i64)).collect();
+        assert_eq!(summary, vec![("/h/Downloads", -50), ("/h/Movies", 40), ("/h/Xcode", 30)]);
+    }
+}
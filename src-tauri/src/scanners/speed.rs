@@ -7,6 +7,33 @@ pub struct SpeedTaskResult {
     pub status: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SpeedRecommendation {
+    pub id: String,
+    pub title: String,
+    pub detail: String,
+    /// "low" | "medium" | "high"
+    pub impact: String,
+}
+
+/// Startup-impact findings for the Speed section.
+pub fn recommendations() -> Vec<SpeedRecommendation> {
+    let mut recs = Vec::new();
+    let fonts = super::fonts::startup_impact();
+    if fonts.impact != "low" {
+        recs.push(SpeedRecommendation {
+            id: "deactivate_fonts".to_string(),
+            title: "Deactivate unused fonts".to_string(),
+            detail: format!(
+                "{} third-party fonts ({} yours, {} shared) are loaded at login. Deactivating rarely used families in Font Book speeds up login and app launch.",
+                fonts.total, fonts.user_fonts, fonts.local_fonts
+            ),
+            impact: fonts.impact,
+        });
+    }
+    recs
+}
+
 pub fn run_optimization_task(task_id: &str) -> SpeedTaskResult {
     match task_id {
         "flush_dns" => {
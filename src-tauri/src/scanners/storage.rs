@@ -0,0 +1,82 @@
+use serde::Serialize;
+use crate::mcp::context_store::ContextStore;
+use crate::scanners::space_lens::FileNode;
+use crate::scanners::system_stats::root_disk_usage;
+
+/// Segments measured by scans; "System Data" is whatever used space they don't explain.
+const MEASURED_SEGMENTS: &[&str] = &["Apps", "Documents", "Developer", "Trash", "Purgeable"];
+
+/// Top-level home folders that Finder counts as "Documents".
+const DOCUMENT_FOLDERS: &[&str] = &["Documents", "Desktop", "Downloads", "Pictures", "Movies", "Music"];
+
+#[derive(Debug, Serialize)]
+pub struct StorageSegment {
+    pub name: String,
+    pub size_bytes: u64,
+    /// When the segment was last measured; None if no scan has measured it yet
+    pub measured_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageSummary {
+    pub disk_total: u64,
+    pub disk_used: u64,
+    pub segments: Vec<StorageSegment>,
+}
+
+/// Map a deep scan category label to a storage map segment.
+pub fn segment_for_deep_scan_label(label: &str) -> Option<&'static str> {
+    if label == "Trash" {
+        Some("Trash")
+    } else if ["Xcode", "Simulator", "NPM", "Yarn", "Gradle", "Maven"].iter().any(|k| label.contains(k)) {
+        Some("Developer")
+    } else {
+        None
+    }
+}
+
+/// Sum of the document folders in a Space Lens tree rooted at the home directory.
+pub fn documents_size_from_home_tree(home: &FileNode) -> Option<u64> {
+    let children = home.children.as_ref()?;
+    Some(children.iter()
+        .filter(|c| DOCUMENT_FOLDERS.contains(&c.name.as_str()))
+        .map(|c| c.size)
+        .sum())
+}
+
+/// Finder-style stacked breakdown built only from cached scan results.
+pub fn storage_summary() -> StorageSummary {
+    let (disk_total, disk_used) = root_disk_usage();
+    let ctx = ContextStore::load();
+
+    // Purgeable space is cheap to query live; the rest comes from earlier scans
+    let purgeable = super::apfs::purgeable().purgeable_bytes;
+    let mut segments: Vec<StorageSegment> = MEASURED_SEGMENTS.iter().map(|name| {
+        if let Some(bytes) = purgeable.filter(|_| *name == "Purgeable") {
+            return StorageSegment {
+                name: name.to_string(),
+                size_bytes: bytes,
+                measured_at: Some(chrono::Local::now().to_rfc3339()),
+            };
+        }
+        let cached = ctx.storage_cache.get(*name);
+        StorageSegment {
+            name: name.to_string(),
+            size_bytes: cached.map(|c| c.size_bytes).unwrap_or(0),
+            measured_at: cached.map(|c| c.measured_at.clone()),
+        }
+    }).collect();
+
+    let explained: u64 = segments.iter().map(|s| s.size_bytes).sum();
+    segments.push(StorageSegment {
+        name: "System Data".to_string(),
+        size_bytes: disk_used.saturating_sub(explained),
+        measured_at: None,
+    });
+
+    StorageSummary {
+        disk_total,
+        disk_used,
+        segments,
+    }
+}
@@ -0,0 +1,140 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::{apfs, dir_byte_size, size_index, storage};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemDataCategory {
+    pub name: String,
+    /// None when macOS doesn't expose the size (e.g. local snapshots)
+    pub size_bytes: Option<u64>,
+    pub locations: Vec<String>,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemDataReport {
+    /// "System Data" as the storage summary computes it
+    pub system_data_bytes: u64,
+    pub categories: Vec<SystemDataCategory>,
+    /// System Data not attributed to any category
+    pub unexplained_bytes: u64,
+}
+
+/// Size of folders Alto can read; folders under home come from the size index.
+fn folders_size(home: &Path, folders: &[PathBuf]) -> u64 {
+    folders
+        .iter()
+        .filter(|p| p.is_dir())
+        .map(|p| if p.starts_with(home) { size_index::dir_size(p).allocated } else { dir_byte_size(p).allocated })
+        .sum()
+}
+
+fn category(name: &str, home: &Path, folders: Vec<PathBuf>, detail: &str) -> SystemDataCategory {
+    SystemDataCategory {
+        name: name.to_string(),
+        size_bytes: Some(folders_size(home, &folders)),
+        locations: folders.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        detail: detail.to_string(),
+    }
+}
+
+/// Used space of the boot container's volumes with the given APFS role (VM, Preboot, Recovery, Update).
+fn role_volumes(containers: &[apfs::ApfsContainer], roles: &[&str]) -> (u64, Vec<String>) {
+    let boot = apfs::container_for_mount(containers, "/");
+    let volumes: Vec<&apfs::ApfsVolume> = boot
+        .iter()
+        .flat_map(|c| c.volumes.iter())
+        .filter(|v| v.roles.iter().any(|r| roles.contains(&r.as_str())))
+        .collect();
+    let used = volumes.iter().map(|v| v.used_bytes).sum();
+    let names = volumes.iter().map(|v| v.mount_point.clone().unwrap_or_else(|| v.name.clone())).collect();
+    (used, names)
+}
+
+/// Break macOS's "System Data" down into caches, logs, local snapshots, app support, container
+/// data, VM/sleep image files and the hidden system volumes.
+pub fn analyze_system_data() -> SystemDataReport {
+    let _priority = super::priority::lower_scan_priority();
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let containers = apfs::containers();
+    let system_data_bytes = storage::storage_summary()
+        .segments
+        .iter()
+        .find(|s| s.name == "System Data")
+        .map(|s| s.size_bytes)
+        .unwrap_or(0);
+
+    let mut categories = vec![
+        category(
+            "Caches",
+            &home,
+            vec![home.join("Library/Caches"), PathBuf::from("/Library/Caches"), PathBuf::from("/private/var/folders")],
+            "Rebuilt automatically; Junk Cleanup removes the safe parts",
+        ),
+        category(
+            "Logs",
+            &home,
+            vec![home.join("Library/Logs"), PathBuf::from("/Library/Logs"), PathBuf::from("/private/var/log")],
+            "Diagnostic logs and crash reports",
+        ),
+        category(
+            "App Support",
+            &home,
+            vec![home.join("Library/Application Support"), PathBuf::from("/Library/Application Support")],
+            "App data such as databases, downloaded models and iOS backups",
+        ),
+        category(
+            "Container Data",
+            &home,
+            vec![home.join("Library/Containers"), home.join("Library/Group Containers")],
+            "Data of sandboxed apps (Mail, Messages, App Store apps)",
+        ),
+    ];
+
+    // Swap lives on its own VM volume on APFS; measure the folder where it doesn't
+    let (vm_bytes, vm_volumes) = match role_volumes(&containers, &["VM"]) {
+        (_, volumes) if volumes.is_empty() => {
+            let vm = PathBuf::from("/private/var/vm");
+            (dir_byte_size(&vm).allocated, vec![vm.to_string_lossy().to_string()])
+        }
+        found => found,
+    };
+    categories.push(SystemDataCategory {
+        name: "Swap & Sleep Image".to_string(),
+        size_bytes: Some(vm_bytes),
+        locations: vm_volumes,
+        detail: "Virtual memory swap files and the hibernation image; shrink after a restart".to_string(),
+    });
+    let (system_bytes, system_volumes) = role_volumes(&containers, &["Preboot", "Recovery", "Update"]);
+    categories.push(SystemDataCategory {
+        name: "System Volumes".to_string(),
+        size_bytes: Some(system_bytes),
+        locations: system_volumes,
+        detail: "Preboot, Recovery and staged macOS updates; managed by macOS".to_string(),
+    });
+
+    let snapshots = apfs::purgeable().local_snapshots;
+    categories.push(SystemDataCategory {
+        name: "Time Machine Local Snapshots".to_string(),
+        size_bytes: None,
+        detail: if snapshots.is_empty() {
+            "No local snapshots".to_string()
+        } else {
+            format!(
+                "{} snapshot(s), oldest {}; macOS frees them when space is needed (counted in Purgeable)",
+                snapshots.len(),
+                snapshots[0]
+            )
+        },
+        locations: snapshots,
+    });
+
+    let attributed: u64 = categories.iter().filter_map(|c| c.size_bytes).sum();
+    categories.sort_by_key(|c| std::cmp::Reverse(c.size_bytes.unwrap_or(0)));
+    SystemDataReport {
+        system_data_bytes,
+        unexplained_bytes: system_data_bytes.saturating_sub(attributed),
+        categories,
+    }
+}
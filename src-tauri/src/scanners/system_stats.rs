@@ -18,13 +18,31 @@ pub struct DeviceInfo {
     pub is_connected: bool,
 }
 
+/// One mounted volume. Volumes sharing an APFS container report the container's shared
+/// free space as `available`, so only sum `available` once per `container_id`.
+#[derive(Serialize)]
+pub struct VolumeStats {
+    pub name: String,
+    pub mount_point: String,
+    pub total: u64,
+    pub available: u64,
+    /// APFS container (e.g. "disk3"); None for non-APFS volumes
+    pub container_id: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct SystemStats {
     pub cpu_load: f32,
     pub memory_used: u64,
     pub memory_total: u64,
+    /// Boot container capacity and usage (all its volumes), as Disk Utility shows them
     pub disk_total: u64,
     pub disk_used: u64,
+    pub disk_container_id: Option<String>,
+    /// Space macOS frees on demand (included in `disk_used`); Finder counts it as available
+    pub disk_purgeable: Option<u64>,
+    pub local_snapshot_count: usize,
+    pub volumes: Vec<VolumeStats>,
     pub network_up: u64,
     pub network_down: u64,
     pub battery_level: Option<f32>,
@@ -95,46 +113,129 @@ fn get_connected_devices() -> Vec<DeviceInfo> {
     }
 }
 
-pub fn get_stats() -> SystemStats {
-    // 1. CPU & Memory
-    let mut sys = SYSTEM.lock().unwrap();
-    sys.refresh_cpu_specifics(CpuRefreshKind::everything());
-    sys.refresh_memory();
-    
-    let cpu_load = sys.global_cpu_info().cpu_usage();
-    let memory_used = sys.used_memory();
-    let memory_total = sys.total_memory();
-
-    // 2. Disks
+/// Total and used bytes of the boot disk. On APFS, "Macintosh HD" and "Macintosh HD - Data" (plus
+/// Preboot, VM, ...) share one container; the container's capacity and free space are what Disk
+/// Utility reports, so they're used instead of the sealed system volume's own figures.
+pub fn root_disk_usage() -> (u64, u64) {
+    if let Some(container) = super::apfs::container_for_mount(&super::apfs::containers(), "/") {
+        if container.total_bytes > 0 {
+            return (container.total_bytes, container.used_bytes());
+        }
+    }
     let mut disks = DISKS.lock().unwrap();
     disks.refresh_list(); // Refresh list in case of mounts/unmounts
-    
-    let mut disk_total = 0;
-    let mut disk_used = 0;
-    
+
     // Find the main disk
     #[cfg(target_os = "macos")]
     let root_path = std::path::Path::new("/");
-    
+
     #[cfg(target_os = "windows")]
     let root_path = std::path::Path::new("C:\\");
 
-    let mut found_root = false;
     for disk in disks.list() {
         if disk.mount_point() == root_path {
-            disk_total = disk.total_space();
-            disk_used = disk.total_space() - disk.available_space();
-            found_root = true;
-            break;
+            return (disk.total_space(), disk.total_space() - disk.available_space());
         }
     }
     // Fallback if no specific root found (rare)
-    if !found_root {
-        if let Some(disk) = disks.list().first() {
-             disk_total = disk.total_space();
-             disk_used = disk.total_space() - disk.available_space();
-        }
+    disks.list().first()
+        .map(|disk| (disk.total_space(), disk.total_space() - disk.available_space()))
+        .unwrap_or((0, 0))
+}
+
+/// File systems of network shares; scanning them is slow and can wake remote servers.
+const NETWORK_FILE_SYSTEMS: &[&str] = &["smbfs", "nfs", "afpfs", "webdav", "cifs", "ftp", "autofs", "9p"];
+
+/// A mounted disk other than the boot volume (USB/Thunderbolt drives, disk images, network shares).
+#[derive(Debug, Clone, Serialize)]
+pub struct ExternalVolume {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total: u64,
+    pub available: u64,
+    pub is_removable: bool,
+    pub is_network: bool,
+}
+
+pub fn is_network_file_system(fs: &str) -> bool {
+    NETWORK_FILE_SYSTEMS.contains(&fs.to_ascii_lowercase().as_str())
+}
+
+fn is_external_mount(mount: &std::path::Path) -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        mount.starts_with("/Volumes") && mount != std::path::Path::new("/Volumes")
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        mount.starts_with("/media") || mount.starts_with("/mnt") || mount.starts_with("/run/media")
     }
+}
+
+/// Mounted external volumes; network shares only when `include_network` is set.
+pub fn external_volumes(include_network: bool) -> Vec<ExternalVolume> {
+    let mut disks = DISKS.lock().unwrap();
+    disks.refresh_list();
+    disks
+        .list()
+        .iter()
+        .filter(|d| is_external_mount(d.mount_point()))
+        .map(|d| {
+            let file_system = d.file_system().to_string_lossy().to_string();
+            ExternalVolume {
+                name: d.name().to_string_lossy().to_string(),
+                mount_point: d.mount_point().to_string_lossy().to_string(),
+                is_network: is_network_file_system(&file_system),
+                file_system,
+                total: d.total_space(),
+                available: d.available_space(),
+                is_removable: d.is_removable(),
+            }
+        })
+        .filter(|v| include_network || !v.is_network)
+        .collect()
+}
+
+/// Mounted volumes annotated with their APFS container.
+pub fn volume_stats() -> Vec<VolumeStats> {
+    let containers = super::apfs::containers();
+    let mut disks = DISKS.lock().unwrap();
+    disks.refresh_list();
+    disks
+        .list()
+        .iter()
+        .map(|d| {
+            let mount_point = d.mount_point().to_string_lossy().to_string();
+            let container = super::apfs::container_for_mount(&containers, &mount_point);
+            VolumeStats {
+                name: d.name().to_string_lossy().to_string(),
+                total: container.as_ref().map(|c| c.total_bytes).filter(|t| *t > 0).unwrap_or(d.total_space()),
+                available: container.as_ref().map(|c| c.free_bytes).unwrap_or(d.available_space()),
+                container_id: container.map(|c| c.container_id),
+                mount_point,
+            }
+        })
+        .collect()
+}
+
+pub fn get_stats() -> SystemStats {
+    // 1. CPU & Memory
+    let mut sys = SYSTEM.lock().unwrap();
+    sys.refresh_cpu_specifics(CpuRefreshKind::everything());
+    sys.refresh_memory();
+    
+    let cpu_load = sys.global_cpu_info().cpu_usage();
+    let memory_used = sys.used_memory();
+    let memory_total = sys.total_memory();
+
+    // 2. Disks
+    let (disk_total, disk_used) = root_disk_usage();
+    let volumes = volume_stats();
+    let disk_container_id = volumes.iter()
+        .find(|v| v.mount_point == "/")
+        .and_then(|v| v.container_id.clone());
+    let purgeable = super::apfs::purgeable();
 
     // 3. Networks
     let mut networks = NETWORKS.lock().unwrap();
@@ -156,6 +257,10 @@ pub fn get_stats() -> SystemStats {
         memory_total,
         disk_total,
         disk_used,
+        disk_container_id,
+        disk_purgeable: purgeable.purgeable_bytes,
+        local_snapshot_count: purgeable.local_snapshots.len(),
+        volumes,
         network_up: up,
         network_down: down,
         battery_level: None, 
@@ -0,0 +1,690 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+use crate::helper_client::{self, Command};
+use crate::mcp::context_store::ContextStore;
+
+/// Top-level /Users/Shared entries macOS creates itself; never reported as clutter.
+const SHARED_SYSTEM_ENTRIES: &[&str] = &[".localized", "SC Info"];
+
+/// Paths per `trash::delete_all` call; one Finder round trip per batch.
+const DELETE_BATCH: usize = 200;
+
+/// Set by `cancel_delete`; a batched delete stops before its next batch.
+static DELETE_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Stop the running batched delete. What was trashed so far stays trashed and is reported.
+pub fn cancel_delete() {
+    DELETE_CANCELLED.store(true, Ordering::Relaxed);
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteProgress {
+    pub deleted: usize,
+    pub total: usize,
+    pub bytes_freed: u64,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BatchDeleteResult {
+    pub removed: Vec<String>,
+    pub bytes_freed: u64,
+    pub errors: Vec<String>,
+    pub cancelled: bool,
+    /// Items not attempted because of the cancel
+    pub remaining: usize,
+}
+
+/// Move `items` (path, size) to the Trash in batches, calling `on_progress` with cumulative totals
+/// after each. A batch that fails is retried path by path so one locked file doesn't stop the rest;
+/// items already gone by then were moved by the failed batch and count as trashed.
+pub fn trash_in_batches(items: &[(String, u64)], on_progress: impl Fn(&DeleteProgress)) -> BatchDeleteResult {
+    DELETE_CANCELLED.store(false, Ordering::Relaxed);
+    let mut result = BatchDeleteResult::default();
+    let mut attempted = 0;
+    for batch in items.chunks(DELETE_BATCH) {
+        if DELETE_CANCELLED.load(Ordering::Relaxed) {
+            result.cancelled = true;
+            break;
+        }
+        let refs: Vec<&str> = batch.iter().map(|(p, _)| p.as_str()).collect();
+        if ::trash::delete_all(&refs).is_ok() {
+            result.removed.extend(batch.iter().map(|(p, _)| p.clone()));
+            result.bytes_freed += batch.iter().map(|(_, size)| size).sum::<u64>();
+        } else {
+            for (path, size) in batch {
+                // A partly failed batch may already have moved this one
+                if std::fs::symlink_metadata(path).is_err() {
+                    result.removed.push(path.clone());
+                    result.bytes_freed += size;
+                    continue;
+                }
+                match ::trash::delete(path) {
+                    Ok(_) => {
+                        result.removed.push(path.clone());
+                        result.bytes_freed += size;
+                    }
+                    Err(e) => result.errors.push(format!("{}: {}", path, e)),
+                }
+            }
+        }
+        attempted += batch.len();
+        on_progress(&DeleteProgress {
+            deleted: result.removed.len(),
+            total: items.len(),
+            bytes_freed: result.bytes_freed,
+            failed: result.errors.len(),
+        });
+        // Let Finder and the UI catch up between batches
+        std::thread::yield_now();
+    }
+    result.remaining = items.len() - attempted;
+    result
+}
+
+/// `trash_in_batches` without progress, then record the cleanup with where each item went so it
+/// shows in the deletion history and can be undone.
+pub fn trash_and_record(items: &[(String, u64)]) -> BatchDeleteResult {
+    let result = trash_in_batches(items, |_| {});
+    if !result.removed.is_empty() {
+        let locations = locate_trashed_all(&result.removed);
+        ContextStore::load().record_deletion_with_locations(result.removed.clone(), result.bytes_freed, None, locations);
+    }
+    result
+}
+
+/// When an item was moved to the Trash. Moving a file updates its ctime (status change time),
+/// so for a top-level Trash entry that is the time it was trashed.
+pub fn trashed_at(entry: &Path) -> Option<SystemTime> {
+    let meta = std::fs::symlink_metadata(entry).ok()?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let secs = u64::try_from(meta.ctime()).ok()?;
+        Some(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+    }
+    #[cfg(not(unix))]
+    {
+        meta.modified().ok()
+    }
+}
+
+/// Cutoff for "trashed more than `days` days ago"; None when `days` is 0 (no age filter).
+pub fn age_cutoff(days: u32) -> Option<SystemTime> {
+    if days == 0 {
+        return None;
+    }
+    SystemTime::now().checked_sub(std::time::Duration::from_secs(days as u64 * 24 * 3600))
+}
+
+/// Empty `trash_dir` directly, without asking Finder. Only covers the local ~/.Trash (no iCloud items).
+/// With `trashed_before`, only entries trashed before that time are removed.
+/// Returns (items removed, bytes freed, errors).
+pub fn empty_trash_directly(trash_dir: &Path, trashed_before: Option<SystemTime>) -> (usize, u64, Vec<String>) {
+    let mut removed = 0usize;
+    let mut bytes_freed = 0u64;
+    let mut errors = Vec::new();
+
+    let entries = match std::fs::read_dir(trash_dir) {
+        Ok(e) => e,
+        Err(e) => return (0, 0, vec![format!("Cannot read {}: {}", trash_dir.display(), e)]),
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue; // .DS_Store and friends belong to Finder
+        }
+        if let Some(cutoff) = trashed_before {
+            if trashed_at(&path).map(|t| t >= cutoff).unwrap_or(true) {
+                continue;
+            }
+        }
+        match remove_permanently(&path) {
+            Ok(size) => {
+                removed += 1;
+                bytes_freed += size;
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+    (removed, bytes_freed, errors)
+}
+
+/// Delete a Trash entry (or any file or folder) for good; returns the bytes it took.
+pub fn remove_permanently(path: &Path) -> Result<u64, String> {
+    let meta = std::fs::symlink_metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let (size, result) = if meta.is_dir() {
+        (super::dir_byte_size(path).allocated, std::fs::remove_dir_all(path))
+    } else {
+        (super::allocated_bytes(&meta), std::fs::remove_file(path))
+    };
+    result.map(|_| size).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Permanently delete chosen items from the Trash, leaving the rest. Each path must be a top-level
+/// entry of one of `trash_dirs`; anything else is refused.
+/// Returns (items removed, bytes freed, errors).
+pub fn delete_trash_items(trash_dirs: &[PathBuf], paths: &[String]) -> (usize, u64, Vec<String>) {
+    let mut removed = 0usize;
+    let mut bytes_freed = 0u64;
+    let mut errors = Vec::new();
+    let trash_dirs: Vec<PathBuf> = trash_dirs.iter().map(|d| d.canonicalize().unwrap_or_else(|_| d.clone())).collect();
+    for path_str in paths {
+        let path = Path::new(path_str);
+        // Resolve the parent only, so a symlink in the Trash is removed rather than followed
+        let in_trash = path.file_name().is_some_and(|n| !n.to_string_lossy().starts_with('.'))
+            && path.parent().and_then(|p| p.canonicalize().ok()).is_some_and(|p| trash_dirs.contains(&p));
+        if !in_trash {
+            errors.push(format!("Not an item in the Trash: {}", path_str));
+            continue;
+        }
+        match remove_permanently(path) {
+            Ok(size) => {
+                removed += 1;
+                bytes_freed += size;
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+    (removed, bytes_freed, errors)
+}
+
+/// One top-level item in ~/.Trash.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashItem {
+    /// Name inside the Trash (Finder renames items that clash)
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub is_directory: bool,
+    /// unix seconds
+    pub trashed_at: i64,
+    /// Where the item was trashed from, when Finder or Alto's history knows
+    pub original_path: Option<String>,
+}
+
+/// UTF-16BE string of `units` code units at `at`.
+fn utf16_at(data: &[u8], at: usize, units: usize) -> Option<String> {
+    let bytes = data.get(at..at + units * 2)?;
+    let units: Vec<u16> = bytes.chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+    String::from_utf16(&units).ok()
+}
+
+fn u32_at(data: &[u8], at: usize) -> Option<usize> {
+    let b = data.get(at..at + 4)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+}
+
+/// Finder's put-back records from ~/.Trash/.DS_Store: Trash item name -> (folder relative to the
+/// volume root, original name). Records are stored as `len, UTF-16 name, code, "ustr", len, UTF-16 value`,
+/// so they are found by their code instead of walking the B-tree.
+fn put_back_records(ds_store: &[u8]) -> HashMap<String, (Option<String>, Option<String>)> {
+    let mut records: HashMap<String, (Option<String>, Option<String>)> = HashMap::new();
+    for (code, is_folder) in [(b"ptbLustr", true), (b"ptbNustr", false)] {
+        for pos in ds_store.windows(8).enumerate().filter(|(_, w)| w == code).map(|(i, _)| i) {
+            // The record's file name ends right before the code; its length precedes it
+            let name = (1..=pos.saturating_sub(4) / 2)
+                .find(|&units| pos >= units * 2 + 4 && u32_at(ds_store, pos - units * 2 - 4) == Some(units))
+                .and_then(|units| utf16_at(ds_store, pos - units * 2, units));
+            let value = u32_at(ds_store, pos + 8).and_then(|units| utf16_at(ds_store, pos + 12, units));
+            if let (Some(name), Some(value)) = (name, value) {
+                let entry = records.entry(name).or_default();
+                if is_folder {
+                    entry.0 = Some(value);
+                } else {
+                    entry.1 = Some(value);
+                }
+            }
+        }
+    }
+    records
+}
+
+/// Original location of each item in `trash_dir`: Finder's put-back info first, then paths Alto
+/// itself moved to the Trash. Put-back folders are relative to `volume_root`.
+fn original_locations(trash_dir: &Path, volume_root: &Path) -> HashMap<String, String> {
+    let mut locations = HashMap::new();
+    // Oldest first, so the latest deletion of a name wins
+    for path in ContextStore::load().deletion_history.iter().flat_map(|r| r.paths_deleted.iter()).filter(|p| Path::new(p).starts_with(volume_root)) {
+        if let Some(name) = Path::new(path).file_name() {
+            locations.insert(name.to_string_lossy().to_string(), path.clone());
+        }
+    }
+    if let Ok(data) = std::fs::read(trash_dir.join(".DS_Store")) {
+        for (name, (folder, original_name)) in put_back_records(&data) {
+            let Some(folder) = folder else { continue };
+            let original = volume_root.join(folder.trim_start_matches('/')).join(original_name.as_deref().unwrap_or(&name));
+            locations.insert(name, original.to_string_lossy().to_string());
+        }
+    }
+    locations
+}
+
+/// The Trash (and its volume root) a path goes to: its external drive's `.Trashes/<uid>`, otherwise ~/.Trash.
+fn trash_dir_for(path: &Path, volume_trashes: &[(String, PathBuf, PathBuf)]) -> Option<(PathBuf, PathBuf)> {
+    volume_trashes
+        .iter()
+        .find(|(_, mount, _)| path.starts_with(mount))
+        .map(|(_, mount, trash)| (trash.clone(), mount.clone()))
+        .or_else(|| Some((dirs::home_dir()?.join(".Trash"), PathBuf::from("/"))))
+}
+
+/// Whether a Trash entry named `name` could be `original`: the same name, or Finder's clash rename
+/// ("report 10.32.01 AM.pdf" for report.pdf).
+fn matches_trashed_name(original: &Path, name: &str) -> bool {
+    let Some(file_name) = original.file_name().map(|n| n.to_string_lossy()) else { return false };
+    if name == file_name {
+        return true;
+    }
+    let stem = original.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    name.starts_with(&format!("{} ", stem)) && Path::new(name).extension() == original.extension()
+}
+
+/// Where items trashed from `originals` ended up, reading each Trash folder once. Finder's put-back
+/// records identify an item exactly; without one, the most recently trashed entry with a matching
+/// name is taken. Each Trash entry is matched to at most one original.
+pub fn locate_trashed_all(originals: &[String]) -> HashMap<String, String> {
+    let volume_trashes = volume_trash_dirs();
+    let mut by_trash: HashMap<(PathBuf, PathBuf), Vec<&String>> = HashMap::new();
+    for original in originals {
+        if let Some(key) = trash_dir_for(Path::new(original), &volume_trashes) {
+            by_trash.entry(key).or_default().push(original);
+        }
+    }
+    let mut found = HashMap::new();
+    for ((trash_dir, volume_root), originals) in by_trash {
+        let recorded: HashMap<PathBuf, String> = std::fs::read(trash_dir.join(".DS_Store"))
+            .map(|data| put_back_records(&data))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(name, (folder, original_name))| {
+                let original = volume_root.join(folder?.trim_start_matches('/')).join(original_name.as_deref().unwrap_or(&name));
+                Some((original, name))
+            })
+            .collect();
+        let mut entries: Vec<(String, Option<SystemTime>)> = std::fs::read_dir(&trash_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| (e.file_name().to_string_lossy().to_string(), trashed_at(&e.path())))
+            .collect();
+        entries.sort_by_key(|(_, at)| std::cmp::Reverse(*at));
+        let mut used: HashSet<String> = HashSet::new();
+        for original in originals {
+            let path = Path::new(original);
+            let exact = recorded.get(path).filter(|n| !used.contains(*n) && entries.iter().any(|(e, _)| e == *n));
+            let name = exact
+                .cloned()
+                .or_else(|| entries.iter().map(|(n, _)| n).find(|n| !used.contains(*n) && matches_trashed_name(path, n)).cloned());
+            if let Some(name) = name {
+                found.insert(original.clone(), trash_dir.join(&name).to_string_lossy().to_string());
+                used.insert(name);
+            }
+        }
+    }
+    found
+}
+
+/// `locate_trashed_all` for a single path.
+pub fn locate_trashed(original: &Path) -> Option<PathBuf> {
+    locate_trashed_all(&[original.to_string_lossy().to_string()]).into_values().next().map(PathBuf::from)
+}
+
+/// Move a Trash item back to `original`. Never overwrites something that reappeared there.
+pub fn put_back(trashed: &Path, original: &Path) -> Result<(), String> {
+    if std::fs::symlink_metadata(original).is_ok() {
+        return Err(format!("{} already exists; not restoring over it", original.display()));
+    }
+    if let Some(parent) = original.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", original.display(), e))?;
+    }
+    std::fs::rename(trashed, original).map_err(|e| format!("Could not restore {}: {}", original.display(), e))
+}
+
+/// Items in `trash_dir` (on the volume mounted at `volume_root`) with size, trash date and original
+/// location, most recently trashed first.
+pub fn list_trash(trash_dir: &Path, volume_root: &Path) -> Vec<TrashItem> {
+    let Ok(entries) = std::fs::read_dir(trash_dir) else { return vec![] };
+    let locations = original_locations(trash_dir, volume_root);
+    let mut items: Vec<TrashItem> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                return None;
+            }
+            let path = entry.path();
+            let meta = std::fs::symlink_metadata(&path).ok()?;
+            let is_directory = meta.is_dir();
+            Some(TrashItem {
+                size_bytes: if is_directory { super::dir_byte_size(&path).allocated } else { super::allocated_bytes(&meta) },
+                trashed_at: trashed_at(&path)
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0),
+                original_path: locations.get(&name).cloned(),
+                path: path.to_string_lossy().to_string(),
+                name,
+                is_directory,
+            })
+        })
+        .collect();
+    items.sort_by_key(|i| std::cmp::Reverse(i.trashed_at));
+    items
+}
+
+/// Put a Trash item back where it came from, or into `destination` (a folder) when given or when
+/// the original location is unknown. A name already taken there gets a " 2" suffix.
+/// Returns the restored path.
+pub fn restore_trash_item(trash_dir: &Path, name: &str, destination: Option<&Path>) -> Result<String, String> {
+    if name.is_empty() || name.contains('/') || name == ".." || name.starts_with('.') {
+        return Err(format!("Invalid Trash item name: {}", name));
+    }
+    let source = trash_dir.join(name);
+    if std::fs::symlink_metadata(&source).is_err() {
+        return Err(format!("{} is no longer in the Trash", name));
+    }
+    let target = match destination {
+        Some(dir) => dir.join(name),
+        None => original_locations(trash_dir, Path::new("/"))
+            .get(name)
+            .map(PathBuf::from)
+            .ok_or_else(|| format!("The original location of {} is unknown; choose a folder to restore it to", name))?,
+    };
+    let parent = target.parent().ok_or("Invalid restore location")?;
+    if !parent.is_dir() {
+        return Err(format!("{} no longer exists; choose a folder to restore {} to", parent.display(), name));
+    }
+    let file_name = target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| name.to_string());
+    let (stem, ext) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), format!(".{}", ext)),
+        _ => (file_name.clone(), String::new()),
+    };
+    let target = std::iter::once(target.clone())
+        .chain((2..1000).map(|n| parent.join(format!("{} {}{}", stem, n, ext))))
+        .find(|p| std::fs::symlink_metadata(p).is_err())
+        .ok_or("No free name to restore to")?;
+    std::fs::rename(&source, &target).map_err(|e| format!("Could not restore {}: {}", name, e))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// This account's Trash on a mounted external drive (`<volume>/.Trashes/<uid>`).
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeTrash {
+    pub volume: String,
+    pub mount_point: String,
+    pub path: String,
+    pub item_count: usize,
+    pub size_bytes: u64,
+    pub items: Vec<TrashItem>,
+}
+
+/// uid owning the home folder, which names the per-user folder in each volume's .Trashes.
+fn current_uid() -> Option<u32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata(dirs::home_dir()?).ok().map(|m| m.uid())
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// (volume name, mount point, trash folder) for every mounted external drive that has one.
+/// Network shares are skipped; their Trash lives on the server.
+pub fn volume_trash_dirs() -> Vec<(String, PathBuf, PathBuf)> {
+    let Some(uid) = current_uid() else { return vec![] };
+    super::system_stats::external_volumes(false)
+        .into_iter()
+        .map(|v| {
+            let mount = PathBuf::from(&v.mount_point);
+            let trash = mount.join(".Trashes").join(uid.to_string());
+            (v.name, mount, trash)
+        })
+        .filter(|(_, _, trash)| trash.is_dir())
+        .collect()
+}
+
+/// Contents and size of this account's Trash on each external drive, reported per volume.
+pub fn volume_trashes() -> Vec<VolumeTrash> {
+    volume_trash_dirs()
+        .into_iter()
+        .map(|(volume, mount, trash)| {
+            let items = list_trash(&trash, &mount);
+            VolumeTrash {
+                volume,
+                mount_point: mount.to_string_lossy().to_string(),
+                path: trash.to_string_lossy().to_string(),
+                item_count: items.len(),
+                size_bytes: items.iter().map(|i| i.size_bytes).sum(),
+                items,
+            }
+        })
+        .filter(|t| t.item_count > 0)
+        .collect()
+}
+
+/// Another account's Trash, as reported by the privileged helper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtherUserTrash {
+    pub user: String,
+    pub item_count: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedFolderItem {
+    pub path: String,
+    pub name: String,
+    pub size_bytes: u64,
+    pub is_directory: bool,
+    /// unix seconds
+    pub modified: i64,
+    /// Why the item is worth a look, e.g. installers or files relocated by a macOS upgrade
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OtherUserTrashCleanResult {
+    pub removed: usize,
+    pub bytes_freed: u64,
+    pub errors: Vec<String>,
+}
+
+fn require_opt_in() -> Result<(), String> {
+    if ContextStore::load().user_preferences.include_other_users {
+        Ok(())
+    } else {
+        Err("Enable \"Include other users\" in Settings to scan other accounts".to_string())
+    }
+}
+
+fn current_user() -> Option<String> {
+    dirs::home_dir().and_then(|h| h.file_name().map(|n| n.to_string_lossy().to_string()))
+}
+
+/// Whether the logged-in account is a member of the admin group.
+fn current_user_is_admin() -> bool {
+    let Some(user) = current_user() else { return false };
+    std::process::Command::new("dseditgroup")
+        .args(["-o", "checkmember", "-m", &user, "admin"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+async fn ask_helper(cmd: Command) -> Result<String, String> {
+    if !helper_client::ensure_helper_installed().await {
+        return Err("Failed to install execution helper".to_string());
+    }
+    let res = helper_client::send_command(cmd).await
+        .map_err(|e| format!("Helper communication failed: {}", e))?;
+    if res.success { Ok(res.message) } else { Err(res.message) }
+}
+
+/// Trash usage of every other account on this Mac. Admin opt-in only; reading another
+/// account's Trash needs root, so this goes through the helper.
+pub async fn other_users_trash() -> Result<Vec<OtherUserTrash>, String> {
+    require_opt_in()?;
+    let message = ask_helper(Command::TrashUsage).await?;
+    let me = current_user();
+    let mut usage: Vec<OtherUserTrash> = serde_json::from_str(&message).map_err(|e| e.to_string())?;
+    usage.retain(|u| Some(&u.user) != me.as_ref() && u.item_count > 0);
+    usage.sort_by_key(|u| std::cmp::Reverse(u.size_bytes));
+    Ok(usage)
+}
+
+/// Permanently empty one other account's Trash through the helper. Only an administrator can,
+/// and only after authenticating in the system password dialog.
+pub async fn empty_other_user_trash(user: &str) -> Result<OtherUserTrashCleanResult, String> {
+    require_opt_in()?;
+    if Some(user) == current_user().as_deref() {
+        return Err("Use Empty Trash for your own account".to_string());
+    }
+    if !current_user_is_admin() {
+        return Err("Only an administrator can empty another account's Trash".to_string());
+    }
+    let reason = format!("Alto wants to permanently empty the Trash of {}.", user);
+    tauri::async_runtime::spawn_blocking(move || super::automation::authorize_admin(&reason))
+        .await
+        .map_err(|e| e.to_string())??;
+    let message = match ask_helper(Command::EmptyUserTrash { user: user.to_string() }).await {
+        Ok(m) => m,
+        // Partial failures still carry the JSON result
+        Err(m) if m.starts_with('{') => m,
+        Err(e) => return Err(e),
+    };
+    serde_json::from_str(&message).map_err(|e| e.to_string())
+}
+
+fn shared_note(name: &str, is_directory: bool) -> Option<String> {
+    let lower = name.to_lowercase();
+    if name == "Relocated Items" || name == "Previously Relocated Items" {
+        Some("Files a macOS upgrade moved aside; review and delete what you don't need".to_string())
+    } else if !is_directory && (lower.ends_with(".dmg") || lower.ends_with(".pkg") || lower.ends_with(".zip")) {
+        Some("Installer or archive".to_string())
+    } else {
+        None
+    }
+}
+
+/// Top-level items in /Users/Shared, largest first. Admin opt-in only.
+pub fn shared_folder_items() -> Result<Vec<SharedFolderItem>, String> {
+    require_opt_in()?;
+    let entries = std::fs::read_dir("/Users/Shared").map_err(|e| format!("Cannot read /Users/Shared: {}", e))?;
+    let mut items: Vec<SharedFolderItem> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || SHARED_SYSTEM_ENTRIES.contains(&name.as_str()) {
+                return None;
+            }
+            let path = entry.path();
+            let meta = std::fs::symlink_metadata(&path).ok()?;
+            let is_directory = meta.is_dir();
+            Some(SharedFolderItem {
+                size_bytes: if is_directory { super::dir_byte_size(&path).allocated } else { super::allocated_bytes(&meta) },
+                modified: meta
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0),
+                note: shared_note(&name, is_directory),
+                path: path.to_string_lossy().to_string(),
+                name,
+                is_directory,
+            })
+        })
+        .collect();
+    items.sort_by_key(|i| std::cmp::Reverse(i.size_bytes));
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(|u| u.to_be_bytes()).collect()
+    }
+
+    fn record(name: &str, code: &[u8; 4], value: &str) -> Vec<u8> {
+        let mut r = (name.encode_utf16().count() as u32).to_be_bytes().to_vec();
+        r.extend(utf16(name));
+        r.extend(code);
+        r.extend(b"ustr");
+        r.extend((value.encode_utf16().count() as u32).to_be_bytes());
+        r.extend(utf16(value));
+        r
+    }
+
+    #[test]
+    fn deletes_only_chosen_trash_items() {
+        let trash = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(trash.path().join("video.mov"), vec![0u8; 4096]).unwrap();
+        std::fs::write(trash.path().join("keep.txt"), b"x").unwrap();
+        std::fs::write(outside.path().join("doc.txt"), b"x").unwrap();
+        let chosen = [trash.path().join("video.mov"), outside.path().join("doc.txt")].map(|p| p.to_string_lossy().to_string());
+
+        let (removed, bytes_freed, errors) = delete_trash_items(&[trash.path().to_path_buf()], &chosen);
+        assert_eq!((removed, errors.len()), (1, 1));
+        assert!(bytes_freed >= 4096);
+        assert!(trash.path().join("keep.txt").exists());
+        assert!(outside.path().join("doc.txt").exists());
+    }
+
+    #[test]
+    fn items_gone_before_the_retry_count_as_trashed() {
+        let dir = tempfile::tempdir().unwrap();
+        let gone = [("a.txt", 10), ("b.txt", 20)].map(|(name, size)| (dir.path().join(name).to_string_lossy().to_string(), size));
+        let result = trash_in_batches(&gone, |_| {});
+        assert_eq!(result.removed.len(), 2);
+        assert_eq!(result.bytes_freed, 30);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn reads_put_back_records() {
+        let mut ds_store = b"\0\0\0\x01Bud1 header".to_vec();
+        ds_store.extend(record("report 10.32.01.pdf", b"ptbL", "Users/me/Documents/"));
+        ds_store.extend(record("report 10.32.01.pdf", b"ptbN", "report.pdf"));
+        ds_store.extend(record("notes.txt", b"ptbL", "Users/me/Desktop/"));
+        let records = put_back_records(&ds_store);
+        assert_eq!(
+            records.get("report 10.32.01.pdf"),
+            Some(&(Some("Users/me/Documents/".to_string()), Some("report.pdf".to_string())))
+        );
+        assert_eq!(records.get("notes.txt"), Some(&(Some("Users/me/Desktop/".to_string()), None)));
+    }
+
+    #[test]
+    fn put_back_never_overwrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let trashed = dir.path().join("report.pdf");
+        let original = dir.path().join("Documents/report.pdf");
+        std::fs::write(&trashed, b"x").unwrap();
+        put_back(&trashed, &original).unwrap();
+        assert!(original.exists() && !trashed.exists());
+
+        std::fs::write(&trashed, b"y").unwrap();
+        assert!(put_back(&trashed, &original).is_err());
+        assert!(trashed.exists());
+    }
+
+    #[test]
+    fn matches_finder_clash_renames() {
+        let original = Path::new("/Users/me/Documents/report.pdf");
+        assert!(matches_trashed_name(original, "report.pdf"));
+        assert!(matches_trashed_name(original, "report 10.32.01 AM.pdf"));
+        assert!(!matches_trashed_name(original, "report 10.32.01 AM.txt"));
+        assert!(!matches_trashed_name(original, "reports.pdf"));
+    }
+}
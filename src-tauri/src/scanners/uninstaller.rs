@@ -1,10 +1,14 @@
 use serde::Serialize;
 #[cfg(target_os = "macos")]
+use crate::mcp::dry_run::{self, DryRunReport};
+#[cfg(target_os = "windows")]
+use crate::mcp::dry_run::{self, DryRunReport, PlannedRemoval};
+#[cfg(target_os = "macos")]
 use std::path::{Path, PathBuf};
 #[cfg(target_os = "macos")]
 use crate::helper_client::{self, Command};
 #[cfg(target_os = "macos")]
-use walkdir::WalkDir;
+use crate::mcp::context_store::ContextStore;
 
 #[cfg(target_os = "windows")]
 use winreg::enums::*;
@@ -52,12 +56,7 @@ pub fn scan_apps() -> Vec<AppInfo> {
                 let path = entry.path();
                 if path.extension().and_then(|s| s.to_str()) == Some("app") {
                     if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
-                         let size_bytes = WalkDir::new(&path)
-                            .into_iter()
-                            .filter_map(|e| e.ok())
-                            .filter_map(|e| e.metadata().ok())
-                            .map(|m| m.len())
-                            .sum();
+                         let size_bytes = super::dir_byte_size(&path).allocated;
 
                         let bundle_id = get_bundle_id(&path);
                         let store = get_store(&path, &bundle_id, name);
@@ -78,6 +77,8 @@ pub fn scan_apps() -> Vec<AppInfo> {
             }
         }
     }
+    let ctx = ContextStore::load();
+    apps.retain(|a| !ctx.is_protected(&a.path));
     apps
 }
 
@@ -120,7 +121,7 @@ pub fn scan_apps() -> Vec<AppInfo> {
 }
 
 #[cfg(target_os = "macos")]
-fn get_bundle_id(app_path: &Path) -> Option<String> {
+pub fn get_bundle_id(app_path: &Path) -> Option<String> {
     let plist_path = app_path.join("Contents/Info.plist");
     let file = std::fs::File::open(plist_path).ok()?;
     let value: serde_json::Value = plist::from_reader(file).ok()?;
@@ -231,8 +232,12 @@ pub fn scan_leftovers(bundle_id: &str) -> LeftoverGroups {
         }
     }
 
+    let ctx = ContextStore::load();
     for path in raw {
         let s = path.to_string_lossy().to_string();
+        if ctx.is_protected(&s) {
+            continue;
+        }
         match categorize_leftover(&path) {
             "logs" => groups.logs.push(s),
             "preferences" => groups.preferences.push(s),
@@ -246,37 +251,79 @@ pub fn scan_leftovers(bundle_id: &str) -> LeftoverGroups {
     groups
 }
 
+/// Move an app bundle to the Trash, falling back to the privileged helper for root-owned apps.
 #[cfg(target_os = "macos")]
-pub async fn uninstall_app(path: &str) -> Result<(), String> {
-    let app_path = Path::new(path);
-    
-    let bundle_id = get_bundle_id(app_path);
-    let groups = if let Some(bid) = &bundle_id {
-        scan_leftovers(bid)
-    } else {
-        LeftoverGroups::default()
-    };
-    let all_leftovers: Vec<String> = groups.logs.iter().chain(groups.preferences.iter())
-        .chain(groups.caches.iter()).chain(groups.crashes.iter())
-        .chain(groups.plugins.iter()).chain(groups.other.iter())
-        .cloned()
-        .collect();
-    let n = all_leftovers.len();
-    println!("Uninstalling {}. Found {} leftovers.", path, n);
-
-    // 2. Try Standard Trash (User Mode)
+async fn trash_bundle(path: &str) -> Result<(), String> {
+    // 1. Try Standard Trash (User Mode)
     if trash::delete(path).is_err() {
         println!("Trash failed. Trying Helper (Root Mode)...");
-        // 3. Upgrade to Protector Mode: Use Helper
-        
+        // 2. Upgrade to Protector Mode: Use Helper
         let cmd = Command::UninstallApp { bundle_path: path.to_string() };
         let res = helper_client::send_command(cmd).await
             .map_err(|e| format!("Helper failed: {}", e))?;
-            
+
         if !res.success {
             return Err(format!("Uninstallation failed: {}", res.message));
         }
     }
+    Ok(())
+}
+
+/// Remove one copy of an app that is installed more than once. Preferences, caches and other
+/// data are shared with the copy that stays, so unlike `uninstall_app` no leftovers are touched.
+#[cfg(target_os = "macos")]
+pub async fn remove_app_copy(path: &str) -> Result<(), String> {
+    if crate::scanners::process::is_bundle_running(path) {
+        let name = Path::new(path).file_stem().unwrap_or_default().to_string_lossy();
+        return Err(format!("Please quit {} before removing it.", name));
+    }
+    trash_bundle(path).await
+}
+
+#[cfg(not(target_os = "macos"))]
+pub async fn remove_app_copy(_path: &str) -> Result<(), String> {
+    Err("Duplicate app removal is only available on macOS".to_string())
+}
+
+/// Every leftover `uninstall_app` removes along with the bundle.
+#[cfg(target_os = "macos")]
+fn leftovers_of(app_path: &Path) -> Vec<String> {
+    let groups = match get_bundle_id(app_path) {
+        Some(bid) => scan_leftovers(&bid),
+        None => LeftoverGroups::default(),
+    };
+    groups.logs.into_iter().chain(groups.preferences)
+        .chain(groups.caches).chain(groups.crashes)
+        .chain(groups.plugins).chain(groups.other)
+        .collect()
+}
+
+/// What `uninstall_app` would remove: the bundle and its leftovers, all to the Trash.
+#[cfg(target_os = "macos")]
+pub fn plan_uninstall(path: &str) -> DryRunReport {
+    let mut paths = vec![path.to_string()];
+    paths.extend(leftovers_of(Path::new(path)));
+    dry_run::plan("Uninstall", &paths, "trash")
+}
+
+#[cfg(target_os = "windows")]
+pub fn plan_uninstall(path: &str) -> DryRunReport {
+    dry_run::report("Uninstall", vec![PlannedRemoval { path: path.to_string(), size_bytes: 0, method: "run_uninstaller".to_string() }])
+}
+
+#[cfg(target_os = "macos")]
+pub async fn uninstall_app(path: &str) -> Result<(), String> {
+    let app_path = Path::new(path);
+    if crate::scanners::process::is_bundle_running(path) {
+        let name = app_path.file_stem().unwrap_or_default().to_string_lossy();
+        return Err(format!("Please quit {} before uninstalling it.", name));
+    }
+    
+    let all_leftovers = leftovers_of(app_path);
+    let n = all_leftovers.len();
+    println!("Uninstalling {}. Found {} leftovers.", path, n);
+
+    trash_bundle(path).await?;
 
     for l_path in &all_leftovers {
         if trash::delete(l_path).is_err() {
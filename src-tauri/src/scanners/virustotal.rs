@@ -0,0 +1,277 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::mcp::context_store::ContextStore;
+
+const API_URL: &str = "https://www.virustotal.com/api/v3/files";
+/// Login Keychain item (generic password) holding the user's API key.
+const KEYCHAIN_SERVICE: &str = "com.maccleaner.app.virustotal";
+const KEYCHAIN_ACCOUNT: &str = "api_key";
+/// The public API allows 4 lookups a minute.
+const MAX_LOOKUPS_PER_MINUTE: usize = 4;
+/// Verdicts are reused for this long; files VirusTotal hasn't seen are asked about again sooner.
+const CACHE_TTL_SECS: i64 = 7 * 86400;
+const UNKNOWN_CACHE_TTL_SECS: i64 = 86400;
+
+lazy_static::lazy_static! {
+    static ref RECENT_LOOKUPS: Mutex<VecDeque<Instant>> = Mutex::new(VecDeque::new());
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct VirusTotalVerdict {
+    pub sha256: String,
+    /// False when VirusTotal has never seen the file
+    pub known: bool,
+    pub malicious: u32,
+    pub suspicious: u32,
+    /// Engines that returned a verdict
+    pub total: u32,
+    /// "12/70"; empty when the file is unknown
+    pub detection_ratio: String,
+    pub permalink: String,
+    pub checked_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VirusTotalLookup {
+    pub path: String,
+    pub verdict: Option<VirusTotalVerdict>,
+    /// Answered from the local cache without asking VirusTotal
+    pub cached: bool,
+    pub error: Option<String>,
+}
+
+/// Which hash a path had when it was last looked up, so cached verdicts can be attached to scan
+/// results without hashing the file again.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct PathEntry {
+    sha256: String,
+    size: u64,
+    modified: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Cache {
+    verdicts: HashMap<String, VirusTotalVerdict>,
+    paths: HashMap<String, PathEntry>,
+}
+
+fn cache_path() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".alto").join("virustotal_cache.json")
+}
+
+impl Cache {
+    fn load() -> Self {
+        std::fs::read_to_string(cache_path()).ok().and_then(|d| serde_json::from_str(&d).ok()).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let path = cache_path();
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+
+    fn fresh(&self, sha256: &str, now: chrono::DateTime<chrono::Local>) -> Option<&VirusTotalVerdict> {
+        let verdict = self.verdicts.get(sha256)?;
+        let checked = chrono::DateTime::parse_from_rfc3339(&verdict.checked_at).ok()?;
+        let ttl = if verdict.known { CACHE_TTL_SECS } else { UNKNOWN_CACHE_TTL_SECS };
+        ((now.timestamp() - checked.timestamp()) < ttl).then_some(verdict)
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    let modified = meta.modified().ok()?.duration_since(SystemTime::UNIX_EPOCH).ok()?.as_secs();
+    Some((meta.len(), modified))
+}
+
+/// Verdict from a `GET /files/{hash}` response body.
+fn parse_report(sha256: &str, body: &serde_json::Value) -> VirusTotalVerdict {
+    let stat = |key: &str| body.pointer(&format!("/data/attributes/last_analysis_stats/{}", key)).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+    let (malicious, suspicious) = (stat("malicious"), stat("suspicious"));
+    let total = malicious + suspicious + stat("undetected") + stat("harmless");
+    VirusTotalVerdict {
+        sha256: sha256.to_string(),
+        known: true,
+        malicious,
+        suspicious,
+        total,
+        detection_ratio: format!("{}/{}", malicious, total),
+        permalink: format!("https://www.virustotal.com/gui/file/{}", sha256),
+        checked_at: chrono::Local::now().to_rfc3339(),
+    }
+}
+
+/// Take a slot in the per-minute budget, or say how many seconds until one frees up.
+fn take_rate_slot(now: Instant) -> Result<(), u64> {
+    let mut recent = RECENT_LOOKUPS.lock().unwrap_or_else(|e| e.into_inner());
+    while recent.front().is_some_and(|t| now.duration_since(*t) >= Duration::from_secs(60)) {
+        recent.pop_front();
+    }
+    if recent.len() >= MAX_LOOKUPS_PER_MINUTE {
+        let wait = 60 - recent.front().map(|t| now.duration_since(*t).as_secs()).unwrap_or(0);
+        return Err(wait.max(1));
+    }
+    recent.push_back(now);
+    Ok(())
+}
+
+/// The API key saved in the login Keychain.
+pub fn stored_api_key() -> Option<String> {
+    let out = Command::new("security")
+        .args(["find-generic-password", "-s", KEYCHAIN_SERVICE, "-a", KEYCHAIN_ACCOUNT, "-w"])
+        .output()
+        .ok()?;
+    let key = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (out.status.success() && !key.is_empty()).then_some(key)
+}
+
+/// Save the API key in the login Keychain, replacing an earlier one. The key goes to `security` on
+/// stdin so it never shows up in `ps`.
+pub fn store_api_key(key: &str) -> Result<(), String> {
+    if !key.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("A VirusTotal API key only has letters and digits".to_string());
+    }
+    let mut child = Command::new("security")
+        .arg("-i")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Could not run security: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "add-generic-password -U -s {} -a {} -w {}", KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT, key).map_err(|e| e.to_string())?;
+    }
+    let out = child.wait_with_output().map_err(|e| e.to_string())?;
+    let err = String::from_utf8_lossy(&out.stderr).trim().to_string();
+    if out.status.success() && err.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Could not save the API key in the Keychain: {}", err))
+    }
+}
+
+/// Move a key that earlier versions kept in the context store into the Keychain. Runs at startup,
+/// before anything rewrites the store, which no longer writes the key out.
+pub fn migrate_api_key() {
+    let mut ctx = ContextStore::load();
+    let legacy = std::mem::take(&mut ctx.user_preferences.virustotal.api_key);
+    if !legacy.is_empty() && store_api_key(legacy.trim()).is_ok() {
+        ctx.save();
+    }
+}
+
+/// Ask VirusTotal about one hash. The API key goes to curl on stdin so it never shows up in `ps`.
+fn query(sha256: &str, api_key: &str) -> Result<VirusTotalVerdict, String> {
+    let mut child = Command::new("curl")
+        .args(["-sS", "--max-time", "20", "-H", "@-", "-w", "\n%{http_code}"])
+        .arg(format!("{}/{}", API_URL, sha256))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Could not run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(format!("x-apikey: {}\n", api_key).as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let out = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !out.status.success() {
+        return Err(format!("VirusTotal request failed: {}", String::from_utf8_lossy(&out.stderr).trim()));
+    }
+    let text = String::from_utf8_lossy(&out.stdout);
+    let (body, status) = text.rsplit_once('\n').unwrap_or(("", &text));
+    match status.trim() {
+        "200" => {
+            let json: serde_json::Value = serde_json::from_str(body).map_err(|e| format!("Unexpected VirusTotal response: {}", e))?;
+            Ok(parse_report(sha256, &json))
+        }
+        "404" => Ok(VirusTotalVerdict { sha256: sha256.to_string(), checked_at: chrono::Local::now().to_rfc3339(), ..Default::default() }),
+        "401" | "403" => Err("VirusTotal rejected the API key".to_string()),
+        "429" => Err("VirusTotal quota exceeded; try again later".to_string()),
+        other => Err(format!("VirusTotal returned HTTP {}", other)),
+    }
+}
+
+/// Look up each file by SHA-256, from the cache when a recent verdict exists. Lookups past the
+/// per-minute limit come back with an error saying when to retry.
+pub fn lookup(paths: &[String], api_key: &str) -> Vec<VirusTotalLookup> {
+    let mut cache = Cache::load();
+    let mut results = Vec::new();
+    for path in paths {
+        let mut result = VirusTotalLookup { path: path.clone(), verdict: None, cached: false, error: None };
+        let Some(sha256) = super::malware::sha256_file(Path::new(path)) else {
+            result.error = Some("Could not read the file".to_string());
+            results.push(result);
+            continue;
+        };
+        if let Some((size, modified)) = file_stamp(Path::new(path)) {
+            cache.paths.insert(path.clone(), PathEntry { sha256: sha256.clone(), size, modified });
+        }
+        if let Some(verdict) = cache.fresh(&sha256, chrono::Local::now()) {
+            result.verdict = Some(verdict.clone());
+            result.cached = true;
+        } else if let Err(wait) = take_rate_slot(Instant::now()) {
+            result.error = Some(format!("VirusTotal allows {} lookups a minute; try again in {} s", MAX_LOOKUPS_PER_MINUTE, wait));
+        } else {
+            match query(&sha256, api_key) {
+                Ok(verdict) => {
+                    cache.verdicts.insert(sha256, verdict.clone());
+                    result.verdict = Some(verdict);
+                }
+                Err(e) => result.error = Some(e),
+            }
+        }
+        results.push(result);
+    }
+    cache.save();
+    results
+}
+
+/// Cached verdicts for files looked up before, keyed by path; files changed since are left out.
+pub fn cached_verdicts() -> HashMap<String, VirusTotalVerdict> {
+    let cache = Cache::load();
+    cache
+        .paths
+        .iter()
+        .filter(|(path, entry)| file_stamp(Path::new(path)) == Some((entry.size, entry.modified)))
+        .filter_map(|(path, entry)| Some((path.clone(), cache.verdicts.get(&entry.sha256)?.clone())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_detection_ratio() {
+        let body = serde_json::json!({ "data": { "attributes": { "last_analysis_stats": {
+            "malicious": 12, "suspicious": 1, "undetected": 50, "harmless": 7, "timeout": 3, "type-unsupported": 4
+        } } } });
+        let verdict = parse_report("abc", &body);
+        assert!(verdict.known);
+        assert_eq!((verdict.malicious, verdict.total), (12, 70));
+        assert_eq!(verdict.detection_ratio, "12/70");
+    }
+
+    #[test]
+    fn rate_limit_allows_four_lookups_a_minute() {
+        let start = Instant::now();
+        for _ in 0..MAX_LOOKUPS_PER_MINUTE {
+            assert!(take_rate_slot(start).is_ok());
+        }
+        assert!(take_rate_slot(start + Duration::from_secs(10)).is_err());
+        assert!(take_rate_slot(start + Duration::from_secs(61)).is_ok());
+    }
+}
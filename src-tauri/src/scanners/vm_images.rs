@@ -0,0 +1,225 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use super::ByteSize;
+use crate::mcp::context_store::ContextStore;
+
+/// Where VM apps keep their machines by default, relative to home.
+const VM_LIBRARIES: &[&str] = &[
+    "Parallels",
+    "Documents/Parallels",
+    "Library/Containers/com.utmapp.UTM/Data/Documents",
+    "VirtualBox VMs",
+    "Virtual Machines.localized",
+    "Documents/Virtual Machines.localized",
+];
+/// VMs moved elsewhere are usually only a couple of levels below home (e.g. ~/VMs/win11.utm).
+const HOME_SEARCH_DEPTH: usize = 3;
+/// VMs not started for this long are suggested for deletion.
+pub const STALE_DAYS: u64 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VmKind {
+    Parallels,
+    Utm,
+    VirtualBox,
+    VmwareFusion,
+    Docker,
+}
+
+impl VmKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            VmKind::Parallels => "Parallels",
+            VmKind::Utm => "UTM",
+            VmKind::VirtualBox => "VirtualBox",
+            VmKind::VmwareFusion => "VMware Fusion",
+            VmKind::Docker => "Docker",
+        }
+    }
+
+    fn compact_hint(self, path: &str) -> String {
+        match self {
+            VmKind::Parallels => "Compact it in Parallels Desktop (Actions > Free Up Disk Space)".to_string(),
+            VmKind::Utm => "Reclaim space in UTM (Edit > Drives > Reclaim Space) while the VM is stopped".to_string(),
+            VmKind::VirtualBox => format!(
+                "Zero free space inside the guest, then run: VBoxManage modifymedium disk \"{}\" --compact",
+                path
+            ),
+            VmKind::VmwareFusion => "Clean it up in VMware Fusion (Settings > Hard Disk > Clean Up Virtual Machine)".to_string(),
+            VmKind::Docker => {
+                "Run docker system prune; Docker Desktop shrinks Docker.raw afterwards, or lower the disk limit in Settings > Resources"
+                    .to_string()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VmImage {
+    pub kind: VmKind,
+    pub name: String,
+    /// The .pvm / .utm / .vmwarevm bundle, or the disk file itself
+    pub path: String,
+    /// Apparent size; disk images are sparse, so this is mostly the guest's maximum disk size
+    pub logical_bytes: u64,
+    /// Bytes actually occupied on disk
+    pub allocated_bytes: u64,
+    /// unix seconds
+    pub modified: i64,
+    pub stale: bool,
+    pub suggestions: Vec<String>,
+}
+
+/// VM the file or bundle at `path` belongs to, judged by its own or an ancestor's extension.
+/// Used to give large-file results context.
+pub fn vm_kind(path: &Path) -> Option<VmKind> {
+    if path.file_name().map(|n| n == "Docker.raw").unwrap_or(false) {
+        return Some(VmKind::Docker);
+    }
+    path.ancestors().find_map(|p| {
+        match p.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "pvm" => Some(VmKind::Parallels),
+            "utm" => Some(VmKind::Utm),
+            "vmwarevm" => Some(VmKind::VmwareFusion),
+            "vdi" => Some(VmKind::VirtualBox),
+            _ => None,
+        }
+    })
+}
+
+fn modified_secs(path: &Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Bundles are touched by the app on every launch, but the disk files inside change while the VM runs.
+fn last_used(path: &Path) -> i64 {
+    WalkDir::new(path)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| modified_secs(e.path()))
+        .max()
+        .unwrap_or(0)
+}
+
+fn image(kind: VmKind, path: &Path, now: i64) -> VmImage {
+    let size = if path.is_dir() {
+        super::dir_byte_size(path)
+    } else {
+        std::fs::metadata(path).map(|m| ByteSize::of(&m)).unwrap_or_default()
+    };
+    let path_str = path.to_string_lossy().to_string();
+    let modified = last_used(path);
+    let idle_days = ((now - modified).max(0) as u64) / (24 * 3600);
+    let stale = modified > 0 && idle_days >= STALE_DAYS;
+
+    let mut suggestions = vec![kind.compact_hint(&path_str)];
+    if stale && kind != VmKind::Docker {
+        suggestions.push(format!("Not used for {} days; delete it if you no longer need this VM", idle_days));
+    }
+    VmImage {
+        kind,
+        name: path.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+        path: path_str,
+        logical_bytes: size.logical,
+        allocated_bytes: size.allocated,
+        modified,
+        stale,
+        suggestions,
+    }
+}
+
+/// Top-level VM items under `root`: bundles (not descended into) and loose VirtualBox disks.
+fn find_in(root: &Path, depth: usize, found: &mut Vec<(VmKind, PathBuf)>) {
+    let mut walker = WalkDir::new(root).max_depth(depth).into_iter().filter_entry(|e| {
+        let name = e.file_name().to_string_lossy();
+        e.depth() == 0 || !(name.starts_with('.') || name == "Library" || name == "node_modules")
+    });
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else { continue };
+        let path = entry.path();
+        let ext = path.extension().and_then(|x| x.to_str()).unwrap_or("").to_ascii_lowercase();
+        let kind = match ext.as_str() {
+            "pvm" => Some(VmKind::Parallels),
+            "utm" => Some(VmKind::Utm),
+            "vmwarevm" => Some(VmKind::VmwareFusion),
+            "vdi" if entry.file_type().is_file() => Some(VmKind::VirtualBox),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            if entry.file_type().is_dir() {
+                walker.skip_current_dir();
+            }
+            found.push((kind, path.to_path_buf()));
+        }
+    }
+}
+
+/// Parallels, UTM, VirtualBox, VMware Fusion and Docker VM disks with sparse-aware sizes, largest on disk first.
+pub fn scan_vm_images() -> Vec<VmImage> {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+    let ctx = ContextStore::load();
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    let mut found = Vec::new();
+    for lib in VM_LIBRARIES {
+        find_in(&home.join(lib), 4, &mut found);
+    }
+    find_in(&home, HOME_SEARCH_DEPTH, &mut found);
+    found.sort_by(|a, b| a.1.cmp(&b.1));
+    found.dedup_by(|a, b| a.1 == b.1);
+
+    let mut images: Vec<VmImage> = found
+        .into_iter()
+        .filter(|(_, p)| !ctx.is_excluded(&p.to_string_lossy()))
+        .map(|(kind, p)| image(kind, &p, now))
+        .collect();
+    images.extend(
+        super::docker::vm_disks(&home)
+            .into_iter()
+            .filter(|d| d.path.ends_with("Docker.raw") || d.path.ends_with("Docker.qcow2"))
+            .map(|d| image(VmKind::Docker, Path::new(&d.path), now)),
+    );
+    images.sort_by_key(|i| std::cmp::Reverse(i.allocated_bytes));
+    images
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_inside_bundles_map_to_their_vm() {
+        assert_eq!(
+            vm_kind(Path::new("/Users/a/Parallels/Win 11.pvm/Win 11-0.hdd/disk.hds")),
+            Some(VmKind::Parallels)
+        );
+        assert_eq!(vm_kind(Path::new("/Users/a/VirtualBox VMs/x/x.vdi")), Some(VmKind::VirtualBox));
+        assert_eq!(vm_kind(Path::new("/Users/a/Library/Containers/com.docker.docker/Data/vms/0/data/Docker.raw")), Some(VmKind::Docker));
+        assert_eq!(vm_kind(Path::new("/Users/a/Movies/trip.mov")), None);
+    }
+
+    #[test]
+    fn finds_bundles_without_descending() {
+        let home = tempfile::tempdir().unwrap();
+        let bundle = home.path().join("VMs/Ubuntu.utm/Data");
+        std::fs::create_dir_all(&bundle).unwrap();
+        std::fs::write(bundle.join("disk.qcow2"), vec![0u8; 1024]).unwrap();
+        let mut found = Vec::new();
+        find_in(home.path(), HOME_SEARCH_DEPTH, &mut found);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, VmKind::Utm);
+    }
+}
@@ -5,12 +5,17 @@ use std::thread;
 use tauri::{AppHandle, Emitter};
 use serde::Serialize;
 use crate::mcp::context_store::{ContextStore, SystemEvent};
+use crate::scanners::{downloads, gatekeeper, power};
 
 #[derive(Clone, Serialize)]
 pub struct AppInstallPayload {
     pub name: String,
     pub path: String,
     pub event_type: String,  // "app_installed" | "file_downloaded" | "suspicious_file"
+    /// True when Low Power Mode / Focus is active and this alert type is suppressed; the UI should not notify.
+    pub silent: bool,
+    /// Quarantine and Gatekeeper status of a downloaded app, installer or executable
+    pub assessment: Option<gatekeeper::DownloadAssessment>,
 }
 
 /// Suspicious file extensions that could indicate malware
@@ -91,6 +96,12 @@ pub fn start_watcher(app_handle: AppHandle) {
                                 handle_new_file(&app_handle, path_buf);
                             }
                         }
+                        // Browsers finish a download by renaming the partial file
+                        notify::EventKind::Modify(notify::event::ModifyKind::Name(_)) => {
+                            for path_buf in event.paths.iter().filter(|p| p.is_file()) {
+                                downloads::organize_new_download(path_buf);
+                            }
+                        }
                         _ => {}
                     }
                 }
@@ -130,26 +141,45 @@ fn handle_new_file(app_handle: &AppHandle, path_buf: &PathBuf) {
             name,
             path: path_str,
             event_type: "app_installed".to_string(),
+            silent: power::should_suppress_alert("app_installed"),
+            assessment: None,
         });
     }
     // 2. New file in Downloads — flag suspicious types
     else if path_str.to_lowercase().contains("downloads") {
-        let is_suspicious = SUSPICIOUS_EXT.contains(&ext.as_str());
+        // Sorted by the user's rules first so the event points at where the file ended up
+        let path_str = downloads::organize_new_download(path_buf)
+            .map(|moved| moved.to_string_lossy().to_string())
+            .unwrap_or(path_str);
+        #[cfg(target_os = "macos")]
+        let assessment = gatekeeper::is_assessable(Path::new(&path_str)).then(|| gatekeeper::assess(Path::new(&path_str)));
+        #[cfg(not(target_os = "macos"))]
+        let assessment: Option<gatekeeper::DownloadAssessment> = None;
+        let high_risk = assessment.as_ref().is_some_and(|a| a.risk == "high");
+        let is_suspicious = high_risk || SUSPICIOUS_EXT.contains(&ext.as_str());
         println!("[Watcher] New download: {} (suspicious: {})", name, is_suspicious);
 
         let mut ctx = ContextStore::load();
         let event_type = if is_suspicious { "suspicious_download" } else { "file_downloaded" }.to_string();
+        let note = match &assessment {
+            Some(a) if high_risk => format!("⚠️ {}", a.reasons.join("; ")),
+            _ if is_suspicious => "⚠️ suspicious type".to_string(),
+            _ => "normal".to_string(),
+        };
         ctx.record_system_event(SystemEvent {
             timestamp: chrono::Local::now().to_rfc3339(),
             event_type: event_type.clone(),
-            description: format!("New file in Downloads: {} ({})", name, if is_suspicious { "⚠️ suspicious type" } else { "normal" }),
+            description: format!("New file in Downloads: {} ({})", name, note),
             path: path_str.clone(),
         });
 
+        let silent = power::should_suppress_alert(&event_type);
         let _ = app_handle.emit("system-event", AppInstallPayload {
             name,
             path: path_str,
             event_type,
+            silent,
+            assessment,
         });
     }
 }
@@ -0,0 +1,386 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+use crate::mcp::context_store::ContextStore;
+use crate::mcp::managed::ManagedPolicy;
+use crate::mcp::safety_gate;
+
+/// Newest DeviceSupport versions kept per platform; older ones are flagged for removal.
+const DEVICE_SUPPORT_KEEP: usize = 2;
+/// Archives older than this are considered stale unless the caller passes another age.
+pub const ARCHIVE_STALE_DAYS: u32 = 180;
+
+const DEVICE_SUPPORT_DIRS: &[&str] = &["iOS DeviceSupport", "watchOS DeviceSupport", "tvOS DeviceSupport", "visionOS DeviceSupport"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum XcodeGroupKind {
+    DerivedData,
+    Simulators,
+    DeviceSupport,
+    Archives,
+    ModuleCaches,
+}
+
+impl XcodeGroupKind {
+    pub const ALL: [XcodeGroupKind; 5] = [
+        XcodeGroupKind::DerivedData,
+        XcodeGroupKind::Simulators,
+        XcodeGroupKind::DeviceSupport,
+        XcodeGroupKind::Archives,
+        XcodeGroupKind::ModuleCaches,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            XcodeGroupKind::DerivedData => "DerivedData",
+            XcodeGroupKind::Simulators => "Unavailable Simulators",
+            XcodeGroupKind::DeviceSupport => "Old Device Support",
+            XcodeGroupKind::Archives => "Archives",
+            XcodeGroupKind::ModuleCaches => "Module Caches",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct XcodeItem {
+    /// Path for file-backed items, simulator UDID for simulators
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// Last modification, unix seconds
+    pub modified: Option<i64>,
+    /// Project workspace, OS version, runtime... depending on the group
+    pub detail: Option<String>,
+    /// Whether cleaning the group without an explicit selection removes this item
+    pub recommended: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct XcodeGroup {
+    pub kind: XcodeGroupKind,
+    pub label: String,
+    pub items: Vec<XcodeItem>,
+    pub total_size_bytes: u64,
+    pub recommended_size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct XcodeCleanResult {
+    pub removed: usize,
+    pub bytes_freed: u64,
+    pub errors: Vec<String>,
+}
+
+fn developer_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join("Library/Developer"))
+}
+
+fn dir_size(path: &Path) -> u64 {
+    super::dir_byte_size(path).allocated
+}
+
+fn modified_secs(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    modified.duration_since(SystemTime::UNIX_EPOCH).ok().map(|d| d.as_secs() as i64)
+}
+
+fn file_item(path: &Path, name: String, detail: Option<String>, recommended: bool) -> XcodeItem {
+    let path_str = path.to_string_lossy().to_string();
+    XcodeItem {
+        id: path_str.clone(),
+        name,
+        path: path_str,
+        size_bytes: dir_size(path),
+        modified: modified_secs(path),
+        detail,
+        recommended,
+    }
+}
+
+/// "MyApp-abcdefghijklmnop" -> "MyApp"
+fn project_name_from_derived_dir(dir_name: &str) -> String {
+    match dir_name.rsplit_once('-') {
+        Some((name, hash)) if !name.is_empty() && hash.len() >= 20 && hash.chars().all(|c| c.is_ascii_alphabetic()) => name.to_string(),
+        _ => dir_name.to_string(),
+    }
+}
+
+/// Workspace or project a DerivedData folder was built from, per its info.plist.
+#[cfg(target_os = "macos")]
+fn derived_data_workspace(dir: &Path) -> Option<String> {
+    let value: serde_json::Value = plist::from_file(dir.join("info.plist")).ok()?;
+    value.get("WorkspacePath").and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn derived_data_workspace(_dir: &Path) -> Option<String> {
+    None
+}
+
+fn scan_derived_data(dev: &Path) -> Vec<XcodeItem> {
+    let root = dev.join("Xcode/DerivedData");
+    let Ok(entries) = std::fs::read_dir(&root) else { return vec![] };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|e| {
+            let dir_name = e.file_name().to_string_lossy().to_string();
+            // Shared caches, reported under module caches
+            if dir_name.ends_with(".noindex") {
+                return None;
+            }
+            let path = e.path();
+            let workspace = derived_data_workspace(&path);
+            let detail = workspace.map(|w| {
+                if Path::new(&w).exists() { w } else { format!("{} (project no longer exists)", w) }
+            });
+            Some(file_item(&path, project_name_from_derived_dir(&dir_name), detail, true))
+        })
+        .collect()
+}
+
+/// Parses `xcrun simctl list -j devices` output into unavailable devices: (udid, name, runtime, reason).
+fn parse_unavailable_simulators(json: &str) -> Vec<(String, String, String, Option<String>)> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else { return vec![] };
+    let Some(devices) = value.get("devices").and_then(|d| d.as_object()) else { return vec![] };
+    let mut out = Vec::new();
+    for (runtime, list) in devices {
+        // "com.apple.CoreSimulator.SimRuntime.iOS-15-0" -> "iOS 15.0"
+        let short = runtime.rsplit('.').next().unwrap_or(runtime);
+        let runtime_name = match short.split_once('-') {
+            Some((os, version)) => format!("{} {}", os, version.replace('-', ".")),
+            None => short.to_string(),
+        };
+        for dev in list.as_array().into_iter().flatten() {
+            if dev.get("isAvailable").and_then(|v| v.as_bool()).unwrap_or(true) {
+                continue;
+            }
+            let Some(udid) = dev.get("udid").and_then(|v| v.as_str()) else { continue };
+            let name = dev.get("name").and_then(|v| v.as_str()).unwrap_or(udid);
+            let reason = dev.get("availabilityError").and_then(|v| v.as_str()).map(|s| s.to_string());
+            out.push((udid.to_string(), name.to_string(), runtime_name.clone(), reason));
+        }
+    }
+    out
+}
+
+fn scan_simulators(dev: &Path) -> Vec<XcodeItem> {
+    let Ok(output) = Command::new("xcrun").args(["simctl", "list", "-j", "devices"]).output() else { return vec![] };
+    if !output.status.success() {
+        return vec![];
+    }
+    let devices_dir = dev.join("CoreSimulator/Devices");
+    parse_unavailable_simulators(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .map(|(udid, name, runtime, reason)| {
+            let path = devices_dir.join(&udid);
+            XcodeItem {
+                id: udid,
+                name,
+                path: path.to_string_lossy().to_string(),
+                size_bytes: dir_size(&path),
+                modified: modified_secs(&path),
+                detail: Some(match reason {
+                    Some(r) => format!("{} — {}", runtime, r),
+                    None => runtime,
+                }),
+                recommended: true,
+            }
+        })
+        .collect()
+}
+
+/// "17.2 (21C62)" or "iPhone15,2 17.2 (21C62)" -> [17, 2]
+fn parse_os_version(dir_name: &str) -> Vec<u32> {
+    dir_name
+        .split_whitespace()
+        .find(|tok| tok.contains('.') && tok.split('.').all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit())))
+        .map(|tok| tok.split('.').filter_map(|p| p.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn scan_device_support(dev: &Path) -> Vec<XcodeItem> {
+    let mut items = Vec::new();
+    for platform in DEVICE_SUPPORT_DIRS {
+        let Ok(entries) = std::fs::read_dir(dev.join("Xcode").join(platform)) else { continue };
+        let mut versions: Vec<(Vec<u32>, PathBuf, String)> = entries
+            .flatten()
+            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                (parse_os_version(&name), e.path(), name)
+            })
+            .collect();
+        // Newest first
+        versions.sort_by(|a, b| b.0.cmp(&a.0));
+        let os = platform.trim_end_matches(" DeviceSupport");
+        for (idx, (_, path, name)) in versions.into_iter().enumerate() {
+            items.push(file_item(&path, format!("{} {}", os, name), None, idx >= DEVICE_SUPPORT_KEEP));
+        }
+    }
+    items
+}
+
+fn scan_archives(dev: &Path, stale_days: u32) -> Vec<XcodeItem> {
+    let cutoff = SystemTime::now()
+        .checked_sub(Duration::from_secs(stale_days as u64 * 24 * 3600))
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let mut items: Vec<XcodeItem> = WalkDir::new(dev.join("Xcode/Archives"))
+        .min_depth(2)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().map(|x| x == "xcarchive").unwrap_or(false))
+        .map(|e| {
+            let path = e.path();
+            let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let day = path.parent().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string());
+            let mut item = file_item(path, name, day, false);
+            item.recommended = item.modified.map(|m| m < cutoff).unwrap_or(false);
+            item
+        })
+        .collect();
+    items.sort_by_key(|i| i.modified.unwrap_or(0));
+    items
+}
+
+fn module_cache_dirs(dev: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![
+        dev.join("Xcode/DerivedData/ModuleCache.noindex"),
+        dev.join("Xcode/DerivedData/SymbolCache.noindex"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join("Library/Caches/com.apple.dt.Xcode"));
+    }
+    // $TMPDIR is /var/folders/../T/; the per-user cache dir is its sibling C/
+    if let Some(user_dir) = std::env::temp_dir().parent() {
+        dirs.push(user_dir.join("C/clang/ModuleCache"));
+        dirs.push(user_dir.join("C/com.apple.DeveloperTools"));
+    }
+    dirs
+}
+
+fn scan_module_caches(dev: &Path) -> Vec<XcodeItem> {
+    module_cache_dirs(dev)
+        .into_iter()
+        .filter(|p| p.is_dir())
+        .map(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy().to_string();
+            file_item(&p, name, None, true)
+        })
+        .collect()
+}
+
+pub fn scan_group(kind: XcodeGroupKind, archive_stale_days: u32) -> XcodeGroup {
+    let ctx = ContextStore::load();
+    let mut items = match developer_dir() {
+        Some(dev) => match kind {
+            XcodeGroupKind::DerivedData => scan_derived_data(&dev),
+            XcodeGroupKind::Simulators => scan_simulators(&dev),
+            XcodeGroupKind::DeviceSupport => scan_device_support(&dev),
+            XcodeGroupKind::Archives => scan_archives(&dev, archive_stale_days),
+            XcodeGroupKind::ModuleCaches => scan_module_caches(&dev),
+        },
+        None => vec![],
+    };
+    let policy = ManagedPolicy::load();
+    items.retain(|i| !ctx.is_excluded(&i.path) && !policy.is_excluded(&i.path));
+    XcodeGroup {
+        kind,
+        label: kind.label().to_string(),
+        total_size_bytes: items.iter().map(|i| i.size_bytes).sum(),
+        recommended_size_bytes: items.iter().filter(|i| i.recommended).map(|i| i.size_bytes).sum(),
+        items,
+    }
+}
+
+/// Scan every Xcode group (DerivedData, simulators, device support, archives, module caches).
+pub fn scan_xcode(archive_stale_days: u32) -> Vec<XcodeGroup> {
+    XcodeGroupKind::ALL.iter().map(|k| scan_group(*k, archive_stale_days)).collect()
+}
+
+/// Clean items of one group. `ids` picks items from a fresh scan of the group (so only paths Alto itself
+/// found can be removed); without `ids`, the recommended items are removed. Simulators are deleted
+/// through simctl, everything else goes to the Trash and is recorded so it can be put back.
+pub fn clean_group(kind: XcodeGroupKind, ids: Option<&[String]>, archive_stale_days: u32) -> XcodeCleanResult {
+    let group = scan_group(kind, archive_stale_days);
+    let selected: Vec<&XcodeItem> = match ids {
+        Some(ids) => group.items.iter().filter(|i| ids.contains(&i.id)).collect(),
+        None => group.items.iter().filter(|i| i.recommended).collect(),
+    };
+
+    let ctx = ContextStore::load();
+    let policy = ManagedPolicy::load();
+    let mut result = XcodeCleanResult { removed: 0, bytes_freed: 0, errors: vec![] };
+    let mut to_trash = Vec::new();
+    for item in selected {
+        if let Err(e) = safety_gate::check_paths("Xcode cleanup", std::slice::from_ref(&item.path), &policy, &ctx) {
+            result.errors.push(e);
+            continue;
+        }
+        if kind != XcodeGroupKind::Simulators {
+            to_trash.push((item.path.clone(), item.size_bytes));
+            continue;
+        }
+        // Let CoreSimulator drop the device so its device set stays consistent
+        let outcome = match Command::new("xcrun").args(["simctl", "delete", &item.id]).output() {
+            Ok(o) if o.status.success() => Ok(()),
+            Ok(o) => Err(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match outcome {
+            Ok(()) => {
+                result.removed += 1;
+                result.bytes_freed += item.size_bytes;
+            }
+            Err(e) => result.errors.push(format!("{}: {}", item.name, e)),
+        }
+    }
+    let trashed = super::trash::trash_and_record(&to_trash);
+    result.removed += trashed.removed.len();
+    result.bytes_freed += trashed.bytes_freed;
+    result.errors.extend(trashed.errors);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_data_project_names() {
+        assert_eq!(project_name_from_derived_dir("MyApp-bvjdqlzmxkrhfxgwcyfbrtnegdkr"), "MyApp");
+        assert_eq!(project_name_from_derived_dir("My-App-bvjdqlzmxkrhfxgwcyfbrtnegdkr"), "My-App");
+        assert_eq!(project_name_from_derived_dir("Scratch-1"), "Scratch-1");
+    }
+
+    #[test]
+    fn os_versions_parse_with_and_without_device_prefix() {
+        assert_eq!(parse_os_version("17.2 (21C62)"), vec![17, 2]);
+        assert_eq!(parse_os_version("iPhone15,2 16.4.1 (20E252)"), vec![16, 4, 1]);
+        assert!(parse_os_version("garbage").is_empty());
+    }
+
+    #[test]
+    fn only_unavailable_simulators_are_listed() {
+        let json = r#"{"devices": {
+            "com.apple.CoreSimulator.SimRuntime.iOS-15-0": [
+                {"udid": "A", "name": "iPhone 8", "isAvailable": false, "availabilityError": "runtime profile not found"}
+            ],
+            "com.apple.CoreSimulator.SimRuntime.iOS-17-2": [
+                {"udid": "B", "name": "iPhone 15", "isAvailable": true}
+            ]
+        }}"#;
+        let devices = parse_unavailable_simulators(json);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].0, "A");
+        assert_eq!(devices[0].2, "iOS 15.0");
+    }
+}
@@ -0,0 +1,170 @@
+use serde::Serialize;
+
+/// XProtect Remediator's periodic scan job.
+const SCAN_JOB_LABEL: &str = "com.apple.XProtect.daemon.scan";
+/// How far back the unified log is searched for XProtect remediations.
+const REMEDIATION_LOOKBACK: &str = "14d";
+
+/// What Apple's built-in protection has installed and is doing, for comparing against Alto's findings.
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct XProtectStatus {
+    /// XProtect definitions version ("5287")
+    pub xprotect_version: Option<String>,
+    /// XProtect Remediator version (macOS 12.3 and later)
+    pub remediator_version: Option<String>,
+    /// Malware Removal Tool version, on Macs that still have it
+    pub mrt_version: Option<String>,
+    /// XProtect Remediator's background scan job is installed and not disabled
+    pub background_scans: bool,
+    /// "Install Security Responses and system files" is on, so definitions stay current
+    pub automatic_updates: bool,
+    /// Paths XProtect reported remediating recently
+    pub remediated_paths: Vec<String>,
+}
+
+/// Whether `label` is listed as disabled in `launchctl print-disabled system` output, which reads
+/// `"label" => disabled` (or `=> true` on older macOS).
+fn job_disabled(listing: &str, label: &str) -> bool {
+    let quoted = format!("\"{}\"", label);
+    listing.lines().any(|l| {
+        let l = l.trim();
+        l.starts_with(&quoted) && (l.ends_with("disabled") || l.ends_with("true"))
+    })
+}
+
+/// Absolute paths in a remediation log message: string values when it's structured JSON, otherwise
+/// the words that start with '/'.
+fn message_paths(message: &str) -> Vec<String> {
+    fn collect(value: &serde_json::Value, out: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s) if s.starts_with('/') && s.len() > 1 => out.push(s.clone()),
+            serde_json::Value::Array(items) => items.iter().for_each(|v| collect(v, out)),
+            serde_json::Value::Object(map) => map.values().for_each(|v| collect(v, out)),
+            _ => {}
+        }
+    }
+    let mut paths = Vec::new();
+    match serde_json::from_str::<serde_json::Value>(message) {
+        Ok(json) => collect(&json, &mut paths),
+        Err(_) => paths.extend(
+            message
+                .split_whitespace()
+                .map(|w| w.trim_matches(|c: char| matches!(c, '"' | '\'' | ',' | ';' | '(' | ')')))
+                .filter(|w| w.starts_with('/') && w.len() > 1)
+                .map(|w| w.to_string()),
+        ),
+    }
+    paths
+}
+
+/// Paths from successful remediations in `log show --style ndjson` output.
+fn parse_remediations(ndjson: &str) -> Vec<String> {
+    let mut paths: Vec<String> = ndjson
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .filter_map(|entry| entry.get("eventMessage").and_then(|m| m.as_str()).map(|m| m.to_string()))
+        .filter(|m| {
+            let lower = m.to_lowercase();
+            lower.contains("remediat") && !lower.contains("fail")
+        })
+        .flat_map(|m| message_paths(&m))
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Split threats into those to report and those naming a path Apple already remediated.
+pub fn skip_remediated(threats: Vec<String>, remediated: &[String]) -> (Vec<String>, Vec<String>) {
+    threats.into_iter().partition(|t| !remediated.iter().any(|p| t.contains(p.as_str())))
+}
+
+#[cfg(target_os = "macos")]
+fn bundle_version(candidates: &[&str]) -> Option<String> {
+    candidates.iter().find_map(|bundle| {
+        let info: serde_json::Value = plist::from_file(format!("{}/Contents/Info.plist", bundle)).ok()?;
+        info.get("CFBundleShortVersionString").and_then(|v| v.as_str()).map(|v| v.to_string())
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn background_scans_enabled() -> bool {
+    let job = format!("/Library/Apple/System/Library/LaunchDaemons/{}.plist", SCAN_JOB_LABEL);
+    if !std::path::Path::new(&job).exists() {
+        return false;
+    }
+    std::process::Command::new("launchctl")
+        .args(["print-disabled", "system"])
+        .output()
+        .map(|o| !job_disabled(&String::from_utf8_lossy(&o.stdout), SCAN_JOB_LABEL))
+        .unwrap_or(true)
+}
+
+/// Security responses install automatically unless the user turned it off.
+#[cfg(target_os = "macos")]
+fn automatic_updates_enabled() -> bool {
+    plist::from_file::<_, serde_json::Value>("/Library/Preferences/com.apple.SoftwareUpdate.plist")
+        .ok()
+        .and_then(|prefs| prefs.get("ConfigDataInstall").and_then(|v| v.as_bool()))
+        .unwrap_or(true)
+}
+
+#[cfg(target_os = "macos")]
+fn recent_remediations() -> Vec<String> {
+    let predicate = "subsystem == \"com.apple.XProtectFramework.PluginAPI\" AND category == \"XPEvent.structured\"";
+    std::process::Command::new("log")
+        .args(["show", "--last", REMEDIATION_LOOKBACK, "--style", "ndjson", "--predicate", predicate])
+        .output()
+        .map(|o| parse_remediations(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+pub fn status() -> XProtectStatus {
+    XProtectStatus {
+        xprotect_version: bundle_version(&[
+            "/private/var/protected/xprotect/XProtect.bundle",
+            "/Library/Apple/System/Library/CoreServices/XProtect.bundle",
+            "/System/Library/CoreServices/XProtect.bundle",
+        ]),
+        remediator_version: bundle_version(&["/Library/Apple/System/Library/CoreServices/XProtect.app"]),
+        mrt_version: bundle_version(&["/Library/Apple/System/Library/CoreServices/MRT.app", "/System/Library/CoreServices/MRT.app"]),
+        background_scans: background_scans_enabled(),
+        automatic_updates: automatic_updates_enabled(),
+        remediated_paths: recent_remediations(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn status() -> XProtectStatus {
+    XProtectStatus::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_disabled_jobs_and_remediated_paths() {
+        let listing = "disabled services = {\n\t\"com.apple.XProtect.daemon.scan\" => disabled\n\t\"com.apple.ftpd\" => enabled\n}\n";
+        assert!(job_disabled(listing, SCAN_JOB_LABEL));
+        assert!(!job_disabled(listing, "com.apple.ftpd"));
+
+        let log = concat!(
+            r#"{"eventMessage":"{\"status\":\"RemediationSucceeded\",\"path\":\"/Users/jane/Library/LaunchAgents/com.pcv.plist\"}"}"#,
+            "\n",
+            r#"{"eventMessage":"Remediation failed for /Applications/Other.app"}"#,
+            "\n",
+        );
+        let remediated = parse_remediations(log);
+        assert_eq!(remediated, vec!["/Users/jane/Library/LaunchAgents/com.pcv.plist".to_string()]);
+
+        let threats = vec![
+            "Known Adware/PUP found: PCVARK at \"/Users/jane/Library/LaunchAgents/com.pcv.plist\"".to_string(),
+            "Risky download: /Users/jane/Downloads/Tool.app — Gatekeeper rejects it".to_string(),
+        ];
+        let (kept, skipped) = skip_remediated(threats, &remediated);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(skipped.len(), 1);
+    }
+}
@@ -5,7 +5,7 @@ import { formatBytes } from '../../utils/formatBytes';
 export interface IndexedFile {
     path: string;
     size_bytes: number;
-    category: 'Cache' | 'Log' | 'Temp' | 'UserData' | 'SystemCritical' | 'AppSupport' | 'Unknown';
+    category: 'Cache' | 'Log' | 'Temp' | 'UserData' | 'SystemCritical' | 'AppSupport' | 'Installer' | 'Unknown';
     app_owner: string | null;
     is_safe_to_delete: boolean;
     reason: string;
@@ -24,6 +24,7 @@ const categoryColors: Record<string, string> = {
     UserData: 'text-red-400',
     SystemCritical: 'text-red-500',
     AppSupport: 'text-orange-400',
+    Installer: 'text-violet-400',
     Unknown: 'text-white/50',
 };
 
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useState, useCallback } from 'react';
+import { errorMessage } from '../utils/errors';
 
 export function useTauri() {
     const [loading, setLoading] = useState(false);
@@ -13,7 +14,7 @@ export function useTauri() {
             return result;
         } catch (err) {
             console.error(`Tauri command '${command}' failed:`, err);
-            setError(err as string);
+            setError(errorMessage(err));
             return null;
         } finally {
             setLoading(false);
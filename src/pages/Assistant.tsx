@@ -4,6 +4,7 @@ import { ArrowUp, Sparkles } from 'lucide-react';
 import ReactMarkdown from 'react-markdown';
 import remarkGfm from 'remark-gfm';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '../utils/errors';
 import { listen } from '@tauri-apps/api/event';
 import { aiService, type ActionResult } from '../services/aiService';
 import { SystemOverviewWidget } from '../components/chat/SystemOverviewWidget';
@@ -19,6 +20,7 @@ import { useScanStore } from '../store/scanStore';
 import { clsx, type ClassValue } from 'clsx';
 import { twMerge } from 'tailwind-merge';
 import { Button } from '../components/Button';
+import { cacheContextStore } from '../utils/contextCache';
 
 function cn(...inputs: ClassValue[]) {
     return twMerge(clsx(inputs));
@@ -41,6 +43,7 @@ interface DeepScanEntry {
     filesFound: number;
     sizeBytes: number;
     percent: number;
+    etaSecs: number | null;
 }
 
 interface DeepScanState {
@@ -175,12 +178,14 @@ export function Assistant({ onNavigate }: AssistantProps) {
     useEffect(() => {
         // Load MCP context and cache for AI
         invoke<Record<string, unknown>>('get_mcp_context').then(ctx => {
-            localStorage.setItem('alto_context_store_cache', JSON.stringify(ctx));
+            cacheContextStore(ctx);
         }).catch(() => { });
 
         // Listen for live system events from the watcher (app installs, downloads)
-        const unlisten = listen<{ name: string; path: string; event_type: string }>('system-event', (evt) => {
-            const { name, event_type } = evt.payload;
+        const unlisten = listen<{ name: string; path: string; event_type: string; silent?: boolean }>('system-event', (evt) => {
+            const { name, event_type, silent } = evt.payload;
+            // Low Power Mode / Focus is active and the user asked to keep this alert quiet
+            if (silent) return;
 
             let alertText = '';
             if (event_type === 'app_installed') {
@@ -202,18 +207,18 @@ export function Assistant({ onNavigate }: AssistantProps) {
 
                 // Also refresh MCP context cache
                 invoke<Record<string, unknown>>('get_mcp_context').then(ctx => {
-                    localStorage.setItem('alto_context_store_cache', JSON.stringify(ctx));
+                    cacheContextStore(ctx);
                 }).catch(() => { });
             }
         });
 
         // Listen for deep scan progress events from background Rust task
-        const unlistenDeepProgress = listen<{ directory: string; files_found: number; size_bytes: number; percent: number }>('deep-scan-progress', (evt) => {
-            const { directory, files_found, size_bytes, percent } = evt.payload;
+        const unlistenDeepProgress = listen<{ directory: string; files_found: number; size_bytes: number; percent: number; eta_secs: number | null }>('deep-scan-progress', (evt) => {
+            const { directory, files_found, size_bytes, percent, eta_secs } = evt.payload;
             setDeepScan(prev => ({
                 ...prev,
                 active: true,
-                entries: [...prev.entries, { directory, filesFound: files_found, sizeBytes: size_bytes, percent }]
+                entries: [...prev.entries, { directory, filesFound: files_found, sizeBytes: size_bytes, percent, etaSecs: eta_secs }]
             }));
             // Auto-scroll
             setTimeout(() => messagesEndRef.current?.scrollIntoView({ behavior: 'smooth' }), 50);
@@ -603,7 +608,7 @@ Would you like me to clean any of these? Just say the word.`;
                                                         ...m, widgetType: null, text: m.text + `\n\n✅ **Done!** Removed ${result.removed} files.`
                                                     } : m));
                                                 } catch (e: unknown) {
-                                                    const errMsg = e instanceof Error ? e.message : 'Delete failed';
+                                                    const errMsg = errorMessage(e, 'Delete failed');
                                                     setMessages(prev => prev.map(m => m.id === msg.id ? { ...m, widgetType: null, text: m.text + `\n\n❌ **Error:** ${errMsg}` } : m));
                                                 }
                                             }}
@@ -665,6 +670,8 @@ Would you like me to clean any of these? Just say the word.`;
                                             {deepScan.active && (
                                                 <span className="text-[10px] text-purple-300/70 font-mono">
                                                     {deepScan.entries[deepScan.entries.length - 1]?.percent ?? 0}%
+                                                    {deepScan.entries[deepScan.entries.length - 1]?.etaSecs != null &&
+                                                        ` · ~${Math.max(1, Math.ceil((deepScan.entries[deepScan.entries.length - 1]!.etaSecs as number) / 60))} min left`}
                                                 </span>
                                             )}
                                         </div>
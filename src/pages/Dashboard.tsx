@@ -3,6 +3,7 @@ import { AlertCircle, Zap } from 'lucide-react';
 import { useScanStore } from '../store/scanStore';
 import { useTauri } from '../hooks/useTauri';
 import { useState, useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { startScanSound, playCompletionSound } from '../utils/sounds';
 import { SmartScanResults } from '../components/dashboard/SmartScanResults';
 
@@ -25,6 +26,24 @@ export function Dashboard({ onNavigate }: DashboardProps) {
     const { call } = useTauri();
     const [scanStatus, setScanStatus] = useState<'idle' | 'scanning' | 'done'>('idle');
     const [error, setError] = useState<string | null>(null);
+    const [scanStage, setScanStage] = useState<string | null>(null);
+
+    // Smart scan reports which sub-scanner is running
+    useEffect(() => {
+        const stageLabels: Record<string, string> = {
+            junk: 'System Junk',
+            large_files: 'Large Files',
+            malware: 'Threat Check',
+        };
+        const unlisten = listen<{ stage: string; status: string; percent: number }>('smart-scan-stage', (evt) => {
+            const { stage, status, percent } = evt.payload;
+            // Sub-scanners run in parallel, so report overall progress as each one finishes
+            if (status === 'finished') {
+                setScanStage(`${percent}% · ${stageLabels[stage] ?? stage} done`);
+            }
+        });
+        return () => { unlisten.then(fn => fn()); };
+    }, []);
 
     // Initial check if we have results
     // Derived state for scan status
@@ -38,6 +57,7 @@ export function Dashboard({ onNavigate }: DashboardProps) {
 
     const handleScan = async () => {
         setScanStatus('scanning');
+        setScanStage(null);
         setError(null);
         useScanStore.getState().reset();
 
@@ -216,7 +236,7 @@ export function Dashboard({ onNavigate }: DashboardProps) {
                                     className="flex flex-col items-center"
                                 >
                                     <span className="text-5xl font-black text-white tracking-widest uppercase">Scanning</span>
-                                    <p className="text-white/40 font-mono mt-4 tracking-[0.3em]">Analyzing Architecture</p>
+                                    <p className="text-white/40 font-mono mt-4 tracking-[0.3em]">{scanStage ?? 'Analyzing Architecture'}</p>
                                 </motion.div>
                             </div>
                         </div>
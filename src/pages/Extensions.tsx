@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { Puzzle, Zap, Trash2, Power } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
+import { errorMessage } from '../utils/errors';
 
 interface ExtensionItem {
     path: string;
@@ -34,7 +35,7 @@ export function Extensions() {
             await invoke('remove_extension_command', { path });
             setItems(items.filter(i => i.path !== path));
         } catch (e) {
-            alert('Failed to remove: ' + e);
+            alert('Failed to remove: ' + errorMessage(e));
         }
     };
 
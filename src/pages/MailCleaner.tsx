@@ -2,6 +2,7 @@ import { useState, useEffect } from 'react';
 import { Mail, CheckCircle2, Trash2 } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
 import { formatBytes } from '../utils/formatBytes';
+import { errorMessage } from '../utils/errors';
 
 interface Attachment {
     path: string;
@@ -9,8 +10,25 @@ interface Attachment {
     size_bytes: number;
 }
 
+interface MailAccountUsage {
+    id: string;
+    size_bytes: number;
+    mailboxes: { name: string; path: string; size_bytes: number }[];
+}
+
+interface MailStoreReport {
+    mail_dir: string | null;
+    envelope_index_bytes: number;
+    envelope_index_free_ratio: number;
+    accounts: MailAccountUsage[];
+    total_mail_bytes: number;
+    suggest_rebuild: boolean;
+    reason: string | null;
+}
+
 export function MailCleaner() {
     const [attachments, setAttachments] = useState<Attachment[]>([]);
+    const [store, setStore] = useState<MailStoreReport | null>(null);
     const [loading, setLoading] = useState(true);
     const [cleaning, setCleaning] = useState(false);
 
@@ -21,6 +39,7 @@ export function MailCleaner() {
             setLoading(false);
         };
         load();
+        invoke<MailStoreReport>('analyze_mail_store_command').then(setStore).catch(console.error);
     }, []);
 
     const totalSize = attachments.reduce((acc, curr) => acc + curr.size_bytes, 0);
@@ -30,10 +49,11 @@ export function MailCleaner() {
         try {
             const paths = attachments.map(a => a.path);
             await invoke('clean_mail_command', { paths });
-            setAttachments([]);
+            // Blocked or in-use files stay; show what's actually left
+            setAttachments(await invoke<Attachment[]>('scan_mail_command'));
         } catch (e) {
             console.error(e);
-            alert("Failed to clean mail: " + e);
+            alert("Failed to clean mail: " + errorMessage(e));
         } finally {
             setCleaning(false);
         }
@@ -44,7 +64,7 @@ export function MailCleaner() {
             <div className="mb-6 flex justify-between items-end">
                 <div>
                     <h1 className="text-3xl font-bold bg-clip-text text-transparent bg-linear-to-r from-blue-400 to-indigo-500">Mail Attachments</h1>
-                    <p className="text-white/60 mt-1">Remove local copies of email attachments to save space.</p>
+                    <p className="text-white/60 mt-1">Remove the copies Mail saves when you open attachments. The messages keep the originals.</p>
                 </div>
                 {attachments.length > 0 && (
                     <div className="text-right">
@@ -54,6 +74,25 @@ export function MailCleaner() {
                 )}
             </div>
 
+            {store?.mail_dir && (
+                <div className="mb-4 p-4 bg-white/5 border border-white/10 rounded-2xl text-sm">
+                    <div className="flex justify-between text-white/60">
+                        <span>Mailboxes: <span className="text-white">{formatBytes(store.total_mail_bytes)}</span></span>
+                        <span>Mail index: <span className="text-white">{formatBytes(store.envelope_index_bytes)}</span></span>
+                    </div>
+                    {store.accounts.slice(0, 3).map(acc => (
+                        <div key={acc.id} className="mt-2 text-xs text-white/40 truncate">
+                            {acc.mailboxes.slice(0, 4).map(m => `${m.name} ${formatBytes(m.size_bytes)}`).join(' · ')}
+                        </div>
+                    ))}
+                    {store.suggest_rebuild && (
+                        <p className="mt-3 text-xs text-amber-400">
+                            {store.reason} Run "Rebuild Mail Index" in Maintenance to compact it.
+                        </p>
+                    )}
+                </div>
+            )}
+
             <div className="flex-1 bg-white/5 border border-white/10 rounded-2xl overflow-hidden flex flex-col">
                 <div className="p-4 border-b border-white/10 bg-white/5 flex justify-between items-center text-sm font-medium text-white/50">
                     <span>{attachments.length} Files Found</span>
@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
 import { useTauri } from '../hooks/useTauri';
+import { errorMessage } from '../utils/errors';
 import {
     MemoryStick,
     FileCode,
@@ -10,7 +11,9 @@ import {
     Type,
     Terminal,
     ChevronRight,
-    Zap
+    Zap,
+    Eye,
+    Image
 } from 'lucide-react';
 import { playCompletionSound } from '../utils/sounds';
 import { AnimatePresence, motion } from 'framer-motion';
@@ -27,6 +30,7 @@ interface MaintenanceTask {
     description: string;
     command: string; // Internal command string, not shown to user
     requires_sudo: boolean;
+    uses_helper: boolean;
 }
 
 const ICON_MAP: Record<string, any> = {
@@ -35,6 +39,10 @@ const ICON_MAP: Record<string, any> = {
     'reindex_spotlight': Search,
     'repair_disk_perms': ShieldCheck,
     'clear_font_cache': Type,
+    'clear_user_font_cache': Type,
+    'clear_quicklook_cache': Eye,
+    'reset_icon_services': Image,
+    'fix_finder_previews': Eye,
     'default': FileCode
 };
 
@@ -82,7 +90,7 @@ export function Maintenance() {
         } catch (e: any) {
             setTaskLogs(prev => ({
                 ...prev,
-                [selectedTask.id]: { status: 'error', output: typeof e === 'string' ? e : "Task failed. Ensure you have permissions." }
+                [selectedTask.id]: { status: 'error', output: errorMessage(e, "Task failed. Ensure you have permissions.") }
             }));
         } finally {
             setRunningTask(null);
@@ -152,7 +160,7 @@ export function Maintenance() {
                                         )}>
                                             {task.name}
                                         </span>
-                                        {task.requires_sudo && (
+                                        {(task.requires_sudo || task.uses_helper) && (
                                             <span className="text-[9px] text-primary/60 font-black uppercase tracking-[0.2em] mt-0.5">Admin Rights</span>
                                         )}
                                     </div>
@@ -146,7 +146,7 @@ export function MenuApp() {
                             </div>
                         </div>
                         <div className="text-xs text-[#fbd349] mb-1 font-medium">
-                            Available: {stats ? formatBytes(stats.disk_total - stats.disk_used) : '...'}
+                            Available: {stats ? formatBytes(stats.disk_total - stats.disk_used + (stats.disk_purgeable ?? 0)) : '...'}
                         </div>
                         {/* Hover Button Overlay */}
                         <div className="absolute bottom-4 right-4 opacity-0 group-hover:opacity-100 transition-opacity">
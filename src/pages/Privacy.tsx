@@ -12,6 +12,14 @@ interface PrivacyItem {
     path: string;
     size_bytes: number;
     description: string;
+    profile?: string | null;
+}
+
+// Mirrors PrivacyCleanOptions in browser_db.rs
+interface PrivacyCleanOptions {
+    history_older_than_days: number | null;
+    cookie_domains: string[];
+    keep_login_cookies: boolean;
 }
 
 export function Privacy() {
@@ -22,6 +30,9 @@ export function Privacy() {
     const [items, setItems] = useState<PrivacyItem[]>([]);
     const [selectedItems, setSelectedItems] = useState<Set<string>>(new Set());
     const [cleaning, setCleaning] = useState(false);
+    const [historyDays, setHistoryDays] = useState('');
+    const [cookieDomains, setCookieDomains] = useState('');
+    const [keepLogins, setKeepLogins] = useState(true);
 
     const handleScan = async () => {
         setScanning(true);
@@ -48,13 +59,18 @@ export function Privacy() {
         const paths = Array.from(selectedItems);
         if (paths.length === 0) return;
 
+        const days = parseInt(historyDays, 10);
+        const options: PrivacyCleanOptions = {
+            history_older_than_days: days > 0 ? days : null,
+            cookie_domains: cookieDomains.split(',').map(d => d.trim()).filter(Boolean),
+            keep_login_cookies: keepLogins,
+        };
+
         setCleaning(true);
         try {
-            // Clean items one by one or batch if supported
-            // Our backend command takes a single path: clean_privacy_item_command(path)
-            // Ideally backend would take a list, but let's loop for now
+            // The backend cleans one item per call; browser databases lose only the rows `options` picks
             for (const path of paths) {
-                await call('clean_privacy_item_command', { path });
+                await call('clean_privacy_item_command', { path, options });
             }
 
             setCleaned(true);
@@ -105,7 +121,7 @@ export function Privacy() {
                         </div>
                         <div>
                             <p className="font-medium text-white/90">Browser Cleanup</p>
-                            <p className="text-sm text-white/40">Removes history, cookies, and cache from Chrome, Safari, and Brave.</p>
+                            <p className="text-sm text-white/40">Removes history, cookies, and cache from Safari, Firefox, and Chromium browsers like Chrome, Edge, Brave, and Arc.</p>
                         </div>
                     </div>
                 </div>
@@ -126,7 +142,7 @@ export function Privacy() {
             <div className="h-full flex flex-col items-center justify-center">
                 <div className="w-20 h-20 border-4 border-amber-200/20 border-t-amber-500 rounded-full animate-spin mb-6" />
                 <p className="text-lg font-medium text-white/80">Scanning browser data...</p>
-                <p className="text-sm text-white/40 mt-2">Checking Chrome, Safari, and Brave</p>
+                <p className="text-sm text-white/40 mt-2">Checking Safari, Firefox, and Chromium browsers</p>
             </div>
         );
     }
@@ -169,6 +185,35 @@ export function Privacy() {
                 </div>
             </header>
 
+            <div className="flex flex-wrap items-center gap-x-6 gap-y-3 mb-4 px-4 py-3 bg-white/5 rounded-xl border border-white/10 text-sm">
+                <label className="flex items-center gap-2 text-white/70">
+                    History older than
+                    <input
+                        type="number"
+                        min={1}
+                        value={historyDays}
+                        onChange={e => setHistoryDays(e.target.value)}
+                        placeholder="all"
+                        className="w-16 px-2 py-1 rounded bg-white/10 border border-white/10 text-white"
+                    />
+                    days
+                </label>
+                <label className="flex items-center gap-2 text-white/70 flex-1 min-w-[220px]">
+                    Cookies of
+                    <input
+                        type="text"
+                        value={cookieDomains}
+                        onChange={e => setCookieDomains(e.target.value)}
+                        placeholder="all sites (or e.g. doubleclick.net, facebook.com)"
+                        className="flex-1 px-2 py-1 rounded bg-white/10 border border-white/10 text-white"
+                    />
+                </label>
+                <label className="flex items-center gap-2 text-white/70 cursor-pointer">
+                    <input type="checkbox" checked={keepLogins} onChange={e => setKeepLogins(e.target.checked)} />
+                    Keep cookies of sites with a saved password
+                </label>
+            </div>
+
             <div className="flex-1 overflow-auto bg-white/5 rounded-xl border border-white/10">
                 {Object.entries(grouped).map(([browser, browserItems]) => (
                     <div key={browser} className="border-b border-white/5 last:border-0">
@@ -195,6 +240,7 @@ export function Privacy() {
                                     <div className="flex items-center gap-2">
                                         <p className="text-sm font-medium truncate">{item.description}</p>
                                         <span className="text-[10px] px-1.5 py-0.5 rounded bg-white/10 text-white/50">{item.data_type}</span>
+                                        {item.profile && <span className="text-[10px] px-1.5 py-0.5 rounded bg-amber-500/10 text-amber-300/80">{item.profile}</span>}
                                     </div>
                                     <p className="text-xs text-white/30 truncate" title={item.path}>{item.path}</p>
                                 </div>
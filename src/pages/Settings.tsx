@@ -11,6 +11,7 @@ import { check } from '@tauri-apps/plugin-updater';
 import { relaunch } from '@tauri-apps/plugin-process';
 import { listen } from '@tauri-apps/api/event';
 import { AltoAvatar } from '../components/AltoAvatar';
+import { cacheContextStore } from '../utils/contextCache';
 
 const USER_PROFILE_KEY = 'alto_user_profile_v1';
 
@@ -32,9 +33,38 @@ interface SystemEvent {
     path: string;
 }
 
+interface QuietModePolicy {
+    respect_low_power: boolean;
+    respect_focus: boolean;
+    suppressed_alerts: string[];
+}
+
+interface AutoCleanPolicy {
+    enabled: boolean;
+    min_free_bytes: number;
+    categories: string[];
+    trash_min_age_days: number;
+}
+
+interface VirusTotalPolicy {
+    enabled: boolean;
+    /** The key itself stays in the Keychain */
+    has_api_key: boolean;
+}
+
 interface UserPrefs {
     always_skip_patterns: string[];
     auto_confirm_caches?: boolean;
+    quiet_mode?: QuietModePolicy;
+    scan_mode?: 'performance' | 'background';
+    trash_min_age_days?: number;
+    project_roots?: string[];
+    include_other_users?: boolean;
+    pre_clean_snapshot?: boolean;
+    dry_run?: boolean;
+    auto_clean?: AutoCleanPolicy;
+    protected_paths?: string[];
+    virustotal?: VirusTotalPolicy;
 }
 
 interface ContextStore {
@@ -193,7 +223,7 @@ export function Settings() {
             const ctx = await invoke<ContextStore>('get_mcp_context');
             console.log("Context loaded:", ctx);
             setContextStore(ctx);
-            localStorage.setItem('alto_context_store_cache', JSON.stringify(ctx));
+            cacheContextStore(ctx);
             setStatus('Context Refresh Completed.');
             setTimeout(() => setStatus(''), 2000);
         } catch (e) {
@@ -500,6 +530,7 @@ export function Settings() {
                                         const pat = newIgnorePattern.trim();
                                         if (!pat) return;
                                         const prefs: UserPrefs = {
+                                            ...contextStore?.user_preferences,
                                             always_skip_patterns: [...(contextStore?.user_preferences?.always_skip_patterns ?? []), pat],
                                             auto_confirm_caches: contextStore?.user_preferences?.auto_confirm_caches ?? false
                                         };
@@ -522,6 +553,7 @@ export function Settings() {
                                                 const list = contextStore?.user_preferences?.always_skip_patterns ?? [];
                                                 const next = list.filter((_, j) => j !== i);
                                                 const prefs: UserPrefs = {
+                                                    ...contextStore?.user_preferences,
                                                     always_skip_patterns: next,
                                                     auto_confirm_caches: contextStore?.user_preferences?.auto_confirm_caches ?? false
                                                 };
@@ -537,6 +569,38 @@ export function Settings() {
                             </div>
                         </div>
 
+                        {/* Scan mode — background throttles scan threads so the Mac stays responsive */}
+                        <div>
+                            <div className="flex items-center gap-2 mb-3">
+                                <Activity size={13} className="text-amber-400" />
+                                <span className="text-xs font-semibold text-white/60 uppercase tracking-wide">Scan priority</span>
+                            </div>
+                            <div className="flex gap-2">
+                                {(['background', 'performance'] as const).map(mode => (
+                                    <button
+                                        key={mode}
+                                        type="button"
+                                        onClick={async () => {
+                                            const prefs: UserPrefs = {
+                                                ...contextStore?.user_preferences,
+                                                always_skip_patterns: contextStore?.user_preferences?.always_skip_patterns ?? [],
+                                                scan_mode: mode
+                                            };
+                                            await invoke('update_user_preferences_command', { prefs });
+                                            setContextStore(prev => prev ? { ...prev, user_preferences: prefs } : null);
+                                        }}
+                                        className={`px-4 py-2 rounded-xl border text-sm font-medium ${
+                                            (contextStore?.user_preferences?.scan_mode ?? 'background') === mode
+                                                ? 'bg-amber-500/20 border-amber-500/30 text-amber-300'
+                                                : 'bg-white/5 border-white/10 text-white/50 hover:bg-white/10'
+                                        }`}
+                                    >
+                                        {mode === 'background' ? 'Background (gentle)' : 'Performance (fastest)'}
+                                    </button>
+                                ))}
+                            </div>
+                        </div>
+
                         {/* Protected Paths */}
                         <div className="bg-red-500/5 border border-red-500/10 rounded-xl p-4">
                             <div className="flex items-center gap-2 mb-3">
@@ -3,6 +3,7 @@ import { motion, AnimatePresence } from 'framer-motion';
 import { FileX, Trash2, ShieldAlert } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { errorMessage } from '../utils/errors';
 
 export function Shredder() {
     const [shredding, setShredding] = useState(false);
@@ -34,7 +35,7 @@ export function Shredder() {
             setDroppedPath(null);
         } catch (error) {
             console.error(error);
-            alert("Failed to shred: " + error);
+            alert("Failed to shred: " + errorMessage(error));
         } finally {
             setShredding(false);
         }
@@ -11,9 +11,46 @@ interface FileNode {
     size: number;
     children?: FileNode[];
     is_dir: boolean;
+    /** .app, .photoslibrary, ... shown as one item */
+    is_package?: boolean;
     // Nivo needs a standardized color or strict hierarchy sometimes, but we can manage
 }
 
+interface ExternalVolume {
+    name: string;
+    mount_point: string;
+    is_network: boolean;
+}
+
+interface CompactNode {
+    name: string;
+    size: number;
+    parent: number | null;
+    is_dir: boolean;
+    expanded: boolean;
+    is_package: boolean;
+}
+
+interface CompactTree {
+    root_path: string;
+    nodes: CompactNode[];
+}
+
+// scan_space_lens_compact_command returns gzip'd JSON with parent ids instead of nested paths
+async function inflateCompactTree(buffer: ArrayBuffer): Promise<FileNode | null> {
+    const stream = new Blob([buffer]).stream().pipeThrough(new DecompressionStream('gzip'));
+    const tree: CompactTree = JSON.parse(await new Response(stream).text());
+    const built: FileNode[] = [];
+    tree.nodes.forEach((n, i) => {
+        const parent = n.parent === null ? null : built[n.parent];
+        const path = parent ? `${parent.path.replace(/\/$/, '')}/${n.name}` : tree.root_path;
+        const node: FileNode = { name: n.name, path, size: n.size, is_dir: n.is_dir, is_package: n.is_package, children: n.expanded ? [] : undefined };
+        built[i] = node;
+        parent?.children?.push(node);
+    });
+    return built[0] ?? null;
+}
+
 // Custom theme for Nivo
 const theme = {
     text: {
@@ -43,19 +80,22 @@ export function SpaceLens() {
     const [isDeleting, setIsDeleting] = useState(false);
     const [scanDepth, setScanDepth] = useState(4);
     const [homePath, setHomePath] = useState<string | null>(null);
+    const [volumes, setVolumes] = useState<ExternalVolume[]>([]);
 
     useEffect(() => {
         call<string>('get_home_dir_command').then(setHomePath).catch(() => {});
+        call<ExternalVolume[]>('list_external_volumes_command').then(v => setVolumes(v ?? [])).catch(() => {});
     }, [call]);
 
     const handleScan = async (path?: string | null, depth: number = scanDepth, asRootScan: boolean = false) => {
         if (asRootScan) setViewState('scanning');
         try {
             if (asRootScan) await new Promise(r => setTimeout(r, 800));
-            const result = await call<FileNode>('scan_space_lens_command', {
+            const compressed = await call<ArrayBuffer>('scan_space_lens_compact_command', {
                 path: path ?? null,
                 depth,
             });
+            const result = compressed ? await inflateCompactTree(compressed) : null;
             if (asRootScan) {
                 setRootNode(result);
                 setCurrentNode(result);
@@ -74,7 +114,7 @@ export function SpaceLens() {
         // node.data is the FileNode
         const fileNode = node.data as FileNode;
 
-        if (!fileNode.is_dir) {
+        if (!fileNode.is_dir || fileNode.is_package) {
             setSelectedPath(fileNode.path);
             return;
         }
@@ -82,10 +122,14 @@ export function SpaceLens() {
         // Drill down
         let nextNode = fileNode;
 
-        // Fetch children if missing (lazy load)
+        // Fetch children if missing (lazy load); sizes come from the backend's cache of earlier scans
         if (!fileNode.children || fileNode.children.length === 0) {
-            const fetched = await handleScan(fileNode.path, undefined, false);
-            if (fetched) nextNode = fetched;
+            try {
+                const fetched = await call<FileNode>('space_lens_expand_node', { path: fileNode.path });
+                if (fetched) nextNode = fetched;
+            } catch (e) {
+                console.error(e);
+            }
         }
 
         if (currentNode) {
@@ -117,8 +161,8 @@ export function SpaceLens() {
                 setCurrentNode(updatedNode);
 
                 // Also update backend for correctness
-                const result = await call<FileNode>('scan_space_lens_command');
-                setRootNode(result);
+                const compressed = await call<ArrayBuffer>('scan_space_lens_compact_command');
+                setRootNode(compressed ? await inflateCompactTree(compressed) : null);
             }
             setSelectedPath(null);
         } catch (e) {
@@ -211,6 +255,15 @@ export function SpaceLens() {
                                             Library
                                         </button>
                                     )}
+                                    {volumes.map(v => (
+                                        <button
+                                            key={v.mount_point}
+                                            onClick={() => handleScan(v.mount_point, scanDepth, true)}
+                                            className="px-6 py-3 rounded-full bg-white/10 hover:bg-white/20 border border-white/20 font-medium text-white transition-all"
+                                        >
+                                            {v.name || v.mount_point.split('/').pop()}
+                                        </button>
+                                    ))}
                                 </div>
                             </div>
                             <div className="flex items-center justify-center">
@@ -258,14 +311,19 @@ export function SpaceLens() {
                                 {currentNode.children?.sort((a, b) => b.size - a.size).map(child => (
                                     <div
                                         key={child.path}
-                                        onClick={() => child.is_dir ? handleNavigate({ data: child }) : setSelectedPath(child.path)}
+                                        onClick={() => child.is_dir && !child.is_package ? handleNavigate({ data: child }) : setSelectedPath(child.path)}
                                         className={`flex items-center gap-3 p-2 rounded-lg cursor-pointer ${selectedPath === child.path ? 'bg-white/10' : 'hover:bg-white/5'}`}
                                     >
                                         <div className="w-8 h-8 rounded bg-white/5 flex items-center justify-center text-white/50">
                                             {child.is_dir ? <Folder size={16} className="text-emerald-400" /> : <File size={16} />}
                                         </div>
                                         <div className="flex-1 min-w-0">
-                                            <div className="text-sm text-white truncate">{child.name}</div>
+                                            <div className="text-sm text-white truncate flex items-center gap-2">
+                                                {child.name}
+                                                {child.is_package && (
+                                                    <span className="text-[10px] uppercase tracking-wide px-1.5 py-0.5 rounded bg-white/10 text-white/60">package</span>
+                                                )}
+                                            </div>
                                             <div className="text-xs text-white/50">{formatBytes(child.size)}</div>
                                         </div>
                                     </div>
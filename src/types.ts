@@ -1,21 +1,30 @@
 export interface ScannedItem {
     path: string;
-    size_bytes: number;
+    size_bytes: number; // on-disk (allocated) bytes
+    logical_bytes?: number;
+    allocated_bytes?: number;
     category_name: string;
     is_directory: boolean;
     accessed_date?: number; // timestamp in seconds
+    file_category?: string;
+    reason?: string;
 }
 
 export interface ScanResult {
     items: ScannedItem[];
     total_size_bytes: number;
     errors: string[];
+    truncated: boolean;
+    scanned_templates: string[];
+    skipped_templates: string[];
 }
 
 export interface FileNode {
     name: string;
     path: string;
-    size: number;
+    size: number; // on-disk (allocated) bytes
+    logical_bytes?: number;
+    allocated_bytes?: number;
     children: FileNode[] | null;
 }
 
@@ -42,14 +51,38 @@ export interface DeviceInfo {
     is_connected: boolean;
 }
 
+export interface VolumeStats {
+    name: string;
+    mount_point: string;
+    total: number;
+    available: number; // shared by every volume with the same container_id
+    container_id: string | null;
+}
+
 export interface SystemStats {
     cpu_load: number;
     memory_used: number;
     memory_total: number;
-    disk_total: number;
+    disk_total: number; // boot APFS container, as in Disk Utility
     disk_used: number;
+    disk_container_id: string | null;
+    disk_purgeable: number | null; // part of disk_used that macOS frees on demand
+    local_snapshot_count: number;
+    volumes: VolumeStats[];
     network_up: number;
     network_down: number;
     battery_level: number | null;
     connected_devices: DeviceInfo[];
 }
+
+export interface StorageSegment {
+    name: string; // 'Apps' | 'Documents' | 'Developer' | 'Trash' | 'Purgeable' | 'System Data'
+    size_bytes: number;
+    measured_at: string | null; // null until a scan has measured it
+}
+
+export interface StorageSummary {
+    disk_total: number;
+    disk_used: number;
+    segments: StorageSegment[];
+}
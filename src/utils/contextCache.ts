@@ -0,0 +1,8 @@
+const CONTEXT_CACHE_KEY = 'alto_context_store_cache';
+
+/** Cache the MCP context store for the AI. The VirusTotal API key lives in the Keychain and never goes into localStorage. */
+export function cacheContextStore(ctx: unknown): void {
+    const copy = JSON.parse(JSON.stringify(ctx ?? {}));
+    if (copy?.user_preferences?.virustotal) delete copy.user_preferences.virustotal.api_key;
+    localStorage.setItem(CONTEXT_CACHE_KEY, JSON.stringify(copy));
+}
@@ -0,0 +1,20 @@
+/** Error returned by Tauri commands (`AltoError` in src-tauri/src/error.rs). */
+export type AltoError =
+    | { code: 'permission_denied'; message: string; path?: string | null }
+    | { code: 'not_found'; message: string; path?: string | null }
+    | { code: 'app_running'; message: string; app: string }
+    | { code: 'blocked'; message: string; path?: string | null }
+    | { code: 'invalid_input'; message: string }
+    | { code: 'automation_permission'; message: string; settings_url: string }
+    | { code: 'failed'; message: string };
+
+export function isAltoError(e: unknown): e is AltoError {
+    return typeof e === 'object' && e !== null && 'code' in e && 'message' in e;
+}
+
+/** Readable text for anything thrown by `invoke`, JS errors and plain strings included. */
+export function errorMessage(e: unknown, fallback = 'Something went wrong'): string {
+    if (isAltoError(e) || e instanceof Error) return e.message;
+    if (typeof e === 'string') return e;
+    return fallback;
+}